@@ -0,0 +1,34 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+#![no_main]
+
+use coppwr_backend::pods::{decode, params, profiler};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|bytes: &[u8]| {
+    profiler::parse(bytes).ok();
+
+    if let Ok(value) = params::parse(bytes) {
+        decode::tree("Pod", &value);
+        params::flatten(&value);
+        params::AudioFormat::from_param(&value).and_then(|format| format.bandwidth());
+        params::PortConfig::from_param(&value);
+        params::Volume::from_props(&value);
+        params::Profile::from_param(&value);
+        params::Route::from_param(&value);
+    }
+});
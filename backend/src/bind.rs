@@ -27,6 +27,10 @@ use pipewire::{
 
 use super::{util, Event, ObjectMethod};
 
+// Errors of binds that the connection doesn't have the permissions for
+const EPERM: i32 = 1;
+const EACCES: i32 = 13;
+
 #[derive(Debug)]
 pub enum Error {
     Unimplemented(ObjectType),
@@ -56,6 +60,9 @@ impl std::error::Error for Error {}
 pub enum Global {
     Client(pw::client::Client),
     Metadata(pw::metadata::Metadata),
+    Node(pw::node::Node),
+    Port(pw::port::Port),
+    Device(pw::device::Device),
     Other(pw::proxy::Proxy),
 }
 
@@ -68,6 +75,9 @@ impl Global {
         match self {
             Self::Metadata(m) => m.upcast_ref(),
             Self::Client(c) => c.upcast_ref(),
+            Self::Node(n) => n.upcast_ref(),
+            Self::Port(p) => p.upcast_ref(),
+            Self::Device(d) => d.upcast_ref(),
             Self::Other(p) => p,
         }
     }
@@ -86,6 +96,7 @@ impl BoundGlobal {
         sx: &std::sync::mpsc::Sender<Event>,
         proxy_removed: impl Fn() + 'static,
     ) -> Result<Self, Error> {
+        let error_sx = sx.clone();
         let sx = sx.clone();
 
         let id = global.id;
@@ -126,6 +137,18 @@ impl BoundGlobal {
             .as_proxy()
             .add_listener_local()
             .removed(proxy_removed)
+            .error(move |_, res, message| {
+                if res == -EACCES || res == -EPERM {
+                    error_sx
+                        .send(Event::GlobalRestricted {
+                            id,
+                            message: message.to_owned(),
+                        })
+                        .ok();
+                } else {
+                    eprintln!("Object {id} error: {res} - {message}");
+                }
+            })
             .register();
 
         Ok(Self {
@@ -135,6 +158,15 @@ impl BoundGlobal {
         })
     }
 
+    pub fn enum_params(&self, param_type: pw::spa::param::ParamType) {
+        match self.global {
+            Global::Node(ref node) => node.enum_params(0, Some(param_type), 0, u32::MAX),
+            Global::Port(ref port) => port.enum_params(0, Some(param_type), 0, u32::MAX),
+            Global::Device(ref device) => device.enum_params(0, Some(param_type), 0, u32::MAX),
+            _ => {}
+        }
+    }
+
     pub fn call(&self, method: ObjectMethod) {
         match method {
             ObjectMethod::ClientGetPermissions { index, num } => {
@@ -172,6 +204,18 @@ impl BoundGlobal {
                     metadata.clear();
                 }
             }
+            ObjectMethod::SetParam { id, flags, pod } => {
+                let Some(pod) = pw::spa::pod::Pod::from_bytes(&pod) else {
+                    eprintln!("Tried to set an invalid {id:?} param");
+                    return;
+                };
+
+                match self.global {
+                    Global::Node(ref node) => node.set_param(id, flags, pod),
+                    Global::Device(ref device) => device.set_param(id, flags, pod),
+                    _ => {}
+                }
+            }
         }
     }
 }
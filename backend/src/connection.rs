@@ -65,7 +65,7 @@ mod portals {
     use std::os::fd::{FromRawFd, OwnedFd};
 
     use ashpd::{
-        desktop::{screencast::SourceType, Session},
+        desktop::{remote_desktop::DeviceType, screencast::SourceType, Session},
         enumflags2::BitFlags,
     };
 
@@ -100,6 +100,49 @@ mod portals {
         })
     }
 
+    pub fn open_remote_desktop_remote<'s>(
+        devices: BitFlags<DeviceType>,
+        types: BitFlags<SourceType>,
+    ) -> Result<(OwnedFd, Session<'s>), ashpd::Error> {
+        pollster::block_on(async {
+            use ashpd::desktop::{
+                remote_desktop::RemoteDesktop,
+                screencast::{CursorMode, PersistMode, Screencast},
+            };
+
+            let proxy = RemoteDesktop::new().await?;
+            let screencast = Screencast::new().await?;
+            let session = proxy.create_session().await?;
+
+            proxy
+                .select_devices(&session, devices, None, PersistMode::DoNot)
+                .await?;
+
+            if !types.is_empty() {
+                screencast
+                    .select_sources(
+                        &session,
+                        CursorMode::Hidden,
+                        types,
+                        true,
+                        None,
+                        PersistMode::DoNot,
+                    )
+                    .await?;
+            }
+
+            proxy
+                .start(&session, &ashpd::WindowIdentifier::default())
+                .await?;
+
+            // The remote is opened through the Screencast portal, which accepts
+            // RemoteDesktop sessions too
+            let fd = screencast.open_pipe_wire_remote(&session).await?;
+
+            Ok((unsafe { OwnedFd::from_raw_fd(fd) }, session))
+        })
+    }
+
     pub fn open_camera_remote() -> Result<Option<OwnedFd>, ashpd::Error> {
         pollster::block_on(ashpd::desktop::camera::request())
             .map(|fd| fd.map(|fd| unsafe { OwnedFd::from_raw_fd(fd) }))
@@ -157,6 +200,14 @@ impl<'s> Connection<'s> {
                     session,
                 ))
             }
+            RemoteInfo::RemoteDesktop { devices, types } => {
+                let (fd, session) = portals::open_remote_desktop_remote(devices, types)?;
+
+                Ok(Self::PortalWithSession(
+                    context.connect_fd(fd, Some(context_properties))?,
+                    session,
+                ))
+            }
             RemoteInfo::Camera => Ok(Self::Simple(context.connect_fd(
                 portals::open_camera_remote()?.ok_or(Error::PortalUnavailable)?,
                 Some(context_properties),
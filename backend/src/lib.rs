@@ -0,0 +1,258 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! The PipeWire side of coppwr.
+//!
+//! [`Handle::run`] connects to a remote on its own thread. [`Request`]s are sent to it
+//! through [`Handle::sx`] and the objects of the remote are reported as [`Event`]s
+//! through [`Handle::rx`]. [`pipewire::init`](pw::init) must have been called before.
+//!
+//! Params are sent and received as serialized pods, the [`pods`] module has
+//! helpers for building and decoding the ones coppwr uses.
+
+mod bind;
+mod connection;
+mod pipewire;
+pub mod pods;
+mod util;
+
+/// The version of `pipewire` used for the types of the API
+pub use ::pipewire as pw;
+
+use connection::Connection;
+
+/// The object type of an interface name, like `PipeWire:Interface:Node`.
+/// Used for the types of the objects factories create.
+pub fn interface_object_type(name: &str) -> pw::types::ObjectType {
+    use pw::types::ObjectType;
+
+    match name {
+        "PipeWire:Interface:Link" => ObjectType::Link,
+        "PipeWire:Interface:Port" => ObjectType::Port,
+        "PipeWire:Interface:Node" => ObjectType::Node,
+        "PipeWire:Interface:Client" => ObjectType::Client,
+        "PipeWire:Interface:Device" => ObjectType::Device,
+        "PipeWire:Interface:Registry" => ObjectType::Registry,
+        "PipeWire:Interface:Profiler" => ObjectType::Profiler,
+        "PipeWire:Interface:Metadata" => ObjectType::Metadata,
+        "PipeWire:Interface:Factory" => ObjectType::Factory,
+        "PipeWire:Interface:Module" => ObjectType::Module,
+        "PipeWire:Interface:Core" => ObjectType::Core,
+        "PipeWire:Interface:Endpoint" => ObjectType::Endpoint,
+        "PipeWire:Interface:EndpointLink" => ObjectType::EndpointLink,
+        "PipeWire:Interface:EndpointStream" => ObjectType::EndpointStream,
+        "PipeWire:Interface:ClientSession" => ObjectType::ClientSession,
+        "PipeWire:Interface:ClientEndpoint" => ObjectType::ClientEndpoint,
+        "PipeWire:Interface:ClientNode" => ObjectType::ClientNode,
+        _ => ObjectType::Other(name.to_owned()),
+    }
+}
+
+/// Sends [`Request`]s to the PipeWire thread
+pub type Sender = pw::channel::Sender<Request>;
+
+/// Methods of specific object types
+pub enum ObjectMethod {
+    /// Results in [`Event::ClientPermissions`]
+    ClientGetPermissions {
+        index: u32,
+        num: u32,
+    },
+    ClientUpdatePermissions(Vec<pw::permissions::Permission>),
+    ClientUpdateProperties(std::collections::BTreeMap<String, String>),
+    MetadataSetProperty {
+        subject: u32,
+        key: String,
+        type_: Option<String>,
+        value: Option<String>,
+    },
+    MetadataClear,
+    /// Sets a param of a node or device. `pod` is a serialized object.
+    SetParam {
+        id: pw::spa::param::ParamType,
+        flags: u32,
+        pod: Vec<u8>,
+    },
+}
+
+pub enum Request {
+    /// Disconnects and ends the thread. Sent when the [`Handle`] is dropped.
+    Stop,
+    /// Creates an object of a type from the factory with the given name and properties
+    CreateObject(pw::types::ObjectType, String, Vec<(String, String)>),
+    DestroyObject(u32),
+    /// Loads a module into the local context
+    LoadModule {
+        module_dir: Option<String>,
+        name: String,
+        args: Option<String>,
+        props: Option<Vec<(String, String)>>,
+    },
+    /// Results in [`Event::ContextProperties`]
+    GetContextProperties,
+    UpdateContextProperties(std::collections::BTreeMap<String, String>),
+    /// Calls a method on the object with the ID
+    CallObjectMethod(u32, ObjectMethod),
+    /// Results in an [`Event::GlobalParam`] for each param of the type
+    EnumParams(u32, pw::spa::param::ParamType),
+    /// Results in an [`Event::SyncDone`] with the same number once the remote
+    /// has processed the requests sent before it and their events have been sent
+    Sync(u32),
+}
+
+/// Changes of the objects of the remote. All the IDs are global IDs.
+pub enum Event {
+    /// The ID, type and properties of a new global
+    GlobalAdded(
+        u32,
+        pw::types::ObjectType,
+        Option<std::collections::BTreeMap<String, String>>,
+    ),
+    GlobalRemoved(u32),
+    /// The global couldn't be bound because the connection lacks the permissions for it,
+    /// so its info, params and property changes won't be reported
+    GlobalRestricted {
+        id: u32,
+        message: String,
+    },
+    /// Labeled values of the info of a global, which differ for each type
+    GlobalInfo(u32, Box<[(&'static str, String)]>),
+    GlobalProperties(u32, std::collections::BTreeMap<String, String>),
+    /// The type, index and serialized pod of a param.
+    /// Params that fail to parse are followed by an [`Event::Error`], and sent anyway so
+    /// that they can be inspected.
+    GlobalParam(u32, pw::spa::param::ParamType, u32, Vec<u8>),
+    /// The index of the first permission and the permissions of a client
+    ClientPermissions(u32, u32, Vec<pw::permissions::Permission>),
+    ProfilerProfile(Vec<self::pods::profiler::Profiling>),
+    /// A property of a metadata object was set or cleared.
+    /// A `key` of `None` means all of its properties were cleared.
+    MetadataProperty {
+        id: u32,
+        subject: u32,
+        key: Option<String>,
+        type_: Option<String>,
+        value: Option<String>,
+    },
+    ContextProperties(std::collections::BTreeMap<String, String>),
+    /// All of the globals that existed when connecting have been announced.
+    /// Sent once per connection, globals added before it are part of the initial state of the remote.
+    InitialSyncDone,
+    /// The remote has processed everything before the [`Request::Sync`] with the number
+    SyncDone(u32),
+    /// The connection to the remote was lost and the backend is trying to reconnect.
+    /// Requests sent meanwhile are dropped.
+    Disconnected,
+    /// The connection was reestablished. The globals of the lost connection are gone and
+    /// the ones of the new connection are announced after this, followed by [`Event::InitialSyncDone`].
+    Reconnected,
+    /// A pod sent by the object with the ID couldn't be parsed
+    Error {
+        id: u32,
+        error: self::pods::ParseError,
+    },
+    /// The connection ended, no more events will be sent
+    Stop,
+}
+
+/// The remote to connect to
+#[derive(Clone)]
+pub enum RemoteInfo {
+    /// A remote name, like `pipewire-0`
+    Regular(String),
+
+    /// A remote opened by the Screencast portal
+    #[cfg(feature = "xdg_desktop_portals")]
+    Screencast {
+        types: ashpd::enumflags2::BitFlags<ashpd::desktop::screencast::SourceType>,
+        multiple: bool,
+    },
+    /// A remote opened by the Camera portal
+    #[cfg(feature = "xdg_desktop_portals")]
+    Camera,
+    /// A remote opened by the RemoteDesktop portal
+    #[cfg(feature = "xdg_desktop_portals")]
+    RemoteDesktop {
+        devices: ashpd::enumflags2::BitFlags<ashpd::desktop::remote_desktop::DeviceType>,
+        /// Sources to also share through the Screencast portal, can be empty
+        types: ashpd::enumflags2::BitFlags<ashpd::desktop::screencast::SourceType>,
+    },
+}
+
+impl PartialEq for RemoteInfo {
+    fn eq(&self, other: &Self) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+}
+
+impl RemoteInfo {
+    /// Whether the remote can be connected to again without the user's interaction
+    const fn can_reconnect(&self) -> bool {
+        matches!(self, Self::Regular(_))
+    }
+}
+
+impl Default for RemoteInfo {
+    fn default() -> Self {
+        Self::Regular(
+            std::env::var("PIPEWIRE_REMOTE").unwrap_or_else(|_| String::from("pipewire-0")),
+        )
+    }
+}
+
+/// A connection to a remote, running on its own thread
+pub struct Handle {
+    thread: Option<std::thread::JoinHandle<()>>,
+    pub rx: std::sync::mpsc::Receiver<Event>,
+    pub sx: Sender,
+}
+
+impl Handle {
+    /// Connects to the remote with properties for the main loop and context
+    pub fn run(
+        remote: RemoteInfo,
+        mainloop_properties: Vec<(String, String)>,
+        context_properties: Vec<(String, String)>,
+    ) -> Self {
+        let (sx, rx) = std::sync::mpsc::channel::<Event>();
+        let (pwsx, pwrx) = pw::channel::channel::<Request>();
+
+        Self {
+            thread: Some(std::thread::spawn(move || {
+                self::pipewire::pipewire_thread(
+                    remote,
+                    mainloop_properties,
+                    context_properties,
+                    sx,
+                    pwrx,
+                );
+            })),
+            rx,
+            sx: pwsx,
+        }
+    }
+}
+
+impl Drop for Handle {
+    fn drop(&mut self) {
+        if self.sx.send(Request::Stop).is_err() {
+            eprintln!("Error sending stop request to PipeWire");
+        }
+        if let Some(Err(e)) = self.thread.take().map(std::thread::JoinHandle::join) {
+            eprintln!("The PipeWire thread has paniced: {e:?}");
+        }
+    }
+}
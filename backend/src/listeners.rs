@@ -14,12 +14,33 @@
 //
 // SPDX-License-Identifier: GPL-3.0-only
 
-use pipewire::{self as pw, spa::pod::deserialize::PodDeserializer};
+use pipewire as pw;
 
-use crate::backend::{bind::Global, pods::profiler, util::dict_to_map, Event};
+use crate::{
+    bind::Global,
+    pods::{params, profiler},
+    util::dict_to_map,
+    Event,
+};
 
 type Bind = (Global, Box<dyn pipewire::proxy::Listener>);
 
+/// Sends the param, followed by the error of parsing it if it's malformed
+fn send_param(
+    sx: &std::sync::mpsc::Sender<Event>,
+    id: u32,
+    param_type: pw::spa::param::ParamType,
+    index: u32,
+    pod: &[u8],
+) {
+    sx.send(Event::GlobalParam(id, param_type, index, pod.to_vec()))
+        .ok();
+
+    if let Err(error) = params::parse(pod) {
+        sx.send(Event::Error { id, error }).ok();
+    }
+}
+
 pub fn module(module: pw::module::Module, id: u32, sx: std::sync::mpsc::Sender<Event>) -> Bind {
     let listener = module
         .add_listener_local()
@@ -80,6 +101,7 @@ pub fn device(device: pw::device::Device, id: u32, sx: std::sync::mpsc::Sender<E
     let listener = device
         .add_listener_local()
         .info({
+            let sx = sx.clone();
             move |info| {
                 if let (true, Some(props)) = (
                     info.change_mask()
@@ -91,8 +113,13 @@ pub fn device(device: pw::device::Device, id: u32, sx: std::sync::mpsc::Sender<E
                 }
             }
         })
+        .param(move |_, param_type, index, _, param| {
+            if let Some(param) = param {
+                send_param(&sx, id, param_type, index, param.as_bytes());
+            }
+        })
         .register();
-    (Global::other(device), Box::new(listener))
+    (Global::Device(device), Box::new(listener))
 }
 
 pub fn client(client: pw::client::Client, id: u32, sx: std::sync::mpsc::Sender<Event>) -> Bind {
@@ -125,6 +152,7 @@ pub fn node(node: pw::node::Node, id: u32, sx: std::sync::mpsc::Sender<Event>) -
     let listener = node
         .add_listener_local()
         .info({
+            let sx = sx.clone();
             move |info| {
                 let state = match info.state() {
                     pw::node::NodeState::Creating => "Creating",
@@ -153,14 +181,20 @@ pub fn node(node: pw::node::Node, id: u32, sx: std::sync::mpsc::Sender<Event>) -
                 }
             }
         })
+        .param(move |_, param_type, index, _, param| {
+            if let Some(param) = param {
+                send_param(&sx, id, param_type, index, param.as_bytes());
+            }
+        })
         .register();
-    (Global::other(node), Box::new(listener))
+    (Global::Node(node), Box::new(listener))
 }
 
 pub fn port(port: pw::port::Port, id: u32, sx: std::sync::mpsc::Sender<Event>) -> Bind {
     let listener = port
         .add_listener_local()
         .info({
+            let sx = sx.clone();
             move |info| {
                 let direction = match info.direction() {
                     pw::spa::utils::Direction::Input => "Input",
@@ -181,8 +215,13 @@ pub fn port(port: pw::port::Port, id: u32, sx: std::sync::mpsc::Sender<Event>) -
                 }
             }
         })
+        .param(move |_, param_type, index, _, param| {
+            if let Some(param) = param {
+                send_param(&sx, id, param_type, index, param.as_bytes());
+            }
+        })
         .register();
-    (Global::other(port), Box::new(listener))
+    (Global::Port(port), Box::new(listener))
 }
 
 pub fn link(link: pw::link::Link, id: u32, sx: std::sync::mpsc::Sender<Event>) -> Bind {
@@ -231,14 +270,12 @@ pub fn profiler(
     let listener = profiler
         .add_listener_local()
         .profile({
-            move |pod| match PodDeserializer::deserialize_from::<profiler::Profilings>(pod)
-                .map(|(_, pod)| pod)
-            {
+            move |pod| match profiler::parse(pod) {
                 Ok(profilings) => {
-                    sx.send(Event::ProfilerProfile(profilings.0)).ok();
+                    sx.send(Event::ProfilerProfile(profilings)).ok();
                 }
-                Err(_) => {
-                    eprintln!("Deserialization of profiler {id} statistics failed");
+                Err(error) => {
+                    sx.send(Event::Error { id, error }).ok();
                 }
             }
         })
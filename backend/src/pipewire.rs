@@ -14,9 +14,15 @@
 //
 // SPDX-License-Identifier: GPL-3.0-only
 
-use std::{cell::RefCell, collections::HashMap, rc::Rc, sync::mpsc};
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    rc::Rc,
+    sync::mpsc,
+    time::Duration,
+};
 
-use crate::backend::connection;
+use crate::connection;
 
 use super::{
     bind::BoundGlobal,
@@ -24,8 +30,22 @@ use super::{
     util, Connection, Event, RemoteInfo, Request,
 };
 
-#[cfg(feature = "pw_v0_3_77")]
-use super::REMOTE_VERSION;
+// Delays between the attempts to reconnect after the connection is lost, doubled after each one
+const RECONNECT_MIN_DELAY: Duration = Duration::from_millis(500);
+const RECONNECT_MAX_DELAY: Duration = Duration::from_secs(30);
+
+// Error of the core proxy when the remote has been closed
+const EPIPE: i32 = 32;
+
+/// How a connection ended
+enum Ended {
+    /// Stop was requested
+    Stopped,
+    /// The remote closed the connection
+    Disconnected,
+    /// Connecting failed
+    Failed(connection::Error),
+}
 
 pub fn pipewire_thread(
     remote: RemoteInfo,
@@ -34,16 +54,7 @@ pub fn pipewire_thread(
     sx: mpsc::Sender<Event>,
     pwrx: pw::channel::Receiver<Request>,
 ) {
-    // Proxies created by core.create_object
-    #[allow(dead_code)] // The fields are never read from
-    struct LocalProxy(pw::proxy::Proxy, pw::proxy::ProxyListener);
-
-    let (mainloop, context, connection, registry): (
-        pw::main_loop::MainLoop,
-        pw::context::Context,
-        Connection,
-        Rc<pw::registry::Registry>,
-    ) = match (|| -> Result<_, connection::Error> {
+    let (mainloop, context) = match (|| -> Result<_, connection::Error> {
         let mainloop = if mainloop_properties.is_empty() {
             pw::main_loop::MainLoop::new(None)?
         } else {
@@ -60,11 +71,7 @@ pub fn pipewire_thread(
             eprintln!("Failed to load the profiler module. No profiler data will be available");
         };
 
-        let connection = Connection::connect(&context, context_properties, remote)?;
-
-        let registry = connection.core().get_registry()?;
-
-        Ok((mainloop, context, connection, Rc::new(registry)))
+        Ok((mainloop, context))
     })() {
         Ok(instance) => instance,
         Err(e) => {
@@ -75,14 +82,152 @@ pub fn pipewire_thread(
             return;
         }
     };
+
+    // Remotes opened by portals need the user's interaction so they're not reconnected to
+    let reconnect_remote = remote.can_reconnect().then(|| remote.clone());
+
+    match connect(
+        &mainloop,
+        &context,
+        context_properties.clone(),
+        remote,
+        &sx,
+        pwrx,
+        false,
+    ) {
+        (Ended::Stopped, _) => {}
+        (Ended::Failed(e), _) => eprintln!("Failed to connect to remote: {e}"),
+        (Ended::Disconnected, pwrx) => {
+            if let Some(remote) = reconnect_remote {
+                reconnect(&mainloop, &context, &context_properties, &remote, &sx, pwrx);
+            }
+        }
+    }
+
+    sx.send(Event::Stop).ok();
+}
+
+/// Tries to connect to the remote again until stop is requested, with exponential backoff
+fn reconnect(
+    mainloop: &pw::main_loop::MainLoop,
+    context: &pw::context::Context,
+    context_properties: &[(String, String)],
+    remote: &RemoteInfo,
+    sx: &mpsc::Sender<Event>,
+    mut pwrx: pw::channel::Receiver<Request>,
+) {
+    sx.send(Event::Disconnected).ok();
+
+    let mut delay = RECONNECT_MIN_DELAY;
+    loop {
+        let stopped;
+        (stopped, pwrx) = wait(mainloop, pwrx, delay);
+        if stopped {
+            return;
+        }
+
+        let ended;
+        (ended, pwrx) = connect(
+            mainloop,
+            context,
+            context_properties.to_vec(),
+            remote.clone(),
+            sx,
+            pwrx,
+            true,
+        );
+        match ended {
+            Ended::Stopped => return,
+            Ended::Disconnected => {
+                sx.send(Event::Disconnected).ok();
+                delay = RECONNECT_MIN_DELAY;
+            }
+            Ended::Failed(e) => {
+                eprintln!("Failed to reconnect to remote: {e}");
+                delay = (delay * 2).min(RECONNECT_MAX_DELAY);
+            }
+        }
+    }
+}
+
+/// Runs the main loop until the delay passes. Returns whether stop was requested meanwhile.
+fn wait(
+    mainloop: &pw::main_loop::MainLoop,
+    pwrx: pw::channel::Receiver<Request>,
+    delay: Duration,
+) -> (bool, pw::channel::Receiver<Request>) {
+    let stopped = Rc::new(Cell::new(false));
+
+    // Other requests are meant for objects of the lost connection, so they're dropped
+    let receiver = pwrx.attach(mainloop.loop_(), {
+        let mainloop = mainloop.clone();
+        let stopped = Rc::clone(&stopped);
+        move |msg| {
+            if let Request::Stop = msg {
+                stopped.set(true);
+                mainloop.quit();
+            }
+        }
+    });
+
+    let timer = mainloop.loop_().add_timer({
+        let mainloop = mainloop.clone();
+        move |_| mainloop.quit()
+    });
+    if let Err(e) = timer.update_timer(Some(delay), None).into_result() {
+        eprintln!("Failed to set the reconnection timer: {e}");
+        std::thread::sleep(delay);
+    } else {
+        mainloop.run();
+    }
+
+    (stopped.get(), receiver.deattach())
+}
+
+/// Connects to the remote and runs the main loop until the connection ends.
+/// The receiver of requests is handed back so that it can be used for the next connection.
+fn connect(
+    mainloop: &pw::main_loop::MainLoop,
+    context: &pw::context::Context,
+    context_properties: Vec<(String, String)>,
+    remote: RemoteInfo,
+    sx: &mpsc::Sender<Event>,
+    pwrx: pw::channel::Receiver<Request>,
+    reconnecting: bool,
+) -> (Ended, pw::channel::Receiver<Request>) {
+    // Proxies created by core.create_object
+    #[allow(dead_code)] // The fields are never read from
+    struct LocalProxy(pw::proxy::Proxy, pw::proxy::ProxyListener);
+
+    let (connection, registry) = match (|| -> Result<_, connection::Error> {
+        let connection = Connection::connect(context, context_properties, remote)?;
+
+        let registry = connection.core().get_registry()?;
+
+        Ok((connection, Rc::new(registry)))
+    })() {
+        Ok(instance) => instance,
+        Err(e) => return (Ended::Failed(e), pwrx),
+    };
+
+    if reconnecting {
+        sx.send(Event::Reconnected).ok();
+    }
+
+    let stopped = Rc::new(Cell::new(false));
+
     let core = connection.core();
 
     let binds = Rc::new(RefCell::new(HashMap::<u32, BoundGlobal>::new()));
 
-    let _receiver = pwrx.attach(mainloop.loop_(), {
+    // Sequence numbers of the requested syncs and the numbers to report them with
+    let syncs = Rc::new(RefCell::new(Vec::new()));
+
+    let receiver = pwrx.attach(mainloop.loop_(), {
         let sx = sx.clone();
         let mainloop = mainloop.clone();
         let context = context.clone();
+        let stopped = Rc::clone(&stopped);
         let core = core.clone();
         let registry = Rc::clone(&registry);
 
@@ -91,9 +236,11 @@ pub fn pipewire_thread(
         // until they become available in the registry and object listeners can be added on them
         let locals = Rc::new(RefCell::new(HashMap::new()));
         let binds = Rc::clone(&binds);
+        let syncs = Rc::clone(&syncs);
 
         move |msg| match msg {
             Request::Stop => {
+                stopped.set(true);
                 mainloop.quit();
             }
             Request::CreateObject(object_type, factory, props) => {
@@ -198,6 +345,18 @@ pub fn pipewire_thread(
                     object.call(method);
                 }
             }
+            Request::EnumParams(id, param_type) => {
+                if let Some(object) = binds.borrow().get(&id) {
+                    object.enum_params(param_type);
+                }
+            }
+            Request::Sync(number) => match core.sync(0) {
+                Ok(seq) => syncs.borrow_mut().push((seq, number)),
+                Err(e) => {
+                    eprintln!("Failed to sync with the remote: {e}");
+                    sx.send(Event::SyncDone(number)).ok();
+                }
+            },
         }
     });
 
@@ -208,17 +367,6 @@ pub fn pipewire_thread(
         .info({
             let sx = sx.clone();
             move |info| {
-                #[cfg(feature = "pw_v0_3_77")]
-                if REMOTE_VERSION.get().is_none() {
-                    let mut version = info.version().split('.').filter_map(|v| v.parse().ok());
-
-                    if let (Some(major), Some(minor), Some(patch)) =
-                        (version.next(), version.next(), version.next())
-                    {
-                        REMOTE_VERSION.set((major, minor, patch)).ok();
-                    }
-                }
-
                 let infos = Box::new([
                     ("Name", info.name().to_owned()),
                     ("Hostname", info.host_name().to_owned()),
@@ -243,8 +391,7 @@ pub fn pipewire_thread(
             move |id, _, res, msg| {
                 eprintln!("Core: Error on proxy {id}: {res} - {msg}");
 
-                // -EPIPE on the core proxy usually means the remote has been closed
-                if id == 0 && res == -32 {
+                if id == 0 && res == -EPIPE {
                     mainloop.quit();
                 }
             }
@@ -291,6 +438,38 @@ pub fn pipewire_thread(
         })
         .register();
 
+    // The remote replies to a sync after it has processed everything sent before it,
+    // so all of the globals that existed when connecting have been announced by then
+    let initial_sync = match core.sync(0) {
+        Ok(seq) => Some(seq),
+        Err(e) => {
+            eprintln!("Failed to sync with the remote: {e}");
+            sx.send(Event::InitialSyncDone).ok();
+            None
+        }
+    };
+    let _sync_listener = core
+        .add_listener_local()
+        .done({
+            let sx = sx.clone();
+            let syncs = Rc::clone(&syncs);
+            move |id, seq| {
+                if id != pw::core::PW_ID_CORE {
+                    return;
+                }
+
+                if initial_sync == Some(seq) {
+                    sx.send(Event::InitialSyncDone).ok();
+                }
+
+                let mut syncs = syncs.borrow_mut();
+                if let Some(i) = syncs.iter().position(|(s, _)| *s == seq) {
+                    sx.send(Event::SyncDone(syncs.remove(i).1)).ok();
+                }
+            }
+        })
+        .register();
+
     sx.send(Event::ContextProperties(util::dict_to_map(
         context.properties().dict(),
     )))
@@ -298,5 +477,11 @@ pub fn pipewire_thread(
 
     mainloop.run();
 
-    sx.send(Event::Stop).ok();
+    let ended = if stopped.get() {
+        Ended::Stopped
+    } else {
+        Ended::Disconnected
+    };
+
+    (ended, receiver.deattach())
 }
@@ -0,0 +1,229 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::fmt::Debug;
+
+use pipewire::spa::{
+    pod::{ChoiceValue, Value, ValueArray},
+    sys,
+    utils::{Choice, ChoiceEnum},
+};
+
+use super::params::{key_name, value_to_string};
+
+const TYPES: [(u32, &str); 20] = [
+    (sys::SPA_TYPE_None, "None"),
+    (sys::SPA_TYPE_Bool, "Bool"),
+    (sys::SPA_TYPE_Id, "Id"),
+    (sys::SPA_TYPE_Int, "Int"),
+    (sys::SPA_TYPE_Long, "Long"),
+    (sys::SPA_TYPE_Float, "Float"),
+    (sys::SPA_TYPE_Double, "Double"),
+    (sys::SPA_TYPE_String, "String"),
+    (sys::SPA_TYPE_Bytes, "Bytes"),
+    (sys::SPA_TYPE_Rectangle, "Rectangle"),
+    (sys::SPA_TYPE_Fraction, "Fraction"),
+    (sys::SPA_TYPE_Bitmap, "Bitmap"),
+    (sys::SPA_TYPE_Array, "Array"),
+    (sys::SPA_TYPE_Struct, "Struct"),
+    (sys::SPA_TYPE_Object, "Object"),
+    (sys::SPA_TYPE_Sequence, "Sequence"),
+    (sys::SPA_TYPE_Pointer, "Pointer"),
+    (sys::SPA_TYPE_Fd, "Fd"),
+    (sys::SPA_TYPE_Choice, "Choice"),
+    (sys::SPA_TYPE_Pod, "Pod"),
+];
+
+const OBJECT_TYPES: [(u32, &str); 11] = [
+    (sys::SPA_TYPE_OBJECT_PropInfo, "PropInfo"),
+    (sys::SPA_TYPE_OBJECT_Props, "Props"),
+    (sys::SPA_TYPE_OBJECT_Format, "Format"),
+    (sys::SPA_TYPE_OBJECT_ParamBuffers, "Buffers"),
+    (sys::SPA_TYPE_OBJECT_ParamMeta, "Meta"),
+    (sys::SPA_TYPE_OBJECT_ParamIO, "IO"),
+    (sys::SPA_TYPE_OBJECT_ParamProfile, "Profile"),
+    (sys::SPA_TYPE_OBJECT_ParamPortConfig, "PortConfig"),
+    (sys::SPA_TYPE_OBJECT_ParamRoute, "Route"),
+    (sys::SPA_TYPE_OBJECT_Profiler, "Profiler"),
+    (sys::SPA_TYPE_OBJECT_ParamLatency, "Latency"),
+];
+
+fn name_of(names: &[(u32, &'static str)], t: u32) -> Option<&'static str> {
+    names
+        .iter()
+        .find_map(|(n, name)| (*n == t).then_some(*name))
+}
+
+/// The name of a basic SPA type, like `Object` or `Int`
+pub fn type_name(t: u32) -> String {
+    name_of(&TYPES, t).map_or_else(|| format!("Type {t}"), str::to_owned)
+}
+
+/// The size and type from the header of a pod, which precede its body
+pub fn header(bytes: &[u8]) -> Option<(u32, u32)> {
+    let word = |i: usize| {
+        bytes
+            .get(i..i + 4)
+            .and_then(|b| b.try_into().ok())
+            .map(u32::from_ne_bytes)
+    };
+
+    Some((word(0)?, word(4)?))
+}
+
+/// A decoded pod, as a tree of labeled values
+pub struct PodNode {
+    pub label: String,
+    pub value: String,
+    pub children: Vec<PodNode>,
+}
+
+impl PodNode {
+    fn leaf(label: impl Into<String>, value: String) -> Self {
+        Self {
+            label: label.into(),
+            value,
+            children: Vec::new(),
+        }
+    }
+}
+
+fn debug_all<T: Debug>(values: &[T]) -> Vec<String> {
+    values.iter().map(|v| format!("{v:?}")).collect()
+}
+
+// The choice types differ for each value type so this can't be a function
+macro_rules! choice {
+    ($choice:expr) => {{
+        let Choice(flags, choice) = $choice;
+        let leaf = |label: &str, value| PodNode::leaf(label, format!("{value:?}"));
+
+        let (kind, children) = match choice {
+            ChoiceEnum::None(value) => ("None", vec![leaf("value", value)]),
+            ChoiceEnum::Range { default, min, max } => (
+                "Range",
+                vec![leaf("default", default), leaf("min", min), leaf("max", max)],
+            ),
+            ChoiceEnum::Step {
+                default,
+                min,
+                max,
+                step,
+            } => (
+                "Step",
+                vec![
+                    leaf("default", default),
+                    leaf("min", min),
+                    leaf("max", max),
+                    leaf("step", step),
+                ],
+            ),
+            ChoiceEnum::Enum {
+                default,
+                alternatives,
+            } => (
+                "Enum",
+                std::iter::once(leaf("default", default))
+                    .chain(alternatives.iter().map(|a| leaf("alternative", a)))
+                    .collect(),
+            ),
+            ChoiceEnum::Flags { default, flags } => (
+                "Flags",
+                std::iter::once(leaf("default", default))
+                    .chain(flags.iter().map(|f| leaf("flag", f)))
+                    .collect(),
+            ),
+        };
+
+        (format!("Choice {kind} {flags:?}"), children)
+    }};
+}
+
+/// Decodes a value into a tree of its objects, properties, choices and fields
+pub fn tree(label: impl Into<String>, value: &Value) -> PodNode {
+    let label = label.into();
+
+    let (value, children) = match value {
+        Value::Object(object) => {
+            let object_type = name_of(&OBJECT_TYPES, object.type_)
+                .map_or_else(|| object.type_.to_string(), str::to_owned);
+
+            let children = object
+                .properties
+                .iter()
+                .map(|prop| {
+                    let mut key = key_name(object.type_, prop.key);
+                    if !prop.flags.is_empty() {
+                        key = format!("{key} {:?}", prop.flags);
+                    }
+                    tree(key, &prop.value)
+                })
+                .collect();
+
+            (format!("Object {object_type}, id {}", object.id), children)
+        }
+        Value::Struct(fields) => (
+            format!("Struct of {}", fields.len()),
+            fields
+                .iter()
+                .enumerate()
+                .map(|(i, field)| tree(i.to_string(), field))
+                .collect(),
+        ),
+        Value::ValueArray(array) => {
+            let items = match array {
+                ValueArray::None(v) => debug_all(v),
+                ValueArray::Bool(v) => debug_all(v),
+                ValueArray::Id(v) => debug_all(v),
+                ValueArray::Int(v) => debug_all(v),
+                ValueArray::Long(v) => debug_all(v),
+                ValueArray::Float(v) => debug_all(v),
+                ValueArray::Double(v) => debug_all(v),
+                ValueArray::Rectangle(v) => debug_all(v),
+                ValueArray::Fraction(v) => debug_all(v),
+                ValueArray::Fd(v) => debug_all(v),
+            };
+
+            (
+                format!("Array of {}", items.len()),
+                items
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, item)| PodNode::leaf(i.to_string(), item))
+                    .collect(),
+            )
+        }
+        Value::Choice(choice) => match choice {
+            ChoiceValue::Bool(c) => choice!(c),
+            ChoiceValue::Int(c) => choice!(c),
+            ChoiceValue::Long(c) => choice!(c),
+            ChoiceValue::Float(c) => choice!(c),
+            ChoiceValue::Double(c) => choice!(c),
+            ChoiceValue::Id(c) => choice!(c),
+            ChoiceValue::Rectangle(c) => choice!(c),
+            ChoiceValue::Fraction(c) => choice!(c),
+            ChoiceValue::Fd(c) => choice!(c),
+        },
+        Value::Bytes(bytes) => (format!("{} bytes", bytes.len()), Vec::new()),
+        v => (value_to_string(v), Vec::new()),
+    };
+
+    PodNode {
+        label,
+        value,
+        children,
+    }
+}
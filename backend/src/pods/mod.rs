@@ -0,0 +1,145 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+pub mod decode;
+pub mod params;
+pub mod profiler;
+pub mod props;
+
+use pipewire::spa::{pod::deserialize::DeserializeError, sys};
+
+/// How deep containers can be nested in a pod before it's rejected.
+/// Deeper pods would overflow the stack of the recursive deserializers.
+pub const MAX_DEPTH: usize = 32;
+
+/// Why a pod couldn't be parsed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseError {
+    /// The pod, or a pod inside it, is shorter than its header says
+    Truncated,
+    /// Containers are nested deeper than [`MAX_DEPTH`]
+    TooDeep,
+    /// An array or choice has children of size 0, which can't be counted
+    InvalidChildSize,
+    /// A value isn't of the type the parser expected
+    InvalidType,
+    /// A field or property the parser needs is missing
+    Missing,
+    /// The pod is malformed in some other way
+    Malformed,
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Truncated => "The pod is truncated",
+            Self::TooDeep => "The pod is nested too deep",
+            Self::InvalidChildSize => "The pod has an array or choice with children of size 0",
+            Self::InvalidType => "The pod has a value of an unexpected type",
+            Self::Missing => "The pod is missing a required field",
+            Self::Malformed => "The pod is malformed",
+        })
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl<I> From<DeserializeError<I>> for ParseError {
+    fn from(e: DeserializeError<I>) -> Self {
+        match e {
+            DeserializeError::InvalidType | DeserializeError::UnsupportedType => Self::InvalidType,
+            DeserializeError::PropertyMissing => Self::Missing,
+            _ => Self::Malformed,
+        }
+    }
+}
+
+fn round_up(size: usize) -> usize {
+    size.saturating_add(7) & !7
+}
+
+/// Splits a pod into its type and body, and the bytes that follow it
+fn split(bytes: &[u8]) -> Result<(u32, &[u8], &[u8]), ParseError> {
+    let (size, t) = decode::header(bytes).ok_or(ParseError::Truncated)?;
+    let end = usize::try_from(size)
+        .ok()
+        .and_then(|size| size.checked_add(8))
+        .ok_or(ParseError::Truncated)?;
+    let body = bytes.get(8..end).ok_or(ParseError::Truncated)?;
+    let rest = bytes.get(round_up(end)..).unwrap_or_default();
+
+    Ok((t, body, rest))
+}
+
+/// Checks that the bytes of a pod of an array or choice hold whole children
+fn check_children(body: &[u8]) -> Result<(), ParseError> {
+    let (size, _) = decode::header(body).ok_or(ParseError::Truncated)?;
+    if size == 0 && body.len() > 8 {
+        return Err(ParseError::InvalidChildSize);
+    }
+
+    Ok(())
+}
+
+fn check_at(bytes: &[u8], depth: usize) -> Result<(), ParseError> {
+    if depth > MAX_DEPTH {
+        return Err(ParseError::TooDeep);
+    }
+
+    let (t, mut body, _) = split(bytes)?;
+
+    match t {
+        sys::SPA_TYPE_Struct => {
+            while !body.is_empty() {
+                check_at(body, depth + 1)?;
+                body = split(body)?.2;
+            }
+        }
+        sys::SPA_TYPE_Object => {
+            // Object type and ID
+            body = body.get(8..).ok_or(ParseError::Truncated)?;
+            while !body.is_empty() {
+                // Property key and flags
+                body = body.get(8..).ok_or(ParseError::Truncated)?;
+                check_at(body, depth + 1)?;
+                body = split(body)?.2;
+            }
+        }
+        sys::SPA_TYPE_Sequence => {
+            // Unit and padding
+            body = body.get(8..).ok_or(ParseError::Truncated)?;
+            while !body.is_empty() {
+                // Control offset and type
+                body = body.get(8..).ok_or(ParseError::Truncated)?;
+                check_at(body, depth + 1)?;
+                body = split(body)?.2;
+            }
+        }
+        sys::SPA_TYPE_Array => check_children(body)?,
+        // Choice type and flags precede the children
+        sys::SPA_TYPE_Choice => check_children(body.get(8..).ok_or(ParseError::Truncated)?)?,
+        sys::SPA_TYPE_Pod => check_at(body, depth + 1)?,
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// Checks that the sizes in the headers of a pod and the pods inside it are within its bytes
+/// and that it's not nested too deep, so that deserializing it can't panic
+pub fn check(bytes: &[u8]) -> Result<(), ParseError> {
+    check_at(bytes, 0)
+}
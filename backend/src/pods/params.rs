@@ -0,0 +1,730 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+use pipewire::spa::{
+    pod::{deserialize::PodDeserializer, CanonicalFixedSizedPod, ChoiceValue, Value, ValueArray},
+    sys,
+    utils::{Choice, ChoiceEnum, Fraction, Rectangle},
+};
+
+use super::ParseError;
+
+const PROPS_KEYS: [(u32, &str); 18] = [
+    (sys::SPA_PROP_device, "device"),
+    (sys::SPA_PROP_deviceName, "deviceName"),
+    (sys::SPA_PROP_rate, "rate"),
+    (sys::SPA_PROP_quality, "quality"),
+    (sys::SPA_PROP_volume, "volume"),
+    (sys::SPA_PROP_mute, "mute"),
+    (sys::SPA_PROP_channelVolumes, "channelVolumes"),
+    (sys::SPA_PROP_channelMap, "channelMap"),
+    (sys::SPA_PROP_monitorMute, "monitorMute"),
+    (sys::SPA_PROP_monitorVolumes, "monitorVolumes"),
+    (sys::SPA_PROP_softMute, "softMute"),
+    (sys::SPA_PROP_softVolumes, "softVolumes"),
+    (sys::SPA_PROP_latencyOffsetNsec, "latencyOffsetNsec"),
+    (sys::SPA_PROP_volumeBase, "volumeBase"),
+    (sys::SPA_PROP_volumeStep, "volumeStep"),
+    (sys::SPA_PROP_iec958Codecs, "iec958Codecs"),
+    (sys::SPA_PROP_bluetoothAudioCodec, "bluetoothAudioCodec"),
+    (sys::SPA_PROP_params, "params"),
+];
+
+const FORMAT_KEYS: [(u32, &str); 10] = [
+    (sys::SPA_FORMAT_mediaType, "mediaType"),
+    (sys::SPA_FORMAT_mediaSubtype, "mediaSubtype"),
+    (sys::SPA_FORMAT_AUDIO_format, "format"),
+    (sys::SPA_FORMAT_AUDIO_flags, "flags"),
+    (sys::SPA_FORMAT_AUDIO_rate, "rate"),
+    (sys::SPA_FORMAT_AUDIO_channels, "channels"),
+    (sys::SPA_FORMAT_AUDIO_position, "position"),
+    (sys::SPA_FORMAT_VIDEO_format, "videoFormat"),
+    (sys::SPA_FORMAT_VIDEO_size, "size"),
+    (sys::SPA_FORMAT_VIDEO_framerate, "framerate"),
+];
+
+const LATENCY_KEYS: [(u32, &str); 7] = [
+    (sys::SPA_PARAM_LATENCY_direction, "direction"),
+    (sys::SPA_PARAM_LATENCY_minQuantum, "minQuantum"),
+    (sys::SPA_PARAM_LATENCY_maxQuantum, "maxQuantum"),
+    (sys::SPA_PARAM_LATENCY_minRate, "minRate"),
+    (sys::SPA_PARAM_LATENCY_maxRate, "maxRate"),
+    (sys::SPA_PARAM_LATENCY_minNs, "minNs"),
+    (sys::SPA_PARAM_LATENCY_maxNs, "maxNs"),
+];
+
+const ROUTE_KEYS: [(u32, &str); 13] = [
+    (sys::SPA_PARAM_ROUTE_index, "index"),
+    (sys::SPA_PARAM_ROUTE_direction, "direction"),
+    (sys::SPA_PARAM_ROUTE_device, "device"),
+    (sys::SPA_PARAM_ROUTE_name, "name"),
+    (sys::SPA_PARAM_ROUTE_description, "description"),
+    (sys::SPA_PARAM_ROUTE_priority, "priority"),
+    (sys::SPA_PARAM_ROUTE_available, "available"),
+    (sys::SPA_PARAM_ROUTE_info, "info"),
+    (sys::SPA_PARAM_ROUTE_profiles, "profiles"),
+    (sys::SPA_PARAM_ROUTE_props, "props"),
+    (sys::SPA_PARAM_ROUTE_devices, "devices"),
+    (sys::SPA_PARAM_ROUTE_profile, "profile"),
+    (sys::SPA_PARAM_ROUTE_save, "save"),
+];
+
+const PROFILE_KEYS: [(u32, &str); 8] = [
+    (sys::SPA_PARAM_PROFILE_index, "index"),
+    (sys::SPA_PARAM_PROFILE_name, "name"),
+    (sys::SPA_PARAM_PROFILE_description, "description"),
+    (sys::SPA_PARAM_PROFILE_priority, "priority"),
+    (sys::SPA_PARAM_PROFILE_available, "available"),
+    (sys::SPA_PARAM_PROFILE_info, "info"),
+    (sys::SPA_PARAM_PROFILE_classes, "classes"),
+    (sys::SPA_PARAM_PROFILE_save, "save"),
+];
+
+const PORT_CONFIG_KEYS: [(u32, &str); 5] = [
+    (sys::SPA_PARAM_PORT_CONFIG_direction, "direction"),
+    (sys::SPA_PARAM_PORT_CONFIG_mode, "mode"),
+    (sys::SPA_PARAM_PORT_CONFIG_monitor, "monitor"),
+    (sys::SPA_PARAM_PORT_CONFIG_control, "control"),
+    (sys::SPA_PARAM_PORT_CONFIG_format, "format"),
+];
+
+/// Decodes a param received from [`Event::GlobalParam`](crate::Event::GlobalParam)
+pub fn parse(bytes: &[u8]) -> Result<Value, ParseError> {
+    super::check(bytes)?;
+
+    PodDeserializer::deserialize_any_from(bytes)
+        .map(|(_, value)| value)
+        .map_err(ParseError::from)
+}
+
+/// Like [`parse`], for when the reason it failed doesn't matter
+pub fn deserialize(bytes: &[u8]) -> Option<Value> {
+    parse(bytes).ok()
+}
+
+/// The known properties of objects of type `object_type` and their names
+pub fn keys(object_type: u32) -> &'static [(u32, &'static str)] {
+    match object_type {
+        sys::SPA_TYPE_OBJECT_Props => &PROPS_KEYS,
+        sys::SPA_TYPE_OBJECT_Format => &FORMAT_KEYS,
+        sys::SPA_TYPE_OBJECT_ParamLatency => &LATENCY_KEYS,
+        sys::SPA_TYPE_OBJECT_ParamRoute => &ROUTE_KEYS,
+        sys::SPA_TYPE_OBJECT_ParamProfile => &PROFILE_KEYS,
+        sys::SPA_TYPE_OBJECT_ParamPortConfig => &PORT_CONFIG_KEYS,
+        _ => &[],
+    }
+}
+
+/// The name of a property of an object of type `object_type`
+pub fn key_name(object_type: u32, key: u32) -> String {
+    keys(object_type)
+        .iter()
+        .find(|(k, _)| *k == key)
+        .map_or_else(|| key.to_string(), |(_, name)| (*name).to_owned())
+}
+
+/// Formats a value that isn't a container
+pub fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::None => String::from("None"),
+        Value::Bool(b) => b.to_string(),
+        Value::Id(id) => format!("Id {}", id.0),
+        Value::Int(i) => i.to_string(),
+        Value::Long(l) => l.to_string(),
+        Value::Float(f) => f.to_string(),
+        Value::Double(d) => d.to_string(),
+        Value::String(s) => s.clone(),
+        Value::Fraction(f) => format!("{}/{}", f.num, f.denom),
+        Value::Rectangle(r) => format!("{}x{}", r.width, r.height),
+        v => format!("{v:?}"),
+    }
+}
+
+/// Flattens a param to a list of paths to its values, like `params.api.alsa.headroom`
+pub fn flatten(value: &Value) -> Vec<(String, String)> {
+    fn join(path: &str, key: &str) -> String {
+        if path.is_empty() {
+            key.to_owned()
+        } else {
+            format!("{path}.{key}")
+        }
+    }
+
+    fn flatten_into(value: &Value, path: &str, out: &mut Vec<(String, String)>) {
+        match value {
+            Value::Object(object) => {
+                for prop in &object.properties {
+                    let key = key_name(object.type_, prop.key);
+                    let path = join(path, &key);
+
+                    // Props store settings as a struct of key-value pairs
+                    let is_params = object.type_ == sys::SPA_TYPE_OBJECT_Props
+                        && prop.key == sys::SPA_PROP_params;
+                    if let (true, Value::Struct(fields)) = (is_params, &prop.value) {
+                        for pair in fields.chunks(2) {
+                            if let [Value::String(k), v] = pair {
+                                flatten_into(v, &join(&path, k), out);
+                            }
+                        }
+                        continue;
+                    }
+
+                    flatten_into(&prop.value, &path, out);
+                }
+            }
+            Value::Struct(fields) => {
+                for (i, field) in fields.iter().enumerate() {
+                    flatten_into(field, &join(path, &i.to_string()), out);
+                }
+            }
+            v => out.push((path.to_owned(), value_to_string(v))),
+        }
+    }
+
+    let mut out = Vec::new();
+    flatten_into(value, "", &mut out);
+    out
+}
+
+/// Size in bytes of a sample of an SPA audio format
+pub const fn sample_size(format: u32) -> Option<u32> {
+    Some(match format {
+        sys::SPA_AUDIO_FORMAT_S8
+        | sys::SPA_AUDIO_FORMAT_U8
+        | sys::SPA_AUDIO_FORMAT_ULAW
+        | sys::SPA_AUDIO_FORMAT_ALAW
+        | sys::SPA_AUDIO_FORMAT_S8P
+        | sys::SPA_AUDIO_FORMAT_U8P => 1,
+        sys::SPA_AUDIO_FORMAT_S16_LE
+        | sys::SPA_AUDIO_FORMAT_S16_BE
+        | sys::SPA_AUDIO_FORMAT_U16_LE
+        | sys::SPA_AUDIO_FORMAT_U16_BE
+        | sys::SPA_AUDIO_FORMAT_S16P => 2,
+        sys::SPA_AUDIO_FORMAT_S24_LE
+        | sys::SPA_AUDIO_FORMAT_S24_BE
+        | sys::SPA_AUDIO_FORMAT_U24_LE
+        | sys::SPA_AUDIO_FORMAT_U24_BE
+        | sys::SPA_AUDIO_FORMAT_S20_LE
+        | sys::SPA_AUDIO_FORMAT_S20_BE
+        | sys::SPA_AUDIO_FORMAT_U20_LE
+        | sys::SPA_AUDIO_FORMAT_U20_BE
+        | sys::SPA_AUDIO_FORMAT_S18_LE
+        | sys::SPA_AUDIO_FORMAT_S18_BE
+        | sys::SPA_AUDIO_FORMAT_U18_LE
+        | sys::SPA_AUDIO_FORMAT_U18_BE
+        | sys::SPA_AUDIO_FORMAT_S24P => 3,
+        sys::SPA_AUDIO_FORMAT_S24_32_LE
+        | sys::SPA_AUDIO_FORMAT_S24_32_BE
+        | sys::SPA_AUDIO_FORMAT_U24_32_LE
+        | sys::SPA_AUDIO_FORMAT_U24_32_BE
+        | sys::SPA_AUDIO_FORMAT_S32_LE
+        | sys::SPA_AUDIO_FORMAT_S32_BE
+        | sys::SPA_AUDIO_FORMAT_U32_LE
+        | sys::SPA_AUDIO_FORMAT_U32_BE
+        | sys::SPA_AUDIO_FORMAT_F32_LE
+        | sys::SPA_AUDIO_FORMAT_F32_BE
+        | sys::SPA_AUDIO_FORMAT_S24_32P
+        | sys::SPA_AUDIO_FORMAT_S32P
+        | sys::SPA_AUDIO_FORMAT_F32P => 4,
+        sys::SPA_AUDIO_FORMAT_F64_LE
+        | sys::SPA_AUDIO_FORMAT_F64_BE
+        | sys::SPA_AUDIO_FORMAT_F64P => 8,
+        _ => return None,
+    })
+}
+
+/// Raw audio format negotiated by a node
+pub struct AudioFormat {
+    pub format: u32,
+    pub rate: u32,
+    pub channels: u32,
+}
+
+impl AudioFormat {
+    /// Extracts the format from a Format param, if it's a raw audio one
+    pub fn from_param(value: &Value) -> Option<Self> {
+        let Value::Object(object) = value else {
+            return None;
+        };
+        if object.type_ != sys::SPA_TYPE_OBJECT_Format {
+            return None;
+        }
+
+        let (mut format, mut rate, mut channels) = (None, None, None);
+        for prop in &object.properties {
+            match (prop.key, &prop.value) {
+                (sys::SPA_FORMAT_AUDIO_format, Value::Id(id)) => format = Some(id.0),
+                (sys::SPA_FORMAT_AUDIO_rate, Value::Int(i)) => rate = u32::try_from(*i).ok(),
+                (sys::SPA_FORMAT_AUDIO_channels, Value::Int(i)) => {
+                    channels = u32::try_from(*i).ok();
+                }
+                _ => {}
+            }
+        }
+
+        Some(Self {
+            format: format?,
+            rate: rate?,
+            channels: channels?,
+        })
+    }
+
+    /// Bytes per second of audio in this format
+    pub fn bandwidth(&self) -> Option<u64> {
+        Some(u64::from(self.rate) * u64::from(self.channels) * u64::from(sample_size(self.format)?))
+    }
+}
+
+/// How an adapter node presents the ports of the node it wraps
+pub struct PortConfig {
+    pub input: bool,
+    pub mode: &'static str,
+}
+
+impl PortConfig {
+    /// Extracts the direction and mode from a PortConfig param
+    pub fn from_param(value: &Value) -> Option<Self> {
+        let Value::Object(object) = value else {
+            return None;
+        };
+        if object.type_ != sys::SPA_TYPE_OBJECT_ParamPortConfig {
+            return None;
+        }
+
+        let (mut input, mut mode) = (None, None);
+        for prop in &object.properties {
+            match (prop.key, &prop.value) {
+                (sys::SPA_PARAM_PORT_CONFIG_direction, Value::Id(id)) => {
+                    input = Some(id.0 == sys::SPA_DIRECTION_INPUT);
+                }
+                (sys::SPA_PARAM_PORT_CONFIG_mode, Value::Id(id)) => {
+                    mode = Some(match id.0 {
+                        sys::SPA_PARAM_PORT_CONFIG_MODE_passthrough => "Passthrough",
+                        sys::SPA_PARAM_PORT_CONFIG_MODE_convert => "Convert",
+                        sys::SPA_PARAM_PORT_CONFIG_MODE_dsp => "DSP",
+                        _ => "None",
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        Some(Self {
+            input: input?,
+            mode: mode?,
+        })
+    }
+}
+
+/// Volume controls of a node, from its Props param
+#[derive(Clone, PartialEq)]
+pub struct Volume {
+    pub volume: Option<f32>,
+    pub channel_volumes: Vec<f32>,
+    pub mute: Option<bool>,
+}
+
+impl Volume {
+    /// Extracts the volume controls from a Props param, if it has any
+    pub fn from_props(value: &Value) -> Option<Self> {
+        let Value::Object(object) = value else {
+            return None;
+        };
+        if object.type_ != sys::SPA_TYPE_OBJECT_Props {
+            return None;
+        }
+
+        let mut volume = Self {
+            volume: None,
+            channel_volumes: Vec::new(),
+            mute: None,
+        };
+        for prop in &object.properties {
+            match (prop.key, &prop.value) {
+                (sys::SPA_PROP_volume, Value::Float(f)) => volume.volume = Some(*f),
+                (sys::SPA_PROP_channelVolumes, Value::ValueArray(ValueArray::Float(v))) => {
+                    volume.channel_volumes = v.clone();
+                }
+                (sys::SPA_PROP_mute, Value::Bool(b)) => volume.mute = Some(*b),
+                _ => {}
+            }
+        }
+
+        (volume.volume.is_some() || !volume.channel_volumes.is_empty() || volume.mute.is_some())
+            .then_some(volume)
+    }
+
+    /// Writes the controls to a Props param, to reflect changes before the node reports them
+    pub fn apply(&self, value: &mut Value) {
+        let Value::Object(object) = value else {
+            return;
+        };
+
+        for prop in &mut object.properties {
+            match (prop.key, &mut prop.value) {
+                (sys::SPA_PROP_volume, Value::Float(f)) => {
+                    if let Some(volume) = self.volume {
+                        *f = volume;
+                    }
+                }
+                (sys::SPA_PROP_channelVolumes, Value::ValueArray(ValueArray::Float(v))) => {
+                    v.clone_from(&self.channel_volumes);
+                }
+                (sys::SPA_PROP_mute, Value::Bool(b)) => {
+                    if let Some(mute) = self.mute {
+                        *b = mute;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Finds the value of a key of the `params` struct of a Props param, like `resample.quality`
+pub fn props_param_mut<'a>(value: &'a mut Value, key: &str) -> Option<&'a mut Value> {
+    let Value::Object(object) = value else {
+        return None;
+    };
+    if object.type_ != sys::SPA_TYPE_OBJECT_Props {
+        return None;
+    }
+
+    let Value::Struct(fields) = &mut object
+        .properties
+        .iter_mut()
+        .find(|prop| prop.key == sys::SPA_PROP_params)?
+        .value
+    else {
+        return None;
+    };
+
+    fields.chunks_mut(2).find_map(|pair| match pair {
+        [Value::String(k), v] if k == key => Some(v),
+        _ => None,
+    })
+}
+
+/// Finds the Props object of a Route param, which holds the volume controls of the route
+pub fn route_props_mut(value: &mut Value) -> Option<&mut Value> {
+    let Value::Object(object) = value else {
+        return None;
+    };
+    if object.type_ != sys::SPA_TYPE_OBJECT_ParamRoute {
+        return None;
+    }
+
+    object
+        .properties
+        .iter_mut()
+        .find(|prop| prop.key == sys::SPA_PARAM_ROUTE_props)
+        .map(|prop| &mut prop.value)
+}
+
+/// An entry of a device's EnumProfile or Profile params
+pub struct Profile {
+    pub index: i32,
+    pub name: String,
+    pub description: Option<String>,
+    pub priority: i32,
+    pub available: bool,
+}
+
+impl Profile {
+    pub fn from_param(value: &Value) -> Option<Self> {
+        let Value::Object(object) = value else {
+            return None;
+        };
+        if object.type_ != sys::SPA_TYPE_OBJECT_ParamProfile {
+            return None;
+        }
+
+        let (mut index, mut name) = (None, None);
+        let mut description = None;
+        let mut priority = 0;
+        let mut available = true;
+        for prop in &object.properties {
+            match (prop.key, &prop.value) {
+                (sys::SPA_PARAM_PROFILE_index, Value::Int(i)) => index = Some(*i),
+                (sys::SPA_PARAM_PROFILE_name, Value::String(s)) => name = Some(s.clone()),
+                (sys::SPA_PARAM_PROFILE_description, Value::String(s)) => {
+                    description = Some(s.clone());
+                }
+                (sys::SPA_PARAM_PROFILE_priority, Value::Int(i)) => priority = *i,
+                (sys::SPA_PARAM_PROFILE_available, Value::Id(id)) => {
+                    available = id.0 != sys::SPA_PARAM_AVAILABILITY_no;
+                }
+                _ => {}
+            }
+        }
+
+        Some(Self {
+            index: index?,
+            name: name?,
+            description,
+            priority,
+            available,
+        })
+    }
+}
+
+/// An entry of a device's EnumRoute or Route params.
+/// Routes are the ports and jacks of a device, like speakers and headphones.
+pub struct Route {
+    pub index: i32,
+    pub input: bool,
+    pub name: String,
+    pub description: Option<String>,
+    pub priority: i32,
+    pub available: bool,
+    /// The device of the active profile that the route is used by, only present in Route params
+    pub device: Option<i32>,
+    /// The profiles and devices the route can be used with, only present in EnumRoute params
+    pub profiles: Vec<i32>,
+    pub devices: Vec<i32>,
+    /// The volume controls of the route, only present in Route params
+    pub volume: Option<Volume>,
+}
+
+impl Route {
+    pub fn from_param(value: &Value) -> Option<Self> {
+        let Value::Object(object) = value else {
+            return None;
+        };
+        if object.type_ != sys::SPA_TYPE_OBJECT_ParamRoute {
+            return None;
+        }
+
+        let (mut index, mut input, mut name) = (None, None, None);
+        let mut route = Self {
+            index: 0,
+            input: false,
+            name: String::new(),
+            description: None,
+            priority: 0,
+            available: true,
+            device: None,
+            profiles: Vec::new(),
+            devices: Vec::new(),
+            volume: None,
+        };
+        for prop in &object.properties {
+            match (prop.key, &prop.value) {
+                (sys::SPA_PARAM_ROUTE_index, Value::Int(i)) => index = Some(*i),
+                (sys::SPA_PARAM_ROUTE_direction, Value::Id(id)) => {
+                    input = Some(id.0 == sys::SPA_DIRECTION_INPUT);
+                }
+                (sys::SPA_PARAM_ROUTE_name, Value::String(s)) => name = Some(s.clone()),
+                (sys::SPA_PARAM_ROUTE_description, Value::String(s)) => {
+                    route.description = Some(s.clone());
+                }
+                (sys::SPA_PARAM_ROUTE_priority, Value::Int(i)) => route.priority = *i,
+                (sys::SPA_PARAM_ROUTE_available, Value::Id(id)) => {
+                    route.available = id.0 != sys::SPA_PARAM_AVAILABILITY_no;
+                }
+                (sys::SPA_PARAM_ROUTE_device, Value::Int(i)) => route.device = Some(*i),
+                (sys::SPA_PARAM_ROUTE_profiles, Value::ValueArray(ValueArray::Int(v))) => {
+                    route.profiles.clone_from(v);
+                }
+                (sys::SPA_PARAM_ROUTE_devices, Value::ValueArray(ValueArray::Int(v))) => {
+                    route.devices.clone_from(v);
+                }
+                (sys::SPA_PARAM_ROUTE_props, props) => route.volume = Volume::from_props(props),
+                _ => {}
+            }
+        }
+
+        route.index = index?;
+        route.input = input?;
+        route.name = name?;
+
+        Some(route)
+    }
+}
+
+const MEDIA_TYPES: [(u32, &str); 6] = [
+    (sys::SPA_MEDIA_TYPE_audio, "Audio"),
+    (sys::SPA_MEDIA_TYPE_video, "Video"),
+    (sys::SPA_MEDIA_TYPE_image, "Image"),
+    (sys::SPA_MEDIA_TYPE_binary, "Binary"),
+    (sys::SPA_MEDIA_TYPE_stream, "Stream"),
+    (sys::SPA_MEDIA_TYPE_application, "Application"),
+];
+
+const MEDIA_SUBTYPES: [(u32, &str); 12] = [
+    (sys::SPA_MEDIA_SUBTYPE_raw, "Raw"),
+    (sys::SPA_MEDIA_SUBTYPE_dsp, "DSP"),
+    (sys::SPA_MEDIA_SUBTYPE_iec958, "IEC958"),
+    (sys::SPA_MEDIA_SUBTYPE_dsd, "DSD"),
+    (sys::SPA_MEDIA_SUBTYPE_mp3, "MP3"),
+    (sys::SPA_MEDIA_SUBTYPE_aac, "AAC"),
+    (sys::SPA_MEDIA_SUBTYPE_opus, "Opus"),
+    (sys::SPA_MEDIA_SUBTYPE_flac, "FLAC"),
+    (sys::SPA_MEDIA_SUBTYPE_h264, "H.264"),
+    (sys::SPA_MEDIA_SUBTYPE_mjpg, "MJPEG"),
+    (sys::SPA_MEDIA_SUBTYPE_midi, "MIDI"),
+    (sys::SPA_MEDIA_SUBTYPE_control, "Control"),
+];
+
+const AUDIO_FORMATS: [(u32, &str); 14] = [
+    (sys::SPA_AUDIO_FORMAT_S8, "S8"),
+    (sys::SPA_AUDIO_FORMAT_U8, "U8"),
+    (sys::SPA_AUDIO_FORMAT_S16_LE, "S16LE"),
+    (sys::SPA_AUDIO_FORMAT_S16_BE, "S16BE"),
+    (sys::SPA_AUDIO_FORMAT_S24_LE, "S24LE"),
+    (sys::SPA_AUDIO_FORMAT_S24_32_LE, "S24_32LE"),
+    (sys::SPA_AUDIO_FORMAT_S32_LE, "S32LE"),
+    (sys::SPA_AUDIO_FORMAT_F32_LE, "F32LE"),
+    (sys::SPA_AUDIO_FORMAT_F64_LE, "F64LE"),
+    (sys::SPA_AUDIO_FORMAT_S16P, "S16P"),
+    (sys::SPA_AUDIO_FORMAT_S24P, "S24P"),
+    (sys::SPA_AUDIO_FORMAT_S32P, "S32P"),
+    (sys::SPA_AUDIO_FORMAT_F32P, "F32P"),
+    (sys::SPA_AUDIO_FORMAT_F64P, "F64P"),
+];
+
+const VIDEO_FORMATS: [(u32, &str); 14] = [
+    (sys::SPA_VIDEO_FORMAT_I420, "I420"),
+    (sys::SPA_VIDEO_FORMAT_YV12, "YV12"),
+    (sys::SPA_VIDEO_FORMAT_YUY2, "YUY2"),
+    (sys::SPA_VIDEO_FORMAT_UYVY, "UYVY"),
+    (sys::SPA_VIDEO_FORMAT_NV12, "NV12"),
+    (sys::SPA_VIDEO_FORMAT_RGBx, "RGBx"),
+    (sys::SPA_VIDEO_FORMAT_BGRx, "BGRx"),
+    (sys::SPA_VIDEO_FORMAT_xRGB, "xRGB"),
+    (sys::SPA_VIDEO_FORMAT_xBGR, "xBGR"),
+    (sys::SPA_VIDEO_FORMAT_RGBA, "RGBA"),
+    (sys::SPA_VIDEO_FORMAT_BGRA, "BGRA"),
+    (sys::SPA_VIDEO_FORMAT_RGB, "RGB"),
+    (sys::SPA_VIDEO_FORMAT_BGR, "BGR"),
+    (sys::SPA_VIDEO_FORMAT_GRAY8, "GRAY8"),
+];
+
+fn id_name(names: &[(u32, &str)], id: u32) -> String {
+    names
+        .iter()
+        .find(|(n, _)| *n == id)
+        .map_or_else(|| id.to_string(), |(_, name)| (*name).to_owned())
+}
+
+/// Lists the possible values of a choice, like `44100, 48000` or `1 - 64`
+fn choice_to_string<T: CanonicalFixedSizedPod + PartialEq>(
+    choice: &Choice<T>,
+    to_string: impl Fn(&T) -> String,
+) -> String {
+    let join = |default: &T, values: &[T]| {
+        std::iter::once(default)
+            .chain(values.iter().filter(|v| *v != default))
+            .map(&to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    match &choice.1 {
+        ChoiceEnum::None(value) => to_string(value),
+        ChoiceEnum::Range { min, max, .. } => format!("{} - {}", to_string(min), to_string(max)),
+        ChoiceEnum::Step { min, max, step, .. } => format!(
+            "{} - {} in steps of {}",
+            to_string(min),
+            to_string(max),
+            to_string(step)
+        ),
+        ChoiceEnum::Enum {
+            default,
+            alternatives,
+        } => join(default, alternatives),
+        ChoiceEnum::Flags { default, flags } => join(default, flags),
+    }
+}
+
+/// Formats a value or the possible values of a choice. IDs are named with `names`.
+fn possible_values(value: &Value, names: &[(u32, &str)]) -> String {
+    let rectangle = |r: &Rectangle| format!("{}x{}", r.width, r.height);
+    let fraction = |f: &Fraction| format!("{}/{}", f.num, f.denom);
+
+    match value {
+        Value::Id(id) => id_name(names, id.0),
+        Value::Choice(ChoiceValue::Int(choice)) => choice_to_string(choice, i32::to_string),
+        Value::Choice(ChoiceValue::Id(choice)) => {
+            choice_to_string(choice, |id| id_name(names, id.0))
+        }
+        Value::Choice(ChoiceValue::Rectangle(choice)) => choice_to_string(choice, rectangle),
+        Value::Choice(ChoiceValue::Fraction(choice)) => choice_to_string(choice, fraction),
+        v => value_to_string(v),
+    }
+}
+
+/// The fields of a Format or EnumFormat param, as readable text.
+/// The fields of EnumFormat params list all of the values they support.
+pub struct FormatSummary {
+    pub media_type: String,
+    pub media_subtype: String,
+    pub format: Option<String>,
+    pub rate: Option<String>,
+    pub channels: Option<String>,
+    pub size: Option<String>,
+    pub framerate: Option<String>,
+}
+
+impl FormatSummary {
+    pub fn from_param(value: &Value) -> Option<Self> {
+        let Value::Object(object) = value else {
+            return None;
+        };
+        if object.type_ != sys::SPA_TYPE_OBJECT_Format {
+            return None;
+        }
+
+        let mut summary = Self {
+            media_type: String::new(),
+            media_subtype: String::new(),
+            format: None,
+            rate: None,
+            channels: None,
+            size: None,
+            framerate: None,
+        };
+
+        for prop in &object.properties {
+            let value = &prop.value;
+            match prop.key {
+                sys::SPA_FORMAT_mediaType => {
+                    summary.media_type = possible_values(value, &MEDIA_TYPES);
+                }
+                sys::SPA_FORMAT_mediaSubtype => {
+                    summary.media_subtype = possible_values(value, &MEDIA_SUBTYPES);
+                }
+                sys::SPA_FORMAT_AUDIO_format => {
+                    summary.format = Some(possible_values(value, &AUDIO_FORMATS));
+                }
+                sys::SPA_FORMAT_VIDEO_format => {
+                    summary.format = Some(possible_values(value, &VIDEO_FORMATS));
+                }
+                sys::SPA_FORMAT_AUDIO_rate => summary.rate = Some(possible_values(value, &[])),
+                sys::SPA_FORMAT_AUDIO_channels => {
+                    summary.channels = Some(possible_values(value, &[]));
+                }
+                sys::SPA_FORMAT_VIDEO_size => summary.size = Some(possible_values(value, &[])),
+                sys::SPA_FORMAT_VIDEO_framerate => {
+                    summary.framerate = Some(possible_values(value, &[]));
+                }
+                _ => {}
+            }
+        }
+
+        Some(summary)
+    }
+}
@@ -16,6 +16,26 @@
 
 use pipewire::spa::{pod::deserialize::*, utils::Fraction};
 
+use super::ParseError;
+
+// Fields missing from malformed pods are reported instead of panicking
+fn field<'de, T: PodDeserialize<'de>>(
+    struct_deserializer: &mut StructPodDeserializer<'de>,
+) -> Result<T, DeserializeError<&'de [u8]>> {
+    struct_deserializer
+        .deserialize_field()?
+        .ok_or(DeserializeError::PropertyMissing)
+}
+
+fn property<'de, T: PodDeserialize<'de>>(
+    object_deserializer: &mut ObjectPodDeserializer<'de>,
+) -> Result<T, DeserializeError<&'de [u8]>> {
+    object_deserializer
+        .deserialize_property()?
+        .map(|(value, _, _)| value)
+        .ok_or(DeserializeError::PropertyMissing)
+}
+
 #[derive(Debug)]
 pub struct Info {
     pub counter: i64,
@@ -43,11 +63,11 @@ impl<'de> PodDeserialize<'de> for Info {
                 struct_deserializer: &mut StructPodDeserializer<'de>,
             ) -> Result<Self::Value, DeserializeError<&'de [u8]>> {
                 Ok(Info {
-                    counter: struct_deserializer.deserialize_field()?.unwrap(),
-                    cpu_load_fast: struct_deserializer.deserialize_field()?.unwrap(),
-                    cpu_load_medium: struct_deserializer.deserialize_field()?.unwrap(),
-                    cpu_load_slow: struct_deserializer.deserialize_field()?.unwrap(),
-                    xrun_count: struct_deserializer.deserialize_field()?.unwrap(),
+                    counter: field(struct_deserializer)?,
+                    cpu_load_fast: field(struct_deserializer)?,
+                    cpu_load_medium: field(struct_deserializer)?,
+                    cpu_load_slow: field(struct_deserializer)?,
+                    xrun_count: field(struct_deserializer)?,
                 })
             }
         }
@@ -89,16 +109,16 @@ impl<'de> PodDeserialize<'de> for Clock {
                 struct_deserializer: &mut StructPodDeserializer<'de>,
             ) -> Result<Self::Value, DeserializeError<&'de [u8]>> {
                 Ok(Clock {
-                    flags: struct_deserializer.deserialize_field()?.unwrap(),
-                    id: struct_deserializer.deserialize_field()?.unwrap(),
-                    name: struct_deserializer.deserialize_field()?.unwrap(),
-                    nsec: struct_deserializer.deserialize_field()?.unwrap(),
-                    rate: struct_deserializer.deserialize_field()?.unwrap(),
-                    position: struct_deserializer.deserialize_field()?.unwrap(),
-                    duration: struct_deserializer.deserialize_field()?.unwrap(),
-                    delay: struct_deserializer.deserialize_field()?.unwrap(),
-                    rate_diff: struct_deserializer.deserialize_field()?.unwrap(),
-                    next_nsec: struct_deserializer.deserialize_field()?.unwrap(),
+                    flags: field(struct_deserializer)?,
+                    id: field(struct_deserializer)?,
+                    name: field(struct_deserializer)?,
+                    nsec: field(struct_deserializer)?,
+                    rate: field(struct_deserializer)?,
+                    position: field(struct_deserializer)?,
+                    duration: field(struct_deserializer)?,
+                    delay: field(struct_deserializer)?,
+                    rate_diff: field(struct_deserializer)?,
+                    next_nsec: field(struct_deserializer)?,
                     transport_state: struct_deserializer.deserialize_field()?,
                 })
             }
@@ -139,14 +159,14 @@ impl<'de> PodDeserialize<'de> for NodeBlock {
                 struct_deserializer: &mut StructPodDeserializer<'de>,
             ) -> Result<Self::Value, DeserializeError<&'de [u8]>> {
                 Ok(NodeBlock {
-                    id: struct_deserializer.deserialize_field()?.unwrap(),
-                    name: struct_deserializer.deserialize_field()?.unwrap(),
-                    prev_signal: struct_deserializer.deserialize_field()?.unwrap(),
-                    signal: struct_deserializer.deserialize_field()?.unwrap(),
-                    awake: struct_deserializer.deserialize_field()?.unwrap(),
-                    finish: struct_deserializer.deserialize_field()?.unwrap(),
-                    status: struct_deserializer.deserialize_field()?.unwrap(),
-                    latency: struct_deserializer.deserialize_field()?.unwrap(),
+                    id: field(struct_deserializer)?,
+                    name: field(struct_deserializer)?,
+                    prev_signal: field(struct_deserializer)?,
+                    signal: field(struct_deserializer)?,
+                    awake: field(struct_deserializer)?,
+                    finish: field(struct_deserializer)?,
+                    status: field(struct_deserializer)?,
+                    latency: field(struct_deserializer)?,
                     xrun_count: struct_deserializer.deserialize_field()?,
                 })
             }
@@ -181,9 +201,9 @@ impl<'de> PodDeserialize<'de> for Profiling {
                 &self,
                 object_deserializer: &mut ObjectPodDeserializer<'de>,
             ) -> Result<Self::Value, DeserializeError<&'de [u8]>> {
-                let info: Info = object_deserializer.deserialize_property()?.unwrap().0;
-                let clock: Clock = object_deserializer.deserialize_property()?.unwrap().0;
-                let driver: NodeBlock = object_deserializer.deserialize_property()?.unwrap().0;
+                let info: Info = property(object_deserializer)?;
+                let clock: Clock = property(object_deserializer)?;
+                let driver: NodeBlock = property(object_deserializer)?;
 
                 let mut followers = Vec::new();
 
@@ -237,3 +257,12 @@ impl<'de> PodDeserialize<'de> for Profilings {
         deserializer.deserialize_struct(ProfilerVisitor)
     }
 }
+
+/// Parses the pod a profiler sends with its samples
+pub fn parse(bytes: &[u8]) -> Result<Vec<Profiling>, ParseError> {
+    super::check(bytes)?;
+
+    PodDeserializer::deserialize_from::<Profilings>(bytes)
+        .map(|(_, profilings)| profilings.0)
+        .map_err(ParseError::from)
+}
@@ -0,0 +1,203 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::io::Cursor;
+
+use pipewire::spa::{
+    pod::{serialize::PodSerializer, Object, Property, PropertyFlags, Value, ValueArray},
+    sys,
+    utils::Id,
+};
+
+use super::params::Volume;
+
+/// Value of an entry of the `params` struct of a Props object
+pub enum ParamValue {
+    Int(i32),
+    Bool(bool),
+    String(String),
+}
+
+impl From<ParamValue> for Value {
+    fn from(value: ParamValue) -> Self {
+        match value {
+            ParamValue::Int(i) => Self::Int(i),
+            ParamValue::Bool(b) => Self::Bool(b),
+            ParamValue::String(s) => Self::String(s),
+        }
+    }
+}
+
+pub fn serialize(value: &Value) -> Option<Vec<u8>> {
+    PodSerializer::serialize(Cursor::new(Vec::new()), value)
+        .ok()
+        .map(|(cursor, _)| cursor.into_inner())
+}
+
+/// Builds a Props object with the given properties
+pub fn props(properties: Vec<Property>) -> Option<Vec<u8>> {
+    serialize(&Value::Object(Object {
+        type_: sys::SPA_TYPE_OBJECT_Props,
+        id: sys::SPA_PARAM_Props,
+        properties,
+    }))
+}
+
+/// Builds a Props object that sets the given key-value pairs through its `params` struct.
+/// This is how nodes expose settings like `api.alsa.headroom` for changing at runtime.
+pub fn params(params: impl IntoIterator<Item = (String, ParamValue)>) -> Option<Vec<u8>> {
+    let mut fields = Vec::new();
+    for (key, value) in params {
+        fields.push(Value::String(key));
+        fields.push(value.into());
+    }
+
+    props(vec![Property {
+        key: sys::SPA_PROP_params,
+        flags: PropertyFlags::empty(),
+        value: Value::Struct(fields),
+    }])
+}
+
+fn volume_properties(volume: &Volume) -> Vec<Property> {
+    let property = |key, value| Property {
+        key,
+        flags: PropertyFlags::empty(),
+        value,
+    };
+
+    let mut properties = Vec::new();
+    if let Some(v) = volume.volume {
+        properties.push(property(sys::SPA_PROP_volume, Value::Float(v)));
+    }
+    if !volume.channel_volumes.is_empty() {
+        properties.push(property(
+            sys::SPA_PROP_channelVolumes,
+            Value::ValueArray(ValueArray::Float(volume.channel_volumes.clone())),
+        ));
+    }
+    if let Some(mute) = volume.mute {
+        properties.push(property(sys::SPA_PROP_mute, Value::Bool(mute)));
+    }
+
+    properties
+}
+
+/// Builds a Props object that sets the volume controls that are present
+pub fn volume(volume: &Volume) -> Option<Vec<u8>> {
+    props(volume_properties(volume))
+}
+
+/// Builds a PortConfig object that sets up the DSP ports of an adapter node
+/// with the given channel positions, in their order
+pub fn port_config(input: bool, positions: &[u32]) -> Option<Vec<u8>> {
+    let property = |key, value| Property {
+        key,
+        flags: PropertyFlags::empty(),
+        value,
+    };
+    let id = |id| Value::Id(Id(id));
+
+    let channels = i32::try_from(positions.len()).ok()?;
+    let format = Value::Object(Object {
+        type_: sys::SPA_TYPE_OBJECT_Format,
+        id: sys::SPA_PARAM_Format,
+        properties: vec![
+            property(sys::SPA_FORMAT_mediaType, id(sys::SPA_MEDIA_TYPE_audio)),
+            property(sys::SPA_FORMAT_mediaSubtype, id(sys::SPA_MEDIA_SUBTYPE_raw)),
+            property(sys::SPA_FORMAT_AUDIO_format, id(sys::SPA_AUDIO_FORMAT_F32P)),
+            property(sys::SPA_FORMAT_AUDIO_channels, Value::Int(channels)),
+            property(
+                sys::SPA_FORMAT_AUDIO_position,
+                Value::ValueArray(ValueArray::Id(positions.iter().copied().map(Id).collect())),
+            ),
+        ],
+    });
+
+    serialize(&Value::Object(Object {
+        type_: sys::SPA_TYPE_OBJECT_ParamPortConfig,
+        id: sys::SPA_PARAM_PortConfig,
+        properties: vec![
+            property(
+                sys::SPA_PARAM_PORT_CONFIG_direction,
+                id(if input {
+                    sys::SPA_DIRECTION_INPUT
+                } else {
+                    sys::SPA_DIRECTION_OUTPUT
+                }),
+            ),
+            property(
+                sys::SPA_PARAM_PORT_CONFIG_mode,
+                id(sys::SPA_PARAM_PORT_CONFIG_MODE_dsp),
+            ),
+            property(sys::SPA_PARAM_PORT_CONFIG_format, format),
+        ],
+    }))
+}
+
+/// Builds a Profile object that switches a device to the profile with the given index
+pub fn profile(index: i32) -> Option<Vec<u8>> {
+    serialize(&Value::Object(Object {
+        type_: sys::SPA_TYPE_OBJECT_ParamProfile,
+        id: sys::SPA_PARAM_Profile,
+        properties: vec![
+            Property {
+                key: sys::SPA_PARAM_PROFILE_index,
+                flags: PropertyFlags::empty(),
+                value: Value::Int(index),
+            },
+            // Let the session manager remember the choice
+            Property {
+                key: sys::SPA_PARAM_PROFILE_save,
+                flags: PropertyFlags::empty(),
+                value: Value::Bool(true),
+            },
+        ],
+    }))
+}
+
+/// Builds a Route object that switches the device of the active profile with the given index
+/// to the route with the given index, optionally setting the volume controls of the route
+pub fn route(index: i32, device: i32, volume: Option<&Volume>) -> Option<Vec<u8>> {
+    let property = |key, value| Property {
+        key,
+        flags: PropertyFlags::empty(),
+        value,
+    };
+
+    let mut properties = vec![
+        property(sys::SPA_PARAM_ROUTE_index, Value::Int(index)),
+        property(sys::SPA_PARAM_ROUTE_device, Value::Int(device)),
+    ];
+    if let Some(volume) = volume {
+        properties.push(property(
+            sys::SPA_PARAM_ROUTE_props,
+            Value::Object(Object {
+                type_: sys::SPA_TYPE_OBJECT_Props,
+                id: sys::SPA_PARAM_Route,
+                properties: volume_properties(volume),
+            }),
+        ));
+    }
+    // Let the session manager remember the choice
+    properties.push(property(sys::SPA_PARAM_ROUTE_save, Value::Bool(true)));
+
+    serialize(&Value::Object(Object {
+        type_: sys::SPA_TYPE_OBJECT_ParamRoute,
+        id: sys::SPA_PARAM_Route,
+        properties,
+    }))
+}
@@ -0,0 +1,97 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+use coppwr_backend::pods::{decode, params, profiler, ParseError};
+
+fn corpus() -> Vec<(String, Vec<u8>)> {
+    let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/fuzz/corpus/pods");
+
+    std::fs::read_dir(dir)
+        .expect("The corpus should exist")
+        .map(|entry| {
+            let path = entry.expect("The corpus should be readable").path();
+            let name = path.file_name().unwrap().to_string_lossy().into_owned();
+            (name, std::fs::read(&path).unwrap())
+        })
+        .collect()
+}
+
+fn pod(name: &str) -> Vec<u8> {
+    corpus()
+        .into_iter()
+        .find_map(|(n, bytes)| (n == name).then_some(bytes))
+        .unwrap()
+}
+
+// The same as the fuzz target
+fn fuzz(bytes: &[u8]) {
+    profiler::parse(bytes).ok();
+
+    if let Ok(value) = params::parse(bytes) {
+        decode::tree("Pod", &value);
+        params::flatten(&value);
+        params::AudioFormat::from_param(&value).and_then(|format| format.bandwidth());
+        params::PortConfig::from_param(&value);
+        params::Volume::from_props(&value);
+        params::Profile::from_param(&value);
+        params::Route::from_param(&value);
+    }
+}
+
+#[test]
+fn corpus_does_not_panic() {
+    for (name, bytes) in corpus() {
+        assert!(
+            std::panic::catch_unwind(|| fuzz(&bytes)).is_ok(),
+            "{name} panicked"
+        );
+    }
+}
+
+#[test]
+fn valid_pods_parse() {
+    for (name, bytes) in corpus() {
+        if name.starts_with("valid-") {
+            assert!(params::parse(&bytes).is_ok(), "{name} should parse");
+        }
+    }
+
+    let profilings = profiler::parse(&pod("valid-profiler")).unwrap();
+    assert_eq!(profilings.len(), 1);
+    assert_eq!(profilings[0].driver.id, 30);
+    assert_eq!(profilings[0].followers.len(), 1);
+}
+
+#[test]
+fn malformed_pods_fail() {
+    let cases = [
+        ("empty", ParseError::Truncated),
+        ("short-header", ParseError::Truncated),
+        ("huge-size", ParseError::Truncated),
+        ("truncated-format", ParseError::Truncated),
+        ("truncated-profiler", ParseError::Truncated),
+        ("zero-child-array", ParseError::InvalidChildSize),
+        ("zero-child-choice", ParseError::InvalidChildSize),
+        ("deep-struct", ParseError::TooDeep),
+    ];
+
+    for (name, error) in cases {
+        assert_eq!(params::parse(&pod(name)).err(), Some(error), "{name}");
+    }
+
+    assert!(profiler::parse(&pod("profiler-missing-fields")).is_err());
+    assert!(profiler::parse(&pod("profiler-wrong-types")).is_err());
+}
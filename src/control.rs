@@ -0,0 +1,113 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+// Control interface for external tools.
+// Clients connect to a socket in the runtime directory and send JSON commands,
+// one per line, and get a JSON response on a line for each. The commands are
+// handled by the UI thread, which owns the globals and the connection to PipeWire.
+
+use std::{
+    io::{BufRead, BufReader, Write},
+    os::unix::net::{UnixListener, UnixStream},
+    sync::mpsc,
+    time::Duration,
+};
+
+use eframe::egui;
+
+use crate::instance::runtime_socket;
+
+const SOCKET_NAME: &str = "coppwr-control.sock";
+
+// How long a client waits for the UI to handle a command, it doesn't while it's blocked
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A command received from a client, which must be replied to
+pub struct Call {
+    pub command: String,
+    reply: mpsc::Sender<String>,
+}
+
+impl Call {
+    /// Sends the response to the client. It must be a single line.
+    pub fn reply(self, response: String) {
+        self.reply.send(response).ok();
+    }
+}
+
+/// Listens for clients of the control interface
+pub struct Listener(UnixListener);
+
+impl Listener {
+    pub fn bind() -> std::io::Result<Self> {
+        let path = runtime_socket(SOCKET_NAME).ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::NotFound, "XDG_RUNTIME_DIR is not set")
+        })?;
+
+        // Only one instance binds, the others forward their arguments to it,
+        // so this is left over by an instance that has exited
+        if path.exists() {
+            std::fs::remove_file(&path)?;
+        }
+
+        UnixListener::bind(path).map(Self)
+    }
+
+    /// Starts accepting clients in a separate thread, each served by its own thread.
+    /// The context is repainted when commands are received so that they're handled promptly.
+    pub fn start(self, ctx: egui::Context) -> mpsc::Receiver<Call> {
+        let (sx, rx) = mpsc::channel();
+
+        std::thread::spawn(move || {
+            for stream in self.0.incoming().flatten() {
+                let sx = sx.clone();
+                let ctx = ctx.clone();
+                std::thread::spawn(move || serve(stream, &sx, &ctx));
+            }
+        });
+
+        rx
+    }
+}
+
+fn serve(stream: UnixStream, sx: &mpsc::Sender<Call>, ctx: &egui::Context) {
+    let Ok(mut writer) = stream.try_clone() else {
+        return;
+    };
+
+    for line in BufReader::new(stream).lines() {
+        let Ok(command) = line else {
+            break;
+        };
+        if command.trim().is_empty() {
+            continue;
+        }
+
+        let (reply, response) = mpsc::channel();
+        if sx.send(Call { command, reply }).is_err() {
+            break;
+        }
+        ctx.request_repaint();
+
+        let response = response.recv_timeout(TIMEOUT).unwrap_or_else(|_| {
+            String::from(r#"{"ok": false, "error": "The command wasn't handled in time"}"#)
+        });
+
+        if writeln!(writer, "{response}").is_err() {
+            break;
+        }
+    }
+}
@@ -0,0 +1,435 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+// Runs some of the operations of the GUI without it, for scripting.
+// Commands are given as flags or read from stdin, and their output is written to stdout.
+
+use std::io::BufRead;
+
+use pipewire::types::ObjectType;
+
+use crate::{
+    backend::{self, Event, ObjectMethod, RemoteInfo, Request},
+    model::{Globals, MetadataProperties, Object},
+    pw_dump,
+    redact::Redactor,
+};
+
+pub const USAGE: &str = "\
+Usage: coppwr --headless [COMMAND]...
+
+Runs the commands in order and exits. Without commands they're read from stdin,
+one per line, without the leading -- and with values that contain spaces in double quotes.
+
+Commands:
+  --dump                                    Print the objects in the JSON format of pw-dump
+  --redact                                  Mask identifying information, like user and host
+                                            names, in the following dumps
+  --set-metadata METADATA SUBJECT KEY [VALUE [TYPE]]
+                                            Set a property of a metadata object, by its ID or
+                                            name, or clear it when there's no value
+  --load-module NAME [ARGUMENTS]...         Load a module into the context of coppwr
+  --create-object FACTORY [KEY=VALUE]...    Create an object from a factory
+  --wait                                    Keep running until interrupted, so that loaded
+                                            modules and created objects are kept
+  --help                                    Print this message
+";
+
+enum Command {
+    Dump,
+    Redact,
+    SetMetadata {
+        metadata: String,
+        subject: u32,
+        key: String,
+        value: Option<String>,
+        type_: Option<String>,
+    },
+    LoadModule {
+        name: String,
+        args: Option<String>,
+    },
+    CreateObject {
+        factory: String,
+        props: Vec<(String, String)>,
+    },
+    Wait,
+}
+
+impl Command {
+    fn parse(name: &str, args: &[String]) -> Result<Self, String> {
+        match name {
+            "dump" if args.is_empty() => Ok(Self::Dump),
+            "redact" if args.is_empty() => Ok(Self::Redact),
+            "wait" if args.is_empty() => Ok(Self::Wait),
+            "set-metadata" => {
+                let [metadata, subject, key, rest @ ..] = args else {
+                    return Err(format!("{name} expects a metadata object, subject and key"));
+                };
+                if rest.len() > 2 {
+                    return Err(format!("{name} expects at most a value and a type"));
+                }
+
+                Ok(Self::SetMetadata {
+                    metadata: metadata.clone(),
+                    subject: subject
+                        .parse()
+                        .map_err(|_| format!("{subject} is not a valid subject"))?,
+                    key: key.clone(),
+                    value: rest.first().cloned(),
+                    type_: rest.get(1).cloned(),
+                })
+            }
+            "load-module" => {
+                let Some((module, module_args)) = args.split_first() else {
+                    return Err(format!("{name} expects the name of a module"));
+                };
+
+                Ok(Self::LoadModule {
+                    name: module.clone(),
+                    args: (!module_args.is_empty()).then(|| module_args.join(" ")),
+                })
+            }
+            "create-object" => {
+                let Some((factory, props)) = args.split_first() else {
+                    return Err(format!("{name} expects the name of a factory"));
+                };
+
+                Ok(Self::CreateObject {
+                    factory: factory.clone(),
+                    props: props
+                        .iter()
+                        .map(|prop| {
+                            prop.split_once('=')
+                                .map(|(k, v)| (k.to_owned(), v.to_owned()))
+                                .ok_or_else(|| format!("{prop} is not in the form key=value"))
+                        })
+                        .collect::<Result<_, _>>()?,
+                })
+            }
+            "dump" | "redact" | "wait" => Err(format!("{name} takes no arguments")),
+            _ => Err(format!("Unknown command {name}")),
+        }
+    }
+}
+
+/// Groups the arguments by the flags that precede them
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Vec<Command>, String> {
+    let mut flags: Vec<(String, Vec<String>)> = Vec::new();
+
+    for arg in args {
+        if let Some(flag) = arg.strip_prefix("--") {
+            flags.push((flag.to_owned(), Vec::new()));
+        } else if let Some((_, args)) = flags.last_mut() {
+            args.push(arg);
+        } else {
+            return Err(format!("Unexpected argument {arg}"));
+        }
+    }
+
+    flags
+        .iter()
+        .map(|(flag, args)| Command::parse(flag, args))
+        .collect()
+}
+
+/// Splits a line into words. Double quotes group words and `\` escapes the next character.
+fn split_words(line: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut quoted = false;
+
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => word.get_or_insert_with(String::new).extend(chars.next()),
+            '"' => {
+                quoted = !quoted;
+                word.get_or_insert_with(String::new);
+            }
+            c if c.is_whitespace() && !quoted => words.extend(word.take()),
+            c => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    words.extend(word);
+
+    words
+}
+
+struct Headless {
+    handle: backend::Handle,
+    globals: Globals,
+    metadata: MetadataProperties,
+    // Number of the last sync requested
+    sync: u32,
+    // Whether dumps are redacted
+    redact: bool,
+}
+
+impl Headless {
+    /// Returns `false` if the connection ended
+    fn process_event(&mut self, e: Event) -> bool {
+        match e {
+            Event::GlobalAdded(id, object_type, props) => {
+                self.globals.add_global(id, object_type, props);
+            }
+            Event::GlobalRemoved(id) => {
+                self.globals.remove_global(id);
+                self.metadata.remove_metadata(id);
+            }
+            Event::GlobalInfo(id, info) => {
+                self.globals.set_global_info(id, info);
+            }
+            Event::GlobalProperties(id, props) => {
+                self.globals.set_global_props(id, props);
+            }
+            Event::MetadataProperty {
+                id,
+                subject,
+                key,
+                type_,
+                value,
+            } => {
+                self.metadata.set_property(id, subject, key, type_, value);
+            }
+            Event::Error { id, error } => {
+                eprintln!("Object {id} sent a pod that couldn't be parsed: {error}");
+            }
+            // The results would mix the objects of two connections
+            Event::Stop | Event::Disconnected => return false,
+            _ => {}
+        }
+
+        true
+    }
+
+    /// Processes events until all of the objects of the remote have been announced,
+    /// and then until their info has arrived. Returns `false` if the connection ended.
+    fn wait_initial_sync(&mut self) -> bool {
+        while let Ok(e) = self.handle.rx.recv() {
+            match e {
+                Event::InitialSyncDone => return self.settle(),
+                e => {
+                    if !self.process_event(e) {
+                        return false;
+                    }
+                }
+            }
+        }
+
+        false
+    }
+
+    /// Processes events until the remote has replied to everything sent before,
+    /// by syncing with it. Returns `false` if the connection ended.
+    fn settle(&mut self) -> bool {
+        self.sync = self.sync.wrapping_add(1);
+        if self.send(Request::Sync(self.sync)).is_err() {
+            return false;
+        }
+
+        while let Ok(e) = self.handle.rx.recv() {
+            match e {
+                Event::SyncDone(sync) if sync == self.sync => return true,
+                e => {
+                    if !self.process_event(e) {
+                        return false;
+                    }
+                }
+            }
+        }
+
+        false
+    }
+
+    fn find_global(&self, object_type: &ObjectType, key: &str, value: &str) -> Option<u32> {
+        self.globals.globals().find_map(|global| {
+            (global.object_type() == object_type
+                && global.props().get(key).is_some_and(|v| v == value))
+            .then_some(global.id())
+        })
+    }
+
+    fn dump(&self) -> String {
+        let redactor = if self.redact {
+            Redactor::new(&self.globals)
+        } else {
+            Redactor::disabled()
+        };
+
+        pw_dump::dump(&self.globals, |id| self.metadata.properties(id), &redactor)
+    }
+
+    fn send(&self, request: Request) -> Result<(), String> {
+        self.handle
+            .sx
+            .send(request)
+            .map_err(|_| String::from("The connection has ended"))
+    }
+
+    fn run(&mut self, command: Command) -> Result<(), String> {
+        match command {
+            Command::Dump => {
+                print!("{}", self.dump());
+                return Ok(());
+            }
+            Command::Redact => {
+                self.redact = true;
+                return Ok(());
+            }
+            Command::SetMetadata {
+                metadata,
+                subject,
+                key,
+                value,
+                type_,
+            } => {
+                let id = metadata
+                    .parse()
+                    .ok()
+                    .or_else(|| self.find_global(&ObjectType::Metadata, "metadata.name", &metadata))
+                    .ok_or_else(|| format!("There's no metadata object {metadata}"))?;
+
+                self.send(Request::CallObjectMethod(
+                    id,
+                    ObjectMethod::MetadataSetProperty {
+                        subject,
+                        key,
+                        type_,
+                        value,
+                    },
+                ))?;
+            }
+            Command::LoadModule { name, args } => {
+                self.send(Request::LoadModule {
+                    module_dir: None,
+                    name,
+                    args,
+                    props: None,
+                })?;
+            }
+            Command::CreateObject { factory, props } => {
+                let id = self
+                    .find_global(&ObjectType::Factory, "factory.name", &factory)
+                    .ok_or_else(|| format!("There's no factory {factory}"))?;
+                let object_type = self
+                    .globals
+                    .get_global(id)
+                    .and_then(|global| global.props().get("factory.type.name"))
+                    .map(|name| backend::interface_object_type(name))
+                    .ok_or_else(|| format!("The type of the objects of {factory} is unknown"))?;
+
+                self.send(Request::CreateObject(object_type, factory, props))?;
+            }
+            Command::Wait => {
+                while let Ok(e) = self.handle.rx.recv() {
+                    if !self.process_event(e) {
+                        break;
+                    }
+                }
+                return Ok(());
+            }
+        }
+
+        // So that the results are reflected in following commands
+        if self.settle() {
+            Ok(())
+        } else {
+            Err(String::from("The connection has ended"))
+        }
+    }
+}
+
+/// Connects to the remote of `PIPEWIRE_REMOTE` or the default one and runs the commands
+/// of the arguments, or of stdin if there are none. Returns whether all of them succeeded.
+pub fn run(args: impl IntoIterator<Item = String>) -> bool {
+    let args: Vec<String> = args.into_iter().collect();
+    if args.iter().any(|arg| arg == "--help") {
+        print!("{USAGE}");
+        return true;
+    }
+
+    let commands = match parse_args(args) {
+        Ok(commands) => commands,
+        Err(e) => {
+            eprintln!("{e}\n\n{USAGE}");
+            return false;
+        }
+    };
+
+    let mut headless = Headless {
+        // The same context properties as the GUI, which the access policy of the
+        // session manager is based on
+        handle: backend::Handle::run(
+            RemoteInfo::default(),
+            Vec::new(),
+            vec![("media.category".to_owned(), "Manager".to_owned())],
+        ),
+        globals: Globals::default(),
+        metadata: MetadataProperties::default(),
+        sync: 0,
+        redact: false,
+    };
+
+    if !headless.wait_initial_sync() {
+        eprintln!("Failed to connect to PipeWire");
+        return false;
+    }
+
+    if !commands.is_empty() {
+        for command in commands {
+            if let Err(e) = headless.run(command) {
+                eprintln!("{e}");
+                return false;
+            }
+        }
+
+        return true;
+    }
+
+    let mut success = true;
+
+    for line in std::io::stdin().lock().lines() {
+        let Ok(line) = line else {
+            break;
+        };
+
+        let words = split_words(&line);
+        let Some((name, args)) = words.split_first() else {
+            continue;
+        };
+
+        if name == "help" {
+            print!("{USAGE}");
+            continue;
+        }
+
+        // Events that arrived while waiting for the command
+        while let Ok(e) = headless.handle.rx.try_recv() {
+            if !headless.process_event(e) {
+                eprintln!("The connection has ended");
+                return false;
+            }
+        }
+
+        if let Err(e) = Command::parse(name, args).and_then(|command| headless.run(command)) {
+            eprintln!("{e}");
+            success = false;
+        }
+    }
+
+    success
+}
@@ -0,0 +1,146 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+// Single instance behavior.
+// The first instance listens on a socket in the runtime directory and later
+// instances forward their arguments to it instead of opening another window.
+
+use std::{
+    io::{Read, Write},
+    os::unix::net::{UnixListener, UnixStream},
+    path::PathBuf,
+    sync::mpsc,
+};
+
+use eframe::egui;
+
+/// An object to show the info of
+pub enum Focus {
+    // Matched by name, description or application name
+    Node(String),
+    Property { key: String, value: String },
+}
+
+/// Command line arguments
+#[derive(Default)]
+pub struct Args {
+    pub minimized: bool,
+    pub focus: Option<Focus>,
+    // Name of a view or tool, e.g. profiler or metadata-editor
+    pub open_tool: Option<String>,
+}
+
+impl Args {
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Self {
+        let mut parsed = Self::default();
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--minimized" => parsed.minimized = true,
+                "--focus-node" => parsed.focus = args.next().map(Focus::Node),
+                "--focus" => match args.next().as_deref().and_then(|f| f.split_once('=')) {
+                    Some((key, value)) => {
+                        parsed.focus = Some(Focus::Property {
+                            key: key.to_owned(),
+                            value: value.to_owned(),
+                        });
+                    }
+                    None => eprintln!("--focus expects a property in the form key=value"),
+                },
+                "--open-tool" => parsed.open_tool = args.next(),
+                _ => eprintln!("Ignoring unknown argument {arg}"),
+            }
+        }
+
+        parsed
+    }
+}
+
+/// A socket in the runtime directory, which only the user can access
+pub(crate) fn runtime_socket(name: &str) -> Option<PathBuf> {
+    std::env::var_os("XDG_RUNTIME_DIR")
+        .filter(|dir| !dir.is_empty())
+        .map(|dir| PathBuf::from(dir).join(name))
+}
+
+fn socket_path() -> Option<PathBuf> {
+    runtime_socket("coppwr.sock")
+}
+
+/// Sends the arguments to an already running instance.
+/// Returns `false` if there is no running instance.
+pub fn forward(args: &[String]) -> bool {
+    let Some(mut stream) = socket_path().and_then(|path| UnixStream::connect(path).ok()) else {
+        return false;
+    };
+
+    // Arguments can't contain NUL so it's used as the separator
+    let mut message = Vec::new();
+    for arg in args {
+        message.extend_from_slice(arg.as_bytes());
+        message.push(0);
+    }
+
+    stream.write_all(&message).is_ok()
+}
+
+/// Listens for arguments forwarded by other instances
+pub struct Listener(UnixListener);
+
+impl Listener {
+    pub fn bind() -> std::io::Result<Self> {
+        let path = socket_path().ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::NotFound, "XDG_RUNTIME_DIR is not set")
+        })?;
+
+        // Left over by an instance that has exited, since forwarding failed
+        if path.exists() {
+            std::fs::remove_file(&path)?;
+        }
+
+        UnixListener::bind(path).map(Self)
+    }
+
+    /// Starts accepting connections in a separate thread.
+    /// The context is repainted when arguments are received so that they're handled promptly.
+    pub fn start(self, ctx: egui::Context) -> mpsc::Receiver<Args> {
+        let (sx, rx) = mpsc::channel();
+
+        std::thread::spawn(move || {
+            for mut stream in self.0.incoming().flatten() {
+                let mut message = String::new();
+                if stream.read_to_string(&mut message).is_err() {
+                    continue;
+                }
+
+                let args = Args::parse(
+                    message
+                        .split_terminator('\0')
+                        .map(ToOwned::to_owned)
+                        .collect::<Vec<_>>(),
+                );
+
+                if sx.send(args).is_err() {
+                    break;
+                }
+                ctx.request_repaint();
+            }
+        });
+
+        rx
+    }
+}
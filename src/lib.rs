@@ -14,4 +14,21 @@
 //
 // SPDX-License-Identifier: GPL-3.0-only
 
-pub mod profiler;
+pub use coppwr_backend as backend;
+#[cfg(feature = "gui")]
+pub mod control;
+pub mod headless;
+#[cfg(feature = "gui")]
+pub mod instance;
+pub mod model;
+pub mod pw_dump;
+pub mod redact;
+pub mod schema;
+#[cfg(feature = "gui")]
+mod ui;
+
+#[cfg(feature = "gui")]
+pub use ui::{
+    ConnectionEvent, CoppwrApp, Global, GlobalEvent, GlobalsStore, MetadataChange, ToolPlugin,
+    ToolRegistry,
+};
@@ -14,12 +14,56 @@
 //
 // SPDX-License-Identifier: GPL-3.0-only
 
-mod backend;
-mod ui;
+use coppwr::headless;
+#[cfg(feature = "gui")]
+use coppwr::{control, instance, CoppwrApp};
 
-use crate::ui::CoppwrApp;
+fn run_headless(args: impl IntoIterator<Item = String>) -> ! {
+    pipewire::init();
+
+    let success = headless::run(args);
 
+    unsafe {
+        pipewire::deinit();
+    }
+
+    std::process::exit(if success { 0 } else { 1 });
+}
+
+// Only the headless mode is available without the GUI
+#[cfg(not(feature = "gui"))]
+fn main() {
+    let mut args = std::env::args().skip(1).peekable();
+    // Accepted so that the same scripts work with both builds
+    args.next_if(|arg| arg == "--headless");
+
+    run_headless(args);
+}
+
+#[cfg(feature = "gui")]
 fn main() {
+    let raw_args: Vec<String> = std::env::args().skip(1).collect();
+
+    if raw_args.first().is_some_and(|arg| arg == "--headless") {
+        run_headless(raw_args.into_iter().skip(1));
+    }
+
+    if instance::forward(&raw_args) {
+        return;
+    }
+
+    let listener = instance::Listener::bind()
+        .map_err(|e| eprintln!("Failed to listen for other instances: {e}"))
+        .ok();
+    // Only the instance that listens for the others handles control commands
+    let control_listener = listener.as_ref().and_then(|_| {
+        control::Listener::bind()
+            .map_err(|e| eprintln!("Failed to listen for control commands: {e}"))
+            .ok()
+    });
+
+    let args = instance::Args::parse(raw_args);
+
     pipewire::init();
 
     if let Err(e) = eframe::run_native(
@@ -37,17 +81,29 @@ fn main() {
             },
             ..eframe::NativeOptions::default()
         },
-        {
-            #[cfg(not(feature = "persistence"))]
-            {
-                Box::new(|_| Box::new(CoppwrApp::new()))
+        Box::new(move |cc| {
+            // Used by the autostart entry
+            if args.minimized {
+                cc.egui_ctx
+                    .send_viewport_cmd(eframe::egui::ViewportCommand::Minimized(true));
             }
 
+            #[cfg(not(feature = "persistence"))]
+            let mut app = CoppwrApp::new();
+
             #[cfg(feature = "persistence")]
-            {
-                Box::new(|cc| Box::new(CoppwrApp::new(cc.storage)))
+            let mut app = CoppwrApp::new(cc.storage);
+
+            app.handle_args(args);
+            if let Some(listener) = listener {
+                app.set_instance_listener(listener.start(cc.egui_ctx.clone()));
             }
-        },
+            if let Some(listener) = control_listener {
+                app.set_control_listener(listener.start(cc.egui_ctx.clone()));
+            }
+
+            Box::new(app)
+        }),
     ) {
         eprintln!("Failed to start the GUI: {e}");
     }
@@ -0,0 +1,171 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+// The objects of a remote as far as exporting them is concerned, so that
+// the exports work both with the GUI's globals and without the GUI.
+
+use std::collections::BTreeMap;
+
+use pipewire::types::ObjectType;
+
+use crate::pw_dump::MetadataProperty;
+
+/// A global of a remote
+pub trait Object {
+    fn id(&self) -> u32;
+    fn object_type(&self) -> &ObjectType;
+    fn props(&self) -> &BTreeMap<String, String>;
+    /// Labeled values of the info, as reported by the backend
+    fn info(&self) -> Option<&[(&'static str, String)]>;
+}
+
+/// The globals of a remote
+pub trait Registry {
+    /// Calls `f` with each global, in no particular order
+    fn for_each_object(&self, f: &mut dyn FnMut(&dyn Object));
+}
+
+/// A global with only what the backend reports about it
+pub struct PlainObject {
+    id: u32,
+    object_type: ObjectType,
+    props: BTreeMap<String, String>,
+    info: Option<Box<[(&'static str, String)]>>,
+}
+
+impl Object for PlainObject {
+    fn id(&self) -> u32 {
+        self.id
+    }
+
+    fn object_type(&self) -> &ObjectType {
+        &self.object_type
+    }
+
+    fn props(&self) -> &BTreeMap<String, String> {
+        &self.props
+    }
+
+    fn info(&self) -> Option<&[(&'static str, String)]> {
+        self.info.as_deref()
+    }
+}
+
+/// Keeps the globals of a remote up to date with the events of the backend
+#[derive(Default)]
+pub struct Globals {
+    globals: BTreeMap<u32, PlainObject>,
+}
+
+impl Globals {
+    pub fn add_global(
+        &mut self,
+        id: u32,
+        object_type: ObjectType,
+        props: Option<BTreeMap<String, String>>,
+    ) {
+        self.globals.insert(
+            id,
+            PlainObject {
+                id,
+                object_type,
+                props: props.unwrap_or_default(),
+                info: None,
+            },
+        );
+    }
+
+    pub fn remove_global(&mut self, id: u32) {
+        self.globals.remove(&id);
+    }
+
+    pub fn set_global_info(&mut self, id: u32, info: Box<[(&'static str, String)]>) {
+        if let Some(global) = self.globals.get_mut(&id) {
+            global.info = Some(info);
+        }
+    }
+
+    pub fn set_global_props(&mut self, id: u32, props: BTreeMap<String, String>) {
+        if let Some(global) = self.globals.get_mut(&id) {
+            global.props = props;
+        }
+    }
+
+    pub fn get_global(&self, id: u32) -> Option<&PlainObject> {
+        self.globals.get(&id)
+    }
+
+    /// The globals sorted by their IDs
+    pub fn globals(&self) -> impl Iterator<Item = &PlainObject> {
+        self.globals.values()
+    }
+}
+
+impl Registry for Globals {
+    fn for_each_object(&self, f: &mut dyn FnMut(&dyn Object)) {
+        for global in self.globals.values() {
+            f(global);
+        }
+    }
+}
+
+/// The properties of metadata objects, kept up to date with the events of the backend
+#[derive(Default)]
+pub struct MetadataProperties {
+    // The types and values by metadata object, subject and key
+    properties: BTreeMap<u32, BTreeMap<(u32, String), (Option<String>, String)>>,
+}
+
+impl MetadataProperties {
+    /// Removes the property if there's no value, or all of the properties if there's no key
+    pub fn set_property(
+        &mut self,
+        id: u32,
+        subject: u32,
+        key: Option<String>,
+        type_: Option<String>,
+        value: Option<String>,
+    ) {
+        let properties = self.properties.entry(id).or_default();
+        match (key, value) {
+            (Some(key), Some(value)) => {
+                properties.insert((subject, key), (type_, value));
+            }
+            (Some(key), None) => {
+                properties.remove(&(subject, key));
+            }
+            (None, _) => properties.clear(),
+        }
+    }
+
+    pub fn remove_metadata(&mut self, id: u32) {
+        self.properties.remove(&id);
+    }
+
+    pub fn properties(&self, id: u32) -> Vec<MetadataProperty<'_>> {
+        self.properties
+            .get(&id)
+            .into_iter()
+            .flatten()
+            .map(|((subject, key), (type_, value))| MetadataProperty {
+                subject: *subject,
+                key,
+                type_: type_.as_deref(),
+                value,
+            })
+            .collect()
+    }
+}
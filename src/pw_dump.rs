@@ -0,0 +1,755 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+// Serialization of the globals in the JSON format of pw-dump.
+// Versions, permissions and params aren't tracked so they're left out.
+
+use std::{collections::BTreeMap, fmt::Write};
+
+use pipewire::types::ObjectType;
+
+use crate::{
+    model::{Object, Registry},
+    redact::Redactor,
+    schema::{self, TypedValue},
+};
+
+pub enum Json {
+    // Numbers, booleans and values that are already JSON
+    Raw(String),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// Property values are strings, but pw-dump shows numbers and booleans as such
+    fn from_value(value: &str) -> Self {
+        if matches!(value, "true" | "false" | "null") || is_number(value) {
+            Self::Raw(value.to_owned())
+        } else {
+            Self::String(value.to_owned())
+        }
+    }
+
+    /// Uses the type of the property if it's known, so that e.g. names that
+    /// look like numbers stay strings and booleans written as 1 become booleans
+    fn from_property(key: &str, value: &str) -> Self {
+        match schema::typed_value(key, value) {
+            Some(TypedValue::Int(i)) => Self::Raw(i.to_string()),
+            Some(TypedValue::Bool(b)) => Self::Raw(b.to_string()),
+            Some(TypedValue::String(s)) => Self::String(s.to_owned()),
+            None => Self::from_value(value),
+        }
+    }
+
+    fn write(&self, out: &mut String, indent: usize) {
+        match self {
+            Self::Raw(raw) => out.push_str(raw),
+            Self::String(s) => write_string(out, s),
+            Self::Array(values) if values.is_empty() => out.push_str("[]"),
+            Self::Array(values) => {
+                out.push_str("[\n");
+                for (i, value) in values.iter().enumerate() {
+                    write_indent(out, indent + 1);
+                    value.write(out, indent + 1);
+                    out.push_str(if i + 1 < values.len() { ",\n" } else { "\n" });
+                }
+                write_indent(out, indent);
+                out.push(']');
+            }
+            Self::Object(members) if members.is_empty() => out.push_str("{}"),
+            Self::Object(members) => {
+                out.push_str("{\n");
+                for (i, (key, value)) in members.iter().enumerate() {
+                    write_indent(out, indent + 1);
+                    write_string(out, key);
+                    out.push_str(": ");
+                    value.write(out, indent + 1);
+                    out.push_str(if i + 1 < members.len() { ",\n" } else { "\n" });
+                }
+                write_indent(out, indent);
+                out.push('}');
+            }
+        }
+    }
+
+    pub fn to_pretty_string(&self) -> String {
+        let mut out = String::new();
+        self.write(&mut out, 0);
+        out.push('\n');
+        out
+    }
+
+    /// Writes the value on a single line
+    fn write_compact(&self, out: &mut String) {
+        match self {
+            Self::Raw(raw) => out.push_str(raw),
+            Self::String(s) => write_string(out, s),
+            Self::Array(values) => {
+                out.push('[');
+                for (i, value) in values.iter().enumerate() {
+                    if i != 0 {
+                        out.push_str(", ");
+                    }
+                    value.write_compact(out);
+                }
+                out.push(']');
+            }
+            Self::Object(members) => {
+                out.push('{');
+                for (i, (key, value)) in members.iter().enumerate() {
+                    if i != 0 {
+                        out.push_str(", ");
+                    }
+                    write_string(out, key);
+                    out.push_str(": ");
+                    value.write_compact(out);
+                }
+                out.push('}');
+            }
+        }
+    }
+
+    pub fn to_compact_string(&self) -> String {
+        let mut out = String::new();
+        self.write_compact(&mut out);
+        out
+    }
+
+    /// Strings without quotes, anything else as JSON
+    pub fn to_plain_string(&self) -> String {
+        match self {
+            Self::Raw(raw) => raw.clone(),
+            Self::String(s) => s.clone(),
+            container => {
+                let mut out = String::new();
+                container.write(&mut out, 0);
+                out
+            }
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Self> {
+        let Self::Object(members) = self else {
+            return None;
+        };
+        members.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+}
+
+// Deeper values are rejected instead of overflowing the stack
+const MAX_DEPTH: usize = 128;
+
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+    // Of the arrays and objects the parser is in
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|b| b.is_ascii_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Option<()> {
+        self.skip_whitespace();
+        if self.peek()? != byte {
+            return None;
+        }
+        self.pos += 1;
+
+        Some(())
+    }
+
+    /// Parses the members of an object or the values of an array after the opening bracket
+    fn members(
+        &mut self,
+        close: u8,
+        mut member: impl FnMut(&mut Self) -> Option<()>,
+    ) -> Option<()> {
+        self.skip_whitespace();
+        if self.peek()? == close {
+            self.pos += 1;
+            return Some(());
+        }
+
+        loop {
+            member(self)?;
+            self.skip_whitespace();
+            match self.peek()? {
+                b',' => self.pos += 1,
+                b if b == close => {
+                    self.pos += 1;
+                    return Some(());
+                }
+                _ => return None,
+            }
+        }
+    }
+
+    fn value(&mut self) -> Option<Json> {
+        self.skip_whitespace();
+        match self.peek()? {
+            b'{' | b'[' if self.depth >= MAX_DEPTH => None,
+            b'{' => {
+                self.pos += 1;
+                self.depth += 1;
+                let mut members = Vec::new();
+                self.members(b'}', |parser| {
+                    let key = parser.string()?;
+                    parser.expect(b':')?;
+                    members.push((key, parser.value()?));
+                    Some(())
+                })?;
+                self.depth -= 1;
+                Some(Json::Object(members))
+            }
+            b'[' => {
+                self.pos += 1;
+                self.depth += 1;
+                let mut values = Vec::new();
+                self.members(b']', |parser| {
+                    values.push(parser.value()?);
+                    Some(())
+                })?;
+                self.depth -= 1;
+                Some(Json::Array(values))
+            }
+            b'"' => self.string().map(Json::String),
+            _ => {
+                let start = self.pos;
+                while self
+                    .peek()
+                    .is_some_and(|b| !matches!(b, b',' | b'}' | b']') && !b.is_ascii_whitespace())
+                {
+                    self.pos += 1;
+                }
+                let raw = &self.text[start..self.pos];
+                (matches!(raw, "true" | "false" | "null") || is_number(raw))
+                    .then(|| Json::Raw(raw.to_owned()))
+            }
+        }
+    }
+
+    fn string(&mut self) -> Option<String> {
+        self.expect(b'"')?;
+
+        let mut out = String::new();
+        let mut chars = self.text[self.pos..].char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.pos += i + 1;
+                    return Some(out);
+                }
+                '\\' => match chars.next()?.1 {
+                    'n' => out.push('\n'),
+                    'r' => out.push('\r'),
+                    't' => out.push('\t'),
+                    'b' => out.push('\u{8}'),
+                    'f' => out.push('\u{c}'),
+                    'u' => {
+                        let hex = chars.by_ref().take(4).map(|(_, c)| c).collect::<String>();
+                        if hex.len() != 4 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                            return None;
+                        }
+                        let code = u32::from_str_radix(&hex, 16).ok()?;
+                        // Surrogate pairs aren't combined
+                        out.push(char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER));
+                    }
+                    c => out.push(c),
+                },
+                c => out.push(c),
+            }
+        }
+
+        None
+    }
+}
+
+/// Parses a JSON value, returns `None` if it isn't valid
+pub fn parse_json(text: &str) -> Option<Json> {
+    let mut parser = Parser {
+        text,
+        pos: 0,
+        depth: 0,
+    };
+    let value = parser.value()?;
+    parser.skip_whitespace();
+    (parser.peek().is_none()).then_some(value)
+}
+
+fn write_indent(out: &mut String, indent: usize) {
+    for _ in 0..indent {
+        out.push_str("  ");
+    }
+}
+
+fn write_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => {
+                write!(out, "\\u{:04x}", u32::from(c)).ok();
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+fn is_number(s: &str) -> bool {
+    fn digits(s: &str) -> (&str, &str) {
+        s.split_at(s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len()))
+    }
+
+    let (int, rest) = digits(s.strip_prefix('-').unwrap_or(s));
+    if int.is_empty() || (int.len() > 1 && int.starts_with('0')) {
+        return false;
+    }
+
+    let rest = match rest.strip_prefix('.') {
+        Some(fraction) => match digits(fraction) {
+            ("", _) => return false,
+            (_, rest) => rest,
+        },
+        None => rest,
+    };
+
+    match rest.strip_prefix(|c| c == 'e' || c == 'E') {
+        Some(exponent) => {
+            let exponent = exponent
+                .strip_prefix(|c| c == '+' || c == '-')
+                .unwrap_or(exponent);
+            !exponent.is_empty() && exponent.bytes().all(|b| b.is_ascii_digit())
+        }
+        None => rest.is_empty(),
+    }
+}
+
+// Info labels of the backend and their pw-dump keys
+const INFO_KEYS: [(&str, &str); 18] = [
+    ("Name", "name"),
+    ("Hostname", "host-name"),
+    ("Username", "user-name"),
+    ("Version", "version"),
+    ("Cookie", "cookie"),
+    ("Filename", "filename"),
+    ("Arguments", "args"),
+    ("Type", "type"),
+    ("Max Input Ports", "max-input-ports"),
+    ("Max Output Ports", "max-output-ports"),
+    ("Input Ports", "n-input-ports"),
+    ("Output Ports", "n-output-ports"),
+    ("State", "state"),
+    ("Direction", "direction"),
+    ("Input Node ID", "input-node-id"),
+    ("Intput Port ID", "input-port-id"),
+    ("Output Node ID", "output-node-id"),
+    ("Output Port ID", "output-port-id"),
+];
+
+// States of nodes and links, anything else is an error message
+const STATES: [&str; 10] = [
+    "Creating",
+    "Idle",
+    "Suspended",
+    "Running",
+    "Init",
+    "Allocating",
+    "Negotiating",
+    "Active",
+    "Paused",
+    "Unlinked",
+];
+
+fn props(global: &dyn Object, redactor: &Redactor) -> Json {
+    Json::Object(
+        global
+            .props()
+            .iter()
+            .map(|(k, v)| (k.clone(), Json::from_property(k, &redactor.property(k, v))))
+            .collect(),
+    )
+}
+
+fn info(global: &dyn Object, redactor: &Redactor) -> Json {
+    let mut members = Vec::new();
+
+    for (label, value) in global.info().unwrap_or_default() {
+        let Some(key) = INFO_KEYS
+            .iter()
+            .find(|(l, _)| l == label)
+            .map(|(_, key)| *key)
+        else {
+            continue;
+        };
+
+        match key {
+            "state" if !STATES.contains(&value.as_str()) => {
+                members.push(("state".to_owned(), Json::String("error".to_owned())));
+                members.push(("error".to_owned(), Json::String(value.clone())));
+            }
+            "state" | "direction" => {
+                members.push((key.to_owned(), Json::String(value.to_lowercase())));
+            }
+            _ => members.push((key.to_owned(), Json::from_value(&redactor.text(value)))),
+        }
+    }
+
+    members.push(("props".to_owned(), props(global, redactor)));
+
+    Json::Object(members)
+}
+
+pub struct MetadataProperty<'a> {
+    pub subject: u32,
+    pub key: &'a str,
+    pub type_: Option<&'a str>,
+    pub value: &'a str,
+}
+
+fn metadata_property(p: &MetadataProperty, redactor: &Redactor) -> Json {
+    let value = redactor.text(p.value);
+
+    Json::Object(vec![
+        ("subject".to_owned(), Json::Raw(p.subject.to_string())),
+        ("key".to_owned(), Json::String(p.key.to_owned())),
+        (
+            "type".to_owned(),
+            p.type_.map_or_else(
+                || Json::Raw("null".to_owned()),
+                |t| Json::String(t.to_owned()),
+            ),
+        ),
+        (
+            "value".to_owned(),
+            if p.type_ == Some("Spa:String:JSON") {
+                // Any client can set values, which may not be valid
+                parse_json(&value).unwrap_or(Json::String(value))
+            } else {
+                Json::from_value(&value)
+            },
+        ),
+    ])
+}
+
+/// A global as an object of pw-dump
+pub fn object<'a>(
+    global: &dyn Object,
+    metadata: impl Fn(u32) -> Vec<MetadataProperty<'a>>,
+    redactor: &Redactor,
+) -> Json {
+    let mut members = vec![
+        ("id".to_owned(), Json::Raw(global.id().to_string())),
+        (
+            "type".to_owned(),
+            Json::String(global.object_type().to_str().to_owned()),
+        ),
+    ];
+
+    // Metadata objects have no info, pw-dump shows their registry properties instead
+    if *global.object_type() == ObjectType::Metadata {
+        members.push(("props".to_owned(), props(global, redactor)));
+        members.push((
+            "metadata".to_owned(),
+            Json::Array(
+                metadata(global.id())
+                    .into_iter()
+                    .map(|p| metadata_property(&p, redactor))
+                    .collect(),
+            ),
+        ));
+    } else {
+        members.push(("info".to_owned(), info(global, redactor)));
+    }
+
+    Json::Object(members)
+}
+
+/// Serializes all globals. `metadata` gives the properties of a metadata object by its ID.
+pub fn dump<'a>(
+    globals: &impl Registry,
+    metadata: impl Fn(u32) -> Vec<MetadataProperty<'a>>,
+    redactor: &Redactor,
+) -> String {
+    let mut objects = Vec::new();
+    globals.for_each_object(&mut |global| {
+        objects.push((global.id(), object(global, &metadata, redactor)));
+    });
+    objects.sort_by_key(|(id, _)| *id);
+
+    Json::Array(objects.into_iter().map(|(_, object)| object).collect()).to_pretty_string()
+}
+
+/// An object read from a pw-dump JSON file
+pub struct DumpedObject {
+    pub id: u32,
+    pub object_type: String,
+    pub info: BTreeMap<String, String>,
+    pub props: BTreeMap<String, String>,
+}
+
+impl DumpedObject {
+    pub fn name(&self) -> Option<&str> {
+        [
+            "node.name",
+            "device.name",
+            "metadata.name",
+            "application.name",
+        ]
+        .into_iter()
+        .find_map(|key| self.props.get(key))
+        .map(String::as_str)
+    }
+
+    /// The node ID of an endpoint of a link
+    pub fn link_node(&self, output: bool) -> Option<u32> {
+        self.info
+            .get(if output {
+                "output-node-id"
+            } else {
+                "input-node-id"
+            })?
+            .parse()
+            .ok()
+    }
+}
+
+fn plain_map(object: Option<&Json>) -> BTreeMap<String, String> {
+    let Some(Json::Object(members)) = object else {
+        return BTreeMap::new();
+    };
+
+    members
+        .iter()
+        .map(|(k, v)| (k.clone(), v.to_plain_string()))
+        .collect()
+}
+
+/// Reads the objects of a pw-dump JSON file.
+/// Returns `None` if it isn't valid JSON or not an array of objects.
+pub fn parse(text: &str) -> Option<Vec<DumpedObject>> {
+    let Json::Array(objects) = parse_json(text)? else {
+        return None;
+    };
+
+    objects
+        .iter()
+        .map(|object| {
+            let info = object.get("info");
+
+            Some(DumpedObject {
+                id: object.get("id")?.to_plain_string().parse().ok()?,
+                object_type: object.get("type")?.to_plain_string(),
+                info: plain_map(info)
+                    .into_iter()
+                    .filter(|(k, _)| !matches!(k.as_str(), "props" | "params" | "change-mask"))
+                    .collect(),
+                props: plain_map(
+                    info.and_then(|info| info.get("props"))
+                        .or_else(|| object.get("props")),
+                ),
+            })
+        })
+        .collect()
+}
+
+/// Serializes the properties of a metadata object like the `metadata` member of pw-dump objects
+pub fn metadata<'a>(properties: impl IntoIterator<Item = MetadataProperty<'a>>) -> String {
+    let redactor = Redactor::disabled();
+
+    Json::Array(
+        properties
+            .into_iter()
+            .map(|p| metadata_property(&p, &redactor))
+            .collect(),
+    )
+    .to_pretty_string()
+}
+
+/// A property of a metadata object read from a file
+pub struct ParsedMetadataProperty {
+    pub subject: u32,
+    pub key: String,
+    pub type_: Option<String>,
+    pub value: String,
+}
+
+/// Reads properties written by [`metadata`].
+/// Returns `None` if it isn't valid JSON or not an array of properties.
+pub fn parse_metadata(text: &str) -> Option<Vec<ParsedMetadataProperty>> {
+    let Json::Array(properties) = parse_json(text)? else {
+        return None;
+    };
+
+    properties
+        .iter()
+        .map(|property| {
+            let type_ = match property.get("type") {
+                Some(Json::String(type_)) => Some(type_.clone()),
+                _ => None,
+            };
+            // Values of the JSON type are JSON themselves, including strings
+            let value = match property.get("value")? {
+                Json::String(s) if type_.as_deref() != Some("Spa:String:JSON") => s.clone(),
+                value => {
+                    let mut out = String::new();
+                    value.write_compact(&mut out);
+                    out
+                }
+            };
+
+            Some(ParsedMetadataProperty {
+                subject: property.get("subject")?.to_plain_string().parse().ok()?,
+                key: property.get("key")?.to_plain_string(),
+                type_,
+                value,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_values() {
+        let json =
+            parse_json(r#" { "a": [1, -2.5e3, true, null], "b": "x\"é\n", "c": {} } "#).unwrap();
+
+        assert_eq!(
+            json.to_compact_string(),
+            r#"{"a": [1, -2.5e3, true, null], "b": "x\"é\n", "c": {}}"#
+        );
+    }
+
+    #[test]
+    fn rejects_malformed() {
+        for text in [
+            "",
+            "{",
+            "[1, 2",
+            r#"{"a": 1,}"#,
+            "[1,,2]",
+            r#"{"a" 1}"#,
+            r#"{1: 2}"#,
+            "[01]",
+            "[foo]",
+            "[1] [2]",
+            r#""unterminated"#,
+            r#""\u12""#,
+            r#""\u+123""#,
+            r#"["\"#,
+        ] {
+            assert!(parse_json(text).is_none(), "{text:?} was accepted");
+        }
+    }
+
+    #[test]
+    fn rejects_truncated() {
+        let text = r#"[{ "subject": 0, "key": "k", "type": "Spa:String:JSON", "value": { "name": "é" } }]"#;
+        assert!(parse_metadata(text).is_some());
+
+        for (end, _) in text.char_indices().skip(1) {
+            assert!(
+                parse_metadata(&text[..end]).is_none(),
+                "{:?} was accepted",
+                &text[..end]
+            );
+        }
+    }
+
+    #[test]
+    fn limits_nesting() {
+        let nested = |depth| "[".repeat(depth) + &"]".repeat(depth);
+
+        assert!(parse_json(&nested(MAX_DEPTH)).is_some());
+        assert!(parse_json(&nested(MAX_DEPTH + 1)).is_none());
+        assert!(parse_json(&"[".repeat(100_000)).is_none());
+        assert!(parse_json(&r#"{"a":"#.repeat(100_000)).is_none());
+    }
+
+    #[test]
+    fn parses_metadata() {
+        let properties = parse_metadata(
+            r#"[
+                { "subject": 0, "key": "default.audio.sink", "type": "Spa:String:JSON",
+                  "value": { "name": "speakers" } },
+                { "subject": 42, "key": "target.object", "value": "mic" }
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(properties.len(), 2);
+        assert_eq!(properties[0].subject, 0);
+        assert_eq!(properties[0].type_.as_deref(), Some("Spa:String:JSON"));
+        assert_eq!(properties[0].value, r#"{"name": "speakers"}"#);
+        assert_eq!(properties[1].subject, 42);
+        assert_eq!(properties[1].key, "target.object");
+        assert_eq!(properties[1].type_, None);
+        assert_eq!(properties[1].value, "mic");
+    }
+
+    #[test]
+    fn keeps_dumps_valid() {
+        let text = metadata([
+            MetadataProperty {
+                subject: 0,
+                key: "valid",
+                type_: Some("Spa:String:JSON"),
+                value: r#"{ "name": "speakers" }"#,
+            },
+            MetadataProperty {
+                subject: 0,
+                key: "truncated",
+                type_: Some("Spa:String:JSON"),
+                value: r#"{ "name": "spea"#,
+            },
+        ]);
+
+        let properties = parse_metadata(&text).unwrap();
+        assert_eq!(properties[0].value, r#"{"name": "speakers"}"#);
+        assert_eq!(properties[1].value, r#""{ \"name\": \"spea""#);
+    }
+
+    #[test]
+    fn rejects_invalid_metadata() {
+        for text in [
+            "{}",
+            r#"[{ "key": "k", "value": "v" }]"#,
+            r#"[{ "subject": "zero", "key": "k", "value": "v" }]"#,
+            r#"[{ "subject": 0, "key": "k" }]"#,
+            r#"[{ "subject": 0, "key": "k", "value": "v" }] trailing"#,
+        ] {
+            assert!(parse_metadata(text).is_none(), "{text:?} was accepted");
+        }
+    }
+}
@@ -0,0 +1,145 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{
+    borrow::Cow,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use crate::model::Registry;
+
+static ENABLED: AtomicBool = AtomicBool::new(true);
+
+/// Whether exports should have identifying information masked
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+// Properties whose values are identifying as a whole
+const MASKED_KEYS: [(&str, &str); 4] = [
+    ("application.process.user", "<username>"),
+    ("application.process.host", "<hostname>"),
+    ("application.process.machine-id", "<machine-id>"),
+    ("application.process.binary", "<binary>"),
+];
+
+fn masked_value(key: &str) -> Option<&'static str> {
+    MASKED_KEYS
+        .iter()
+        .find(|(k, _)| *k == key)
+        .map(|(_, replacement)| *replacement)
+        // object.serial is a counter of the daemon, not a hardware serial number
+        .or_else(|| (key.ends_with("serial") && key != "object.serial").then_some("<serial>"))
+}
+
+/// Replaces occurrences of `word` that aren't part of a longer word,
+/// so that short names don't mangle unrelated text
+fn replace_word(text: &str, word: &str, replacement: &str) -> String {
+    let is_word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(i) = rest.find(word) {
+        out.push_str(&rest[..i]);
+        let after = &rest[i + word.len()..];
+        if is_word(out.chars().next_back()) || is_word(after.chars().next()) {
+            out.push_str(word);
+        } else {
+            out.push_str(replacement);
+        }
+        rest = after;
+    }
+    out.push_str(rest);
+
+    out
+}
+
+/// Masks usernames, hostnames, serial numbers and binaries in exported text.
+/// Does nothing if redaction is disabled.
+pub struct Redactor {
+    active: bool,
+    // Identifying strings found in the remote and their replacements
+    identifiers: Vec<(String, &'static str)>,
+}
+
+impl Redactor {
+    pub fn new(globals: &impl Registry) -> Self {
+        let mut identifiers = Vec::new();
+
+        globals.for_each_object(&mut |global| {
+            // The core
+            if global.id() == 0 {
+                for (key, value) in global.info().unwrap_or_default() {
+                    match *key {
+                        "Username" => identifiers.push((value.clone(), "<username>")),
+                        "Hostname" => identifiers.push((value.clone(), "<hostname>")),
+                        _ => {}
+                    }
+                }
+            }
+
+            for (key, value) in global.props() {
+                // Binaries and serials can be common words, only mask them as property values
+                if let Some(replacement @ ("<username>" | "<hostname>")) = masked_value(key) {
+                    identifiers.push((value.clone(), replacement));
+                }
+            }
+        });
+
+        identifiers.retain(|(value, _)| !value.is_empty());
+        identifiers.sort_unstable();
+        identifiers.dedup();
+        // Replace longer strings first in case some contain others
+        identifiers.sort_by_key(|(value, _)| std::cmp::Reverse(value.len()));
+
+        Self {
+            active: enabled(),
+            identifiers,
+        }
+    }
+
+    /// A redactor that leaves everything as is, for data that doesn't leave the machine
+    pub const fn disabled() -> Self {
+        Self {
+            active: false,
+            identifiers: Vec::new(),
+        }
+    }
+
+    pub fn property<'a>(&self, key: &str, value: &'a str) -> Cow<'a, str> {
+        if !self.active {
+            return Cow::Borrowed(value);
+        }
+
+        masked_value(key).map_or_else(|| Cow::Owned(self.text(value)), Cow::Borrowed)
+    }
+
+    pub fn text(&self, text: &str) -> String {
+        if !self.active {
+            return text.to_owned();
+        }
+
+        self.identifiers
+            .iter()
+            .fold(text.to_owned(), |text, (identifier, replacement)| {
+                replace_word(&text, identifier, replacement)
+            })
+    }
+}
@@ -0,0 +1,141 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+// Types of the values of well known properties. PipeWire stores all values as strings.
+
+/// The type of the value of a property
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum PropertyType {
+    Int,
+    Bool,
+    /// One of a set of values
+    Enum(&'static [&'static str]),
+    String,
+}
+
+const MEDIA_TYPES: &[&str] = &["Audio", "Video", "Midi"];
+const MEDIA_CATEGORIES: &[&str] = &["Playback", "Capture", "Duplex", "Monitor", "Manager"];
+const MEDIA_ROLES: &[&str] = &[
+    "Movie",
+    "Music",
+    "Camera",
+    "Screen",
+    "Communication",
+    "Game",
+    "Notification",
+    "DSP",
+    "Production",
+    "Accessibility",
+    "Test",
+];
+const AUDIO_FORMATS: &[&str] = &[
+    "U8", "S16LE", "S16BE", "S24LE", "S24BE", "S24_32LE", "S24_32BE", "S32LE", "S32BE", "F32LE",
+    "F32BE", "F64LE", "F64BE", "F32P",
+];
+
+const SCHEMA: [(&str, PropertyType); 53] = [
+    ("object.id", PropertyType::Int),
+    ("object.serial", PropertyType::Int),
+    ("object.linger", PropertyType::Bool),
+    ("object.register", PropertyType::Bool),
+    ("factory.id", PropertyType::Int),
+    ("module.id", PropertyType::Int),
+    ("client.id", PropertyType::Int),
+    ("device.id", PropertyType::Int),
+    ("node.id", PropertyType::Int),
+    ("port.id", PropertyType::Int),
+    ("link.input.node", PropertyType::Int),
+    ("link.input.port", PropertyType::Int),
+    ("link.output.node", PropertyType::Int),
+    ("link.output.port", PropertyType::Int),
+    ("link.passive", PropertyType::Bool),
+    ("link.feedback", PropertyType::Bool),
+    ("application.process.id", PropertyType::Int),
+    ("pipewire.sec.pid", PropertyType::Int),
+    ("pipewire.sec.uid", PropertyType::Int),
+    ("pipewire.sec.gid", PropertyType::Int),
+    ("pipewire.access.portal.is_portal", PropertyType::Bool),
+    ("priority.session", PropertyType::Int),
+    ("priority.driver", PropertyType::Int),
+    ("node.driver", PropertyType::Bool),
+    ("node.passive", PropertyType::Bool),
+    ("node.virtual", PropertyType::Bool),
+    ("node.exclusive", PropertyType::Bool),
+    ("node.autoconnect", PropertyType::Bool),
+    ("node.dont-reconnect", PropertyType::Bool),
+    ("node.always-process", PropertyType::Bool),
+    ("node.want-driver", PropertyType::Bool),
+    ("node.pause-on-idle", PropertyType::Bool),
+    ("node.suspend-on-idle", PropertyType::Bool),
+    ("node.lock-quantum", PropertyType::Bool),
+    ("node.lock-rate", PropertyType::Bool),
+    ("node.force-quantum", PropertyType::Int),
+    ("node.force-rate", PropertyType::Int),
+    ("port.direction", PropertyType::Enum(&["in", "out"])),
+    ("port.physical", PropertyType::Bool),
+    ("port.terminal", PropertyType::Bool),
+    ("port.monitor", PropertyType::Bool),
+    ("port.control", PropertyType::Bool),
+    ("media.type", PropertyType::Enum(MEDIA_TYPES)),
+    ("media.category", PropertyType::Enum(MEDIA_CATEGORIES)),
+    ("media.role", PropertyType::Enum(MEDIA_ROLES)),
+    ("audio.channels", PropertyType::Int),
+    ("audio.rate", PropertyType::Int),
+    ("audio.format", PropertyType::Enum(AUDIO_FORMATS)),
+    ("resample.quality", PropertyType::Int),
+    ("resample.disable", PropertyType::Bool),
+    ("channelmix.normalize", PropertyType::Bool),
+    ("monitor.channel-volumes", PropertyType::Bool),
+    ("card.profile.device", PropertyType::Int),
+];
+
+fn schema_type(key: &str) -> Option<PropertyType> {
+    SCHEMA.iter().find_map(|(k, t)| (*k == key).then_some(*t))
+}
+
+/// The type of a property's value, [`PropertyType::String`] if it's not known
+pub fn property_type(key: &str) -> PropertyType {
+    schema_type(key).unwrap_or(PropertyType::String)
+}
+
+/// Parses booleans like PipeWire does, which also accepts 1 for true
+pub fn parse_bool(value: &str) -> Option<bool> {
+    match value.trim() {
+        "true" | "1" => Some(true),
+        "false" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+/// A property value parsed according to its type
+pub enum TypedValue<'a> {
+    Int(i64),
+    Bool(bool),
+    String(&'a str),
+}
+
+/// Parses a value according to the type of its property.
+/// Returns `None` if the property isn't known. Values that fail to parse are kept as strings.
+pub fn typed_value<'a>(key: &str, value: &'a str) -> Option<TypedValue<'a>> {
+    Some(match schema_type(key)? {
+        PropertyType::Int => value
+            .trim()
+            .parse()
+            .map_or(TypedValue::String(value), TypedValue::Int),
+        PropertyType::Bool => parse_bool(value).map_or(TypedValue::String(value), TypedValue::Bool),
+        PropertyType::Enum(_) | PropertyType::String => TypedValue::String(value),
+    })
+}
@@ -0,0 +1,304 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::collections::BTreeMap;
+
+use eframe::egui;
+use pipewire::spa::param::ParamType;
+
+use crate::{
+    backend::{
+        self,
+        pods::props::{self, ParamValue},
+        ObjectMethod, Request,
+    },
+    schema,
+};
+
+pub const COMMON_RATES: [u32; 6] = [44100, 48000, 88200, 96000, 176_400, 192_000];
+const FORMATS: [&str; 5] = ["S16LE", "S24LE", "S24_32LE", "S32LE", "F32LE"];
+
+struct IntParam {
+    key: &'static str,
+    label: &'static str,
+    explanation: &'static str,
+    value: Option<i32>,
+}
+
+/// Editor for the runtime parameters of ALSA nodes
+pub struct AlsaParams {
+    ints: [IntParam; 4],
+    rate: Option<i32>,
+    allowed_rates: Option<Vec<u32>>,
+    format: Option<String>,
+    disable_batch: Option<bool>,
+}
+
+/// Whether the properties belong to an object created by the ALSA SPA plugin
+pub fn is_alsa(props: &BTreeMap<String, String>) -> bool {
+    props.get("device.api").is_some_and(|api| api == "alsa")
+        || props.contains_key("api.alsa.path")
+        || props
+            .get("factory.name")
+            .is_some_and(|f| f.starts_with("api.alsa."))
+}
+
+pub fn parse_rates(s: &str) -> Vec<u32> {
+    s.trim_matches(|c: char| c == '[' || c == ']' || c.is_whitespace())
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter_map(|r| r.parse().ok())
+        .collect()
+}
+
+impl AlsaParams {
+    pub fn from_props(props: &BTreeMap<String, String>) -> Self {
+        let int = |key: &str| props.get(key).and_then(|v| v.parse().ok());
+
+        Self {
+            ints: [
+                IntParam {
+                    key: "api.alsa.period-size",
+                    label: "Period size",
+                    explanation: "Number of frames the device processes in one go. \
+                        Lower values reduce latency but make xruns more likely.",
+                    value: int("api.alsa.period-size"),
+                },
+                IntParam {
+                    key: "api.alsa.period-num",
+                    label: "Periods",
+                    explanation: "Number of periods in the device's buffer",
+                    value: int("api.alsa.period-num"),
+                },
+                IntParam {
+                    key: "api.alsa.headroom",
+                    label: "Headroom",
+                    explanation: "Extra frames to keep in the device's buffer. \
+                        Increasing this often fixes crackling on USB and HDMI devices, at the cost of latency.",
+                    value: int("api.alsa.headroom"),
+                },
+                IntParam {
+                    key: "api.alsa.start-delay",
+                    label: "Start delay",
+                    explanation: "Frames of silence to write when starting the device. \
+                        Helps with devices that need time to start up.",
+                    value: int("api.alsa.start-delay"),
+                },
+            ],
+            rate: int("audio.rate"),
+            allowed_rates: props
+                .get("audio.allowed-rates")
+                .map(|rates| parse_rates(rates)),
+            format: props.get("audio.format").cloned(),
+            disable_batch: props
+                .get("api.alsa.disable-batch")
+                .and_then(|b| schema::parse_bool(b)),
+        }
+    }
+
+    fn params(&self) -> Vec<(String, ParamValue)> {
+        let mut params = Vec::new();
+
+        for int in &self.ints {
+            if let Some(value) = int.value {
+                params.push((int.key.to_owned(), ParamValue::Int(value)));
+            }
+        }
+
+        if let Some(rate) = self.rate {
+            params.push(("audio.rate".to_owned(), ParamValue::Int(rate)));
+        }
+
+        if let Some(rates) = &self.allowed_rates {
+            let rates = rates
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(" ");
+            params.push((
+                "audio.allowed-rates".to_owned(),
+                ParamValue::String(format!("[ {rates} ]")),
+            ));
+        }
+
+        if let Some(format) = &self.format {
+            params.push(("audio.format".to_owned(), ParamValue::String(format.clone())));
+        }
+
+        if let Some(disable_batch) = self.disable_batch {
+            params.push((
+                "api.alsa.disable-batch".to_owned(),
+                ParamValue::Bool(disable_batch),
+            ));
+        }
+
+        params
+    }
+
+    /// The parameters that have been set, in their string representation
+    pub fn adjusted(&self) -> Vec<(String, String)> {
+        self.params()
+            .into_iter()
+            .map(|(k, v)| {
+                let v = match v {
+                    ParamValue::Int(i) => i.to_string(),
+                    ParamValue::Bool(b) => b.to_string(),
+                    ParamValue::String(s) => s,
+                };
+                (k, v)
+            })
+            .collect()
+    }
+
+    /// Shows the editor. The parameters are applied to all of the `targets` nodes.
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        sx: &backend::Sender,
+        targets: impl Iterator<Item = u32>,
+    ) {
+        ui.label("Unchecked parameters are left unchanged");
+
+        egui::Grid::new("alsa_params")
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |ui| {
+                for int in &mut self.ints {
+                    let mut set = int.value.is_some();
+                    if ui
+                        .checkbox(&mut set, int.label)
+                        .on_hover_text(int.explanation)
+                        .changed()
+                    {
+                        int.value = set.then_some(0);
+                    }
+                    if let Some(value) = int.value.as_mut() {
+                        ui.add(egui::DragValue::new(value).clamp_range(0..=65536).suffix(" frames"));
+                    } else {
+                        ui.label("");
+                    }
+                    ui.end_row();
+                }
+
+                let mut set = self.rate.is_some();
+                if ui
+                    .checkbox(&mut set, "Rate")
+                    .on_hover_text("Sample rate to open the device with. 0 follows the graph's rate.")
+                    .changed()
+                {
+                    self.rate = set.then_some(0);
+                }
+                if let Some(rate) = self.rate.as_mut() {
+                    ui.add(egui::DragValue::new(rate).clamp_range(0..=768_000).suffix(" Hz"));
+                } else {
+                    ui.label("");
+                }
+                ui.end_row();
+
+                let mut set = self.allowed_rates.is_some();
+                if ui
+                    .checkbox(&mut set, "Allowed rates")
+                    .on_hover_text(
+                        "Rates the device can be switched to when the graph's rate changes. \
+                        Avoids resampling for streams with these rates.",
+                    )
+                    .changed()
+                {
+                    self.allowed_rates = set.then(|| vec![48000]);
+                }
+                if let Some(allowed_rates) = self.allowed_rates.as_mut() {
+                    ui.horizontal_wrapped(|ui| {
+                        for rate in COMMON_RATES {
+                            let mut allowed = allowed_rates.contains(&rate);
+                            if ui.toggle_value(&mut allowed, rate.to_string()).changed() {
+                                if allowed {
+                                    allowed_rates.push(rate);
+                                    allowed_rates.sort_unstable();
+                                } else {
+                                    allowed_rates.retain(|r| *r != rate);
+                                }
+                            }
+                        }
+                    });
+                } else {
+                    ui.label("");
+                }
+                ui.end_row();
+
+                let mut set = self.format.is_some();
+                if ui
+                    .checkbox(&mut set, "Format")
+                    .on_hover_text("Sample format to open the device with")
+                    .changed()
+                {
+                    self.format = set.then(|| FORMATS[0].to_owned());
+                }
+                if let Some(format) = self.format.as_mut() {
+                    egui::ComboBox::from_id_source("alsa_format")
+                        .selected_text(format.as_str())
+                        .show_ui(ui, |ui| {
+                            for f in FORMATS {
+                                ui.selectable_value(format, f.to_owned(), f);
+                            }
+                        });
+                } else {
+                    ui.label("");
+                }
+                ui.end_row();
+
+                let mut set = self.disable_batch.is_some();
+                if ui
+                    .checkbox(&mut set, "Disable batch")
+                    .on_hover_text(
+                        "Treat the device as one that doesn't transfer data in batches. \
+                        Can reduce latency on some USB devices.",
+                    )
+                    .changed()
+                {
+                    self.disable_batch = set.then_some(false);
+                }
+                if let Some(disable_batch) = self.disable_batch.as_mut() {
+                    ui.checkbox(disable_batch, "");
+                } else {
+                    ui.label("");
+                }
+                ui.end_row();
+            });
+
+        let params = self.params();
+
+        ui.add_enabled_ui(!params.is_empty(), |ui| {
+            if ui
+                .button("Apply")
+                .on_hover_text("Some parameters only take effect after the device is restarted")
+                .clicked()
+            {
+                if let Some(pod) = props::params(params) {
+                    for id in targets {
+                        sx.send(Request::CallObjectMethod(
+                            id,
+                            ObjectMethod::SetParam {
+                                id: ParamType::Props,
+                                flags: 0,
+                                pod: pod.clone(),
+                            },
+                        ))
+                        .ok();
+                    }
+                }
+            }
+        });
+    }
+}
@@ -14,21 +14,38 @@
 //
 // SPDX-License-Identifier: GPL-3.0-only
 
+use std::sync::mpsc;
+
 use eframe::egui;
 use egui_dock::DockState;
 
 #[cfg(feature = "xdg_desktop_portals")]
-use ashpd::{desktop::screencast::SourceType, enumflags2::BitFlags};
-
-use crate::{backend::RemoteInfo, ui::util::uis::EditableKVList};
+use ashpd::{
+    desktop::{remote_desktop::DeviceType, screencast::SourceType},
+    enumflags2::BitFlags,
+};
+
+use crate::{
+    backend::RemoteInfo,
+    control::Call,
+    instance::{Args, Focus},
+    redact,
+    ui::{
+        control, shortcuts,
+        theme::ThemeSettings,
+        util::{config, uis::EditableKVList},
+        DaemonRestart, ToolRegistry,
+    },
+};
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "persistence", derive(serde::Serialize, serde::Deserialize))]
 pub enum View {
     GlobalTracker = 1 << 0,
     Profiler = 1 << 1,
     ProcessViewer = 1 << 2,
     Graph = 1 << 3,
+    Remotes = 1 << 4,
 }
 
 impl View {
@@ -38,12 +55,16 @@ impl View {
             Self::ProcessViewer => "Process Viewer",
             Self::GlobalTracker => "Global Tracker",
             Self::Graph => "Graph",
+            Self::Remotes => "Remotes",
         }
     }
 }
 
 mod inspector {
-    use std::rc::Rc;
+    use std::{
+        cell::RefCell,
+        rc::{Rc, Weak},
+    };
 
     use eframe::egui;
 
@@ -51,14 +72,54 @@ mod inspector {
 
     use crate::{
         backend::{self, Event, RemoteInfo},
+        instance::Focus,
         ui::{
-            globals_store::ObjectData, util::persistence::PersistentView, ContextManager,
-            GlobalsStore, Graph, MetadataEditor, ObjectCreator, Profiler, Windowed,
+            compat, control,
+            globals_store::{Global, ObjectData},
+            journal,
+            pins::{Pin, Pins},
+            register_tools,
+            shortcuts::{self, Action},
+            util::persistence::PersistentView,
+            ChurnStats, ClientPermissions, ConfigRecipe, ConnectionEvent, ContextManager,
+            DebugBundle, Defaults, EventLog, Export, GlobalEvent, GlobalsStore, Graph, HealthPanel,
+            MetadataChange, MetadataEditor, NetworkDiscovery, NodeCompare, NoiseSuppression,
+            ObjectCreator, OwnClient, PermissionTemplates, Profiler, Remotes, SessionRecorder,
+            SettingsEditor, Snapshots, SpatializerWizard, StatusBar, ToolRegistry, UndoHistory,
         },
     };
 
     use super::View;
 
+    const VIEWS: [(View, &str, &str); 5] = [
+        (
+            View::GlobalTracker,
+            "📑 Global Tracker",
+            "List of all the objects in the remote",
+        ),
+        (View::Profiler, "📈 Profiler", "Graphs of profiling data"),
+        (
+            View::ProcessViewer,
+            "⏱ Process Viewer",
+            "Performance measurements of running nodes",
+        ),
+        (View::Graph, "🖧 Graph", "Visual representation of the graph"),
+        (
+            View::Remotes,
+            "🔗 Remotes",
+            "The objects of other remotes alongside the main connection",
+        ),
+    ];
+
+    /// Name of a view or tool for the command line, e.g. "📈 Profiler" -> "profiler"
+    fn cli_name(title: &str) -> String {
+        title
+            .split_once(' ')
+            .map_or(title, |(_, name)| name)
+            .to_lowercase()
+            .replace(' ', "-")
+    }
+
     /// Stores the persistent view states
     #[derive(Default)]
     #[cfg_attr(
@@ -68,6 +129,18 @@ mod inspector {
     )]
     pub struct ViewsData {
         graph: Option<<Graph as PersistentView>::Data>,
+        object_creator: Option<<ObjectCreator as PersistentView>::Data>,
+        metadata_editor: Option<<MetadataEditor as PersistentView>::Data>,
+        globals_store: Option<<GlobalsStore as PersistentView>::Data>,
+        profiler: Option<<Profiler as PersistentView>::Data>,
+        context_manager: Option<<ContextManager as PersistentView>::Data>,
+        permission_templates: Option<<PermissionTemplates as PersistentView>::Data>,
+        client_permissions: Option<<ClientPermissions as PersistentView>::Data>,
+        // Command line names of the open tools
+        open_tools: Option<Vec<String>>,
+        detail_panel: Option<bool>,
+        // Pins by ID aren't kept since IDs are reused
+        pins: Option<Vec<Pin>>,
     }
 
     /// Holds all of the UIs, and their states, for interacting with PipeWire.
@@ -78,10 +151,20 @@ mod inspector {
         globals: GlobalsStore,
         profiler: Profiler,
         graph: Graph,
+        remotes: Remotes,
+        status_bar: StatusBar,
 
-        object_creator: Windowed<ObjectCreator>,
-        metadata_editor: Windowed<MetadataEditor>,
-        context_manager: Windowed<ContextManager>,
+        tools: ToolRegistry,
+
+        // Shown in a window, requested from the command line
+        focused: Option<Weak<RefCell<Global>>>,
+        // Whether the selected object is shown in a side panel
+        detail_panel: bool,
+        // The object to be destroyed once confirmed, requested with its shortcut
+        destroy_confirmation: Option<u32>,
+
+        // Whether the backend is connected, the data shown is stale otherwise
+        connected: bool,
     }
 
     impl Inspector {
@@ -89,26 +172,138 @@ mod inspector {
             remote: RemoteInfo,
             mainloop_properties: Vec<(String, String)>,
             context_properties: Vec<(String, String)>,
+            client_properties: Vec<(String, String)>,
             views_data: Option<&ViewsData>,
+            tool_plugins: &[fn(&mut ToolRegistry)],
         ) -> Self {
-            Self {
+            let mut tools = ToolRegistry::default();
+            tools.register(
+                views_data
+                    .and_then(|vd| vd.object_creator.as_ref())
+                    .map(ObjectCreator::with_data)
+                    .unwrap_or_default(),
+            );
+            tools.register(
+                views_data
+                    .and_then(|vd| vd.metadata_editor.as_ref())
+                    .map(MetadataEditor::with_data)
+                    .unwrap_or_default(),
+            );
+            tools.register(
+                views_data
+                    .and_then(|vd| vd.context_manager.as_ref())
+                    .map(ContextManager::with_data)
+                    .unwrap_or_default(),
+            );
+            tools.register(NetworkDiscovery::default());
+            tools.register(SpatializerWizard::default());
+            tools.register(NoiseSuppression::default());
+            tools.register(SettingsEditor::default());
+            tools.register(ChurnStats::default());
+            tools.register(EventLog::default());
+            tools.register(HealthPanel::default());
+            tools.register(NodeCompare::default());
+            tools.register(OwnClient::with_pending_properties(
+                client_properties.clone(),
+            ));
+            tools.register(DebugBundle::new(
+                mainloop_properties.clone(),
+                context_properties.clone(),
+                client_properties,
+            ));
+            tools.register(Export::default());
+            tools.register(ConfigRecipe::default());
+            tools.register(Snapshots::default());
+            tools.register(SessionRecorder::default());
+            tools.register(Defaults::default());
+            tools.register(
+                views_data
+                    .and_then(|vd| vd.permission_templates.as_ref())
+                    .map(PermissionTemplates::with_data)
+                    .unwrap_or_default(),
+            );
+            tools.register(
+                views_data
+                    .and_then(|vd| vd.client_permissions.as_ref())
+                    .map(ClientPermissions::with_data)
+                    .unwrap_or_default(),
+            );
+            tools.register(UndoHistory);
+            register_tools(&mut tools);
+            for register in tool_plugins {
+                register(&mut tools);
+            }
+
+            let mut inspector = Self {
                 handle: backend::Handle::run(remote, mainloop_properties, context_properties),
 
-                globals: GlobalsStore::new(),
-                profiler: Profiler::with_max_profilings(250),
+                globals: views_data
+                    .and_then(|vd| vd.globals_store.as_ref())
+                    .map_or_else(GlobalsStore::new, GlobalsStore::with_data),
+                profiler: views_data
+                    .and_then(|vd| vd.profiler.as_ref())
+                    .map_or_else(|| Profiler::with_max_profilings(250), Profiler::with_data),
                 graph: views_data
                     .and_then(|vd| vd.graph.as_ref())
                     .map_or_else(Graph::new, Graph::with_data),
+                remotes: Remotes::default(),
+                status_bar: StatusBar::default(),
+
+                tools,
 
-                object_creator: Windowed::default(),
-                metadata_editor: Windowed::default(),
-                context_manager: Windowed::default(),
+                focused: None,
+                detail_panel: views_data.and_then(|vd| vd.detail_panel).unwrap_or(true),
+                destroy_confirmation: None,
+
+                connected: true,
+            };
+
+            if let Some(open_tools) = views_data.and_then(|vd| vd.open_tools.as_ref()) {
+                for (open, title, _) in inspector.tools() {
+                    *open = open_tools.contains(&cli_name(title));
+                }
+            }
+            if let Some(pins) = views_data.and_then(|vd| vd.pins.clone()) {
+                inspector.globals.view_state_mut().pins = Pins::new(pins);
             }
+
+            inspector
         }
 
-        pub fn save_data(&self, data: &mut Option<ViewsData>) {
+        pub fn save_data(&mut self, data: &mut Option<ViewsData>) {
             let new_data = ViewsData {
                 graph: self.graph.save_data(),
+                object_creator: self
+                    .tools
+                    .get::<ObjectCreator>()
+                    .and_then(ObjectCreator::save_data),
+                metadata_editor: self
+                    .tools
+                    .get::<MetadataEditor>()
+                    .and_then(MetadataEditor::save_data),
+                globals_store: self.globals.save_data(),
+                profiler: self.profiler.save_data(),
+                context_manager: self
+                    .tools
+                    .get::<ContextManager>()
+                    .and_then(ContextManager::save_data),
+                permission_templates: self
+                    .tools
+                    .get::<PermissionTemplates>()
+                    .and_then(PermissionTemplates::save_data),
+                client_permissions: self
+                    .tools
+                    .get::<ClientPermissions>()
+                    .and_then(ClientPermissions::save_data),
+                open_tools: Some(
+                    self.tools()
+                        .into_iter()
+                        .filter(|(open, ..)| **open)
+                        .map(|(_, title, _)| cli_name(title))
+                        .collect(),
+                ),
+                detail_panel: Some(self.detail_panel),
+                pins: Some(self.globals.view_state().pins.saved()),
             };
 
             match data {
@@ -116,11 +311,63 @@ mod inspector {
                     if let Some(graph) = new_data.graph {
                         data.graph = Some(graph);
                     }
+                    if let Some(object_creator) = new_data.object_creator {
+                        data.object_creator = Some(object_creator);
+                    }
+                    if let Some(metadata_editor) = new_data.metadata_editor {
+                        data.metadata_editor = Some(metadata_editor);
+                    }
+                    if let Some(globals_store) = new_data.globals_store {
+                        data.globals_store = Some(globals_store);
+                    }
+                    if let Some(profiler) = new_data.profiler {
+                        data.profiler = Some(profiler);
+                    }
+                    if let Some(context_manager) = new_data.context_manager {
+                        data.context_manager = Some(context_manager);
+                    }
+                    if let Some(permission_templates) = new_data.permission_templates {
+                        data.permission_templates = Some(permission_templates);
+                    }
+                    if let Some(client_permissions) = new_data.client_permissions {
+                        data.client_permissions = Some(client_permissions);
+                    }
+                    data.open_tools = new_data.open_tools;
+                    data.detail_panel = new_data.detail_panel;
+                    data.pins = new_data.pins;
                 }
                 None => *data = Some(new_data),
             }
         }
 
+        /// The state of the views, to be restored with [`Self::apply_workspace`]
+        pub fn workspace_data(&mut self) -> ViewsData {
+            let mut data = None;
+            self.save_data(&mut data);
+            data.unwrap_or_default()
+        }
+
+        /// Opens the tools of a workspace and applies its filters and layouts to the views
+        pub fn apply_workspace(&mut self, data: &ViewsData) {
+            if let Some(open_tools) = &data.open_tools {
+                for (open, title, _) in self.tools() {
+                    *open = open_tools.contains(&cli_name(title));
+                }
+            }
+            if let Some(detail_panel) = data.detail_panel {
+                self.detail_panel = detail_panel;
+            }
+            if let Some(globals_store) = &data.globals_store {
+                self.globals.set_data(globals_store);
+            }
+            if let Some(profiler) = &data.profiler {
+                self.profiler.set_data(profiler);
+            }
+            if let Some(graph) = &data.graph {
+                self.graph.set_data(graph);
+            }
+        }
+
         pub fn views_menu_buttons(
             &mut self,
             ui: &mut egui::Ui,
@@ -131,20 +378,7 @@ mod inspector {
                 .fold(0, |acc, (_, &tab)| acc | tab as u8);
 
             ui.menu_button("View", |ui| {
-                for (tab, title, description) in [
-                    (
-                        View::GlobalTracker,
-                        "📑 Global Tracker",
-                        "List of all the objects in the remote",
-                    ),
-                    (View::Profiler, "📈 Profiler", "Graphs of profiling data"),
-                    (
-                        View::ProcessViewer,
-                        "⏱ Process Viewer",
-                        "Performance measurements of running nodes",
-                    ),
-                    (View::Graph, "🖧 Graph", "Visual representation of the graph"),
-                ] {
+                for &(tab, title, description) in &VIEWS {
                     let open = open_tabs & tab as u8 != 0;
 
                     ui.add_enabled_ui(!open, |ui| {
@@ -157,41 +391,328 @@ mod inspector {
                         }
                     });
                 }
+
+                ui.separator();
+
+                ui.checkbox(&mut self.detail_panel, "Detail panel")
+                    .on_hover_text("Show the selected object in a panel on the right");
             });
         }
 
+        fn tools(&mut self) -> Vec<(&mut bool, &'static str, &'static str)> {
+            self.tools.entries().collect()
+        }
+
+        /// Registers more tools, after the ones already in the Tools menu
+        pub fn add_tools(&mut self, register: fn(&mut ToolRegistry)) {
+            register(&mut self.tools);
+        }
+
         pub fn tools_menu_buttons(&mut self, ui: &mut egui::Ui) {
             ui.menu_button("Tools", |ui| {
-                for (open, name, description) in [
-                    (
-                        &mut self.object_creator.open,
-                        "⛭ Object Creator",
-                        "Create an object on the remote",
-                    ),
-                    (
-                        &mut self.metadata_editor.open,
-                        "🗐 Metadata Editor",
-                        "Edit remote metadata",
-                    ),
-                    (
-                        &mut self.context_manager.open,
-                        "🗄 Context Manager",
-                        "Manage the PipeWire context",
-                    ),
-                ] {
+                for (open, name, description) in self.tools() {
                     ui.toggle_value(open, name).on_hover_text(description);
                 }
+
+                ui.separator();
+
+                if ui
+                    .button("↩ Revert permission changes")
+                    .on_hover_text(
+                        "Restore the permissions of all clients whose permissions \
+                        were changed in this session",
+                    )
+                    .clicked()
+                {
+                    for global in self.globals.globals() {
+                        global.borrow_mut().revert_permissions(&self.handle.sx);
+                    }
+                }
             });
         }
 
+        /// Opens a view or tool by its command line name.
+        /// Returns `false` if there's no view or tool with that name.
+        pub fn open_tool(
+            &mut self,
+            name: &str,
+            dock_state: &mut egui_dock::DockState<View>,
+        ) -> bool {
+            if let Some(&(view, ..)) = VIEWS.iter().find(|(_, title, _)| cli_name(title) == name) {
+                match dock_state.find_tab(&view) {
+                    Some(location) => dock_state.set_active_tab(location),
+                    None => dock_state.push_to_focused_leaf(view),
+                }
+                return true;
+            }
+
+            let Some((open, ..)) = self
+                .tools()
+                .into_iter()
+                .find(|(_, title, _)| cli_name(title) == name)
+            else {
+                return false;
+            };
+            *open = true;
+
+            true
+        }
+
+        pub fn handle_shortcuts(
+            &mut self,
+            ctx: &egui::Context,
+            dock_state: &mut egui_dock::DockState<View>,
+        ) {
+            journal::handle_shortcuts(ctx, &self.handle.sx);
+
+            match shortcuts::take_action(ctx) {
+                Some(Action::Search) => {
+                    match dock_state.find_tab(&View::GlobalTracker) {
+                        Some(location) => dock_state.set_active_tab(location),
+                        None => dock_state.push_to_focused_leaf(View::GlobalTracker),
+                    }
+                    shortcuts::request_search_focus();
+                }
+                Some(Action::Destroy) => {
+                    self.destroy_confirmation = self
+                        .globals
+                        .view_state()
+                        .selection
+                        .selected()
+                        .filter(|id| self.globals.get_global(*id).is_some());
+                }
+                Some(Action::SwitchTab(index)) => {
+                    let tab = dock_state.iter_all_tabs().nth(index).map(|(_, tab)| *tab);
+                    if let Some(location) = tab.and_then(|tab| dock_state.find_tab(&tab)) {
+                        dock_state.set_active_tab(location);
+                    }
+                }
+                None => {}
+            }
+
+            self.destroy_confirmation_window(ctx);
+        }
+
+        fn destroy_confirmation_window(&mut self, ctx: &egui::Context) {
+            let Some(id) = self.destroy_confirmation else {
+                return;
+            };
+            let Some(global) = self.globals.get_global(id) else {
+                self.destroy_confirmation = None;
+                return;
+            };
+
+            let mut open = true;
+            let mut close = false;
+            egui::Window::new("Destroy Object")
+                .collapsible(false)
+                .resizable(false)
+                .open(&mut open)
+                .show(ctx, |ui| {
+                    let global = global.borrow();
+                    ui.label(format!(
+                        "Destroy {} {id}{}?",
+                        global.object_type().to_str(),
+                        global
+                            .name()
+                            .map(|name| format!(" ({name})"))
+                            .unwrap_or_default()
+                    ));
+
+                    ui.horizontal(|ui| {
+                        if ui.button("Destroy").clicked() {
+                            journal::destroy_object(&self.handle.sx, id);
+                            close = true;
+                        }
+                        close |= ui.button("Cancel").clicked();
+                    });
+                });
+
+            if !open || close {
+                self.destroy_confirmation = None;
+            }
+        }
+
+        /// Copies the object whose copy button was clicked as JSON
+        pub fn handle_copy_request(&mut self, ctx: &egui::Context) {
+            self.globals.handle_copy_request(ctx);
+        }
+
+        pub fn status_bar(&mut self, ctx: &egui::Context) {
+            egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
+                self.status_bar.show(ui);
+            });
+        }
+
+        pub fn detail_panel(&mut self, ctx: &egui::Context) {
+            if !self.detail_panel {
+                return;
+            }
+
+            let global = self
+                .globals
+                .view_state()
+                .selection
+                .selected()
+                .and_then(|id| self.globals.get_global(id))
+                .cloned();
+
+            egui::SidePanel::right("detail_panel")
+                .resizable(true)
+                .default_width(400.)
+                .show(ctx, |ui| {
+                    ui.set_enabled(self.connected);
+
+                    ui.horizontal(|ui| {
+                        ui.heading("Details");
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            if ui
+                                .add_enabled(global.is_some(), egui::Button::new("Deselect"))
+                                .clicked()
+                            {
+                                self.globals.view_state_mut().selection.select(None);
+                            }
+                        });
+                    });
+
+                    let Some(global) = global else {
+                        ui.label("Select an object with 🎯 or in the graph to show it here");
+                        return;
+                    };
+
+                    let id = global.borrow().id();
+                    let mut select = None;
+                    ui.horizontal_wrapped(|ui| {
+                        for (i, g) in self.globals.hierarchy(id).into_iter().enumerate() {
+                            if i != 0 {
+                                ui.label("▸");
+                            }
+
+                            let g = g.borrow();
+                            let text = match g.name() {
+                                Some(name) => format!("{} {name}", g.object_type().to_str()),
+                                None => format!("{} {}", g.object_type().to_str(), g.id()),
+                            };
+                            if g.id() == id {
+                                ui.strong(text);
+                            } else if ui.link(text).on_hover_text(g.id().to_string()).clicked() {
+                                select = Some(g.id());
+                            }
+                        }
+                    });
+                    if select.is_some() {
+                        self.globals.view_state_mut().selection.select(select);
+                    }
+
+                    ui.separator();
+
+                    egui::ScrollArea::vertical().show(ui, |ui| {
+                        ui.with_layout(egui::Layout::top_down_justified(egui::Align::Min), |ui| {
+                            global.borrow_mut().show(
+                                ui,
+                                true,
+                                &self.handle.sx,
+                                Some(self.globals.view_state_mut()),
+                            );
+                        });
+                    });
+                });
+        }
+
         pub fn tool_windows(&mut self, ctx: &egui::Context) {
-            self.object_creator.window(ctx, &self.handle.sx);
-            self.metadata_editor.window(ctx, &self.handle.sx);
-            self.context_manager.window(ctx, &self.handle.sx);
+            // The recipe includes the modules loaded from the context manager
+            if self
+                .tools
+                .get_mut::<ConfigRecipe>()
+                .is_some_and(ConfigRecipe::take_generate_request)
+            {
+                let modules = self
+                    .tools
+                    .get::<ContextManager>()
+                    .map(|context_manager| context_manager.loaded_modules().to_vec())
+                    .unwrap_or_default();
+                if let Some(config_recipe) = self.tools.get_mut::<ConfigRecipe>() {
+                    config_recipe.generate(&self.globals, &modules);
+                }
+            }
+
+            self.tools.windows(ctx, &self.globals, &self.handle.sx);
+
+            let selection = &mut self.globals.view_state_mut().selection;
+            if selection.take_changed() {
+                let selected = selection.selected();
+                self.graph.select(selected);
+                self.tools.dispatch_selection(selected);
+                if let Some(id) = selected {
+                    self.globals.scroll_to(id);
+                }
+                // The detail panel shows the selection instead
+                if !self.detail_panel {
+                    self.focused = selected
+                        .and_then(|id| self.globals.get_global(id))
+                        .map(Rc::downgrade);
+                }
+            }
+
+            if let Some(global) = self.focused.as_ref().and_then(Weak::upgrade) {
+                let mut open = true;
+                egui::Window::new("Focused Object")
+                    .vscroll(true)
+                    .open(&mut open)
+                    .show(ctx, |ui| {
+                        global.borrow_mut().show(
+                            ui,
+                            true,
+                            &self.handle.sx,
+                            Some(self.globals.view_state_mut()),
+                        );
+                    });
+
+                if !open {
+                    self.focused = None;
+                    self.globals.view_state_mut().selection.select(None);
+                }
+            }
+        }
+
+        /// Handles a command of the control interface and returns the response
+        pub fn handle_control(&mut self, command: &str) -> String {
+            control::handle(
+                command,
+                &self.globals,
+                |id| self.globals.metadata_properties(id),
+                &self.handle.sx,
+            )
+        }
+
+        /// Shows the info of the first object that matches.
+        /// Returns `false` if there's no such object yet.
+        pub fn focus(&mut self, focus: &Focus) -> bool {
+            const NAME_KEYS: [&str; 3] = ["node.name", "node.description", "application.name"];
+
+            let global = self.globals.globals().find(|global| {
+                let global = global.borrow();
+                let props = global.props();
+                match focus {
+                    Focus::Node(name) => {
+                        *global.object_type() == ObjectType::Node
+                            && NAME_KEYS
+                                .iter()
+                                .any(|key| props.get(*key).is_some_and(|v| v == name))
+                    }
+                    Focus::Property { key, value } => props.get(key).is_some_and(|v| v == value),
+                }
+            });
+
+            self.focused = global.map(Rc::downgrade);
+
+            self.focused.is_some()
         }
 
         #[must_use = "Indicates whether the connection to the backend has ended"]
         pub fn process_events_or_stop(&mut self) -> bool {
+            self.remotes.process_events();
+
             while let Ok(e) = self.handle.rx.try_recv() {
                 match e {
                     Event::Stop => return true,
@@ -199,42 +720,43 @@ mod inspector {
                 }
             }
 
+            // The views of the main connection are shown after this
+            compat::set_daemon_version(self.globals.daemon_version());
+
             false
         }
 
         fn process_event(&mut self, e: Event) {
+            journal::process_event(&e);
+
             match e {
                 Event::GlobalAdded(id, object_type, props) => {
                     let global = self.globals.add_global(id, object_type, props);
-                    let global_borrow = global.borrow();
-
-                    if global_borrow.props().is_empty() {
-                        return;
-                    }
 
-                    match *global_borrow.object_type() {
-                        ObjectType::Factory => {
-                            self.object_creator.tool.add_factory(global);
-                        }
-                        ObjectType::Metadata => self.metadata_editor.tool.add_metadata(global),
-
-                        _ => {}
-                    }
+                    self.tools
+                        .dispatch(GlobalEvent::Added(global), &self.handle.sx);
                 }
                 Event::GlobalRemoved(id) => {
                     if let Some(removed) = self.globals.remove_global(id) {
-                        match *removed.borrow().object_type() {
-                            ObjectType::Metadata => {
-                                self.metadata_editor.tool.remove_metadata(id);
-                            }
-                            ObjectType::Factory => {
-                                self.object_creator.tool.remove_factory(id);
-                            }
-                            _ => {}
+                        self.tools.dispatch(
+                            GlobalEvent::Removed {
+                                id,
+                                global: &removed,
+                            },
+                            &self.handle.sx,
+                        );
+
+                        if *removed.borrow().object_type() == ObjectType::Node {
+                            self.status_bar.remove_driver(id);
                         }
                     }
                     self.graph.remove_item(id);
                 }
+                Event::GlobalRestricted { id, message } => {
+                    if let Some(global) = self.globals.get_global(id) {
+                        global.borrow_mut().set_restricted(message);
+                    }
+                }
                 Event::GlobalInfo(id, info) => {
                     let Some(global) = self.globals.get_global(id) else {
                         return;
@@ -254,7 +776,12 @@ mod inspector {
                                         "Input" => {
                                             self.graph.add_input_port(id, parent, name);
                                         }
-                                        "Output" => self.graph.add_output_port(id, parent, name),
+                                        "Output" => self.graph.add_output_port(
+                                            id,
+                                            parent,
+                                            name,
+                                            global_borrow.is_monitor_port(),
+                                        ),
                                         _ => {}
                                     }
                                 }
@@ -270,12 +797,31 @@ mod inspector {
                         }
                     }
 
-                    global.borrow_mut().set_info(Some(info));
+                    let previous = global.borrow_mut().set_info(Some(info));
+                    self.tools.dispatch(
+                        GlobalEvent::InfoChanged(global, previous.as_deref()),
+                        &self.handle.sx,
+                    );
                 }
                 Event::GlobalProperties(id, props) => {
                     self.globals.set_global_props(id, props);
+
+                    if let Some(global) = self.globals.get_global(id) {
+                        self.tools
+                            .dispatch(GlobalEvent::PropertiesChanged(global), &self.handle.sx);
+                    }
+                }
+                Event::GlobalParam(id, param_type, index, param) => {
+                    let Some(global) = self.globals.get_global(id) else {
+                        return;
+                    };
+
+                    global.borrow_mut().set_param(param_type, index, param);
                 }
                 Event::ProfilerProfile(samples) => {
+                    self.tools.dispatch_profiler_samples(&samples);
+                    self.status_bar.add_profilings(&samples);
+                    self.globals.add_profilings(&samples);
                     self.profiler.add_profilings(samples, |id| {
                         id.try_into()
                             .ok()
@@ -289,35 +835,90 @@ mod inspector {
                     key,
                     type_,
                     value,
-                } => match key {
-                    Some(key) => match value {
-                        Some(value) => {
-                            let Some(metadata) = self.globals.get_global(id) else {
-                                return;
-                            };
-                            self.metadata_editor
-                                .tool
-                                .add_property(metadata, subject, key, type_, value);
-                        }
-                        None => {
-                            self.metadata_editor.tool.remove_property(id, &key);
-                        }
-                    },
-                    None => {
-                        self.metadata_editor.tool.clear_properties(id);
-                    }
-                },
+                } => {
+                    self.globals.set_metadata_property(
+                        id,
+                        subject,
+                        key.as_deref(),
+                        type_.as_deref(),
+                        value.as_deref(),
+                    );
+
+                    let Some(metadata) = self.globals.get_global(id) else {
+                        return;
+                    };
+                    self.tools.dispatch_metadata_property(
+                        MetadataChange {
+                            metadata,
+                            subject,
+                            subject_global: self.globals.get_global(subject),
+                            key: key.as_deref(),
+                            type_: type_.as_deref(),
+                            value: value.as_deref(),
+                        },
+                        &self.handle.sx,
+                    );
+                }
                 Event::ClientPermissions(id, _, perms) => {
                     if let Some(global) = self.globals.get_global(id) {
-                        if let ObjectData::Client { permissions, .. } =
-                            global.borrow_mut().object_data_mut()
+                        if let ObjectData::Client {
+                            permissions,
+                            fetched_permissions,
+                            ..
+                        } = global.borrow_mut().object_data_mut()
                         {
+                            if fetched_permissions.is_none() {
+                                *fetched_permissions = Some(perms.clone());
+                            }
                             *permissions = Some(perms);
                         }
                     }
                 }
                 Event::ContextProperties(properties) => {
-                    self.context_manager.tool.set_context_properties(properties);
+                    self.tools.dispatch_connection_event(
+                        ConnectionEvent::ContextProperties(&properties),
+                        &self.handle.sx,
+                    );
+                }
+                // Not requested by the GUI
+                Event::SyncDone(_) => {}
+                Event::InitialSyncDone => {
+                    self.globals.initial_sync_done();
+                    self.tools.dispatch_connection_event(
+                        ConnectionEvent::InitialSyncDone,
+                        &self.handle.sx,
+                    );
+                    self.status_bar.initial_sync_done();
+                }
+                Event::Disconnected => {
+                    self.connected = false;
+                    self.status_bar.disconnected();
+                }
+                Event::Reconnected => {
+                    // The IDs of the globals of the lost connection may be reused by the new one
+                    let ids: Vec<u32> = self.globals.globals().map(|g| g.borrow().id()).collect();
+                    for id in ids {
+                        self.process_event(Event::GlobalRemoved(id));
+                    }
+
+                    self.globals.reconnected();
+                    self.tools
+                        .dispatch_connection_event(ConnectionEvent::Reconnected, &self.handle.sx);
+                    self.status_bar.reconnected();
+
+                    self.connected = true;
+                }
+                Event::Error { id, error } => {
+                    let Some(global) = self.globals.get_global(id) else {
+                        return;
+                    };
+
+                    // Profilers send samples, other objects send params
+                    if *global.borrow().object_type() == ObjectType::Profiler {
+                        self.profiler.set_parse_error(id, error);
+                    } else {
+                        global.borrow_mut().set_param_error(error);
+                    }
                 }
                 Event::Stop => unreachable!(),
             }
@@ -328,10 +929,15 @@ mod inspector {
         type Tab = View;
 
         fn ui(&mut self, ui: &mut egui::Ui, tab: &mut Self::Tab) {
+            if !self.connected {
+                ui.set_enabled(false);
+            }
+
             match *tab {
                 View::Profiler => {
                     egui::ScrollArea::vertical().show(ui, |ui| {
-                        self.profiler.show_profiler(ui, &self.handle.sx);
+                        self.profiler
+                            .show_profiler(ui, &self.globals, &self.handle.sx);
                     });
                 }
                 View::ProcessViewer => {
@@ -343,7 +949,14 @@ mod inspector {
                     self.globals.show(ui, &self.handle.sx);
                 }
                 View::Graph => {
-                    self.graph.show(ui, &mut self.handle.sx);
+                    self.graph.show(
+                        ui,
+                        &mut self.handle.sx,
+                        &mut self.globals.view_state_mut().selection,
+                    );
+                }
+                View::Remotes => {
+                    self.remotes.show(ui);
                 }
             }
         }
@@ -370,6 +983,7 @@ enum State {
         remote: RemoteInfo,
         mainloop_properties: EditableKVList,
         context_properties: EditableKVList,
+        client_properties: EditableKVList,
     },
 }
 
@@ -384,6 +998,7 @@ impl State {
             remote: RemoteInfo::default(),
             mainloop_properties: EditableKVList::new(),
             context_properties,
+            client_properties: EditableKVList::new(),
         }
     }
 
@@ -391,31 +1006,42 @@ impl State {
         remote: RemoteInfo,
         mainloop_properties: Vec<(String, String)>,
         context_properties: Vec<(String, String)>,
+        client_properties: Vec<(String, String)>,
         inspector_data: Option<&ViewsData>,
+        tool_plugins: &[fn(&mut ToolRegistry)],
     ) -> Self {
         Self::Connected {
             inspector: Inspector::new(
                 remote,
                 mainloop_properties,
                 context_properties,
+                client_properties,
                 inspector_data,
+                tool_plugins,
             ),
             about: false,
         }
     }
 
-    pub fn connect(&mut self, inspector_data: Option<&ViewsData>) {
+    pub fn connect(
+        &mut self,
+        inspector_data: Option<&ViewsData>,
+        tool_plugins: &[fn(&mut ToolRegistry)],
+    ) {
         if let Self::Unconnected {
             remote,
             mainloop_properties,
             context_properties,
+            client_properties,
         } = self
         {
             *self = Self::new_connected(
                 std::mem::take(remote),
                 mainloop_properties.take(),
                 context_properties.take(),
+                client_properties.take(),
                 inspector_data,
+                tool_plugins,
             );
         }
     }
@@ -426,23 +1052,211 @@ impl State {
         }
     }
 
-    fn save_inspector_data(&self, data: &mut Option<ViewsData>) {
+    fn save_inspector_data(&mut self, data: &mut Option<ViewsData>) {
         if let Self::Connected { inspector, .. } = self {
             inspector.save_data(data);
         }
     }
+
+    /// Fills the properties of the connection window with the ones of the profile
+    fn apply_profile(&mut self, profile: &PropertiesProfile) {
+        if let Self::Unconnected {
+            mainloop_properties,
+            context_properties,
+            ..
+        } = self
+        {
+            *mainloop_properties.list_mut() = profile.mainloop_properties.clone();
+            *context_properties.list_mut() = profile.context_properties.clone();
+        }
+    }
 }
 
 #[cfg(feature = "persistence")]
 mod storage_keys {
     pub const DOCK: &str = "dock";
     pub const INSPECTOR: &str = "inspector";
+    pub const REMOTE: &str = "remote";
+    pub const WORKSPACES: &str = "workspaces";
+    pub const THEME: &str = "theme";
+    pub const PROFILES: &str = "profiles";
+    pub const PROFILE: &str = "profile";
+}
+
+/// A named set of open views and tools, with their layouts and filters
+#[cfg_attr(feature = "persistence", derive(serde::Serialize, serde::Deserialize))]
+struct Workspace {
+    name: String,
+    dock_state: DockState<View>,
+    views: ViewsData,
+}
+
+/// Menu for saving the current views as workspaces and switching between them
+fn workspaces_menu_button(
+    ui: &mut egui::Ui,
+    workspaces: &mut Vec<Workspace>,
+    new_name: &mut String,
+    dock_state: &mut DockState<View>,
+    inspector: &mut Inspector,
+) {
+    ui.menu_button("Workspaces", |ui| {
+        if workspaces.is_empty() {
+            ui.weak("No saved workspaces");
+        }
+
+        workspaces.retain(|workspace| {
+            ui.horizontal(|ui| {
+                if ui
+                    .button(&workspace.name)
+                    .on_hover_text("Switch to this workspace")
+                    .clicked()
+                {
+                    *dock_state = workspace.dock_state.clone();
+                    inspector.apply_workspace(&workspace.views);
+                    ui.close_menu();
+                }
+                !ui.small_button("🗑").on_hover_text("Delete").clicked()
+            })
+            .inner
+        });
+
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(new_name)
+                    .hint_text("Name")
+                    .desired_width(150.),
+            );
+
+            let exists = workspaces.iter().any(|w| w.name == *new_name);
+            if ui
+                .add_enabled(
+                    !new_name.trim().is_empty(),
+                    egui::Button::new(if exists { "Overwrite" } else { "Save" }),
+                )
+                .on_hover_text("Save the open views and tools with their layouts and filters")
+                .clicked()
+            {
+                let workspace = Workspace {
+                    name: new_name.trim().to_owned(),
+                    dock_state: dock_state.clone(),
+                    views: inspector.workspace_data(),
+                };
+                match workspaces.iter_mut().find(|w| w.name == workspace.name) {
+                    Some(existing) => *existing = workspace,
+                    None => workspaces.push(workspace),
+                }
+                new_name.clear();
+            }
+        });
+    });
+}
+
+/// Named mainloop and context properties to connect with
+#[derive(Clone)]
+#[cfg_attr(feature = "persistence", derive(serde::Serialize, serde::Deserialize))]
+struct PropertiesProfile {
+    name: String,
+    mainloop_properties: Vec<(String, String)>,
+    context_properties: Vec<(String, String)>,
+}
+
+/// Choosing, saving and deleting profiles in the connection window
+fn profiles_ui(
+    ui: &mut egui::Ui,
+    profiles: &mut Vec<PropertiesProfile>,
+    selected: &mut Option<String>,
+    new_name: &mut String,
+    mainloop_properties: &mut EditableKVList,
+    context_properties: &mut EditableKVList,
+) {
+    ui.horizontal(|ui| {
+        egui::ComboBox::new("properties_profile", "Profile")
+            .selected_text(selected.as_deref().unwrap_or("None"))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(selected, None, "None");
+                for profile in profiles.iter() {
+                    if ui
+                        .selectable_label(selected.as_ref() == Some(&profile.name), &profile.name)
+                        .clicked()
+                    {
+                        *selected = Some(profile.name.clone());
+                        *mainloop_properties.list_mut() = profile.mainloop_properties.clone();
+                        *context_properties.list_mut() = profile.context_properties.clone();
+                    }
+                }
+            })
+            .response
+            .on_hover_text("The profile is also applied when coppwr starts and reconnects");
+
+        if let Some(name) = selected.clone() {
+            if ui
+                .small_button("🗑")
+                .on_hover_text("Delete the profile")
+                .clicked()
+            {
+                profiles.retain(|profile| profile.name != name);
+                *selected = None;
+            }
+        }
+    });
+
+    ui.horizontal(|ui| {
+        ui.add(
+            egui::TextEdit::singleline(new_name)
+                .hint_text("Profile name")
+                .desired_width(150.),
+        );
+
+        let exists = profiles.iter().any(|p| p.name == new_name.trim());
+        if ui
+            .add_enabled(
+                !new_name.trim().is_empty(),
+                egui::Button::new(if exists { "Overwrite" } else { "Save" }),
+            )
+            .on_hover_text("Save the mainloop and context properties as a profile")
+            .clicked()
+        {
+            let profile = PropertiesProfile {
+                name: new_name.trim().to_owned(),
+                mainloop_properties: mainloop_properties.list().clone(),
+                context_properties: context_properties.list().clone(),
+            };
+            *selected = Some(profile.name.clone());
+            match profiles.iter_mut().find(|p| p.name == profile.name) {
+                Some(existing) => *existing = profile,
+                None => profiles.push(profile),
+            }
+            new_name.clear();
+        }
+    });
 }
 
 pub struct App {
     dock_state: DockState<View>,
     inspector_data: Option<ViewsData>,
+    workspaces: Vec<Workspace>,
+    new_workspace_name: String,
     state: State,
+    // Name of the last remote connected to from the connection window
+    last_remote: Option<String>,
+    daemon_restart: DaemonRestart,
+    theme_settings: ThemeSettings,
+    autostart_error: Option<String>,
+
+    profiles: Vec<PropertiesProfile>,
+    // Name of the profile to connect with
+    profile: Option<String>,
+    new_profile_name: String,
+
+    instance_listener: Option<mpsc::Receiver<Args>>,
+    control_listener: Option<mpsc::Receiver<Call>>,
+    // Handled once connected, and once the object appears
+    pending_focus: Option<Focus>,
+    pending_open_tool: Option<String>,
+    // Registers the tools of forks, again on every connection
+    tool_plugins: Vec<fn(&mut ToolRegistry)>,
 }
 
 impl App {
@@ -451,12 +1265,30 @@ impl App {
         Self {
             dock_state: egui_dock::DockState::new(vec![View::Graph, View::GlobalTracker]),
             inspector_data: None,
+            workspaces: Vec::new(),
+            new_workspace_name: String::new(),
             state: State::new_connected(
                 RemoteInfo::default(),
                 Vec::new(),
                 vec![("media.category".to_owned(), "Manager".to_owned())],
+                Vec::new(),
                 None,
+                &[],
             ),
+            last_remote: None,
+            daemon_restart: DaemonRestart::default(),
+            theme_settings: ThemeSettings::default(),
+            autostart_error: None,
+
+            profiles: Vec::new(),
+            profile: None,
+            new_profile_name: String::new(),
+
+            instance_listener: None,
+            control_listener: None,
+            pending_focus: None,
+            pending_open_tool: None,
+            tool_plugins: Vec::new(),
         }
     }
 
@@ -464,26 +1296,146 @@ impl App {
     pub fn new(storage: Option<&dyn eframe::Storage>) -> Self {
         let inspector_data =
             storage.and_then(|storage| eframe::get_value(storage, storage_keys::INSPECTOR));
+        let last_remote: Option<String> =
+            storage.and_then(|storage| eframe::get_value(storage, storage_keys::REMOTE));
+        let profiles: Vec<PropertiesProfile> = storage
+            .and_then(|storage| eframe::get_value(storage, storage_keys::PROFILES))
+            .unwrap_or_default();
+        let profile: Option<String> =
+            storage.and_then(|storage| eframe::get_value(storage, storage_keys::PROFILE));
+        let (mainloop_properties, context_properties) = profiles
+            .iter()
+            .find(|p| profile.as_ref() == Some(&p.name))
+            .map_or_else(
+                || {
+                    (
+                        Vec::new(),
+                        vec![("media.category".to_owned(), "Manager".to_owned())],
+                    )
+                },
+                |p| (p.mainloop_properties.clone(), p.context_properties.clone()),
+            );
 
         Self {
             dock_state: storage
                 .and_then(|storage| eframe::get_value(storage, storage_keys::DOCK))
                 .unwrap_or_else(|| DockState::new(vec![View::Graph, View::GlobalTracker])),
+            workspaces: storage
+                .and_then(|storage| eframe::get_value(storage, storage_keys::WORKSPACES))
+                .unwrap_or_default(),
+            new_workspace_name: String::new(),
 
             state: State::new_connected(
-                RemoteInfo::default(),
+                // The environment variable takes precedence
+                last_remote
+                    .clone()
+                    .filter(|_| std::env::var_os("PIPEWIRE_REMOTE").is_none())
+                    .map_or_else(RemoteInfo::default, RemoteInfo::Regular),
+                mainloop_properties,
+                context_properties,
                 Vec::new(),
-                vec![("media.category".to_owned(), "Manager".to_owned())],
                 inspector_data.as_ref(),
+                &[],
             ),
 
             inspector_data,
+            last_remote,
+            daemon_restart: DaemonRestart::default(),
+            theme_settings: ThemeSettings::new(
+                storage
+                    .and_then(|storage| eframe::get_value(storage, storage_keys::THEME))
+                    .unwrap_or_default(),
+            ),
+            autostart_error: None,
+
+            profiles,
+            profile,
+            new_profile_name: String::new(),
+
+            instance_listener: None,
+            control_listener: None,
+            pending_focus: None,
+            pending_open_tool: None,
+            tool_plugins: Vec::new(),
         }
     }
 
     fn disconnect(&mut self) {
         self.state.save_inspector_data(&mut self.inspector_data);
         self.state.disconnect();
+
+        if let Some(profile) = self
+            .profile
+            .as_ref()
+            .and_then(|name| self.profiles.iter().find(|p| p.name == *name))
+        {
+            self.state.apply_profile(profile);
+        }
+
+        // Offer to reconnect to the same remote
+        if let (State::Unconnected { remote, .. }, Some(last_remote)) =
+            (&mut self.state, &self.last_remote)
+        {
+            *remote = RemoteInfo::Regular(last_remote.clone());
+        }
+    }
+
+    /// Adds tools to the Tools menu, for forks and embedders of coppwr.
+    /// The function is called for every connection, to register new instances of the tools.
+    pub fn add_tools(&mut self, register: fn(&mut ToolRegistry)) {
+        if let State::Connected { inspector, .. } = &mut self.state {
+            inspector.add_tools(register);
+        }
+        self.tool_plugins.push(register);
+    }
+
+    pub fn set_instance_listener(&mut self, listener: mpsc::Receiver<Args>) {
+        self.instance_listener = Some(listener);
+    }
+
+    pub fn set_control_listener(&mut self, listener: mpsc::Receiver<Call>) {
+        self.control_listener = Some(listener);
+    }
+
+    pub fn handle_args(&mut self, args: Args) {
+        if let Some(focus) = args.focus {
+            self.pending_focus = Some(focus);
+        }
+        if let Some(tool) = args.open_tool {
+            self.pending_open_tool = Some(tool);
+        }
+    }
+
+    fn handle_forwarded_args(&mut self, ctx: &egui::Context) {
+        let Some(listener) = &self.instance_listener else {
+            return;
+        };
+
+        let forwarded: Vec<Args> = listener.try_iter().collect();
+        if forwarded.is_empty() {
+            return;
+        }
+
+        ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(false));
+        ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+
+        for args in forwarded {
+            self.handle_args(args);
+        }
+    }
+
+    fn handle_control_calls(&mut self) {
+        let Some(listener) = &self.control_listener else {
+            return;
+        };
+
+        for call in listener.try_iter() {
+            let response = match &mut self.state {
+                State::Connected { inspector, .. } => inspector.handle_control(&call.command),
+                State::Unconnected { .. } => control::failure("Not connected to PipeWire"),
+            };
+            call.reply(response);
+        }
     }
 }
 
@@ -496,27 +1448,49 @@ impl eframe::App for App {
     #[cfg(feature = "persistence")]
     fn save(&mut self, storage: &mut dyn eframe::Storage) {
         eframe::set_value(storage, storage_keys::DOCK, &self.dock_state);
+        eframe::set_value(storage, storage_keys::WORKSPACES, &self.workspaces);
+        eframe::set_value(storage, storage_keys::THEME, self.theme_settings.theme());
+        eframe::set_value(storage, storage_keys::PROFILES, &self.profiles);
+        eframe::set_value(storage, storage_keys::PROFILE, &self.profile);
 
         self.state.save_inspector_data(&mut self.inspector_data);
 
         if let Some(inspector_data) = &self.inspector_data {
             eframe::set_value(storage, storage_keys::INSPECTOR, inspector_data);
         }
+
+        if let Some(remote) = &self.last_remote {
+            eframe::set_value(storage, storage_keys::REMOTE, remote);
+        }
     }
 
     fn on_exit(&mut self, _: Option<&eframe::glow::Context>) {
         self.state.disconnect();
     }
 
-    fn update(&mut self, ctx: &egui::Context, _: &mut eframe::Frame) {
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
         // egui won't update until there is interaction so data shown may be out of date
         ctx.request_repaint_after(std::time::Duration::from_millis(500));
 
+        self.theme_settings
+            .apply(ctx, frame.info().system_theme != Some(eframe::Theme::Light));
+        self.theme_settings.window(ctx);
+
         let window_size = ctx
             .input(|i| i.viewport().inner_rect)
             .unwrap_or(egui::Rect::ZERO)
             .size();
 
+        self.handle_forwarded_args(ctx);
+        self.handle_control_calls();
+
+        if self.daemon_restart.poll() {
+            self.disconnect();
+            self.state
+                .connect(self.inspector_data.as_ref(), &self.tool_plugins);
+        }
+        self.daemon_restart.window(ctx);
+
         match &mut self.state {
             State::Connected { inspector, about } => {
                 if inspector.process_events_or_stop() {
@@ -524,6 +1498,20 @@ impl eframe::App for App {
                     return;
                 }
 
+                if self
+                    .pending_focus
+                    .as_ref()
+                    .is_some_and(|focus| inspector.focus(focus))
+                {
+                    self.pending_focus = None;
+                }
+
+                if let Some(tool) = self.pending_open_tool.take() {
+                    if !inspector.open_tool(&tool, &mut self.dock_state) {
+                        eprintln!("There is no view or tool named {tool}");
+                    }
+                }
+
                 let mut disconnect = false;
                 egui::TopBottomPanel::top("menu_bar").show(ctx, |ui| {
                     egui::menu::bar(ui, |ui| {
@@ -533,6 +1521,49 @@ impl eframe::App for App {
                                 .on_hover_text("Disconnect from the PipeWire remote")
                                 .clicked();
 
+                            let mut redact = redact::enabled();
+                            if ui
+                                .checkbox(&mut redact, "Redact exports")
+                                .on_hover_text(
+                                    "Mask usernames, hostnames, serial numbers and \
+                                    application binaries in exported data",
+                                )
+                                .changed()
+                            {
+                                redact::set_enabled(redact);
+                            }
+
+                            // Only checked while the menu is open
+                            let mut autostart = config::autostart_installed();
+                            if ui
+                                .checkbox(&mut autostart, "Start at login")
+                                .on_hover_text("Launch coppwr minimized when logging in")
+                                .changed()
+                            {
+                                self.autostart_error = config::set_autostart(autostart)
+                                    .err()
+                                    .map(|e| format!("Failed to update the autostart entry: {e}"));
+                            }
+                            if let Some(e) = &self.autostart_error {
+                                ui.colored_label(ui.visuals().error_fg_color, e);
+                            }
+
+                            if ui
+                                .button("🔄 Restart PipeWire")
+                                .on_hover_text("Restart the PipeWire systemd user services")
+                                .clicked()
+                            {
+                                self.daemon_restart.open();
+                            }
+
+                            if ui
+                                .button("🎨 Appearance")
+                                .on_hover_text("Theme, scale, font size and colors")
+                                .clicked()
+                            {
+                                self.theme_settings.open();
+                            }
+
                             ui.separator();
 
                             if ui.button("❌ Quit").clicked() {
@@ -542,8 +1573,16 @@ impl eframe::App for App {
 
                         inspector.views_menu_buttons(ui, &mut self.dock_state);
                         inspector.tools_menu_buttons(ui);
+                        workspaces_menu_button(
+                            ui,
+                            &mut self.workspaces,
+                            &mut self.new_workspace_name,
+                            &mut self.dock_state,
+                            inspector,
+                        );
 
                         ui.menu_button("Help", |ui| {
+                            ui.menu_button("⌨ Keyboard Shortcuts", shortcuts::show_list);
                             if ui.button("❓ About").clicked() {
                                 *about = true;
                             }
@@ -583,6 +1622,10 @@ impl eframe::App for App {
                         });
                     });
 
+                inspector.handle_shortcuts(ctx, &mut self.dock_state);
+                inspector.handle_copy_request(ctx);
+                inspector.status_bar(ctx);
+                inspector.detail_panel(ctx);
                 inspector.tool_windows(ctx);
 
                 let mut style = egui_dock::Style::from_egui(ctx.style().as_ref());
@@ -596,6 +1639,7 @@ impl eframe::App for App {
                 remote,
                 mainloop_properties,
                 context_properties,
+                client_properties,
             } => {
                 let mut connect = false;
                 egui::CentralPanel::default().show(ctx, |_| {});
@@ -612,6 +1656,7 @@ impl eframe::App for App {
                                         RemoteInfo::Regular(..) => "Regular",
                                         RemoteInfo::Screencast { .. } => "Screencast portal",
                                         RemoteInfo::Camera => "Camera portal",
+                                        RemoteInfo::RemoteDesktop { .. } => "Remote desktop portal",
                                     }
                                 })
                                 .show_ui(ui, |ui| {
@@ -629,6 +1674,14 @@ impl eframe::App for App {
                                         RemoteInfo::Camera,
                                         "Camera portal",
                                     );
+                                    ui.selectable_value(
+                                        remote,
+                                        RemoteInfo::RemoteDesktop {
+                                            devices: BitFlags::EMPTY,
+                                            types: BitFlags::EMPTY,
+                                        },
+                                        "Remote desktop portal",
+                                    );
                                 });
 
                             match remote {
@@ -662,14 +1715,65 @@ impl eframe::App for App {
                                 }
                                 #[cfg(feature = "xdg_desktop_portals")]
                                 RemoteInfo::Camera => {}
+                                #[cfg(feature = "xdg_desktop_portals")]
+                                RemoteInfo::RemoteDesktop { devices, types } => {
+                                    ui.horizontal(|ui| {
+                                        ui.label("Device types");
+                                        for (label, device_type) in [
+                                            ("Keyboard", DeviceType::Keyboard),
+                                            ("Pointer", DeviceType::Pointer),
+                                            ("Touchscreen", DeviceType::Touchscreen),
+                                        ] {
+                                            if ui
+                                                .selectable_label(
+                                                    devices.contains(device_type),
+                                                    label,
+                                                )
+                                                .clicked()
+                                            {
+                                                devices.toggle(device_type);
+                                            }
+                                        }
+                                    });
+                                    ui.horizontal(|ui| {
+                                        ui.label("Source types").on_hover_text(
+                                            "Sources to share along with the devices, if any",
+                                        );
+                                        for (label, source_type) in [
+                                            ("Monitor", SourceType::Monitor),
+                                            ("Window", SourceType::Window),
+                                            ("Virtual", SourceType::Virtual),
+                                        ] {
+                                            if ui
+                                                .selectable_label(
+                                                    types.contains(source_type),
+                                                    label,
+                                                )
+                                                .clicked()
+                                            {
+                                                types.toggle(source_type);
+                                            }
+                                        }
+                                    });
+                                }
                             }
                         });
 
                         ui.separator();
 
+                        profiles_ui(
+                            ui,
+                            &mut self.profiles,
+                            &mut self.profile,
+                            &mut self.new_profile_name,
+                            mainloop_properties,
+                            context_properties,
+                        );
+
                         for (heading, properties) in [
                             ("Mainloop properties", mainloop_properties),
                             ("Context properties", context_properties),
+                            ("Client properties", client_properties),
                         ] {
                             egui::CollapsingHeader::new(heading)
                                 .show_unindented(ui, |ui| properties.show(ui));
@@ -686,7 +1790,11 @@ impl eframe::App for App {
                     });
 
                 if connect {
-                    self.state.connect(self.inspector_data.as_ref());
+                    if let RemoteInfo::Regular(name) = remote {
+                        self.last_remote = Some(name.clone());
+                    }
+                    self.state
+                        .connect(self.inspector_data.as_ref(), &self.tool_plugins);
                 }
             }
         }
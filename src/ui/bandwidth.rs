@@ -0,0 +1,219 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet},
+    rc::Rc,
+};
+
+use eframe::egui;
+use pipewire::{spa::param::ParamType, types::ObjectType};
+
+use crate::{
+    backend::{self, pods::params::AudioFormat, Request},
+    ui::{globals_store::Global, util::uis::global_info_button, GlobalEvent, ToolPlugin},
+};
+
+#[allow(clippy::cast_precision_loss)]
+fn format_bandwidth(bytes_per_second: u64) -> String {
+    let kib = bytes_per_second as f64 / 1024.;
+    if kib < 1024. {
+        format!("{kib:.1} KiB/s")
+    } else {
+        format!("{:.2} MiB/s", kib / 1024.)
+    }
+}
+
+/// Estimates the audio data throughput of the nodes of each client
+/// from their negotiated formats
+#[derive(Default)]
+pub struct Bandwidth {
+    clients: BTreeMap<u32, Rc<RefCell<Global>>>,
+    nodes: BTreeMap<u32, Rc<RefCell<Global>>>,
+    links: BTreeMap<u32, Rc<RefCell<Global>>>,
+}
+
+impl ToolPlugin for Bandwidth {
+    fn label(&self) -> &'static str {
+        "📶 Bandwidth"
+    }
+
+    fn description(&self) -> &'static str {
+        "Estimated audio data throughput of each client"
+    }
+
+    fn interested_in(&self, object_type: &ObjectType) -> bool {
+        matches!(
+            object_type,
+            ObjectType::Client | ObjectType::Node | ObjectType::Link
+        )
+    }
+
+    fn global_event(&mut self, event: GlobalEvent, _: &backend::Sender) {
+        match event {
+            GlobalEvent::Added(global) => match *global.borrow().object_type() {
+                ObjectType::Client => self.add_client(global),
+                ObjectType::Node => self.add_node(global),
+                ObjectType::Link => self.add_link(global),
+                _ => {}
+            },
+            GlobalEvent::Removed { id, .. } => self.remove_global(id),
+            GlobalEvent::PropertiesChanged(_) | GlobalEvent::InfoChanged(..) => {}
+        }
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        self.show(ui, sx);
+    }
+}
+
+impl Bandwidth {
+    fn add_client(&mut self, global: &Rc<RefCell<Global>>) {
+        let id = global.borrow().id();
+        self.clients.insert(id, Rc::clone(global));
+    }
+
+    fn add_node(&mut self, global: &Rc<RefCell<Global>>) {
+        let id = global.borrow().id();
+        self.nodes.insert(id, Rc::clone(global));
+    }
+
+    fn add_link(&mut self, global: &Rc<RefCell<Global>>) {
+        let id = global.borrow().id();
+        self.links.insert(id, Rc::clone(global));
+    }
+
+    fn remove_global(&mut self, id: u32) {
+        self.clients.remove(&id);
+        self.nodes.remove(&id);
+        self.links.remove(&id);
+    }
+
+    /// Nodes that have at least one active link
+    fn active_nodes(&self) -> BTreeSet<u32> {
+        self.links
+            .values()
+            .filter_map(|link| {
+                let link = link.borrow();
+                if link.info_value("State")? != "Active" {
+                    return None;
+                }
+
+                Some(
+                    ["Input Node ID", "Output Node ID"]
+                        .map(|key| link.info_value(key).and_then(|id| id.parse().ok())),
+                )
+            })
+            .flatten()
+            .flatten()
+            .collect()
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        ui.label(
+            "Estimated from the rate, channels and sample size of the formats \
+            negotiated by nodes with active links. Compressed and video formats are not counted.",
+        );
+
+        let active = self.active_nodes();
+
+        if ui
+            .button("Get formats")
+            .on_hover_text("Request the formats of the active nodes")
+            .clicked()
+        {
+            for id in &active {
+                sx.send(Request::EnumParams(*id, ParamType::Format)).ok();
+            }
+        }
+
+        ui.separator();
+
+        // Client ID -> nodes and their bandwidth
+        let mut per_client: BTreeMap<Option<u32>, Vec<(&Rc<RefCell<Global>>, Option<u64>)>> =
+            BTreeMap::new();
+        for node in active.iter().filter_map(|id| self.nodes.get(id)) {
+            let node_borrow = node.borrow();
+            let client = node_borrow
+                .props()
+                .get("client.id")
+                .and_then(|id| id.parse().ok());
+            let bandwidth = node_borrow
+                .params(ParamType::Format)
+                .find_map(AudioFormat::from_param)
+                .and_then(|format| format.bandwidth());
+
+            per_client
+                .entry(client)
+                .or_default()
+                .push((node, bandwidth));
+        }
+
+        let total: u64 = per_client
+            .values()
+            .flatten()
+            .filter_map(|(_, bandwidth)| *bandwidth)
+            .sum();
+        ui.heading(format!("Total: {}", format_bandwidth(total)));
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for (client_id, nodes) in &per_client {
+                let client = client_id.and_then(|id| self.clients.get(&id));
+                let name = client.map_or_else(
+                    || String::from("No client"),
+                    |client| {
+                        let client = client.borrow();
+                        client
+                            .props()
+                            .get("application.name")
+                            .cloned()
+                            .unwrap_or_else(|| format!("Client {}", client.id()))
+                    },
+                );
+                let client_total: u64 = nodes.iter().filter_map(|(_, b)| *b).sum();
+
+                egui::CollapsingHeader::new(format!("{name} - {}", format_bandwidth(client_total)))
+                    .id_source(("bandwidth_client", client_id))
+                    .show(ui, |ui| {
+                        global_info_button(ui, client, sx);
+
+                        egui::Grid::new(("bandwidth_nodes", client_id))
+                            .striped(true)
+                            .num_columns(3)
+                            .show(ui, |ui| {
+                                for (node, bandwidth) in nodes {
+                                    global_info_button(ui, Some(node), sx);
+                                    {
+                                        let node = node.borrow();
+                                        ui.label(format!(
+                                            "{} {}",
+                                            node.id(),
+                                            node.name().map_or("", String::as_str)
+                                        ));
+                                    }
+                                    ui.label(bandwidth.map_or_else(
+                                        || String::from("Unknown format"),
+                                        format_bandwidth,
+                                    ));
+                                    ui.end_row();
+                                }
+                            });
+                    });
+            }
+        });
+    }
+}
@@ -0,0 +1,204 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::collections::BTreeMap;
+
+use eframe::egui;
+use pipewire::spa::{param::ParamType, sys};
+
+use crate::backend::{self, pods::props, ObjectMethod, Request};
+
+const CHANNELS: [(&str, u32); 20] = [
+    ("MONO", sys::SPA_AUDIO_CHANNEL_MONO),
+    ("FL", sys::SPA_AUDIO_CHANNEL_FL),
+    ("FR", sys::SPA_AUDIO_CHANNEL_FR),
+    ("FC", sys::SPA_AUDIO_CHANNEL_FC),
+    ("LFE", sys::SPA_AUDIO_CHANNEL_LFE),
+    ("SL", sys::SPA_AUDIO_CHANNEL_SL),
+    ("SR", sys::SPA_AUDIO_CHANNEL_SR),
+    ("FLC", sys::SPA_AUDIO_CHANNEL_FLC),
+    ("FRC", sys::SPA_AUDIO_CHANNEL_FRC),
+    ("RC", sys::SPA_AUDIO_CHANNEL_RC),
+    ("RL", sys::SPA_AUDIO_CHANNEL_RL),
+    ("RR", sys::SPA_AUDIO_CHANNEL_RR),
+    ("TC", sys::SPA_AUDIO_CHANNEL_TC),
+    ("TFL", sys::SPA_AUDIO_CHANNEL_TFL),
+    ("TFC", sys::SPA_AUDIO_CHANNEL_TFC),
+    ("TFR", sys::SPA_AUDIO_CHANNEL_TFR),
+    ("TRL", sys::SPA_AUDIO_CHANNEL_TRL),
+    ("TRC", sys::SPA_AUDIO_CHANNEL_TRC),
+    ("TRR", sys::SPA_AUDIO_CHANNEL_TRR),
+    ("LLFE", sys::SPA_AUDIO_CHANNEL_LLFE),
+];
+
+// Auxiliary channels are named AUX0, AUX1...
+const AUX_CHANNELS: u32 = 64;
+
+fn channel_id(name: &str) -> Option<u32> {
+    CHANNELS
+        .iter()
+        .find_map(|(n, id)| (*n == name).then_some(*id))
+        .or_else(|| {
+            name.strip_prefix("AUX")
+                .and_then(|n| n.parse::<u32>().ok())
+                .filter(|n| *n < AUX_CHANNELS)
+                .map(|n| sys::SPA_AUDIO_CHANNEL_AUX0 + n)
+        })
+}
+
+/// Editor for the channel positions of the DSP ports of adapter nodes
+pub struct ChannelMap {
+    positions: Vec<String>,
+}
+
+impl ChannelMap {
+    /// Reads the positions from `audio.position`, like `[ FL FR ]` or `FL,FR`
+    pub fn from_props(props: &BTreeMap<String, String>) -> Option<Self> {
+        let positions = props
+            .get("audio.position")?
+            .trim_matches(|c: char| c == '[' || c == ']' || c.is_whitespace())
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|p| !p.is_empty())
+            .map(str::to_owned)
+            .collect();
+
+        Some(Self { positions })
+    }
+
+    fn validate(&self, channels: Option<u32>) -> Result<Vec<u32>, String> {
+        if self.positions.is_empty() {
+            return Err(String::from("There must be at least one channel"));
+        }
+
+        if let Some(channels) = channels {
+            if self.positions.len() != channels as usize {
+                return Err(format!(
+                    "The node has {channels} channels but {} positions are set",
+                    self.positions.len()
+                ));
+            }
+        }
+
+        let mut ids = Vec::with_capacity(self.positions.len());
+        for position in &self.positions {
+            let id = channel_id(position).ok_or_else(|| format!("Unknown position {position}"))?;
+            if ids.contains(&id) {
+                return Err(format!("{position} is used more than once"));
+            }
+            ids.push(id);
+        }
+
+        Ok(ids)
+    }
+
+    /// Shows the editor. `input` is the direction of the node's ports
+    /// and `channels` the channel count of the device, if known.
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        sx: &backend::Sender,
+        id: u32,
+        input: Option<bool>,
+        channels: Option<u32>,
+    ) {
+        ui.label("Drag the slots to reorder the channels");
+
+        let mut moved = None;
+        let mut removed = None;
+
+        for (i, position) in self.positions.iter_mut().enumerate() {
+            let row = ui
+                .horizontal(|ui| {
+                    ui.dnd_drag_source(egui::Id::new(("channel_slot", id, i)), i, |ui| {
+                        ui.label("☰");
+                    });
+
+                    ui.label(i.to_string());
+
+                    egui::ComboBox::from_id_source(("channel_position", i))
+                        .selected_text(position.as_str())
+                        .show_ui(ui, |ui| {
+                            for (name, _) in CHANNELS {
+                                ui.selectable_value(position, name.to_owned(), name);
+                            }
+                            for n in 0..AUX_CHANNELS {
+                                let name = format!("AUX{n}");
+                                ui.selectable_value(position, name.clone(), name);
+                            }
+                        });
+
+                    if ui.small_button("🗑").clicked() {
+                        removed = Some(i);
+                    }
+                })
+                .response;
+
+            if let Some(from) = row.dnd_release_payload::<usize>() {
+                moved = Some((*from, i));
+            }
+        }
+
+        if let Some((from, to)) = moved {
+            let position = self.positions.remove(from);
+            self.positions.insert(to, position);
+        }
+        if let Some(i) = removed {
+            self.positions.remove(i);
+        }
+
+        if ui.button("Add").clicked() {
+            self.positions.push(format!(
+                "AUX{}",
+                self.positions.len().min(AUX_CHANNELS as usize - 1)
+            ));
+        }
+
+        ui.separator();
+
+        let Some(input) = input else {
+            ui.label("The PortConfig param is needed to know the direction of the ports");
+            if ui.small_button("Load").clicked() {
+                sx.send(Request::EnumParams(id, ParamType::PortConfig)).ok();
+            }
+            return;
+        };
+
+        match self.validate(channels) {
+            Ok(positions) => {
+                if ui
+                    .button("Apply")
+                    .on_hover_text("Reconfigures the ports of the node, which recreates them")
+                    .clicked()
+                {
+                    if let Some(pod) = props::port_config(input, &positions) {
+                        sx.send(Request::CallObjectMethod(
+                            id,
+                            ObjectMethod::SetParam {
+                                id: ParamType::PortConfig,
+                                flags: 0,
+                                pod,
+                            },
+                        ))
+                        .ok();
+                    }
+                }
+            }
+            Err(e) => {
+                ui.colored_label(ui.visuals().error_fg_color, e);
+            }
+        }
+    }
+}
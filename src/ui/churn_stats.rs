@@ -0,0 +1,279 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
+    time::{Duration, Instant},
+};
+
+use eframe::egui;
+use pipewire::types::ObjectType;
+
+use crate::{
+    backend,
+    ui::{ConnectionEvent, GlobalEvent, ToolPlugin},
+};
+
+const WINDOW: Duration = Duration::from_secs(60);
+const MAX_REUSES: usize = 100;
+
+#[derive(Default)]
+struct Rates {
+    created: VecDeque<Instant>,
+    removed: VecDeque<Instant>,
+    total_created: usize,
+    total_removed: usize,
+}
+
+impl Rates {
+    fn prune(&mut self, now: Instant) {
+        for events in [&mut self.created, &mut self.removed] {
+            while events.front().is_some_and(|t| now.duration_since(*t) > WINDOW) {
+                events.pop_front();
+            }
+        }
+    }
+}
+
+struct Reuse {
+    when: Instant,
+    id: u32,
+    previous: String,
+    current: String,
+}
+
+/// Statistics on the creation and removal of globals
+#[derive(Default)]
+pub struct ChurnStats {
+    per_type: BTreeMap<String, Rates>,
+
+    removed_ids: HashSet<u32>,
+    reuses: VecDeque<Reuse>,
+    total_reuses: usize,
+    // Last removed object with each ID, for describing reuses
+    removed_descriptions: HashMap<u32, String>,
+
+    // How many times objects with the same type and name have been created
+    recreations: HashMap<(String, String), usize>,
+
+    // Globals added before this are the initial state of the remote, not churn
+    initial_sync_done: bool,
+}
+
+impl ToolPlugin for ChurnStats {
+    fn label(&self) -> &'static str {
+        "📈 Churn Statistics"
+    }
+
+    fn description(&self) -> &'static str {
+        "Statistics on object creation, removal and ID reuse"
+    }
+
+    fn interested_in(&self, _: &ObjectType) -> bool {
+        true
+    }
+
+    fn global_event(&mut self, event: GlobalEvent, _: &backend::Sender) {
+        match event {
+            GlobalEvent::Added(global) => {
+                let global = global.borrow();
+                self.global_added(global.id(), global.object_type(), global.name());
+            }
+            GlobalEvent::Removed { id, global } => {
+                let global = global.borrow();
+                self.global_removed(id, global.object_type(), global.name());
+            }
+            GlobalEvent::PropertiesChanged(_) | GlobalEvent::InfoChanged(..) => {}
+        }
+    }
+
+    fn connection_event(&mut self, event: ConnectionEvent, _: &backend::Sender) {
+        match event {
+            ConnectionEvent::InitialSyncDone => self.initial_sync_done(),
+            ConnectionEvent::Reconnected => self.reconnected(),
+            ConnectionEvent::ContextProperties(_) => {}
+        }
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, _sx: &backend::Sender) {
+        self.show(ui);
+    }
+}
+
+fn describe(object_type: &ObjectType, name: Option<&String>) -> String {
+    match name {
+        Some(name) => format!("{} {name}", object_type.to_str()),
+        None => object_type.to_str().to_owned(),
+    }
+}
+
+impl ChurnStats {
+    fn global_added(&mut self, id: u32, object_type: &ObjectType, name: Option<&String>) {
+        let now = Instant::now();
+
+        if self.initial_sync_done {
+            let rates = self
+                .per_type
+                .entry(object_type.to_str().to_owned())
+                .or_default();
+            rates.created.push_back(now);
+            rates.total_created += 1;
+            rates.prune(now);
+        }
+
+        let current = describe(object_type, name);
+
+        if self.removed_ids.remove(&id) {
+            self.total_reuses += 1;
+            if self.reuses.len() == MAX_REUSES {
+                self.reuses.pop_front();
+            }
+            self.reuses.push_back(Reuse {
+                when: now,
+                id,
+                previous: self.removed_descriptions.remove(&id).unwrap_or_default(),
+                current,
+            });
+        }
+
+        if let Some(name) = name {
+            *self
+                .recreations
+                .entry((object_type.to_str().to_owned(), name.clone()))
+                .or_default() += 1;
+        }
+    }
+
+    fn global_removed(&mut self, id: u32, object_type: &ObjectType, name: Option<&String>) {
+        let now = Instant::now();
+
+        let rates = self
+            .per_type
+            .entry(object_type.to_str().to_owned())
+            .or_default();
+        rates.removed.push_back(now);
+        rates.total_removed += 1;
+        rates.prune(now);
+
+        self.removed_ids.insert(id);
+        self.removed_descriptions.insert(id, describe(object_type, name));
+    }
+
+    fn initial_sync_done(&mut self) {
+        self.initial_sync_done = true;
+    }
+
+    /// IDs of the lost connection being used by the new one aren't reuses, and
+    /// the globals of the new connection are its initial state, not churn
+    fn reconnected(&mut self) {
+        self.removed_ids.clear();
+        self.removed_descriptions.clear();
+        self.initial_sync_done = false;
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui) {
+        let now = Instant::now();
+
+        ui.horizontal(|ui| {
+            if ui.button("Reset").clicked() {
+                *self = Self {
+                    initial_sync_done: self.initial_sync_done,
+                    ..Self::default()
+                };
+            }
+
+            if self.initial_sync_done {
+                ui.label("Objects that existed when connecting aren't counted as created");
+            } else {
+                ui.spinner();
+                ui.label("Loading the objects of the remote");
+            }
+        });
+
+        ui.heading("Per type");
+        egui::Grid::new("churn_rates")
+            .num_columns(5)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Type");
+                ui.label("Created/min");
+                ui.label("Removed/min");
+                ui.label("Total created");
+                ui.label("Total removed");
+                ui.end_row();
+
+                for (object_type, rates) in &mut self.per_type {
+                    rates.prune(now);
+
+                    ui.label(object_type);
+                    ui.label(rates.created.len().to_string());
+                    ui.label(rates.removed.len().to_string());
+                    ui.label(rates.total_created.to_string());
+                    ui.label(rates.total_removed.to_string());
+                    ui.end_row();
+                }
+            });
+
+        ui.separator();
+
+        ui.heading("Most recreated objects");
+        ui.label("Objects that keep getting recreated may indicate a session manager loop");
+
+        let mut recreated = self
+            .recreations
+            .iter()
+            .filter(|(_, count)| **count > 1)
+            .collect::<Vec<_>>();
+        recreated.sort_by(|a, b| b.1.cmp(a.1));
+
+        if recreated.is_empty() {
+            ui.label("No object has been recreated");
+        } else {
+            egui::Grid::new("churn_recreations")
+                .num_columns(3)
+                .striped(true)
+                .show(ui, |ui| {
+                    for ((object_type, name), count) in recreated.into_iter().take(20) {
+                        ui.label(object_type);
+                        ui.label(name);
+                        ui.label(format!("{count} times"));
+                        ui.end_row();
+                    }
+                });
+        }
+
+        ui.separator();
+
+        ui.heading(format!("ID reuses ({})", self.total_reuses));
+
+        if self.reuses.is_empty() {
+            ui.label("No ID has been reused");
+            return;
+        }
+
+        egui::Grid::new("churn_reuses")
+            .num_columns(3)
+            .striped(true)
+            .show(ui, |ui| {
+                for reuse in self.reuses.iter().rev() {
+                    ui.label(format!("{}s ago", now.duration_since(reuse.when).as_secs()));
+                    ui.label(reuse.id.to_string());
+                    ui.label(format!("{} → {}", reuse.previous, reuse.current));
+                    ui.end_row();
+                }
+            });
+    }
+}
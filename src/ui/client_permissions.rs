@@ -0,0 +1,290 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet},
+    rc::Rc,
+};
+
+use eframe::egui;
+use pipewire::{
+    permissions::{Permission, PermissionFlags},
+    types::ObjectType,
+};
+
+use crate::{
+    backend,
+    ui::{
+        compat::{self, Area},
+        globals_store::{draw_permission_list, Global},
+        util::{persistence::PersistentView, uis::global_info_button},
+        GlobalEvent, ToolPlugin,
+    },
+};
+
+// Permissions for this ID are the default ones of the client
+const PW_ID_ANY: u32 = u32::MAX;
+
+#[derive(Clone)]
+#[cfg_attr(feature = "persistence", derive(serde::Serialize, serde::Deserialize))]
+struct Preset {
+    name: String,
+    // Object ID and permission flags
+    permissions: Vec<(u32, u32)>,
+}
+
+fn builtin_presets() -> [Preset; 3] {
+    let preset = |name: &str, flags: PermissionFlags| Preset {
+        name: name.to_owned(),
+        permissions: vec![(PW_ID_ANY, flags.bits())],
+    };
+
+    [
+        preset("None", PermissionFlags::empty()),
+        preset("Read-only", PermissionFlags::R),
+        preset(
+            "Full",
+            PermissionFlags::R | PermissionFlags::W | PermissionFlags::X | PermissionFlags::M,
+        ),
+    ]
+}
+
+/// Applies a set of permissions to many clients at once
+#[derive(Default)]
+pub struct ClientPermissions {
+    clients: BTreeMap<u32, Rc<RefCell<Global>>>,
+    selected: BTreeSet<u32>,
+    filter: String,
+
+    permissions: Vec<(u32, u32)>,
+
+    presets: Vec<Preset>,
+    preset_name: String,
+}
+
+impl ToolPlugin for ClientPermissions {
+    fn label(&self) -> &'static str {
+        "🔐 Client Permissions"
+    }
+
+    fn description(&self) -> &'static str {
+        "Set the permissions of many clients at once"
+    }
+
+    fn interested_in(&self, object_type: &ObjectType) -> bool {
+        *object_type == ObjectType::Client
+    }
+
+    fn global_event(&mut self, event: GlobalEvent, _: &backend::Sender) {
+        match event {
+            GlobalEvent::Added(global) | GlobalEvent::PropertiesChanged(global) => {
+                self.add_client(global);
+            }
+            GlobalEvent::Removed { id, .. } => self.remove_client(id),
+            GlobalEvent::InfoChanged(..) => {}
+        }
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        self.show(ui, sx);
+    }
+}
+
+impl ClientPermissions {
+    fn add_client(&mut self, global: &Rc<RefCell<Global>>) {
+        let id = global.borrow().id();
+        self.clients.insert(id, Rc::clone(global));
+    }
+
+    fn remove_client(&mut self, id: u32) {
+        self.clients.remove(&id);
+        self.selected.remove(&id);
+    }
+
+    fn client_name(global: &Global) -> String {
+        global
+            .props()
+            .get("application.name")
+            .or_else(|| global.name())
+            .map_or_else(
+                || global.id().to_string(),
+                |name| format!("{} {name}", global.id()),
+            )
+    }
+
+    fn show_presets(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal_wrapped(|ui| {
+            for preset in builtin_presets() {
+                if ui.button(&preset.name).clicked() {
+                    self.permissions = preset.permissions;
+                }
+            }
+
+            let mut removed = None;
+            for (i, preset) in self.presets.iter().enumerate() {
+                let button = ui.button(&preset.name);
+                if button.clicked() {
+                    self.permissions.clone_from(&preset.permissions);
+                }
+                button.context_menu(|ui| {
+                    if ui.button("Delete").clicked() {
+                        removed = Some(i);
+                        ui.close_menu();
+                    }
+                });
+            }
+            if let Some(i) = removed {
+                self.presets.remove(i);
+            }
+        });
+
+        ui.horizontal(|ui| {
+            egui::TextEdit::singleline(&mut self.preset_name)
+                .hint_text("Preset name")
+                .show(ui);
+
+            let name = self.preset_name.trim();
+            let existing = self.presets.iter().position(|p| p.name == name);
+            let builtin = builtin_presets().iter().any(|p| p.name == name);
+
+            if ui
+                .add_enabled(
+                    !name.is_empty() && !builtin,
+                    egui::Button::new(if existing.is_some() {
+                        "Overwrite"
+                    } else {
+                        "Save"
+                    }),
+                )
+                .on_hover_text("Save the permissions below as a preset")
+                .clicked()
+            {
+                let preset = Preset {
+                    name: name.to_owned(),
+                    permissions: self.permissions.clone(),
+                };
+                match existing {
+                    Some(i) => self.presets[i] = preset,
+                    None => self.presets.push(preset),
+                }
+                self.preset_name.clear();
+            }
+        });
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        ui.label(
+            "Sets the same permissions on all of the selected clients. \
+            Custom presets can be deleted by right clicking them.",
+        );
+
+        compat::show_warnings(ui, Area::Permissions);
+
+        ui.separator();
+
+        ui.collapsing("Presets", |ui| {
+            self.show_presets(ui);
+        });
+
+        ui.group(|ui| {
+            ui.label("Permissions");
+            draw_permission_list(ui, &mut self.permissions);
+        });
+
+        ui.separator();
+
+        let clients: Vec<(u32, String)> = self
+            .clients
+            .iter()
+            .map(|(id, global)| (*id, Self::client_name(&global.borrow())))
+            .filter(|(_, name)| name.contains(self.filter.as_str()))
+            .collect();
+
+        egui::TextEdit::singleline(&mut self.filter)
+            .hint_text("Filter")
+            .show(ui);
+
+        ui.horizontal(|ui| {
+            if ui.button("Select all").clicked() {
+                self.selected.extend(clients.iter().map(|(id, _)| *id));
+            }
+            if ui.button("Select none").clicked() {
+                self.selected.clear();
+            }
+
+            if ui
+                .add_enabled(
+                    !self.selected.is_empty() && !self.permissions.is_empty(),
+                    egui::Button::new(format!("Apply to {} selected", self.selected.len())),
+                )
+                .on_hover_text("Can be reverted from the Tools menu")
+                .clicked()
+            {
+                let permissions: Vec<Permission> = self
+                    .permissions
+                    .iter()
+                    .map(|&(id, flags)| {
+                        Permission::new(id, PermissionFlags::from_bits_truncate(flags))
+                    })
+                    .collect();
+
+                for global in self.selected.iter().filter_map(|id| self.clients.get(id)) {
+                    global.borrow_mut().set_permissions(permissions.clone(), sx);
+                }
+            }
+        });
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for (id, name) in &clients {
+                ui.horizontal(|ui| {
+                    let mut selected = self.selected.contains(id);
+                    if ui.checkbox(&mut selected, name.as_str()).changed() {
+                        if selected {
+                            self.selected.insert(*id);
+                        } else {
+                            self.selected.remove(id);
+                        }
+                    }
+
+                    global_info_button(ui, self.clients.get(id), sx);
+                });
+            }
+        });
+    }
+}
+
+#[cfg_attr(feature = "persistence", derive(serde::Serialize, serde::Deserialize))]
+pub struct PersistentData {
+    presets: Vec<Preset>,
+}
+
+impl PersistentView for ClientPermissions {
+    type Data = PersistentData;
+
+    fn with_data(data: &Self::Data) -> Self {
+        Self {
+            presets: data.presets.clone(),
+            ..Self::default()
+        }
+    }
+
+    fn save_data(&self) -> Option<Self::Data> {
+        Some(PersistentData {
+            presets: self.presets.clone(),
+        })
+    }
+}
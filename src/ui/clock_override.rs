@@ -0,0 +1,259 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::collections::BTreeMap;
+
+use eframe::egui;
+use pipewire::spa::param::ParamType;
+
+use crate::{
+    backend::{
+        self,
+        pods::props::{self, ParamValue},
+        ObjectMethod, Request,
+    },
+    ui::alsa_params::COMMON_RATES,
+};
+
+const MAX_QUANTUM: u32 = 8192;
+
+/// The settings of the graph's clock that node overrides are checked against
+#[derive(Clone, Default)]
+pub struct ClockSettings {
+    /// ID of the settings metadata
+    pub metadata: Option<u32>,
+    /// From `clock.allowed-rates`, empty if unknown
+    pub allowed_rates: Vec<u32>,
+}
+
+/// Editor for the latency a node requests and the quantum and rate it forces
+pub struct ClockOverride {
+    // node.latency, as quantum/rate
+    quantum: u32,
+    rate: u32,
+    // 0 doesn't force
+    force_quantum: u32,
+    force_rate: u32,
+
+    settings: ClockSettings,
+}
+
+impl Default for ClockOverride {
+    fn default() -> Self {
+        Self {
+            quantum: 1024,
+            rate: 48000,
+            force_quantum: 0,
+            force_rate: 0,
+
+            settings: ClockSettings::default(),
+        }
+    }
+}
+
+impl ClockOverride {
+    pub fn update(&mut self, props: &BTreeMap<String, String>) {
+        let int = |key: &str| props.get(key).and_then(|v| v.parse().ok());
+
+        if let Some((quantum, rate)) = props
+            .get("node.latency")
+            .and_then(|l| l.split_once('/'))
+            .and_then(|(q, r)| q.trim().parse().ok().zip(r.trim().parse().ok()))
+        {
+            self.quantum = quantum;
+            self.rate = rate;
+        }
+        self.force_quantum = int("node.force-quantum").unwrap_or(0);
+        self.force_rate = int("node.force-rate").unwrap_or(0);
+    }
+
+    pub fn set_settings(&mut self, settings: &ClockSettings) {
+        self.settings = settings.clone();
+    }
+
+    fn rates(&self) -> &[u32] {
+        if self.settings.allowed_rates.is_empty() {
+            &COMMON_RATES
+        } else {
+            &self.settings.allowed_rates
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.force_rate != 0
+            && !self.settings.allowed_rates.is_empty()
+            && !self.settings.allowed_rates.contains(&self.force_rate)
+        {
+            return Err(format!(
+                "{} Hz isn't one of the allowed rates of the graph",
+                self.force_rate
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn rate_combo(
+        ui: &mut egui::Ui,
+        id_source: impl std::hash::Hash,
+        rate: &mut u32,
+        rates: &[u32],
+        none: bool,
+    ) {
+        egui::ComboBox::from_id_source(id_source)
+            .selected_text(if *rate == 0 {
+                String::from("Don't force")
+            } else {
+                format!("{rate} Hz")
+            })
+            .show_ui(ui, |ui| {
+                if none {
+                    ui.selectable_value(rate, 0, "Don't force");
+                }
+                for r in rates {
+                    ui.selectable_value(rate, *r, format!("{r} Hz"));
+                }
+            });
+    }
+
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        sx: &backend::Sender,
+        id: u32,
+        props: &BTreeMap<String, String>,
+    ) {
+        for key in ["node.latency", "node.force-quantum", "node.force-rate"] {
+            if let Some(value) = props.get(key) {
+                ui.label(format!("Current {key}: {value}"));
+            }
+        }
+
+        let rates = self.rates().to_vec();
+
+        egui::Grid::new(("clock_override", id))
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label("Latency").on_hover_text(
+                    "The quantum the node would like the graph to use, at the given rate",
+                );
+                ui.horizontal(|ui| {
+                    ui.add(egui::DragValue::new(&mut self.quantum).clamp_range(1..=MAX_QUANTUM));
+                    ui.label("/");
+                    Self::rate_combo(ui, ("latency_rate", id), &mut self.rate, &rates, false);
+                    ui.label(format!(
+                        "{:.2} ms",
+                        f64::from(self.quantum) * 1000. / f64::from(self.rate.max(1))
+                    ));
+                });
+                ui.end_row();
+
+                ui.label("Force quantum")
+                    .on_hover_text("Forces the graph to this quantum while the node is active");
+                ui.add(
+                    egui::DragValue::new(&mut self.force_quantum)
+                        .clamp_range(0..=MAX_QUANTUM)
+                        .custom_formatter(|v, _| {
+                            if v == 0. {
+                                String::from("Don't force")
+                            } else {
+                                v.to_string()
+                            }
+                        }),
+                );
+                ui.end_row();
+
+                ui.label("Force rate")
+                    .on_hover_text("Forces the graph to this rate while the node is active");
+                Self::rate_combo(ui, ("force_rate", id), &mut self.force_rate, &rates, true);
+                ui.end_row();
+            });
+
+        if self.settings.allowed_rates.is_empty() {
+            ui.label("The allowed rates of the graph are unknown, common rates are shown");
+        }
+
+        if let Err(e) = self.validate() {
+            ui.colored_label(ui.visuals().error_fg_color, e);
+            return;
+        }
+
+        ui.horizontal(|ui| {
+            if ui
+                .button("Apply to node")
+                .on_hover_text("Sets the node's properties through its Props param")
+                .clicked()
+            {
+                #[allow(clippy::cast_possible_wrap)]
+                let params = vec![
+                    (
+                        String::from("node.latency"),
+                        ParamValue::String(format!("{}/{}", self.quantum, self.rate)),
+                    ),
+                    (
+                        String::from("node.force-quantum"),
+                        ParamValue::Int(self.force_quantum as i32),
+                    ),
+                    (
+                        String::from("node.force-rate"),
+                        ParamValue::Int(self.force_rate as i32),
+                    ),
+                ];
+
+                if let Some(pod) = props::params(params) {
+                    sx.send(Request::CallObjectMethod(
+                        id,
+                        ObjectMethod::SetParam {
+                            id: ParamType::Props,
+                            flags: 0,
+                            pod,
+                        },
+                    ))
+                    .ok();
+                }
+            }
+
+            let Some(metadata) = self.settings.metadata else {
+                return;
+            };
+
+            if ui
+                .button("Force for the graph")
+                .on_hover_text(
+                    "Sets clock.force-quantum and clock.force-rate in the settings metadata, \
+                    which apply regardless of the node",
+                )
+                .clicked()
+            {
+                for (key, value) in [
+                    ("clock.force-quantum", self.force_quantum),
+                    ("clock.force-rate", self.force_rate),
+                ] {
+                    sx.send(Request::CallObjectMethod(
+                        metadata,
+                        ObjectMethod::MetadataSetProperty {
+                            subject: 0,
+                            key: key.to_owned(),
+                            type_: None,
+                            value: Some(value.to_string()),
+                        },
+                    ))
+                    .ok();
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,114 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::cell::Cell;
+
+use eframe::egui;
+
+pub type Version = (u32, u32, u32);
+
+// The version of the daemon of the connection whose UI is being shown.
+// Each connection keeps its own in the info of its core.
+thread_local! {
+    static DAEMON_VERSION: Cell<Option<Version>> = const { Cell::new(None) };
+}
+
+/// The parts of the UI that behave differently depending on the daemon version
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Area {
+    Permissions,
+    Metadata,
+    Settings,
+}
+
+/// A known difference in the behavior of daemons older than a version
+struct Quirk {
+    area: Area,
+    since: Version,
+    message: &'static str,
+}
+
+const QUIRKS: [Quirk; 3] = [
+    Quirk {
+        area: Area::Permissions,
+        since: (0, 3, 77),
+        message:
+            "The Link (L) permission was added in PipeWire 0.3.77 and is ignored by this daemon",
+    },
+    Quirk {
+        area: Area::Metadata,
+        since: (0, 3, 64),
+        message: "target.object was added in PipeWire 0.3.64, this daemon only follows target.node",
+    },
+    Quirk {
+        area: Area::Settings,
+        since: (1, 0, 0),
+        message: "Per-topic log levels were added in PipeWire 1.0, \
+            this daemon only applies the global level",
+    },
+];
+
+pub fn parse_version(version: &str) -> Option<Version> {
+    // Versions may have suffixes, like 1.0.3-1
+    let mut parts = version.split(|c: char| !c.is_ascii_digit());
+    Some((
+        parts.next()?.parse().ok()?,
+        parts.next()?.parse().ok()?,
+        parts.next()?.parse().ok()?,
+    ))
+}
+
+/// Sets the version of the daemon of the connection whose UI is shown next,
+/// or forgets it when `None`
+pub fn set_daemon_version(version: Option<Version>) {
+    DAEMON_VERSION.with(|v| v.set(version));
+}
+
+/// Shows the UI of another connection with the version of its daemon,
+/// then goes back to the version of the previous one
+pub fn scoped<R>(version: Option<Version>, f: impl FnOnce() -> R) -> R {
+    let previous = DAEMON_VERSION.with(|v| v.replace(version));
+    let result = f();
+    DAEMON_VERSION.with(|v| v.set(previous));
+    result
+}
+
+pub fn daemon_version() -> Option<Version> {
+    DAEMON_VERSION.with(Cell::get)
+}
+
+/// Whether the daemon is known to be older than the version
+pub fn older_than(version: Version) -> bool {
+    daemon_version().is_some_and(|v| v < version)
+}
+
+/// Shows a warning, with the version of the daemon on hover
+pub fn warning(ui: &mut egui::Ui, message: &str) {
+    let response = ui.colored_label(ui.visuals().warn_fg_color, format!("⚠ {message}"));
+    if let Some((major, minor, micro)) = daemon_version() {
+        response.on_hover_text(format!("Daemon version: {major}.{minor}.{micro}"));
+    }
+}
+
+/// Shows warnings about the known differences of the daemon that affect the area
+pub fn show_warnings(ui: &mut egui::Ui, area: Area) {
+    for quirk in QUIRKS
+        .iter()
+        .filter(|q| q.area == area && older_than(q.since))
+    {
+        warning(ui, quirk.message);
+    }
+}
@@ -0,0 +1,394 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use eframe::egui;
+use pipewire::types::ObjectType;
+
+use crate::{
+    backend,
+    ui::{
+        globals_store::Global,
+        util::{
+            config,
+            spa_json::{self, Value},
+            uis::global_info_button,
+        },
+        GlobalEvent, ToolPlugin,
+    },
+};
+
+// Properties that objects can be identified by when they're declared with them
+const NAME_KEYS: [&str; 3] = ["node.name", "device.name", "metadata.name"];
+
+/// The directories the daemon reads its configuration from, from lowest to highest priority
+fn config_dirs() -> Vec<PathBuf> {
+    let mut dirs = vec![
+        PathBuf::from("/usr/share/pipewire"),
+        PathBuf::from("/etc/pipewire"),
+    ];
+    if let Some(dir) = config::user_config_dir() {
+        dirs.push(dir.join("pipewire"));
+    }
+    dirs
+}
+
+/// The main configuration file followed by its drop-in fragments, in the order they're applied
+fn config_files(name: &str) -> Vec<PathBuf> {
+    let dirs = config_dirs();
+
+    // Only the highest priority main file is used
+    let mut files: Vec<PathBuf> = dirs
+        .iter()
+        .rev()
+        .map(|dir| dir.join(name))
+        .find(|path| path.is_file())
+        .into_iter()
+        .collect();
+
+    // Fragments with the same file name override each other
+    let mut fragments = BTreeMap::new();
+    for dir in &dirs {
+        let Ok(entries) = std::fs::read_dir(dir.join(format!("{name}.d"))) else {
+            continue;
+        };
+        for path in entries.filter_map(|e| e.ok()).map(|e| e.path()) {
+            if path.extension().is_some_and(|ext| ext == "conf") {
+                if let Some(file_name) = path.file_name() {
+                    fragments.insert(file_name.to_owned(), path);
+                }
+            }
+        }
+    }
+    files.extend(fragments.into_values());
+
+    files
+}
+
+enum Matcher {
+    Module(String),
+    Named(&'static str, String),
+    Factory(String),
+}
+
+struct Declared {
+    description: String,
+    file: PathBuf,
+    matcher: Matcher,
+    // Has a condition that may have prevented it from being created
+    conditional: bool,
+    // Declared with the nofail or ifexists flags, so failures are ignored by the daemon
+    optional: bool,
+}
+
+impl Declared {
+    fn from_value(section: &str, value: &Value, file: &Path) -> Option<Self> {
+        let matcher = if section == "context.modules" {
+            Matcher::Module(value.get("name")?.as_str()?.to_owned())
+        } else {
+            let factory = value.get("factory")?.as_str()?;
+            let args = value.get("args");
+            NAME_KEYS
+                .iter()
+                .find_map(|&key| {
+                    let name = args?.get(key)?.as_str()?;
+                    Some(Matcher::Named(key, name.to_owned()))
+                })
+                .unwrap_or_else(|| Matcher::Factory(factory.to_owned()))
+        };
+
+        let description = match &matcher {
+            Matcher::Module(name) => name.clone(),
+            Matcher::Named(key, name) => format!("{key} {name}"),
+            Matcher::Factory(factory) => format!("Object from {factory}"),
+        };
+
+        let optional = value
+            .get("flags")
+            .and_then(Value::as_array)
+            .is_some_and(|flags| {
+                flags
+                    .iter()
+                    .any(|flag| matches!(flag.as_str(), Some("nofail" | "ifexists")))
+            });
+
+        Some(Self {
+            description,
+            file: file.to_owned(),
+            matcher,
+            conditional: value.get("condition").is_some(),
+            optional,
+        })
+    }
+}
+
+/// Compares the modules and objects declared in the daemon's configuration against the live graph
+#[derive(Default)]
+pub struct ConfigCheck {
+    globals: BTreeMap<u32, Rc<RefCell<Global>>>,
+
+    config_name: String,
+    modules: Vec<Declared>,
+    objects: Vec<Declared>,
+    files: Vec<PathBuf>,
+    errors: Vec<(PathBuf, String)>,
+    checked: bool,
+
+    only_missing: bool,
+}
+
+impl ToolPlugin for ConfigCheck {
+    fn label(&self) -> &'static str {
+        "📋 Config Check"
+    }
+
+    fn description(&self) -> &'static str {
+        "Find modules and objects declared in the daemon's configuration that failed to appear"
+    }
+
+    fn interested_in(&self, object_type: &ObjectType) -> bool {
+        matches!(
+            object_type,
+            ObjectType::Core
+                | ObjectType::Module
+                | ObjectType::Factory
+                | ObjectType::Node
+                | ObjectType::Device
+                | ObjectType::Metadata
+        )
+    }
+
+    fn global_event(&mut self, event: GlobalEvent, _: &backend::Sender) {
+        match event {
+            GlobalEvent::Added(global) => {
+                let id = global.borrow().id();
+                self.globals.insert(id, Rc::clone(global));
+            }
+            GlobalEvent::Removed { id, .. } => {
+                self.globals.remove(&id);
+            }
+            GlobalEvent::PropertiesChanged(_) | GlobalEvent::InfoChanged(..) => {}
+        }
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        self.show(ui, sx);
+    }
+}
+
+impl ConfigCheck {
+    /// The configuration file the daemon was started with, as reported by the core
+    fn daemon_config_name(&self) -> Option<String> {
+        self.globals.values().find_map(|global| {
+            let global = global.borrow();
+            if *global.object_type() != ObjectType::Core {
+                return None;
+            }
+            global
+                .props()
+                .get("config.name")
+                .or_else(|| {
+                    global
+                        .info()?
+                        .iter()
+                        .find_map(|(k, v)| (*k == "config.name").then_some(v))
+                })
+                .cloned()
+        })
+    }
+
+    fn check(&mut self) {
+        self.modules.clear();
+        self.objects.clear();
+        self.errors.clear();
+        self.files = config_files(&self.config_name);
+
+        for file in &self.files {
+            let entries = match std::fs::read_to_string(file)
+                .map_err(|e| e.to_string())
+                .and_then(|contents| spa_json::parse(&contents))
+            {
+                Ok(entries) => entries,
+                Err(e) => {
+                    self.errors.push((file.clone(), e));
+                    continue;
+                }
+            };
+
+            for (section, value) in &entries {
+                let declared = match section.as_str() {
+                    "context.modules" => &mut self.modules,
+                    "context.objects" => &mut self.objects,
+                    _ => continue,
+                };
+                let Some(values) = value.as_array() else {
+                    continue;
+                };
+                declared.extend(
+                    values
+                        .iter()
+                        .filter_map(|value| Declared::from_value(section, value, file)),
+                );
+            }
+        }
+
+        self.checked = true;
+    }
+
+    /// Finds a live object that matches the declaration and hasn't been matched by another one
+    fn find(&self, declared: &Declared, used: &mut BTreeSet<u32>) -> Option<&Rc<RefCell<Global>>> {
+        let factory_name = |id: &str| {
+            let factory = self.globals.get(&id.parse().ok()?)?.borrow();
+            let name = factory.props().get("factory.name").cloned();
+            name
+        };
+
+        let (id, global) = self.globals.iter().find(|(id, global)| {
+            if used.contains(id) {
+                return false;
+            }
+
+            let global = global.borrow();
+            let props = global.props();
+            match &declared.matcher {
+                Matcher::Module(name) => {
+                    *global.object_type() == ObjectType::Module
+                        && props.get("module.name") == Some(name)
+                }
+                Matcher::Named(key, name) => props.get(*key) == Some(name),
+                Matcher::Factory(factory) => {
+                    !matches!(
+                        global.object_type(),
+                        ObjectType::Core | ObjectType::Module | ObjectType::Factory
+                    ) && props
+                        .get("factory.id")
+                        .and_then(|id| factory_name(id))
+                        .as_ref()
+                        == Some(factory)
+                }
+            }
+        })?;
+
+        used.insert(*id);
+        Some(global)
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        ui.label(
+            "Modules and objects that fail to load are often silently skipped by the daemon. \
+            This compares what its configuration declares against what exists. \
+            The configuration is read from this machine, so the results are only accurate \
+            for local remotes.",
+        );
+
+        if self.config_name.is_empty() {
+            self.config_name = self
+                .daemon_config_name()
+                .unwrap_or_else(|| String::from("pipewire.conf"));
+        }
+
+        ui.horizontal(|ui| {
+            ui.label("Configuration");
+            ui.add(egui::TextEdit::singleline(&mut self.config_name).desired_width(200.));
+            if ui
+                .add_enabled(!self.config_name.is_empty(), egui::Button::new("Check"))
+                .clicked()
+            {
+                self.check();
+            }
+            ui.checkbox(&mut self.only_missing, "Only show missing");
+        });
+
+        if !self.checked {
+            return;
+        }
+
+        ui.collapsing("Files", |ui| {
+            if self.files.is_empty() {
+                ui.weak("No configuration files found");
+            }
+            for file in &self.files {
+                ui.label(file.display().to_string());
+            }
+        });
+
+        for (file, e) in &self.errors {
+            ui.colored_label(
+                ui.visuals().error_fg_color,
+                format!("Failed to read {}: {e}", file.display()),
+            );
+        }
+
+        ui.separator();
+
+        let mut used = BTreeSet::new();
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for (heading, declared) in [("Modules", &self.modules), ("Objects", &self.objects)] {
+                ui.heading(heading);
+                if declared.is_empty() {
+                    ui.weak("None declared");
+                    continue;
+                }
+
+                egui::Grid::new(heading)
+                    .num_columns(3)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for declared in declared {
+                            let global = self.find(declared, &mut used);
+                            if global.is_some() && self.only_missing {
+                                continue;
+                            }
+
+                            ui.horizontal(|ui| {
+                                global_info_button(ui, global, sx);
+                                if global.is_some() {
+                                    ui.label("Found");
+                                } else if declared.conditional {
+                                    ui.weak("Missing").on_hover_text(
+                                        "Has a condition, which may not have been met",
+                                    );
+                                } else if declared.optional {
+                                    ui.colored_label(ui.visuals().warn_fg_color, "Missing")
+                                        .on_hover_text(
+                                            "Allowed to fail, so the daemon ignored the failure",
+                                        );
+                                } else {
+                                    ui.colored_label(ui.visuals().error_fg_color, "Missing");
+                                }
+                            });
+                            ui.label(&declared.description);
+                            ui.weak(
+                                declared
+                                    .file
+                                    .file_name()
+                                    .map_or_else(String::new, |n| n.to_string_lossy().into_owned()),
+                            )
+                            .on_hover_text(declared.file.display().to_string());
+                            ui.end_row();
+                        }
+                    });
+            }
+        });
+    }
+}
@@ -0,0 +1,287 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{collections::BTreeSet, fmt::Write};
+
+use eframe::egui;
+use pipewire::types::ObjectType;
+
+use crate::{
+    backend,
+    ui::{
+        context_manager::LoadedModule, globals_store::GlobalsStore, own_client::is_own,
+        util::config, ToolPlugin,
+    },
+};
+
+// Properties that are assigned by the daemon and would be wrong after a restart
+const RUNTIME_KEYS: [&str; 8] = [
+    "object.id",
+    "object.serial",
+    "object.linger",
+    "client.id",
+    "factory.id",
+    "module.id",
+    "node.id",
+    "factory.name",
+];
+
+// Properties of links that refer to objects by ID, and the name properties of those objects
+const LINK_KEYS: [(&str, &str); 4] = [
+    ("link.output.node", "node.name"),
+    ("link.output.port", "port.name"),
+    ("link.input.node", "node.name"),
+    ("link.input.port", "port.name"),
+];
+
+struct Item {
+    include: bool,
+    description: String,
+    fragment: String,
+}
+
+/// Generates configuration that recreates the objects and modules that were created in coppwr
+pub struct ConfigRecipe {
+    generate: bool,
+    modules: Vec<Item>,
+    objects: Vec<Item>,
+
+    file_name: String,
+    result: Option<Result<String, String>>,
+}
+
+impl Default for ConfigRecipe {
+    fn default() -> Self {
+        Self {
+            generate: false,
+            modules: Vec::new(),
+            objects: Vec::new(),
+
+            file_name: String::from("coppwr-recipe.conf"),
+            result: None,
+        }
+    }
+}
+
+impl ToolPlugin for ConfigRecipe {
+    fn label(&self) -> &'static str {
+        "📜 Config Recipe"
+    }
+
+    fn description(&self) -> &'static str {
+        "Turn the objects and modules created in coppwr into daemon configuration"
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, _: &backend::Sender) {
+        self.show(ui);
+    }
+}
+
+fn module_fragment(module: &LoadedModule) -> String {
+    let mut fragment = String::new();
+
+    if let Some(dir) = &module.dir {
+        writeln!(fragment, "    # Loaded from {dir}").ok();
+    }
+    match &module.args {
+        Some(args) => {
+            writeln!(fragment, "    {{ name = {}\n        args =", module.name).ok();
+            for line in args.lines() {
+                writeln!(fragment, "            {line}").ok();
+            }
+            fragment.push_str("    }");
+        }
+        None => {
+            write!(fragment, "    {{ name = {} }}", module.name).ok();
+        }
+    }
+
+    fragment
+}
+
+impl ConfigRecipe {
+    /// Whether the recipe should be generated, which requires access to the globals
+    pub fn take_generate_request(&mut self) -> bool {
+        std::mem::take(&mut self.generate)
+    }
+
+    pub fn generate(&mut self, globals: &GlobalsStore, modules: &[LoadedModule]) {
+        self.modules = modules
+            .iter()
+            .map(|module| Item {
+                include: true,
+                description: module.name.clone(),
+                fragment: module_fragment(module),
+            })
+            .collect();
+
+        let own_clients: BTreeSet<String> = globals
+            .globals()
+            .map(|g| g.borrow())
+            .filter(|g| *g.object_type() == ObjectType::Client && is_own(g))
+            .map(|g| g.id().to_string())
+            .collect();
+
+        let prop_of = |id: &str, key: &str| {
+            let global = globals.get_global(id.parse().ok()?)?;
+            let value = global.borrow().props().get(key).cloned();
+            value
+        };
+
+        self.objects = globals
+            .globals()
+            .filter_map(|global| {
+                let global = global.borrow();
+                let props = global.props();
+
+                if !props
+                    .get("client.id")
+                    .is_some_and(|id| own_clients.contains(id))
+                {
+                    return None;
+                }
+
+                // Only objects that were created by factories can be recreated
+                let factory = prop_of(props.get("factory.id")?, "factory.name")?;
+
+                let mut fragment = format!("    {{ factory = {factory}\n        args = {{\n");
+                for (k, v) in props
+                    .iter()
+                    .filter(|(k, _)| !RUNTIME_KEYS.contains(&k.as_str()))
+                {
+                    // IDs change when the daemon restarts, so objects are referred to by name
+                    let v = match LINK_KEYS.iter().find(|(key, _)| *key == k.as_str()) {
+                        Some((_, name_key)) => prop_of(v, name_key).unwrap_or_else(|| v.clone()),
+                        None => v.clone(),
+                    };
+                    writeln!(fragment, "            {k} = {}", config::quote(&v)).ok();
+                }
+                fragment.push_str("        }\n        flags = [ nofail ]\n    }");
+
+                Some(Item {
+                    include: true,
+                    description: format!(
+                        "{} {} from {factory}",
+                        global.id(),
+                        global.object_type().to_str()
+                    ),
+                    fragment,
+                })
+            })
+            .collect();
+
+        self.result = None;
+    }
+
+    fn recipe(&self) -> String {
+        let mut recipe = String::new();
+
+        for (section, items) in [
+            ("context.modules", &self.modules),
+            ("context.objects", &self.objects),
+        ] {
+            let mut items = items.iter().filter(|item| item.include).peekable();
+            if items.peek().is_none() {
+                continue;
+            }
+
+            writeln!(recipe, "{section} = [").ok();
+            for item in items {
+                writeln!(recipe, "{}", item.fragment).ok();
+            }
+            recipe.push_str("]\n");
+        }
+
+        recipe
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui) {
+        ui.label(
+            "Generates a configuration fragment for the daemon that recreates the modules \
+            loaded from the Context Manager and the objects coppwr created, so that they \
+            persist across restarts.",
+        );
+
+        self.generate = ui.button("Generate").clicked();
+
+        if self.modules.is_empty() && self.objects.is_empty() {
+            ui.label("Nothing to include, generate the recipe after creating objects or loading modules in coppwr");
+            return;
+        }
+
+        for (heading, items) in [
+            ("Modules", &mut self.modules),
+            ("Objects", &mut self.objects),
+        ] {
+            if items.is_empty() {
+                continue;
+            }
+
+            ui.separator();
+            ui.label(heading);
+            for item in items {
+                ui.checkbox(&mut item.include, &item.description)
+                    .on_hover_text(&item.fragment);
+            }
+        }
+
+        ui.separator();
+
+        let mut recipe = self.recipe();
+
+        ui.horizontal(|ui| {
+            if ui.button("Copy").clicked() {
+                ui.output_mut(|o| o.copied_text.clone_from(&recipe));
+            }
+
+            ui.add(
+                egui::TextEdit::singleline(&mut self.file_name)
+                    .hint_text("File name")
+                    .desired_width(200.),
+            );
+            if ui
+                .add_enabled(!self.file_name.is_empty(), egui::Button::new("Save"))
+                .on_hover_text("Write the fragment to the daemon's drop-in configuration directory")
+                .clicked()
+            {
+                self.result = Some(
+                    config::write_pipewire_conf_fragment(&self.file_name, &recipe)
+                        .map(|path| {
+                            format!("Saved to {}. Restart PipeWire to apply it.", path.display())
+                        })
+                        .map_err(|e| format!("Failed to save the configuration: {e}")),
+                );
+            }
+        });
+
+        match &self.result {
+            Some(Ok(message)) => {
+                ui.label(message);
+            }
+            Some(Err(e)) => {
+                ui.colored_label(ui.visuals().error_fg_color, e);
+            }
+            None => {}
+        }
+
+        ui.add(
+            egui::TextEdit::multiline(&mut recipe)
+                .code_editor()
+                .desired_width(f32::INFINITY)
+                .interactive(false),
+        );
+    }
+}
@@ -14,18 +14,33 @@
 //
 // SPDX-License-Identifier: GPL-3.0-only
 
-use std::{collections::BTreeMap, ops::Not};
+use std::{cell::RefCell, collections::BTreeMap, ops::Not, rc::Rc};
 
 use eframe::egui;
+use pipewire::types::ObjectType;
 
 use crate::{
     backend::{self, Request},
     ui::{
-        util::uis::{EditableKVList, MapEditor},
-        Tool,
+        globals_store::Global,
+        util::{
+            persistence::PersistentView,
+            uis::{global_info_button, EditableKVList, MapEditor},
+        },
+        ConnectionEvent, GlobalEvent, ToolPlugin,
     },
 };
 
+const MAX_HISTORY: usize = 20;
+
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "persistence", derive(serde::Serialize, serde::Deserialize))]
+pub struct LoadedModule {
+    pub dir: Option<String>,
+    pub name: String,
+    pub args: Option<String>,
+}
+
 #[derive(PartialEq, Eq)]
 enum View {
     PropertiesEditor,
@@ -57,10 +72,40 @@ pub struct ContextManager {
     module_name: String,
     module_args: String,
     module_props: EditableKVList,
+
+    modules: BTreeMap<u32, Rc<RefCell<Global>>>,
+    // Most recently loaded first
+    history: Vec<LoadedModule>,
+    // Loaded since coppwr connected, in order
+    loaded: Vec<LoadedModule>,
 }
 
-impl Tool for ContextManager {
-    const NAME: &'static str = "Context Manager";
+impl ToolPlugin for ContextManager {
+    fn label(&self) -> &'static str {
+        "🗄 Context Manager"
+    }
+
+    fn description(&self) -> &'static str {
+        "Manage the PipeWire context"
+    }
+
+    fn interested_in(&self, object_type: &ObjectType) -> bool {
+        *object_type == ObjectType::Module
+    }
+
+    fn global_event(&mut self, event: GlobalEvent, _: &backend::Sender) {
+        match event {
+            GlobalEvent::Added(global) => self.add_module(global),
+            GlobalEvent::Removed { id, .. } => self.remove_module(id),
+            GlobalEvent::PropertiesChanged(_) | GlobalEvent::InfoChanged(..) => {}
+        }
+    }
+
+    fn connection_event(&mut self, event: ConnectionEvent, _: &backend::Sender) {
+        if let ConnectionEvent::ContextProperties(properties) = event {
+            self.set_context_properties(properties.clone());
+        }
+    }
 
     fn show(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
         self.show(ui, sx);
@@ -68,10 +113,78 @@ impl Tool for ContextManager {
 }
 
 impl ContextManager {
-    pub fn set_context_properties(&mut self, properties: BTreeMap<String, String>) {
+    fn set_context_properties(&mut self, properties: BTreeMap<String, String>) {
         self.properties.set_map(properties);
     }
 
+    fn add_module(&mut self, global: &Rc<RefCell<Global>>) {
+        let id = global.borrow().id();
+        self.modules.insert(id, Rc::clone(global));
+    }
+
+    fn remove_module(&mut self, id: u32) {
+        self.modules.remove(&id);
+    }
+
+    /// The modules that have been loaded in coppwr's context since connecting
+    pub fn loaded_modules(&self) -> &[LoadedModule] {
+        &self.loaded
+    }
+
+    fn add_to_history(&mut self, module: LoadedModule) {
+        self.history.retain(|m| *m != module);
+        self.history.insert(0, module);
+        self.history.truncate(MAX_HISTORY);
+    }
+
+    fn show_local_modules(&self, ui: &mut egui::Ui) {
+        ui.label(
+            "Modules loaded from here are in coppwr's context, so they aren't visible to \
+            the remote and can't be unloaded. They last only as long as the connection.",
+        );
+
+        egui::Grid::new("local_modules")
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |ui| {
+                for module in &self.loaded {
+                    ui.label(&module.name);
+                    ui.label(module.args.as_deref().unwrap_or_default());
+                    ui.end_row();
+                }
+            });
+    }
+
+    fn show_loaded_modules(&self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        egui::Grid::new("loaded_modules")
+            .num_columns(4)
+            .striped(true)
+            .show(ui, |ui| {
+                for (id, module) in &self.modules {
+                    let module_borrow = module.borrow();
+
+                    global_info_button(ui, Some(module), sx);
+                    ui.label(format!(
+                        "{id} {}",
+                        module_borrow.info_value("Name").unwrap_or_default()
+                    ));
+                    ui.label(module_borrow.info_value("Arguments").unwrap_or_default());
+
+                    if ui
+                        .small_button("Unload")
+                        .on_hover_text(
+                            "Destroy the module, which also removes the objects it created",
+                        )
+                        .clicked()
+                    {
+                        sx.send(Request::DestroyObject(*id)).ok();
+                    }
+
+                    ui.end_row();
+                }
+            });
+    }
+
     fn show(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
         egui::ComboBox::new("view", "View")
             .selected_text(self.view.as_str())
@@ -136,8 +249,8 @@ impl ContextManager {
                             .on_disabled_hover_text("Provide a module name first")
                             .clicked()
                         {
-                            sx.send(Request::LoadModule {
-                                module_dir: self
+                            let module = LoadedModule {
+                                dir: self
                                     .module_dir
                                     .is_empty()
                                     .not()
@@ -148,6 +261,12 @@ impl ContextManager {
                                     .is_empty()
                                     .not()
                                     .then(|| self.module_args.clone()),
+                            };
+
+                            sx.send(Request::LoadModule {
+                                module_dir: module.dir.clone(),
+                                name: module.name.clone(),
+                                args: module.args.clone(),
                                 props: self
                                     .module_props
                                     .list()
@@ -156,6 +275,9 @@ impl ContextManager {
                                     .then(|| self.module_props.list().clone()),
                             })
                             .ok();
+
+                            self.loaded.push(module.clone());
+                            self.add_to_history(module);
                         }
                     });
                     if ui.button("Clear").clicked() {
@@ -165,7 +287,75 @@ impl ContextManager {
                         self.module_props.clear();
                     }
                 });
+
+                ui.separator();
+
+                ui.collapsing("History", |ui| {
+                    let mut selected = None;
+                    for module in &self.history {
+                        let text = match &module.args {
+                            Some(args) => format!("{} {args}", module.name),
+                            None => module.name.clone(),
+                        };
+                        if ui
+                            .selectable_label(false, text)
+                            .on_hover_text("Fill in the loader with this module")
+                            .clicked()
+                        {
+                            selected = Some(module.clone());
+                        }
+                    }
+
+                    if let Some(module) = selected {
+                        self.module_dir = module.dir.unwrap_or_default();
+                        self.module_name = module.name;
+                        self.module_args = module.args.unwrap_or_default();
+                    }
+
+                    if self.history.is_empty() {
+                        ui.label("Modules loaded from here will be listed for loading again");
+                    } else if ui.small_button("Clear history").clicked() {
+                        self.history.clear();
+                    }
+                });
+
+                egui::CollapsingHeader::new(format!("Loaded in coppwr ({})", self.loaded.len()))
+                    .id_source("local_modules")
+                    .show(ui, |ui| {
+                        self.show_local_modules(ui);
+                    });
+
+                egui::CollapsingHeader::new(format!(
+                    "Modules loaded in the remote ({})",
+                    self.modules.len()
+                ))
+                .id_source("loaded_modules")
+                .show(ui, |ui| {
+                    self.show_loaded_modules(ui, sx);
+                });
             }
         }
     }
 }
+
+#[cfg_attr(feature = "persistence", derive(serde::Serialize, serde::Deserialize))]
+pub struct PersistentData {
+    history: Vec<LoadedModule>,
+}
+
+impl PersistentView for ContextManager {
+    type Data = PersistentData;
+
+    fn with_data(data: &Self::Data) -> Self {
+        Self {
+            history: data.history.clone(),
+            ..Self::default()
+        }
+    }
+
+    fn save_data(&self) -> Option<Self::Data> {
+        Some(PersistentData {
+            history: self.history.clone(),
+        })
+    }
+}
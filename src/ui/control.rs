@@ -0,0 +1,224 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+// Handling of the commands of the control interface.
+// Commands are objects with a "command" member and their arguments, e.g.
+// {"command": "set-metadata", "id": 30, "key": "default.audio.sink", "value": "..."}
+// Responses have an "ok" member and either a "result" or an "error".
+
+use pipewire::types::ObjectType;
+
+use crate::{
+    backend::{self, ObjectMethod, Request},
+    pw_dump::{self, Json, MetadataProperty},
+    redact::Redactor,
+    ui::{globals_store::GlobalsStore, journal},
+};
+
+// Names and descriptions of the commands, sent for the "commands" command
+const COMMANDS: [(&str, &str); 6] = [
+    ("commands", "List the commands"),
+    (
+        "globals",
+        "Get the objects in the format of pw-dump. Takes an optional \"type\", e.g. \"Node\"",
+    ),
+    ("get", "Get the object with the \"id\""),
+    (
+        "set-metadata",
+        "Set the \"key\" of the \"subject\" (0 if omitted) of the metadata with the \"id\" \
+        to the \"value\", with an optional \"type\". A null value removes the property.",
+    ),
+    (
+        "create-object",
+        "Create an object of the \"type\" from the \"factory\" with the \"props\" object",
+    ),
+    ("destroy", "Destroy the object with the \"id\""),
+];
+
+fn success(result: Option<Json>) -> String {
+    let mut members = vec![("ok".to_owned(), Json::Raw("true".to_owned()))];
+    if let Some(result) = result {
+        members.push(("result".to_owned(), result));
+    }
+    Json::Object(members).to_compact_string()
+}
+
+/// A response for a failed command
+pub fn failure(error: &str) -> String {
+    Json::Object(vec![
+        ("ok".to_owned(), Json::Raw("false".to_owned())),
+        ("error".to_owned(), Json::String(error.to_owned())),
+    ])
+    .to_compact_string()
+}
+
+fn string(command: &Json, key: &str) -> Result<String, String> {
+    match command.get(key) {
+        Some(Json::String(s)) => Ok(s.clone()),
+        _ => Err(format!("\"{key}\" must be a string")),
+    }
+}
+
+fn id(command: &Json, key: &str) -> Result<u32, String> {
+    command
+        .get(key)
+        .and_then(|id| id.to_plain_string().parse().ok())
+        .ok_or_else(|| format!("\"{key}\" must be an ID"))
+}
+
+/// Accepts both the full interface names and the short ones, like `PipeWire:Interface:Node` and `Node`
+fn object_type(name: &str) -> ObjectType {
+    if name.starts_with("PipeWire:Interface:") {
+        backend::interface_object_type(name)
+    } else {
+        backend::interface_object_type(&format!("PipeWire:Interface:{name}"))
+    }
+}
+
+fn set_metadata(
+    command: &Json,
+    globals: &GlobalsStore,
+    sx: &backend::Sender,
+) -> Result<Option<Json>, String> {
+    let metadata = id(command, "id")?;
+    let subject = match command.get("subject") {
+        Some(_) => id(command, "subject")?,
+        None => 0,
+    };
+    let key = string(command, "key")?;
+    let type_ = match command.get("type") {
+        Some(Json::String(type_)) => Some(type_.clone()),
+        _ => None,
+    };
+
+    if !globals
+        .get_global(metadata)
+        .is_some_and(|g| *g.borrow().object_type() == ObjectType::Metadata)
+    {
+        return Err(format!("There is no metadata object with ID {metadata}"));
+    }
+
+    match command.get("value") {
+        None => return Err(String::from("\"value\" is missing")),
+        Some(Json::Raw(raw)) if raw == "null" => {
+            journal::remove_metadata_property(sx, metadata, subject, key, type_);
+        }
+        Some(value) => {
+            sx.send(Request::CallObjectMethod(
+                metadata,
+                ObjectMethod::MetadataSetProperty {
+                    subject,
+                    key,
+                    type_,
+                    value: Some(value.to_plain_string()),
+                },
+            ))
+            .ok();
+        }
+    }
+
+    Ok(None)
+}
+
+fn create_object(command: &Json, sx: &backend::Sender) -> Result<Option<Json>, String> {
+    let object_type = object_type(&string(command, "type")?);
+    let factory = string(command, "factory")?;
+    let props = match command.get("props") {
+        Some(Json::Object(members)) => members
+            .iter()
+            .map(|(k, v)| (k.clone(), v.to_plain_string()))
+            .collect(),
+        None => Vec::new(),
+        Some(_) => return Err(String::from("\"props\" must be an object")),
+    };
+
+    sx.send(Request::CreateObject(object_type, factory, props))
+        .ok();
+
+    Ok(None)
+}
+
+/// Handles a command and returns the response
+pub fn handle<'a>(
+    command: &str,
+    globals: &GlobalsStore,
+    metadata: impl Fn(u32) -> Vec<MetadataProperty<'a>>,
+    sx: &backend::Sender,
+) -> String {
+    let Some(command) = pw_dump::parse_json(command) else {
+        return failure("The command isn't valid JSON");
+    };
+
+    let Some(Json::String(name)) = command.get("command") else {
+        return failure("The command must be an object with a \"command\" string");
+    };
+
+    let result = match name.as_str() {
+        "commands" => Ok(Some(Json::Object(
+            COMMANDS
+                .iter()
+                .map(|(name, description)| {
+                    ((*name).to_owned(), Json::String((*description).to_owned()))
+                })
+                .collect(),
+        ))),
+        "globals" => {
+            let object_type = match command.get("type") {
+                Some(Json::String(name)) => Some(object_type(name)),
+                _ => None,
+            };
+
+            let mut all_globals: Vec<_> = globals
+                .globals()
+                .map(|g| g.borrow())
+                .filter(|g| object_type.as_ref().map_or(true, |t| g.object_type() == t))
+                .collect();
+            all_globals.sort_by_key(|g| g.id());
+
+            let redactor = Redactor::disabled();
+            Ok(Some(Json::Array(
+                all_globals
+                    .iter()
+                    .map(|global| pw_dump::object(&**global, &metadata, &redactor))
+                    .collect(),
+            )))
+        }
+        "get" => id(&command, "id").and_then(|id| {
+            let global = globals
+                .get_global(id)
+                .ok_or_else(|| format!("There is no object with ID {id}"))?;
+            Ok(Some(pw_dump::object(
+                &*global.borrow(),
+                &metadata,
+                &Redactor::disabled(),
+            )))
+        }),
+        "set-metadata" => set_metadata(&command, globals, sx),
+        "create-object" => create_object(&command, sx),
+        "destroy" => id(&command, "id").map(|id| {
+            journal::destroy_object(sx, id);
+            None
+        }),
+        _ => Err(format!(
+            "Unknown command {name}, send \"commands\" for a list"
+        )),
+    };
+
+    match result {
+        Ok(result) => success(result),
+        Err(e) => failure(&e),
+    }
+}
@@ -0,0 +1,155 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{
+    io::Read,
+    process::{Child, Command, Stdio},
+};
+
+use eframe::egui;
+
+const UNITS: [&str; 3] = [
+    "pipewire.service",
+    "pipewire-pulse.service",
+    "wireplumber.service",
+];
+
+enum State {
+    Confirming,
+    Running(Child),
+    Failed(String),
+}
+
+/// Restarts the user's PipeWire services through systemd
+pub struct DaemonRestart {
+    open: bool,
+    units: [bool; UNITS.len()],
+    state: State,
+}
+
+impl Default for DaemonRestart {
+    fn default() -> Self {
+        Self {
+            open: false,
+            units: [true; UNITS.len()],
+            state: State::Confirming,
+        }
+    }
+}
+
+impl DaemonRestart {
+    pub fn open(&mut self) {
+        if !matches!(self.state, State::Running(_)) {
+            self.state = State::Confirming;
+        }
+        self.open = true;
+    }
+
+    fn start(&mut self) {
+        let units = UNITS
+            .iter()
+            .zip(self.units)
+            .filter_map(|(unit, selected)| selected.then_some(*unit));
+
+        self.state = match Command::new("systemctl")
+            .args(["--user", "restart"])
+            .args(units)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+        {
+            Ok(child) => State::Running(child),
+            Err(e) => State::Failed(format!("Failed to run systemctl: {e}")),
+        };
+    }
+
+    /// Checks whether the restart is done.
+    /// Returns `true` once when the services have been restarted successfully.
+    #[must_use = "Indicates whether the connection should be reestablished"]
+    pub fn poll(&mut self) -> bool {
+        let State::Running(child) = &mut self.state else {
+            return false;
+        };
+
+        match child.try_wait() {
+            Ok(None) => false,
+            Ok(Some(status)) if status.success() => {
+                self.state = State::Confirming;
+                self.open = false;
+                true
+            }
+            Ok(Some(status)) => {
+                let mut stderr = String::new();
+                if let Some(mut pipe) = child.stderr.take() {
+                    pipe.read_to_string(&mut stderr).ok();
+                }
+                self.state = State::Failed(format!("systemctl exited with {status}\n{stderr}"));
+                false
+            }
+            Err(e) => {
+                self.state = State::Failed(format!("Failed to wait for systemctl: {e}"));
+                false
+            }
+        }
+    }
+
+    pub fn window(&mut self, ctx: &egui::Context) {
+        let mut open = self.open;
+        let mut start = false;
+        let mut cancel = false;
+
+        egui::Window::new("Restart PipeWire")
+            .collapsible(false)
+            .resizable(false)
+            .open(&mut open)
+            .show(ctx, |ui| match &self.state {
+                State::Confirming | State::Failed(_) => {
+                    ui.label(
+                        "The selected systemd user services will be restarted. \
+                        Streams will be interrupted and coppwr will reconnect afterwards.",
+                    );
+
+                    for (unit, selected) in UNITS.iter().zip(&mut self.units) {
+                        ui.checkbox(selected, *unit);
+                    }
+
+                    if let State::Failed(e) = &self.state {
+                        ui.colored_label(ui.visuals().error_fg_color, e);
+                    }
+
+                    ui.horizontal(|ui| {
+                        start = ui
+                            .add_enabled(self.units.contains(&true), egui::Button::new("Restart"))
+                            .clicked();
+                        cancel = ui.button("Cancel").clicked();
+                    });
+                }
+                State::Running(_) => {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label("Restarting...");
+                    });
+                }
+            });
+
+        self.open = open && !cancel;
+
+        if start {
+            self.start();
+        }
+    }
+}
@@ -0,0 +1,277 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fmt::Write,
+};
+
+use eframe::egui;
+
+use crate::{
+    backend::{self, pods::profiler::Profiling, Request},
+    redact::{self, Redactor},
+    ui::{globals_store::GlobalsStore, util::zip::ZipWriter, ConnectionEvent, ToolPlugin},
+};
+
+const MAX_PROFILINGS: usize = 100;
+
+fn write_properties<'a>(
+    out: &mut String,
+    properties: impl IntoIterator<Item = (&'a String, &'a String)>,
+    redactor: &Redactor,
+) {
+    for (k, v) in properties {
+        writeln!(out, "{k} = {}", redactor.property(k, v)).ok();
+    }
+}
+
+/// Generates a zip with the state of the remote and coppwr, to be attached to bug reports
+pub struct DebugBundle {
+    // Properties given in the connection dialog
+    connection_properties: [(&'static str, Vec<(String, String)>); 3],
+    context_properties: Option<BTreeMap<String, String>>,
+    profilings: VecDeque<String>,
+
+    path: String,
+    generate: bool,
+    result: Option<Result<String, String>>,
+}
+
+impl ToolPlugin for DebugBundle {
+    fn label(&self) -> &'static str {
+        "🐞 Debug Bundle"
+    }
+
+    fn description(&self) -> &'static str {
+        "Generate a zip with debugging information to attach to bug reports"
+    }
+
+    fn connection_event(&mut self, event: ConnectionEvent, _: &backend::Sender) {
+        if let ConnectionEvent::ContextProperties(properties) = event {
+            self.set_context_properties(properties);
+        }
+    }
+
+    fn profiler_samples(&mut self, samples: &[Profiling]) {
+        self.add_profilings(samples);
+    }
+
+    fn update(&mut self, globals: &GlobalsStore, _: &backend::Sender) {
+        if self.take_generate_request() {
+            self.generate(globals);
+        }
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        self.show(ui, sx);
+    }
+}
+
+impl DebugBundle {
+    pub fn new(
+        mainloop_properties: Vec<(String, String)>,
+        context_properties: Vec<(String, String)>,
+        client_properties: Vec<(String, String)>,
+    ) -> Self {
+        Self {
+            connection_properties: [
+                ("Mainloop properties", mainloop_properties),
+                ("Context properties", context_properties),
+                ("Client properties", client_properties),
+            ],
+            context_properties: None,
+            profilings: VecDeque::with_capacity(MAX_PROFILINGS),
+
+            path: String::from("coppwr-debug.zip"),
+            generate: false,
+            result: None,
+        }
+    }
+
+    fn set_context_properties(&mut self, properties: &BTreeMap<String, String>) {
+        self.context_properties = Some(properties.clone());
+    }
+
+    fn add_profilings(&mut self, profilings: &[Profiling]) {
+        for p in profilings {
+            if self.profilings.len() == MAX_PROFILINGS {
+                self.profilings.pop_front();
+            }
+
+            self.profilings.push_back(format!(
+                "{} ({}): rate {}/{}, quantum {}, delay {}, \
+                cpu load {:.3} {:.3} {:.3}, xruns {}, {} followers",
+                p.driver.name,
+                p.driver.id,
+                p.clock.rate.num,
+                p.clock.rate.denom,
+                p.clock.duration,
+                p.clock.delay,
+                p.info.cpu_load_fast,
+                p.info.cpu_load_medium,
+                p.info.cpu_load_slow,
+                p.driver.xrun_count.unwrap_or(p.info.xrun_count),
+                p.followers.len()
+            ));
+        }
+    }
+
+    /// Whether the bundle should be generated, which requires access to the globals
+    fn take_generate_request(&mut self) -> bool {
+        std::mem::take(&mut self.generate)
+    }
+
+    fn generate(&mut self, globals: &GlobalsStore) {
+        let redactor = Redactor::new(globals);
+
+        let daemon_version = globals
+            .get_global(0)
+            .and_then(|core| {
+                core.borrow()
+                    .info()?
+                    .iter()
+                    .find(|(k, _)| *k == "Version")
+                    .map(|(_, v)| v.clone())
+            })
+            .unwrap_or_else(|| String::from("Unknown"));
+
+        let mut versions = String::new();
+        writeln!(versions, "coppwr: {}", env!("CARGO_PKG_VERSION")).ok();
+        writeln!(
+            versions,
+            "Client library: {}",
+            // SAFETY: The returned string is static and null terminated
+            unsafe { std::ffi::CStr::from_ptr(pipewire::sys::pw_get_library_version()) }
+                .to_string_lossy()
+        )
+        .ok();
+        writeln!(versions, "Daemon: {daemon_version}").ok();
+
+        let mut graph = String::new();
+        let mut all_globals: Vec<_> = globals.globals().map(|g| g.borrow()).collect();
+        all_globals.sort_by_key(|g| g.id());
+        for global in &all_globals {
+            writeln!(
+                graph,
+                "{} {}{}",
+                global.id(),
+                global.object_type().to_str(),
+                global.name().map(|n| format!(" {n}")).unwrap_or_default()
+            )
+            .ok();
+            for (k, v) in global.info().unwrap_or_default() {
+                writeln!(graph, "  {k}: {v}").ok();
+            }
+            for (k, v) in global.props() {
+                writeln!(graph, "  {k} = {}", redactor.property(k, v)).ok();
+            }
+            graph.push('\n');
+        }
+
+        let mut context_properties = String::new();
+        if let Some(properties) = &self.context_properties {
+            write_properties(&mut context_properties, properties, &redactor);
+        }
+
+        let mut profiler = String::new();
+        for p in &self.profilings {
+            profiler.push_str(p);
+            profiler.push('\n');
+        }
+
+        let features: &[&str] = &[
+            #[cfg(feature = "cpu_frequency")]
+            "cpu_frequency",
+            #[cfg(feature = "persistence")]
+            "persistence",
+            #[cfg(feature = "pw_v0_3_77")]
+            "pw_v0_3_77",
+            #[cfg(feature = "xdg_desktop_portals")]
+            "xdg_desktop_portals",
+        ];
+        let mut settings = String::new();
+        writeln!(settings, "Features: {}", features.join(", ")).ok();
+        for (heading, properties) in &self.connection_properties {
+            writeln!(settings, "\n{heading}").ok();
+            write_properties(
+                &mut settings,
+                properties.iter().map(|(k, v)| (k, v)),
+                &redactor,
+            );
+        }
+
+        let files = [
+            ("versions.txt", versions),
+            ("graph.txt", graph),
+            ("context-properties.txt", context_properties),
+            ("profiler.txt", profiler),
+            ("settings.txt", settings),
+        ];
+
+        let mut zip = ZipWriter::new();
+        for (name, contents) in &files {
+            zip.add_file(name, redactor.text(contents).as_bytes());
+        }
+
+        self.result = Some(
+            std::fs::write(&self.path, zip.finish())
+                .map(|()| format!("Saved to {}", self.path))
+                .map_err(|e| format!("Failed to save the bundle: {e}")),
+        );
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        // The context properties are only sent by the backend when requested
+        if self.context_properties.is_none() {
+            self.context_properties = Some(BTreeMap::new());
+            sx.send(Request::GetContextProperties).ok();
+        }
+
+        ui.label(
+            "Collects the objects of the graph, the context properties, versions, \
+            recent profiler data and coppwr's settings in a zip to attach to bug reports.",
+        );
+
+        let mut redact = redact::enabled();
+        if ui
+            .checkbox(&mut redact, "Redact identifying information")
+            .on_hover_text("Applies to all exports")
+            .changed()
+        {
+            redact::set_enabled(redact);
+        }
+
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.path)
+                    .hint_text("File path")
+                    .desired_width(ui.available_width() - 80.),
+            );
+            self.generate = ui.button("Generate").clicked();
+        });
+
+        match &self.result {
+            Some(Ok(message)) => {
+                ui.label(message);
+            }
+            Some(Err(e)) => {
+                ui.colored_label(ui.visuals().error_fg_color, e);
+            }
+            None => {}
+        }
+    }
+}
@@ -0,0 +1,291 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
+
+use eframe::egui;
+use pipewire::types::ObjectType;
+
+use crate::{
+    backend::{self, ObjectMethod, Request},
+    ui::{
+        globals_store::Global,
+        util::{
+            config::{metadata_name, quote},
+            uis::global_info_button,
+        },
+        GlobalEvent, MetadataChange, ToolPlugin,
+    },
+};
+
+struct DefaultDevice {
+    label: &'static str,
+    // Without the default. or default.configured. prefix
+    key: &'static str,
+    media_classes: &'static [&'static str],
+}
+
+const DEFAULTS: [DefaultDevice; 3] = [
+    DefaultDevice {
+        label: "Audio sink",
+        key: "audio.sink",
+        media_classes: &["Audio/Sink", "Audio/Duplex"],
+    },
+    DefaultDevice {
+        label: "Audio source",
+        key: "audio.source",
+        media_classes: &["Audio/Source", "Audio/Source/Virtual", "Audio/Duplex"],
+    },
+    DefaultDevice {
+        label: "Video source",
+        key: "video.source",
+        media_classes: &["Video/Source"],
+    },
+];
+
+/// Switches the default devices through the `default` metadata object
+#[derive(Default)]
+pub struct Defaults {
+    metadata: Option<Rc<RefCell<Global>>>,
+    values: BTreeMap<String, String>,
+    nodes: BTreeMap<u32, Rc<RefCell<Global>>>,
+}
+
+impl ToolPlugin for Defaults {
+    fn label(&self) -> &'static str {
+        "⭐ Defaults"
+    }
+
+    fn description(&self) -> &'static str {
+        "Choose the default devices"
+    }
+
+    fn interested_in(&self, object_type: &ObjectType) -> bool {
+        matches!(object_type, ObjectType::Metadata | ObjectType::Node)
+    }
+
+    fn global_event(&mut self, event: GlobalEvent, _: &backend::Sender) {
+        match event {
+            GlobalEvent::Added(global) => {
+                let object_type = global.borrow().object_type().clone();
+                match object_type {
+                    ObjectType::Metadata => self.add_metadata(global),
+                    ObjectType::Node => self.add_node(global),
+                    _ => {}
+                }
+            }
+            GlobalEvent::Removed { id, .. } => self.remove_global(id),
+            GlobalEvent::PropertiesChanged(_) | GlobalEvent::InfoChanged(..) => {}
+        }
+    }
+
+    fn metadata_property(&mut self, change: MetadataChange, _: &backend::Sender) {
+        let id = change.metadata.borrow().id();
+        match change.key {
+            Some(key) => self.set_property(id, change.subject, key, change.value),
+            None => self.clear_properties(id),
+        }
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        self.show(ui, sx);
+    }
+}
+
+fn node_label(node: &Global) -> String {
+    let props = node.props();
+    let name = props.get("node.name").map_or("", String::as_str);
+
+    match props
+        .get("node.description")
+        .or_else(|| props.get("node.nick"))
+    {
+        Some(description) => format!("{description} ({name})"),
+        None => name.to_owned(),
+    }
+}
+
+impl Defaults {
+    fn add_metadata(&mut self, global: &Rc<RefCell<Global>>) {
+        if global
+            .borrow()
+            .props()
+            .get("metadata.name")
+            .is_some_and(|name| name == "default")
+        {
+            self.metadata = Some(Rc::clone(global));
+            self.values.clear();
+        }
+    }
+
+    fn add_node(&mut self, global: &Rc<RefCell<Global>>) {
+        let id = global.borrow().id();
+        self.nodes.insert(id, Rc::clone(global));
+    }
+
+    fn is_default(&self, id: u32) -> bool {
+        self.metadata
+            .as_ref()
+            .is_some_and(|metadata| metadata.borrow().id() == id)
+    }
+
+    fn remove_global(&mut self, id: u32) {
+        self.nodes.remove(&id);
+
+        if self.is_default(id) {
+            self.metadata = None;
+            self.values.clear();
+        }
+    }
+
+    fn set_property(&mut self, id: u32, subject: u32, key: &str, value: Option<&str>) {
+        if subject != 0 || !self.is_default(id) {
+            return;
+        }
+
+        match value {
+            Some(value) => {
+                self.values.insert(key.to_owned(), value.to_owned());
+            }
+            None => {
+                self.values.remove(key);
+            }
+        }
+    }
+
+    fn clear_properties(&mut self, id: u32) {
+        if self.is_default(id) {
+            self.values.clear();
+        }
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        let Some(metadata) = self.metadata.as_ref() else {
+            ui.label("The default metadata object could not be found");
+            return;
+        };
+
+        let id = metadata.borrow().id();
+
+        ui.horizontal(|ui| {
+            global_info_button(ui, Some(metadata), sx);
+            ui.label(format!("ID: {id}"));
+        });
+
+        ui.label(
+            "The session manager chooses the current default, \
+            preferring the configured one when it's available",
+        );
+
+        ui.separator();
+
+        let set = |key: String, value: Option<String>| {
+            sx.send(Request::CallObjectMethod(
+                id,
+                ObjectMethod::MetadataSetProperty {
+                    subject: 0,
+                    key,
+                    type_: value.is_some().then(|| String::from("Spa:String:JSON")),
+                    value,
+                },
+            ))
+            .ok();
+        };
+
+        egui::Grid::new("defaults")
+            .num_columns(4)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("");
+                ui.label("Current");
+                ui.label("Configured");
+                ui.end_row();
+
+                for default in &DEFAULTS {
+                    let name = |prefix: &str| {
+                        self.values
+                            .get(&format!("{prefix}.{}", default.key))
+                            .and_then(|v| metadata_name(v))
+                    };
+                    let current = name("default");
+                    let configured = name("default.configured");
+
+                    ui.label(default.label);
+
+                    ui.label(current.unwrap_or("None"))
+                        .on_hover_text(format!("default.{}", default.key));
+
+                    let selected = configured
+                        .and_then(|configured| {
+                            self.nodes.values().find(|node| {
+                                node.borrow()
+                                    .props()
+                                    .get("node.name")
+                                    .is_some_and(|n| n == configured)
+                            })
+                        })
+                        .map_or_else(
+                            || configured.unwrap_or("None").to_owned(),
+                            |node| node_label(&node.borrow()),
+                        );
+
+                    egui::ComboBox::from_id_source(default.key)
+                        .selected_text(selected)
+                        .width(300.)
+                        .show_ui(ui, |ui| {
+                            for node in self.nodes.values() {
+                                let node = node.borrow();
+                                let props = node.props();
+
+                                if !props.get("media.class").is_some_and(|class| {
+                                    default.media_classes.contains(&class.as_str())
+                                }) {
+                                    continue;
+                                }
+                                let Some(node_name) = props.get("node.name") else {
+                                    continue;
+                                };
+
+                                if ui
+                                    .selectable_label(
+                                        configured == Some(node_name.as_str()),
+                                        node_label(&node),
+                                    )
+                                    .clicked()
+                                {
+                                    set(
+                                        format!("default.configured.{}", default.key),
+                                        Some(format!("{{ \"name\": {} }}", quote(node_name))),
+                                    );
+                                }
+                            }
+                        })
+                        .response
+                        .on_hover_text(format!("default.configured.{}", default.key));
+
+                    if ui
+                        .add_enabled(configured.is_some(), egui::Button::new("Unset"))
+                        .on_hover_text("Let the session manager choose")
+                        .clicked()
+                    {
+                        set(format!("default.configured.{}", default.key), None);
+                    }
+
+                    ui.end_row();
+                }
+            });
+    }
+}
@@ -0,0 +1,132 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
+
+use eframe::egui;
+use pipewire::types::ObjectType;
+
+use crate::{
+    backend,
+    ui::{globals_store::Global, util::uis::global_info_button, GlobalEvent, ToolPlugin},
+};
+
+fn name_key(object_type: &ObjectType) -> Option<&'static str> {
+    match object_type {
+        ObjectType::Node => Some("node.name"),
+        ObjectType::Device => Some("device.name"),
+        _ => None,
+    }
+}
+
+/// Finds nodes and devices that have the same name
+#[derive(Default)]
+pub struct DuplicateNames {
+    globals: BTreeMap<u32, Rc<RefCell<Global>>>,
+}
+
+impl ToolPlugin for DuplicateNames {
+    fn label(&self) -> &'static str {
+        "👥 Duplicate Names"
+    }
+
+    fn description(&self) -> &'static str {
+        "Find nodes and devices that share a name"
+    }
+
+    fn interested_in(&self, object_type: &ObjectType) -> bool {
+        name_key(object_type).is_some()
+    }
+
+    fn global_event(&mut self, event: GlobalEvent, _: &backend::Sender) {
+        match event {
+            GlobalEvent::Added(global) => {
+                let id = global.borrow().id();
+                self.globals.insert(id, Rc::clone(global));
+            }
+            GlobalEvent::Removed { id, .. } => {
+                self.globals.remove(&id);
+            }
+            // Names are read when shown
+            GlobalEvent::PropertiesChanged(_) | GlobalEvent::InfoChanged(..) => {}
+        }
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        self.show(ui, sx);
+    }
+}
+
+impl DuplicateNames {
+    /// Groups of objects with the same type and name, for the names that are used more than once
+    fn duplicates(&self) -> BTreeMap<(&'static str, String), Vec<&Rc<RefCell<Global>>>> {
+        let mut names: BTreeMap<(&'static str, String), Vec<&Rc<RefCell<Global>>>> =
+            BTreeMap::new();
+
+        for global in self.globals.values() {
+            let global_borrow = global.borrow();
+            let Some(key) = name_key(global_borrow.object_type()) else {
+                continue;
+            };
+            if let Some(name) = global_borrow.props().get(key) {
+                names.entry((key, name.clone())).or_default().push(global);
+            }
+        }
+
+        names.retain(|_, globals| globals.len() > 1);
+
+        names
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        ui.label(
+            "Objects are looked up by name when they're targeted with target.object and when \
+            the session manager restores their state, so only one of the objects that share \
+            a name is used.",
+        );
+
+        ui.separator();
+
+        let duplicates = self.duplicates();
+
+        if duplicates.is_empty() {
+            ui.label("No duplicate names");
+            return;
+        }
+
+        for ((key, name), globals) in duplicates {
+            ui.group(|ui| {
+                ui.label(format!("{key} {name}"));
+
+                for global in globals {
+                    ui.horizontal(|ui| {
+                        global_info_button(ui, Some(global), sx);
+
+                        let global = global.borrow();
+                        let description = global
+                            .props()
+                            .get("node.description")
+                            .or_else(|| global.props().get("device.description"));
+                        ui.label(match description {
+                            Some(description) => format!("{} {description}", global.id()),
+                            None => global.id().to_string(),
+                        });
+                    });
+                }
+            });
+        }
+    }
+}
@@ -0,0 +1,434 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    fmt::Write,
+    time::{Instant, SystemTime},
+};
+
+use eframe::egui;
+use pipewire::types::ObjectType;
+
+use crate::{
+    backend,
+    redact::Redactor,
+    ui::{
+        globals_store::{Global, GlobalsStore},
+        util::time,
+        GlobalEvent, ToolPlugin,
+    },
+};
+
+const MAX_ENTRIES: usize = 10000;
+
+// Properties shown next to the name of added objects
+const KEY_PROPS: [&str; 4] = [
+    "media.class",
+    "application.name",
+    "factory.name",
+    "module.name",
+];
+
+#[derive(Clone, Copy)]
+enum Destination {
+    Clipboard,
+    File,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Added,
+    Removed,
+    Info,
+}
+
+impl Kind {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Added => "Added",
+            Self::Removed => "Removed",
+            Self::Info => "Info",
+        }
+    }
+}
+
+struct Entry {
+    // Seconds since the log started
+    time: f64,
+    kind: Kind,
+    id: u32,
+    object_type: String,
+    name: Option<String>,
+    details: String,
+}
+
+impl Entry {
+    fn line(&self) -> String {
+        let mut line = format!(
+            "{:>10.3}s {:<7} {} {}",
+            self.time,
+            self.kind.as_str(),
+            self.id,
+            self.object_type
+        );
+        if let Some(name) = &self.name {
+            write!(line, " {name}").ok();
+        }
+        if !self.details.is_empty() {
+            write!(line, " ({})", self.details).ok();
+        }
+        line
+    }
+}
+
+/// Log of the additions, removals and info changes of globals, for seeing
+/// objects that only exist for a short time
+pub struct EventLog {
+    start: Instant,
+    started_at: SystemTime,
+    entries: VecDeque<Entry>,
+    dropped: usize,
+
+    paused: bool,
+    kinds: [(Kind, bool); 3],
+    object_type: Option<String>,
+    search: String,
+    // Only show the events of the selected object, if there is one
+    follow_selection: bool,
+    selected: Option<u32>,
+
+    path: String,
+    // Where the log should be written, which requires the globals for redacting it
+    destination: Option<Destination>,
+    // Redacted text to put on the clipboard on the next frame
+    copied: Option<String>,
+    result: Option<Result<String, String>>,
+}
+
+impl Default for EventLog {
+    fn default() -> Self {
+        Self {
+            start: Instant::now(),
+            started_at: SystemTime::now(),
+            entries: VecDeque::new(),
+            dropped: 0,
+
+            paused: false,
+            kinds: [
+                (Kind::Added, true),
+                (Kind::Removed, true),
+                (Kind::Info, true),
+            ],
+            object_type: None,
+            search: String::new(),
+            follow_selection: true,
+            selected: None,
+
+            path: String::from("coppwr-events.txt"),
+            destination: None,
+            copied: None,
+            result: None,
+        }
+    }
+}
+
+impl ToolPlugin for EventLog {
+    fn label(&self) -> &'static str {
+        "📃 Event Log"
+    }
+
+    fn description(&self) -> &'static str {
+        "Log of objects being added, removed and changing"
+    }
+
+    fn interested_in(&self, _: &ObjectType) -> bool {
+        true
+    }
+
+    fn global_event(&mut self, event: GlobalEvent, _: &backend::Sender) {
+        match event {
+            GlobalEvent::Added(global) => self.global_added(&global.borrow()),
+            GlobalEvent::Removed { global, .. } => self.global_removed(&global.borrow()),
+            GlobalEvent::InfoChanged(global, previous) => {
+                self.global_info(&global.borrow(), previous);
+            }
+            GlobalEvent::PropertiesChanged(_) => {}
+        }
+    }
+
+    fn selection_changed(&mut self, selected: Option<u32>) {
+        self.set_selected(selected);
+    }
+
+    fn update(&mut self, globals: &GlobalsStore, _: &backend::Sender) {
+        if let Some(destination) = self.destination.take() {
+            self.write(destination, globals);
+        }
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, _: &backend::Sender) {
+        self.show(ui);
+    }
+}
+
+impl EventLog {
+    fn push(
+        &mut self,
+        kind: Kind,
+        id: u32,
+        object_type: &ObjectType,
+        name: Option<&String>,
+        details: String,
+    ) {
+        if self.paused {
+            return;
+        }
+
+        if self.entries.len() == MAX_ENTRIES {
+            self.entries.pop_front();
+            self.dropped += 1;
+        }
+
+        self.entries.push_back(Entry {
+            time: self.start.elapsed().as_secs_f64(),
+            kind,
+            id,
+            object_type: object_type.to_str().to_owned(),
+            name: name.cloned(),
+            details,
+        });
+    }
+
+    /// Sets the object selected in the views of the connection the events are from
+    fn set_selected(&mut self, id: Option<u32>) {
+        self.selected = id;
+    }
+
+    fn global_added(&mut self, global: &Global) {
+        let details = KEY_PROPS
+            .iter()
+            .filter_map(|key| Some(format!("{key}: {}", global.props().get(*key)?)))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        self.push(
+            Kind::Added,
+            global.id(),
+            global.object_type(),
+            global.name(),
+            details,
+        );
+    }
+
+    fn global_removed(&mut self, global: &Global) {
+        self.push(
+            Kind::Removed,
+            global.id(),
+            global.object_type(),
+            global.name(),
+            String::new(),
+        );
+    }
+
+    /// Logs the fields of the global's info that differ from its previous one
+    fn global_info(&mut self, global: &Global, previous: Option<&[(&'static str, String)]>) {
+        let Some(info) = global.info() else {
+            return;
+        };
+
+        let details = info
+            .iter()
+            .filter_map(|(key, value)| {
+                let old = previous
+                    .and_then(|previous| previous.iter().find(|(k, _)| k == key))
+                    .map(|(_, v)| v);
+                match old {
+                    Some(old) if old == value => None,
+                    Some(old) => Some(format!("{key}: {old} → {value}")),
+                    None => Some(format!("{key}: {value}")),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        // Info events are also emitted when only the properties or params change
+        if details.is_empty() {
+            return;
+        }
+
+        self.push(
+            Kind::Info,
+            global.id(),
+            global.object_type(),
+            global.name(),
+            details.join(", "),
+        );
+    }
+
+    fn matches(&self, entry: &Entry) -> bool {
+        self.kinds
+            .iter()
+            .any(|(kind, shown)| *shown && *kind == entry.kind)
+            && (!self.follow_selection || self.selected.map_or(true, |id| id == entry.id))
+            && self
+                .object_type
+                .as_ref()
+                .map_or(true, |t| *t == entry.object_type)
+            && (self.search.is_empty()
+                || entry.id.to_string() == self.search
+                || entry
+                    .name
+                    .as_ref()
+                    .is_some_and(|name| name.contains(&self.search))
+                || entry.details.contains(&self.search))
+    }
+
+    fn text(&self) -> String {
+        let mut text = format!(
+            "Event log started at {}\n",
+            time::format_timestamp(time::unix_secs(self.started_at))
+        );
+        if self.dropped != 0 {
+            writeln!(text, "{} older events were dropped", self.dropped).ok();
+        }
+        for entry in self.entries.iter().filter(|e| self.matches(e)) {
+            writeln!(text, "{}", entry.line()).ok();
+        }
+        text
+    }
+
+    fn write(&mut self, destination: Destination, globals: &GlobalsStore) {
+        let text = Redactor::new(globals).text(&self.text());
+        match destination {
+            Destination::Clipboard => self.copied = Some(text),
+            Destination::File => {
+                self.result = Some(
+                    std::fs::write(&self.path, text)
+                        .map(|()| format!("Saved to {}", self.path))
+                        .map_err(|e| format!("Failed to save the log: {e}")),
+                );
+            }
+        }
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui) {
+        if let Some(text) = self.copied.take() {
+            ui.output_mut(|o| o.copied_text = text);
+        }
+
+        ui.horizontal(|ui| {
+            ui.toggle_value(&mut self.paused, "⏸ Pause");
+            if ui.button("Clear").clicked() {
+                self.entries.clear();
+                self.dropped = 0;
+            }
+            ui.label(format!("{} events", self.entries.len()))
+                .on_hover_text(format!("Up to {MAX_ENTRIES} events are kept"));
+        });
+
+        ui.horizontal(|ui| {
+            for (kind, shown) in &mut self.kinds {
+                ui.checkbox(shown, kind.as_str());
+            }
+
+            egui::ComboBox::from_label("Type")
+                .selected_text(self.object_type.as_deref().unwrap_or("All"))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.object_type, None, "All");
+                    let types: BTreeSet<&String> =
+                        self.entries.iter().map(|e| &e.object_type).collect();
+                    for t in types {
+                        ui.selectable_value(&mut self.object_type, Some(t.clone()), t);
+                    }
+                });
+
+            ui.add(
+                egui::TextEdit::singleline(&mut self.search)
+                    .hint_text("ID, name or details")
+                    .desired_width(150.),
+            );
+
+            ui.checkbox(&mut self.follow_selection, "Follow selection")
+                .on_hover_text("Only show the events of the selected object");
+        });
+
+        ui.horizontal(|ui| {
+            if ui.button("Copy").clicked() {
+                self.destination = Some(Destination::Clipboard);
+            }
+            ui.add(
+                egui::TextEdit::singleline(&mut self.path)
+                    .hint_text("File path")
+                    .desired_width(ui.available_width() - 80.),
+            );
+            if ui.button("Save").clicked() {
+                self.destination = Some(Destination::File);
+            }
+        });
+
+        match &self.result {
+            Some(Ok(message)) => {
+                ui.label(message);
+            }
+            Some(Err(e)) => {
+                ui.colored_label(ui.visuals().error_fg_color, e);
+            }
+            None => {}
+        }
+
+        ui.separator();
+
+        let entries: Vec<&Entry> = self.entries.iter().filter(|e| self.matches(e)).collect();
+
+        if entries.is_empty() {
+            ui.label("No events");
+            return;
+        }
+
+        // How long the objects of removals existed, if their addition is logged
+        let mut added = BTreeMap::new();
+        let lifetimes: Vec<Option<f64>> = entries
+            .iter()
+            .map(|entry| match entry.kind {
+                Kind::Added => {
+                    added.insert(entry.id, entry.time);
+                    None
+                }
+                Kind::Removed => added.remove(&entry.id).map(|time| entry.time - time),
+                Kind::Info => None,
+            })
+            .collect();
+
+        let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+        egui::ScrollArea::vertical()
+            .auto_shrink([false, true])
+            .stick_to_bottom(true)
+            .show_rows(ui, row_height, entries.len(), |ui, rows| {
+                for (entry, lifetime) in entries[rows.clone()].iter().zip(&lifetimes[rows]) {
+                    let color = match entry.kind {
+                        Kind::Added => egui::Color32::GREEN,
+                        Kind::Removed => ui.visuals().error_fg_color,
+                        Kind::Info => ui.visuals().text_color(),
+                    };
+                    let response =
+                        ui.colored_label(color, egui::RichText::new(entry.line()).monospace());
+                    if let Some(lifetime) = lifetime {
+                        response.on_hover_text(format!("Existed for {lifetime:.3}s"));
+                    }
+                }
+            });
+    }
+}
@@ -0,0 +1,302 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::fmt::Write;
+
+use eframe::egui;
+use pipewire::types::ObjectType;
+
+use crate::{
+    backend,
+    pw_dump::{self, Json},
+    redact::{self, Redactor},
+    ui::{
+        globals_store::{Global, GlobalsStore},
+        ToolPlugin,
+    },
+};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Format {
+    PwDump,
+    Obs,
+    CarlaPatchbay,
+}
+
+impl Format {
+    const ALL: [Self; 3] = [Self::PwDump, Self::Obs, Self::CarlaPatchbay];
+
+    const fn name(self) -> &'static str {
+        match self {
+            Self::PwDump => "pw-dump",
+            Self::Obs => "OBS applications",
+            Self::CarlaPatchbay => "Carla/JACK patchbay",
+        }
+    }
+
+    const fn description(self) -> &'static str {
+        match self {
+            Self::PwDump => {
+                "Saves the objects in the JSON format of pw-dump, so that they can be compared \
+                with its output. Versions, permissions and params are not included."
+            }
+            Self::Obs => {
+                "Saves the applications that play audio as a JSON list of their names, \
+                binaries and node IDs, for choosing the ones to capture with OBS \
+                application audio capture."
+            }
+            Self::CarlaPatchbay => {
+                "Saves the links between ports as the external patchbay of a Carla project, \
+                with the port names JACK clients see, so that the routing can be restored \
+                by Carla."
+            }
+        }
+    }
+
+    const fn default_path(self) -> &'static str {
+        match self {
+            Self::PwDump => "coppwr-dump.json",
+            Self::Obs => "coppwr-obs-applications.json",
+            Self::CarlaPatchbay => "coppwr-patchbay.carxp",
+        }
+    }
+}
+
+/// Saves the objects of the remote in the format of pw-dump or for other tools
+pub struct Export {
+    format: Format,
+    path: String,
+    export: bool,
+    result: Option<Result<String, String>>,
+}
+
+impl Default for Export {
+    fn default() -> Self {
+        Self {
+            format: Format::PwDump,
+            path: String::from(Format::PwDump.default_path()),
+            export: false,
+            result: None,
+        }
+    }
+}
+
+impl ToolPlugin for Export {
+    fn label(&self) -> &'static str {
+        "📤 Export"
+    }
+
+    fn description(&self) -> &'static str {
+        "Save the objects as pw-dump compatible JSON, or for OBS and Carla"
+    }
+
+    fn update(&mut self, globals: &GlobalsStore, _: &backend::Sender) {
+        if self.take_export_request() {
+            self.export(globals);
+        }
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, _: &backend::Sender) {
+        self.show(ui);
+    }
+}
+
+/// Streams that play audio, by the application they belong to
+fn obs_applications(globals: &GlobalsStore, redactor: &Redactor) -> String {
+    let mut nodes: Vec<_> = globals
+        .globals()
+        .map(|g| g.borrow())
+        .filter(|g| {
+            *g.object_type() == ObjectType::Node
+                && g.props().get("media.class").map(String::as_str) == Some("Stream/Output/Audio")
+        })
+        .collect();
+    nodes.sort_by_key(|g| g.id());
+
+    let applications = nodes
+        .iter()
+        .map(|node| {
+            let mut members = vec![("id".to_owned(), Json::Raw(node.id().to_string()))];
+            for key in [
+                "application.name",
+                "application.process.binary",
+                "node.name",
+                "media.name",
+            ] {
+                if let Some(value) = node.props().get(key) {
+                    members.push((
+                        key.to_owned(),
+                        Json::String(redactor.property(key, value).into_owned()),
+                    ));
+                }
+            }
+            Json::Object(members)
+        })
+        .collect();
+
+    Json::Array(applications).to_pretty_string()
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '\'' => escaped.push_str("&apos;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// The name of a port as JACK clients of PipeWire see it, `client:port`
+fn jack_port_name(globals: &GlobalsStore, port: &Global) -> Option<String> {
+    if let Some(alias) = port.props().get("port.alias") {
+        return Some(alias.clone());
+    }
+
+    let node = globals.get_global(port.parent_id()?)?;
+    let node = node.borrow();
+    let props = node.props();
+    let client = props
+        .get("node.description")
+        .or_else(|| props.get("node.nick"))
+        .or_else(|| props.get("node.name"))?;
+
+    Some(format!("{client}:{}", port.props().get("port.name")?))
+}
+
+/// The links as the connections of the external patchbay of a Carla project
+fn carla_patchbay(globals: &GlobalsStore) -> String {
+    let port_name = |key: &str, link: &Global| {
+        let port = globals.get_global(link.props().get(key)?.parse().ok()?)?;
+        let port = port.borrow();
+        jack_port_name(globals, &port)
+    };
+
+    let mut connections: Vec<(String, String)> = globals
+        .globals()
+        .map(|g| g.borrow())
+        .filter(|g| *g.object_type() == ObjectType::Link)
+        .filter_map(|link| {
+            Some((
+                port_name("link.output.port", &link)?,
+                port_name("link.input.port", &link)?,
+            ))
+        })
+        .collect();
+    connections.sort();
+    connections.dedup();
+
+    let mut xml = String::from(
+        "<?xml version='1.0' encoding='UTF-8'?>\n\
+        <!DOCTYPE CARLA-PROJECT>\n\
+        <CARLA-PROJECT VERSION='2.5'>\n \
+        <ExternalPatchbay>\n",
+    );
+    for (source, target) in &connections {
+        writeln!(
+            xml,
+            "  <Connection>\n   \
+            <Source>{}</Source>\n   \
+            <Target>{}</Target>\n  \
+            </Connection>",
+            escape_xml(source),
+            escape_xml(target)
+        )
+        .ok();
+    }
+    xml.push_str(" </ExternalPatchbay>\n</CARLA-PROJECT>\n");
+
+    xml
+}
+
+impl Export {
+    /// Whether the objects should be exported, which requires access to the globals
+    fn take_export_request(&mut self) -> bool {
+        std::mem::take(&mut self.export)
+    }
+
+    fn export(&mut self, globals: &GlobalsStore) {
+        let redactor = Redactor::new(globals);
+        let text = match self.format {
+            Format::PwDump => {
+                pw_dump::dump(globals, |id| globals.metadata_properties(id), &redactor)
+            }
+            Format::Obs => obs_applications(globals, &redactor),
+            Format::CarlaPatchbay => carla_patchbay(globals),
+        };
+
+        self.result = Some(
+            std::fs::write(&self.path, text)
+                .map(|()| format!("Saved to {}", self.path))
+                .map_err(|e| format!("Failed to export: {e}")),
+        );
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Format");
+            for format in Format::ALL {
+                if ui
+                    .selectable_label(self.format == format, format.name())
+                    .clicked()
+                    && self.format != format
+                {
+                    // Keep paths that were edited
+                    if self.path == self.format.default_path() {
+                        self.path = String::from(format.default_path());
+                    }
+                    self.format = format;
+                    self.result = None;
+                }
+            }
+        });
+
+        ui.label(self.format.description());
+
+        let mut redact = redact::enabled();
+        if ui
+            .checkbox(&mut redact, "Redact identifying information")
+            .on_hover_text("Applies to all exports")
+            .changed()
+        {
+            redact::set_enabled(redact);
+        }
+
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.path)
+                    .hint_text("File path")
+                    .desired_width(ui.available_width() - 80.),
+            );
+            self.export = ui.button("Export").clicked();
+        });
+
+        match &self.result {
+            Some(Ok(message)) => {
+                ui.label(message);
+            }
+            Some(Err(e)) => {
+                ui.colored_label(ui.visuals().error_fg_color, e);
+            }
+            None => {}
+        }
+    }
+}
@@ -0,0 +1,252 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
+
+use eframe::egui;
+use pipewire::types::ObjectType;
+
+use crate::{
+    backend,
+    ui::{globals_store::Global, util::uis::global_info_button, GlobalEvent, ToolPlugin},
+};
+
+struct CommonFactory {
+    name: &'static str,
+    module: &'static str,
+    needed_for: &'static str,
+}
+
+const COMMON_FACTORIES: [CommonFactory; 7] = [
+    CommonFactory {
+        name: "link-factory",
+        module: "libpipewire-module-link-factory",
+        needed_for: "Creating links",
+    },
+    CommonFactory {
+        name: "adapter",
+        module: "libpipewire-module-adapter",
+        needed_for: "Creating audio nodes with format conversion",
+    },
+    CommonFactory {
+        name: "spa-node-factory",
+        module: "libpipewire-module-spa-node-factory",
+        needed_for: "Creating nodes from SPA plugins",
+    },
+    CommonFactory {
+        name: "spa-device-factory",
+        module: "libpipewire-module-spa-device-factory",
+        needed_for: "Creating devices from SPA plugins",
+    },
+    CommonFactory {
+        name: "client-node",
+        module: "libpipewire-module-client-node",
+        needed_for: "Clients exporting their own nodes, like streams",
+    },
+    CommonFactory {
+        name: "client-device",
+        module: "libpipewire-module-client-device",
+        needed_for: "Clients exporting devices, like session managers",
+    },
+    CommonFactory {
+        name: "metadata",
+        module: "libpipewire-module-metadata",
+        needed_for: "Creating metadata objects, like the default devices",
+    },
+];
+
+/// Shows which factories the loaded modules provide and which common ones are missing
+#[derive(Default)]
+pub struct Factories {
+    factories: BTreeMap<u32, Rc<RefCell<Global>>>,
+    modules: BTreeMap<u32, Rc<RefCell<Global>>>,
+}
+
+impl ToolPlugin for Factories {
+    fn label(&self) -> &'static str {
+        "🏭 Factories"
+    }
+
+    fn description(&self) -> &'static str {
+        "See which factories are available and which modules provide them"
+    }
+
+    fn interested_in(&self, object_type: &ObjectType) -> bool {
+        matches!(object_type, ObjectType::Factory | ObjectType::Module)
+    }
+
+    fn global_event(&mut self, event: GlobalEvent, _: &backend::Sender) {
+        match event {
+            GlobalEvent::Added(global) => {
+                let id = global.borrow().id();
+                match *global.borrow().object_type() {
+                    ObjectType::Factory => {
+                        self.factories.insert(id, Rc::clone(global));
+                    }
+                    ObjectType::Module => {
+                        self.modules.insert(id, Rc::clone(global));
+                    }
+                    _ => {}
+                }
+            }
+            GlobalEvent::Removed { id, .. } => {
+                self.factories.remove(&id);
+                self.modules.remove(&id);
+            }
+            GlobalEvent::PropertiesChanged(_) | GlobalEvent::InfoChanged(..) => {}
+        }
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        self.show(ui, sx);
+    }
+}
+
+impl Factories {
+    fn find_factory(&self, name: &str) -> Option<&Rc<RefCell<Global>>> {
+        self.factories.values().find(|factory| {
+            factory
+                .borrow()
+                .props()
+                .get("factory.name")
+                .is_some_and(|n| n == name)
+        })
+    }
+
+    fn find_module(&self, name: &str) -> Option<&Rc<RefCell<Global>>> {
+        self.modules.values().find(|module| {
+            module
+                .borrow()
+                .props()
+                .get("module.name")
+                .is_some_and(|n| n == name)
+        })
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        ui.heading("Common factories");
+        egui::Grid::new("common_factories")
+            .num_columns(3)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Factory");
+                ui.label("Module");
+                ui.label("Needed for");
+                ui.end_row();
+
+                for common in &COMMON_FACTORIES {
+                    let factory = self.find_factory(common.name);
+                    ui.horizontal(|ui| {
+                        global_info_button(ui, factory, sx);
+                        if factory.is_some() {
+                            ui.label(common.name);
+                        } else {
+                            ui.colored_label(ui.visuals().warn_fg_color, common.name)
+                                .on_hover_text(format!(
+                                    "Missing, load {} in the daemon's configuration to provide it",
+                                    common.module
+                                ));
+                        }
+                    });
+
+                    let module = self.find_module(common.module);
+                    ui.horizontal(|ui| {
+                        global_info_button(ui, module, sx);
+                        if module.is_some() {
+                            ui.label(common.module);
+                        } else {
+                            ui.weak(common.module).on_hover_text("Not loaded");
+                        }
+                    });
+
+                    ui.label(common.needed_for);
+
+                    ui.end_row();
+                }
+            });
+
+        ui.separator();
+
+        // Module ID -> factories
+        let mut provided: BTreeMap<Option<u32>, Vec<&Rc<RefCell<Global>>>> = BTreeMap::new();
+        for factory in self.factories.values() {
+            let module = factory
+                .borrow()
+                .props()
+                .get("module.id")
+                .and_then(|id| id.parse().ok());
+            provided.entry(module).or_default().push(factory);
+        }
+
+        ui.heading("Modules");
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            egui::Grid::new("module_factories")
+                .num_columns(2)
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.label("Module");
+                    ui.label("Factories");
+                    ui.end_row();
+
+                    for (id, module) in &self.modules {
+                        ui.horizontal(|ui| {
+                            global_info_button(ui, Some(module), sx);
+                            ui.label(
+                                module
+                                    .borrow()
+                                    .props()
+                                    .get("module.name")
+                                    .map_or("Unnamed module", String::as_str),
+                            );
+                        });
+                        show_factories(ui, provided.remove(&Some(*id)), sx);
+                        ui.end_row();
+                    }
+
+                    // Factories of modules that aren't visible, or that aren't provided by modules
+                    for (_, factories) in provided {
+                        ui.weak("Unknown module");
+                        show_factories(ui, Some(factories), sx);
+                        ui.end_row();
+                    }
+                });
+        });
+    }
+}
+
+fn show_factories(
+    ui: &mut egui::Ui,
+    factories: Option<Vec<&Rc<RefCell<Global>>>>,
+    sx: &backend::Sender,
+) {
+    let Some(factories) = factories else {
+        ui.weak("None");
+        return;
+    };
+
+    ui.horizontal_wrapped(|ui| {
+        for factory in factories {
+            global_info_button(ui, Some(factory), sx);
+            ui.label(
+                factory
+                    .borrow()
+                    .props()
+                    .get("factory.name")
+                    .map_or("Unnamed factory", String::as_str),
+            );
+        }
+    });
+}
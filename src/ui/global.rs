@@ -18,61 +18,244 @@ use std::{
     cell::RefCell,
     collections::BTreeMap,
     rc::{Rc, Weak},
-    sync::OnceLock,
+    time::SystemTime,
 };
 
 use eframe::egui;
 use pipewire::{
     self as pw,
     permissions::{Permission, PermissionFlags},
+    spa::{param::ParamType, pod::Value},
     types::ObjectType,
 };
 
 use crate::{
-    backend::{self, ObjectMethod, Request},
-    ui::util::uis::{key_val_display, map_editor, EditableKVList},
+    backend::{
+        self,
+        pods::{self, params, ParseError},
+        ObjectMethod, Request,
+    },
+    model,
+    schema::{self, TypedValue},
+    ui::{
+        alsa_params::{is_alsa, AlsaParams},
+        channel_map::ChannelMap,
+        clock_override::{ClockOverride, ClockSettings},
+        compat::{self, Area},
+        globals_store::ViewState,
+        journal,
+        rule_generator::RuleGenerator,
+        util::{
+            activity::Activity,
+            pod_view::pod_view,
+            procfs::{self, ProcessInfo},
+            time::{format_timestamp, unix_secs},
+            uis::{global_info_button, key_val_display, map_editor, EditableKVList},
+        },
+    },
 };
 
 fn draw_permissions(ui: &mut egui::Ui, p: &mut Permission) {
-    static PERMISSIONS: OnceLock<&[(PermissionFlags, &'static str)]> = OnceLock::new();
-
     ui.label("ID");
     ui.add(egui::widgets::DragValue::new(&mut p.id()));
 
-    for (permission, label) in PERMISSIONS
-        .get_or_init(|| {
-            #[cfg(feature = "pw_v0_3_77")]
-            if crate::backend::remote_version().is_some_and(|ver| ver.0 > 0 || ver.2 >= 77) {
-                return [
-                    (PermissionFlags::R, "Read"),
-                    (PermissionFlags::W, "Write"),
-                    (PermissionFlags::X, "Execute"),
-                    (PermissionFlags::M, "Metadata"),
-                    (PermissionFlags::L, "Link"),
-                ]
-                .as_slice();
-            }
-
-            [
-                (PermissionFlags::R, "Read"),
-                (PermissionFlags::W, "Write"),
-                (PermissionFlags::X, "Execute"),
-                (PermissionFlags::M, "Metadata"),
-            ]
-            .as_slice()
+    let mut flags = p.permission_flags();
+    if draw_permission_flags(ui, &mut flags) {
+        p.set_permission_flags(flags);
+    }
+}
+
+/// Shows toggles for the permissions supported by the remote.
+/// Returns whether any were toggled.
+pub fn draw_permission_flags(ui: &mut egui::Ui, flags: &mut PermissionFlags) -> bool {
+    const PERMISSIONS: [(PermissionFlags, &str); 4] = [
+        (PermissionFlags::R, "Read"),
+        (PermissionFlags::W, "Write"),
+        (PermissionFlags::X, "Execute"),
+        (PermissionFlags::M, "Metadata"),
+    ];
+
+    #[cfg(feature = "pw_v0_3_77")]
+    let link = compat::daemon_version()
+        .is_some_and(|version| version >= (0, 3, 77))
+        .then_some((PermissionFlags::L, "Link"));
+    #[cfg(not(feature = "pw_v0_3_77"))]
+    let link = None;
+
+    let mut changed = false;
+
+    for (permission, label) in PERMISSIONS.into_iter().chain(link) {
+        if ui
+            .selectable_label(flags.contains(permission), label)
+            .clicked()
+        {
+            flags.toggle(permission);
+            changed = true;
+        }
+    }
+
+    changed
+}
+
+/// Edits a list of object IDs and the permission flags for them
+pub fn draw_permission_list(ui: &mut egui::Ui, permissions: &mut Vec<(u32, u32)>) {
+    permissions.retain_mut(|(id, flags)| {
+        ui.horizontal(|ui| {
+            ui.label("ID");
+            ui.add(egui::DragValue::new(id))
+                .on_hover_text("4294967295 sets the default permissions");
+
+            let mut permission_flags = PermissionFlags::from_bits_truncate(*flags);
+            if draw_permission_flags(ui, &mut permission_flags) {
+                *flags = permission_flags.bits();
+            }
+
+            !ui.small_button("Delete").clicked()
         })
+        .inner
+    });
+
+    if ui.button("Add").clicked() {
+        permissions.push((0, 0));
+    }
+}
+
+fn update_permissions(sx: &backend::Sender, id: u32, permissions: Vec<Permission>) {
+    sx.send(Request::CallObjectMethod(
+        id,
+        ObjectMethod::ClientUpdatePermissions(permissions),
+    ))
+    .ok();
+
+    // Request the permissions instantly to update the UI
+    sx.send(Request::CallObjectMethod(
+        id,
+        ObjectMethod::ClientGetPermissions {
+            index: 0,
+            num: u32::MAX,
+        },
+    ))
+    .ok();
+}
+
+/// Permissions that restore `fetched`. Objects that got permissions after
+/// they were fetched are reset to the client's default permissions.
+fn reverted_permissions(fetched: &[Permission], current: &[Permission]) -> Vec<Permission> {
+    // The default permissions are stored under PW_ID_ANY
+    let default = fetched
         .iter()
-        .map(|(p, l)| (*p, *l))
+        .find(|p| p.id() == u32::MAX)
+        .map(Permission::permission_flags);
+
+    let mut reverted = fetched.to_vec();
+    if let Some(default) = default {
+        reverted.extend(
+            current
+                .iter()
+                .filter(|p| !fetched.iter().any(|f| f.id() == p.id()))
+                .map(|p| Permission::new(p.id(), default)),
+        );
+    }
+
+    reverted
+}
+
+const NODE_PARAMS: [(ParamType, &str); 5] = [
+    (ParamType::Props, "Props"),
+    (ParamType::EnumFormat, "EnumFormat"),
+    (ParamType::Format, "Format"),
+    (ParamType::Latency, "Latency"),
+    (ParamType::PortConfig, "PortConfig"),
+];
+
+const PORT_PARAMS: [(ParamType, &str); 3] = [
+    (ParamType::EnumFormat, "EnumFormat"),
+    (ParamType::Format, "Format"),
+    (ParamType::Latency, "Latency"),
+];
+
+const DEVICE_PARAMS: [(ParamType, &str); 5] = [
+    (ParamType::Props, "Props"),
+    (ParamType::EnumProfile, "EnumProfile"),
+    (ParamType::Profile, "Profile"),
+    (ParamType::EnumRoute, "EnumRoute"),
+    (ParamType::Route, "Route"),
+];
+
+fn show_process_info(
+    ui: &mut egui::Ui,
+    pid: u32,
+    process: &mut Option<ProcessInfo>,
+    terminate_error: &mut Option<String>,
+) {
+    let info = process.get_or_insert_with(|| ProcessInfo::read(pid));
+
+    if info
+        .last_update()
+        .map_or(true, |t| t.elapsed() >= std::time::Duration::from_secs(1))
     {
+        info.update_cpu_usage(pid);
+    }
+
+    egui::Grid::new(("process_info", pid))
+        .num_columns(2)
+        .show(ui, |ui| {
+            ui.label("PID");
+            ui.label(pid.to_string());
+            ui.end_row();
+
+            for (label, value) in [
+                (
+                    "Executable",
+                    info.executable.as_ref().map(|e| e.display().to_string()),
+                ),
+                ("Command line", info.cmdline.clone()),
+                ("Cgroup", info.cgroup.clone()),
+                ("Flatpak app ID", info.flatpak_app_id.clone()),
+                ("Systemd unit", info.systemd_unit.clone()),
+                (
+                    "CPU usage",
+                    info.cpu_usage.map(|usage| format!("{usage:.1}%")),
+                ),
+                (
+                    "Uptime",
+                    info.uptime().map(|uptime| {
+                        let secs = uptime.as_secs();
+                        format!("{}h {}m {}s", secs / 3600, secs / 60 % 60, secs % 60)
+                    }),
+                ),
+            ] {
+                ui.label(label);
+                ui.label(value.as_deref().unwrap_or("Unknown"));
+                ui.end_row();
+            }
+        });
+
+    ui.horizontal(|ui| {
         if ui
-            .selectable_label(p.permission_flags().contains(permission), label)
+            .small_button("Refresh")
+            .on_hover_text("Read the process information again, in case the PID was reused")
             .clicked()
         {
-            let mut flags = p.permission_flags();
-            flags.toggle(permission);
-
-            p.set_permission_flags(flags);
+            *process = None;
         }
+
+        ui.menu_button("Terminate", |ui| {
+            if ui
+                .button("Send SIGTERM")
+                .on_hover_text("Ask the process to exit")
+                .clicked()
+            {
+                if let Err(e) = procfs::terminate(pid) {
+                    *terminate_error = Some(e);
+                }
+                ui.close_menu();
+            }
+        });
+    });
+
+    if let Some(e) = terminate_error {
+        ui.colored_label(ui.visuals().error_fg_color, e.as_str());
     }
 }
 
@@ -80,8 +263,24 @@ fn draw_permissions(ui: &mut egui::Ui, p: &mut Permission) {
 pub enum ObjectData {
     Client {
         permissions: Option<Vec<Permission>>,
+        // The permissions as they were first fetched, for reverting changes
+        fetched_permissions: Option<Vec<Permission>>,
+        permissions_changed: bool,
         user_permissions: Vec<Permission>,
         user_properties: EditableKVList,
+        process: Option<ProcessInfo>,
+        terminate_error: Option<String>,
+    },
+    Node {
+        alsa_params: Option<AlsaParams>,
+        rule_generator: RuleGenerator,
+        show_monitor_ports: bool,
+        channel_map: Option<ChannelMap>,
+        clock_override: ClockOverride,
+    },
+    Device {
+        alsa_params: Option<AlsaParams>,
+        rule_generator: RuleGenerator,
     },
     Other(ObjectType),
 }
@@ -91,8 +290,23 @@ impl From<ObjectType> for ObjectData {
         match value {
             ObjectType::Client => Self::Client {
                 permissions: None,
+                fetched_permissions: None,
+                permissions_changed: false,
                 user_permissions: Vec::new(),
                 user_properties: EditableKVList::new(),
+                process: None,
+                terminate_error: None,
+            },
+            ObjectType::Node => Self::Node {
+                alsa_params: None,
+                rule_generator: RuleGenerator::default(),
+                show_monitor_ports: true,
+                channel_map: None,
+                clock_override: ClockOverride::default(),
+            },
+            ObjectType::Device => Self::Device {
+                alsa_params: None,
+                rule_generator: RuleGenerator::default(),
             },
             t => Self::Other(t),
         }
@@ -103,18 +317,61 @@ impl ObjectData {
     const fn pipewire_type(&self) -> &ObjectType {
         match self {
             Self::Client { .. } => &ObjectType::Client,
+            Self::Node { .. } => &ObjectType::Node,
+            Self::Device { .. } => &ObjectType::Device,
             Self::Other(t) => t,
         }
     }
 
-    fn show(&mut self, ui: &mut egui::Ui, sx: &backend::Sender, id: u32) {
+    fn update(&mut self, props: &BTreeMap<String, String>) {
+        if let Self::Node { alsa_params, .. } | Self::Device { alsa_params, .. } = self {
+            if alsa_params.is_none() && is_alsa(props) {
+                *alsa_params = Some(AlsaParams::from_props(props));
+            }
+        }
+
+        if let Self::Node {
+            channel_map,
+            clock_override,
+            ..
+        } = self
+        {
+            if channel_map.is_none() {
+                *channel_map = ChannelMap::from_props(props);
+            }
+            clock_override.update(props);
+        }
+    }
+
+    /// `subobjects` are the IDs of the object's children, used
+    /// for applying device wide settings to the device's nodes
+    fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        sx: &backend::Sender,
+        id: u32,
+        props: &BTreeMap<String, String>,
+        subobjects: impl Iterator<Item = u32>,
+    ) {
         match self {
             Self::Client {
                 permissions,
+                fetched_permissions,
+                permissions_changed,
                 user_permissions,
+                process,
+                terminate_error,
                 ..
             } => {
+                if let Some(pid) = procfs::client_pid(props) {
+                    ui.collapsing("Process", |ui| {
+                        show_process_info(ui, pid, process, terminate_error);
+                    });
+                }
+
                 ui.collapsing("Permissions", |ui| {
+                    compat::show_warnings(ui, Area::Permissions);
+
                     if ui.small_button("Get permissions").clicked() {
                         sx.send(Request::CallObjectMethod(
                             id,
@@ -154,29 +411,71 @@ impl ObjectData {
                         }
                     });
 
-                    if ui.small_button("Update permissions").clicked() {
-                        let mut all_permissions =
-                            Vec::with_capacity(permissions.len() + user_permissions.len());
+                    ui.horizontal(|ui| {
+                        if ui.small_button("Update permissions").clicked() {
+                            let mut all_permissions =
+                                Vec::with_capacity(permissions.len() + user_permissions.len());
 
-                        all_permissions.append(&mut permissions.clone());
-                        all_permissions.append(user_permissions);
+                            all_permissions.append(&mut permissions.clone());
+                            all_permissions.append(user_permissions);
 
-                        sx.send(Request::CallObjectMethod(
-                            id,
-                            ObjectMethod::ClientUpdatePermissions(all_permissions),
-                        ))
-                        .ok();
+                            *permissions_changed = true;
+                            update_permissions(sx, id, all_permissions);
+                        }
 
-                        // Request the permissions instantly to update the UI
-                        sx.send(Request::CallObjectMethod(
-                            id,
-                            ObjectMethod::ClientGetPermissions {
-                                index: 0,
-                                num: u32::MAX,
-                            },
-                        ))
-                        .ok();
-                    }
+                        if ui
+                            .add_enabled(
+                                *permissions_changed,
+                                egui::Button::new("Revert to fetched"),
+                            )
+                            .on_hover_text("Restore the permissions as they were first fetched")
+                            .clicked()
+                        {
+                            if let Some(fetched) = fetched_permissions {
+                                *permissions_changed = false;
+                                update_permissions(
+                                    sx,
+                                    id,
+                                    reverted_permissions(fetched, permissions),
+                                );
+                            }
+                        }
+                    });
+                });
+            }
+            Self::Node {
+                alsa_params,
+                rule_generator,
+                ..
+            } => {
+                if let Some(alsa_params) = alsa_params {
+                    ui.collapsing("ALSA parameters", |ui| {
+                        alsa_params.show(ui, sx, std::iter::once(id));
+                    });
+                }
+
+                ui.collapsing("WirePlumber rule", |ui| {
+                    rule_generator.show(
+                        ui,
+                        &ObjectType::Node,
+                        props,
+                        alsa_params.as_ref().map(AlsaParams::adjusted).unwrap_or_default(),
+                    );
+                });
+            }
+            Self::Device {
+                alsa_params,
+                rule_generator,
+            } => {
+                if let Some(alsa_params) = alsa_params {
+                    ui.collapsing("ALSA parameters", |ui| {
+                        ui.label("These are applied to all of the device's nodes");
+                        alsa_params.show(ui, sx, subobjects);
+                    });
+                }
+
+                ui.collapsing("WirePlumber rule", |ui| {
+                    rule_generator.show(ui, &ObjectType::Device, props, Vec::new());
                 });
             }
             Self::Other(_) => {}
@@ -191,9 +490,33 @@ pub struct Global {
     parent: Option<u32>,
 
     subobjects: Vec<Weak<RefCell<Global>>>,
+    // Objects that a module provides, directly or through its factories
+    provided: Vec<Weak<RefCell<Global>>>,
 
     info: Option<Box<[(&'static str, String)]>>,
     props: BTreeMap<String, String>,
+    // Param type -> param index -> param
+    params: BTreeMap<u32, BTreeMap<u32, Value>>,
+    // The params as they were received, including ones that failed to decode
+    raw_params: BTreeMap<u32, BTreeMap<u32, Vec<u8>>>,
+    // The error of the last param that failed to parse, until the params are enumerated again
+    param_error: Option<ParseError>,
+
+    // The error of binding the object, if the connection lacks the permissions for it
+    restricted: Option<String>,
+
+    // PipeWire doesn't report when objects are created or changed,
+    // so these are the times their events were received
+    first_seen: SystemTime,
+    // When the info, properties or params last changed
+    last_updated: SystemTime,
+    // Whether the object existed when connecting, so it was first seen then
+    existed_on_connect: bool,
+    activity: Activity,
+    // Not readable by the client the globals are viewed as
+    dimmed: bool,
+    // Whether destroying it is recorded in the journal, which only tracks the main connection
+    journaled: bool,
 
     object_data: ObjectData,
 }
@@ -204,13 +527,25 @@ impl Global {
         object_type: pw::types::ObjectType,
         props: Option<BTreeMap<String, String>>,
     ) -> Self {
+        let now = SystemTime::now();
         let mut this = Self {
             id,
             name: None,
             parent: None,
             subobjects: Vec::new(),
+            provided: Vec::new(),
             info: None,
             props: props.unwrap_or_default(),
+            params: BTreeMap::new(),
+            raw_params: BTreeMap::new(),
+            param_error: None,
+            restricted: None,
+            first_seen: now,
+            last_updated: now,
+            existed_on_connect: false,
+            activity: Activity::default(),
+            dimmed: false,
+            journaled: true,
             object_data: ObjectData::from(object_type),
         };
 
@@ -267,15 +602,26 @@ impl Global {
         }
 
         self.name = name.cloned();
+
+        self.object_data.update(&self.props);
     }
 
-    pub fn show(&mut self, ui: &mut egui::Ui, draw_subobjects: bool, sx: &backend::Sender) {
+    /// Shows the object, with the buttons for selecting and pinning it if it's shown
+    /// in a view of its connection instead of e.g. a popup
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        draw_subobjects: bool,
+        sx: &backend::Sender,
+        mut view: Option<&mut ViewState>,
+    ) {
         fn subobjects_display(
             ui: &mut egui::Ui,
             id_source: Option<&str>,
             len: usize,
             subobjects: impl Iterator<Item = Rc<RefCell<Global>>>,
             sx: &backend::Sender,
+            mut view: Option<&mut ViewState>,
         ) {
             let width = ui.available_width() / len as f32 - 6.;
 
@@ -291,7 +637,7 @@ impl Global {
                     for sub in subobjects {
                         ui.with_layout(egui::Layout::top_down_justified(egui::Align::Min), |ui| {
                             ui.set_max_width(width);
-                            sub.borrow_mut().show(ui, true, sx);
+                            sub.borrow_mut().show(ui, true, sx, view.as_deref_mut());
                         });
                     }
                 });
@@ -304,6 +650,10 @@ impl Global {
                 ui.set_width(ui.available_width());
             }
 
+            // Subobjects inherit the visuals, so they're reset for readable ones
+            let text_color = self.dimmed.then(|| ui.visuals().weak_text_color());
+            ui.visuals_mut().override_text_color = text_color;
+
             ui.scope(|ui| {
                 ui.style_mut().wrap = Some(false);
 
@@ -312,13 +662,77 @@ impl Global {
                 }
 
                 ui.horizontal(|ui| {
+                    if let Some(view) = view.as_deref_mut() {
+                        view.selection.button(ui, self.id);
+                        view.pins.button(ui, self);
+                        if ui
+                            .small_button("📋")
+                            .on_hover_text(
+                                "Copy as JSON in the format of pw-dump, replacing the \
+                                clipboard, to paste it into an editor or terminal",
+                            )
+                            .clicked()
+                        {
+                            view.copy_request = Some(self.id);
+                        }
+                    }
                     ui.label(self.id.to_string());
                     ui.label(self.object_type().to_str());
+                    self.activity.show(ui);
+                    if self.is_monitor_port() {
+                        ui.label("Monitor")
+                            .on_hover_text("Carries a copy of the signal of the node");
+                    }
+                    if self.dimmed {
+                        ui.label("👁 Hidden").on_hover_text(
+                            "The client the globals are viewed as can't read this object",
+                        );
+                    }
+                    if let Some(message) = &self.restricted {
+                        ui.colored_label(ui.visuals().warn_fg_color, "🔒 Restricted")
+                            .on_hover_text(format!(
+                                "The connection doesn't have the permissions to bind this object, \
+                                which is common for connections opened by portals or restricted \
+                                by the session manager. Its properties are the ones it was \
+                                announced with and its info and params aren't available.\n\
+                                PipeWire: {message}"
+                            ));
+                    }
+                    if let Some(error) = self.param_error {
+                        ui.colored_label(ui.visuals().warn_fg_color, "⚠ Malformed param")
+                            .on_hover_text(format!(
+                                "A param of this object couldn't be parsed: {error}. \
+                                It can be inspected in the params."
+                            ));
+                    }
                 });
 
+                ui.weak(format!(
+                    "Seen {}, updated {}",
+                    self.first_seen_text(),
+                    format_timestamp(unix_secs(self.last_updated))
+                ))
+                .on_hover_text(
+                    "When coppwr first received the object and when its info, properties \
+                    or params last changed. PipeWire doesn't report when objects are created.",
+                );
+
+                if let Some(mode) = self.processing_mode() {
+                    ui.label(format!("Mode: {mode}")).on_hover_text(
+                        "DSP: F32 planar audio with a port per channel\n\
+                        Convert: Converted to a format the node accepts\n\
+                        Passthrough: The format is passed to the node unchanged\n\
+                        Determined from the PortConfig and Format params",
+                    );
+                }
+
                 ui.with_layout(egui::Layout::default(), |ui| {
                     if ui.small_button("Destroy").clicked() {
-                        sx.send(Request::DestroyObject(self.id)).ok();
+                        if self.journaled {
+                            journal::destroy_object(sx, self.id);
+                        } else {
+                            sx.send(Request::DestroyObject(self.id)).ok();
+                        }
                     }
                 });
             });
@@ -326,6 +740,8 @@ impl Global {
             ui.push_id(self.id, |ui| {
                 if let Some(info) = self.info() {
                     key_val_display(ui, 400f32, f32::INFINITY, "Info", info.iter().cloned());
+                } else if self.restricted.is_some() {
+                    ui.label("Info unavailable, the object is restricted");
                 }
 
                 // Clients can have their properties updated
@@ -353,6 +769,64 @@ impl Global {
                     key_val_display(ui, 400f32, f32::INFINITY, "Properties", self.props().iter());
                 }
 
+                let param_types: &[(ParamType, &str)] = match self.object_type() {
+                    ObjectType::Node => &NODE_PARAMS,
+                    ObjectType::Port => &PORT_PARAMS,
+                    ObjectType::Device => &DEVICE_PARAMS,
+                    _ => &[],
+                };
+                if !param_types.is_empty() {
+                    ui.collapsing("Params", |ui| {
+                        self.show_params(ui, param_types, sx);
+                    });
+                }
+
+                if *self.object_type() == ObjectType::Node {
+                    ui.collapsing("Volume", |ui| {
+                        self.show_volume(ui, sx);
+                    });
+                    ui.collapsing("Resampler", |ui| {
+                        self.show_resampler(ui, sx);
+                    });
+                    self.show_channel_map(ui, sx);
+                    if let ObjectData::Node { clock_override, .. } = &mut self.object_data {
+                        ui.collapsing("Latency", |ui| {
+                            clock_override.show(ui, sx, self.id, &self.props);
+                        });
+                    }
+                }
+
+                if *self.object_type() == ObjectType::Port {
+                    ui.collapsing("Formats", |ui| {
+                        self.show_formats(ui, sx);
+                    });
+                }
+
+                if *self.object_type() == ObjectType::Device {
+                    ui.collapsing("Profile", |ui| {
+                        self.show_profiles(ui, sx);
+                    });
+                    ui.collapsing("Routes", |ui| {
+                        self.show_routes(ui, sx);
+                    });
+                    ui.collapsing("Pro Audio", |ui| {
+                        self.show_pro_audio(ui, sx);
+                    });
+                }
+
+                if *self.object_type() == ObjectType::Module {
+                    self.provided.retain(|p| p.upgrade().is_some());
+
+                    ui.collapsing("Provides", |ui| {
+                        self.show_provided(ui, sx);
+                    })
+                    .header_response
+                    .on_hover_text(
+                        "Unloading the module destroys its factories and the objects it created. \
+                        Objects that were created through its factories may be destroyed too.",
+                    );
+                }
+
                 let subobjects_header = match self.object_type() {
                     ObjectType::Device | ObjectType::Client => "Nodes",
                     ObjectType::Node => "Ports",
@@ -374,7 +848,12 @@ impl Global {
                                         egui::Layout::top_down_justified(egui::Align::Min),
                                         |ui| {
                                             for sub in subobjects {
-                                                sub.borrow_mut().show(ui, true, sx);
+                                                sub.borrow_mut().show(
+                                                    ui,
+                                                    true,
+                                                    sx,
+                                                    view.as_deref_mut(),
+                                                );
                                             }
                                         },
                                     );
@@ -384,7 +863,23 @@ impl Global {
                                     let mut ins = Vec::with_capacity(self.subobjects.len());
                                     let mut unk = Vec::with_capacity(self.subobjects.len());
 
-                                    for port in subobjects {
+                                    let ports: Vec<_> = subobjects.collect();
+                                    if ports.iter().any(|port| port.borrow().is_monitor_port()) {
+                                        if let ObjectData::Node {
+                                            show_monitor_ports, ..
+                                        } = &mut self.object_data
+                                        {
+                                            ui.checkbox(show_monitor_ports, "Show monitor ports")
+                                                .on_hover_text("Also applies to the graph");
+                                        }
+                                    }
+                                    let hide_monitors = self.monitor_ports_hidden();
+
+                                    for port in ports {
+                                        if hide_monitors && port.borrow().is_monitor_port() {
+                                            continue;
+                                        }
+
                                         match port
                                             .borrow()
                                             .props
@@ -413,6 +908,7 @@ impl Global {
                                             ports.len(),
                                             ports.into_iter(),
                                             sx,
+                                            view.as_deref_mut(),
                                         );
                                     }
                                 }
@@ -423,6 +919,7 @@ impl Global {
                                         self.subobjects.len(),
                                         subobjects,
                                         sx,
+                                        view.as_deref_mut(),
                                     );
                                 }
                                 _ => {}
@@ -435,11 +932,577 @@ impl Global {
                     });
                 }
 
-                self.object_data.show(ui, sx, self.id);
+                self.object_data.show(
+                    ui,
+                    sx,
+                    self.id,
+                    &self.props,
+                    self.subobjects.iter().filter_map(Weak::upgrade).map(|sub| {
+                        let id = sub.borrow().id;
+                        id
+                    }),
+                );
+            });
+        });
+    }
+
+    fn show_provided(&self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        if self.provided.is_empty() {
+            ui.label("No objects");
+            return;
+        }
+
+        egui::Grid::new(("provided", self.id))
+            .num_columns(3)
+            .striped(true)
+            .show(ui, |ui| {
+                for provided in self.provided.iter().filter_map(Weak::upgrade) {
+                    global_info_button(ui, Some(&provided), sx);
+
+                    let provided = provided.borrow();
+                    ui.label(format!(
+                        "{} {} {}",
+                        provided.id,
+                        provided.object_type().to_str(),
+                        provided.name().map_or("", String::as_str)
+                    ));
+
+                    if provided.module_id() == Some(self.id) {
+                        ui.label("Directly");
+                    } else {
+                        ui.label(format!(
+                            "Through factory {}",
+                            provided.props.get("factory.id").map_or("", String::as_str)
+                        ));
+                    }
+
+                    ui.end_row();
+                }
             });
+    }
+
+    fn show_params(
+        &mut self,
+        ui: &mut egui::Ui,
+        param_types: &[(ParamType, &str)],
+        sx: &backend::Sender,
+    ) {
+        if ui.small_button("Enumerate all").clicked() {
+            for (param_type, _) in param_types {
+                self.clear_params(*param_type);
+                sx.send(Request::EnumParams(self.id, *param_type)).ok();
+            }
+        }
+
+        for &(param_type, name) in param_types {
+            let count = self
+                .raw_params
+                .get(&param_type.as_raw())
+                .map_or(0, BTreeMap::len);
+
+            egui::CollapsingHeader::new(format!("{name} ({count})"))
+                .id_source(name)
+                .show(ui, |ui| {
+                    if ui.small_button("Enumerate").clicked() {
+                        self.clear_params(param_type);
+                        sx.send(Request::EnumParams(self.id, param_type)).ok();
+                    }
+
+                    let decoded = self.params.get(&param_type.as_raw());
+                    for (i, (index, raw)) in self
+                        .raw_params
+                        .get(&param_type.as_raw())
+                        .into_iter()
+                        .flatten()
+                        .enumerate()
+                    {
+                        if count > 1 {
+                            ui.separator();
+                        }
+
+                        if let Some(param) = decoded.and_then(|params| params.get(index)) {
+                            egui::Grid::new((name, i))
+                                .num_columns(2)
+                                .striped(true)
+                                .show(ui, |ui| {
+                                    for (key, value) in params::flatten(param) {
+                                        ui.label(key);
+                                        ui.label(value);
+                                        ui.end_row();
+                                    }
+                                });
+                        }
+
+                        egui::CollapsingHeader::new("Inspect")
+                            .id_source((name, i, "inspect"))
+                            .show(ui, |ui| {
+                                pod_view(ui, (name, i), raw);
+                            });
+                    }
+                });
+        }
+    }
+
+    fn show_formats(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        if ui
+            .small_button("Enumerate")
+            .on_hover_text("Get the formats the port supports and the one it has negotiated")
+            .clicked()
+        {
+            for param_type in [ParamType::EnumFormat, ParamType::Format] {
+                self.clear_params(param_type);
+                sx.send(Request::EnumParams(self.id, param_type)).ok();
+            }
+        }
+
+        let formats: Vec<(&str, params::FormatSummary)> = [
+            (ParamType::Format, "Negotiated"),
+            (ParamType::EnumFormat, "Supported"),
+        ]
+        .into_iter()
+        .flat_map(|(param_type, label)| {
+            self.params(param_type)
+                .filter_map(params::FormatSummary::from_param)
+                .map(move |format| (label, format))
+        })
+        .collect();
+
+        if formats.is_empty() {
+            ui.label("No formats have been enumerated");
+            return;
+        }
+
+        let audio = formats
+            .iter()
+            .any(|(_, f)| f.rate.is_some() || f.channels.is_some());
+        let video = formats
+            .iter()
+            .any(|(_, f)| f.size.is_some() || f.framerate.is_some());
+
+        egui::Grid::new("formats").striped(true).show(ui, |ui| {
+            ui.label("");
+            ui.label("Media");
+            ui.label("Format");
+            if audio {
+                ui.label("Rate");
+                ui.label("Channels");
+            }
+            if video {
+                ui.label("Size");
+                ui.label("Framerate");
+            }
+            ui.end_row();
+
+            let optional = |ui: &mut egui::Ui, value: &Option<String>| {
+                ui.label(value.as_deref().unwrap_or("-"));
+            };
+
+            for (label, format) in &formats {
+                ui.label(*label);
+                ui.label(format!("{} {}", format.media_type, format.media_subtype));
+                optional(ui, &format.format);
+                if audio {
+                    optional(ui, &format.rate);
+                    optional(ui, &format.channels);
+                }
+                if video {
+                    optional(ui, &format.size);
+                    optional(ui, &format.framerate);
+                }
+                ui.end_row();
+            }
         });
     }
 
+    fn show_volume(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        let Some(props) = self
+            .params
+            .get_mut(&ParamType::Props.as_raw())
+            .and_then(|params| params.values_mut().next())
+        else {
+            if ui.small_button("Load").clicked() {
+                sx.send(Request::EnumParams(self.id, ParamType::Props)).ok();
+            }
+            return;
+        };
+
+        let Some(mut volume) = params::Volume::from_props(props) else {
+            ui.label("This node has no volume controls");
+            return;
+        };
+        let previous = volume.clone();
+
+        if let Some(mute) = &mut volume.mute {
+            ui.checkbox(mute, "Mute");
+        }
+
+        // Volumes are linear, 1 leaves the signal unchanged
+        if let Some(v) = &mut volume.volume {
+            ui.add(egui::Slider::new(v, 0f32..=1.5).text("Volume"));
+        }
+        for (i, v) in volume.channel_volumes.iter_mut().enumerate() {
+            ui.add(egui::Slider::new(v, 0f32..=1.5).text(format!("Channel {i}")));
+        }
+
+        if volume == previous {
+            return;
+        }
+
+        volume.apply(props);
+
+        if let Some(pod) = pods::props::volume(&volume) {
+            sx.send(Request::CallObjectMethod(
+                self.id,
+                ObjectMethod::SetParam {
+                    id: ParamType::Props,
+                    flags: 0,
+                    pod,
+                },
+            ))
+            .ok();
+        }
+    }
+
+    fn show_profiles(&self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        let profiles: Vec<_> = self
+            .params(ParamType::EnumProfile)
+            .filter_map(params::Profile::from_param)
+            .collect();
+        let active = self
+            .params(ParamType::Profile)
+            .find_map(params::Profile::from_param);
+
+        let Some(active) = active.filter(|_| !profiles.is_empty()) else {
+            if ui.small_button("Load").clicked() {
+                sx.send(Request::EnumParams(self.id, ParamType::EnumProfile))
+                    .ok();
+                sx.send(Request::EnumParams(self.id, ParamType::Profile))
+                    .ok();
+            }
+            return;
+        };
+
+        let label = |profile: &params::Profile| {
+            let name = profile.description.as_ref().unwrap_or(&profile.name);
+            if profile.available {
+                name.clone()
+            } else {
+                format!("{name} (unavailable)")
+            }
+        };
+
+        let mut selected = active.index;
+        egui::ComboBox::from_id_source(("profile", self.id))
+            .selected_text(label(&active))
+            .show_ui(ui, |ui| {
+                for profile in &profiles {
+                    ui.selectable_value(&mut selected, profile.index, label(profile))
+                        .on_hover_text(&profile.name);
+                }
+            });
+
+        if selected == active.index {
+            return;
+        }
+
+        if let Some(pod) = pods::props::profile(selected) {
+            sx.send(Request::CallObjectMethod(
+                self.id,
+                ObjectMethod::SetParam {
+                    id: ParamType::Profile,
+                    flags: 0,
+                    pod,
+                },
+            ))
+            .ok();
+            // The routes depend on the profile
+            for param_type in [ParamType::Profile, ParamType::Route] {
+                sx.send(Request::EnumParams(self.id, param_type)).ok();
+            }
+        }
+    }
+
+    fn show_routes(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        let enum_routes: Vec<_> = self
+            .params(ParamType::EnumRoute)
+            .filter_map(params::Route::from_param)
+            .collect();
+        let profile = self
+            .params(ParamType::Profile)
+            .find_map(params::Profile::from_param)
+            .map(|p| p.index);
+
+        let Some(routes) = self
+            .params
+            .get_mut(&ParamType::Route.as_raw())
+            .filter(|_| !enum_routes.is_empty())
+        else {
+            if ui.small_button("Load").clicked() {
+                for param_type in [ParamType::EnumRoute, ParamType::Route, ParamType::Profile] {
+                    sx.send(Request::EnumParams(self.id, param_type)).ok();
+                }
+            }
+            return;
+        };
+
+        if routes.is_empty() {
+            ui.label("The active profile has no routes");
+            return;
+        }
+
+        let label = |route: &params::Route| {
+            let name = route.description.as_ref().unwrap_or(&route.name);
+            if route.available {
+                name.clone()
+            } else {
+                format!("{name} (unplugged)")
+            }
+        };
+
+        for (i, value) in routes.values_mut().enumerate() {
+            let Some(route) = params::Route::from_param(value) else {
+                continue;
+            };
+            let Some(device) = route.device else {
+                continue;
+            };
+
+            let mut request = None;
+
+            ui.group(|ui| {
+                ui.horizontal(|ui| {
+                    ui.label(if route.input { "Input" } else { "Output" });
+
+                    let mut selected = route.index;
+                    egui::ComboBox::from_id_source(("route", self.id, i))
+                        .selected_text(label(&route))
+                        .show_ui(ui, |ui| {
+                            for r in enum_routes.iter().filter(|r| {
+                                r.input == route.input
+                                    && r.devices.contains(&device)
+                                    && profile.map_or(true, |p| r.profiles.contains(&p))
+                            }) {
+                                ui.selectable_value(&mut selected, r.index, label(r))
+                                    .on_hover_text(&r.name);
+                            }
+                        });
+
+                    if selected != route.index {
+                        request = Some((selected, None));
+                    }
+                });
+
+                let Some(mut volume) = route.volume.clone() else {
+                    return;
+                };
+                let previous = volume.clone();
+
+                if let Some(mute) = &mut volume.mute {
+                    ui.checkbox(mute, "Mute");
+                }
+                if let Some(v) = &mut volume.volume {
+                    ui.add(egui::Slider::new(v, 0f32..=1.5).text("Volume"));
+                }
+                for (i, v) in volume.channel_volumes.iter_mut().enumerate() {
+                    ui.add(egui::Slider::new(v, 0f32..=1.5).text(format!("Channel {i}")));
+                }
+
+                if volume != previous {
+                    if let Some(props) = params::route_props_mut(value) {
+                        volume.apply(props);
+                    }
+                    request = Some((route.index, Some(volume)));
+                }
+            });
+
+            let Some((index, volume)) = request else {
+                continue;
+            };
+            if let Some(pod) = pods::props::route(index, device, volume.as_ref()) {
+                sx.send(Request::CallObjectMethod(
+                    self.id,
+                    ObjectMethod::SetParam {
+                        id: ParamType::Route,
+                        flags: 0,
+                        pod,
+                    },
+                ))
+                .ok();
+                // Volume changes are applied locally
+                if volume.is_none() {
+                    sx.send(Request::EnumParams(self.id, ParamType::Route)).ok();
+                }
+            }
+        }
+    }
+
+    fn show_pro_audio(&self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        let profiles: Vec<_> = self
+            .params(ParamType::EnumProfile)
+            .filter_map(params::Profile::from_param)
+            .collect();
+        let active = self
+            .params(ParamType::Profile)
+            .find_map(params::Profile::from_param);
+
+        let Some(active) = active.filter(|_| !profiles.is_empty()) else {
+            if ui.small_button("Load").clicked() {
+                sx.send(Request::EnumParams(self.id, ParamType::EnumProfile))
+                    .ok();
+                sx.send(Request::EnumParams(self.id, ParamType::Profile))
+                    .ok();
+            }
+            return;
+        };
+
+        let Some(pro_audio) = profiles.iter().find(|p| p.name == "pro-audio") else {
+            ui.label("This device has no Pro Audio profile");
+            return;
+        };
+
+        ui.label(
+            "The Pro Audio profile opens the device with its raw channels. \
+            There is no channel mapping so every channel is a separate AUX port, \
+            and mixer controls and routes like hardware volume and output switching are unavailable.",
+        );
+
+        let mut enabled = active.index == pro_audio.index;
+
+        // Switching back goes to the best profile, like the session manager would choose
+        let fallback = profiles
+            .iter()
+            .filter(|p| p.available && p.name != "pro-audio" && p.name != "off")
+            .max_by_key(|p| p.priority);
+
+        if !ui
+            .add_enabled(
+                enabled || fallback.is_some(),
+                egui::Checkbox::new(&mut enabled, "Pro Audio"),
+            )
+            .changed()
+        {
+            return;
+        }
+
+        let index = if enabled {
+            Some(pro_audio.index)
+        } else {
+            fallback.map(|p| p.index)
+        };
+
+        if let Some(pod) = index.and_then(pods::props::profile) {
+            sx.send(Request::CallObjectMethod(
+                self.id,
+                ObjectMethod::SetParam {
+                    id: ParamType::Profile,
+                    flags: 0,
+                    pod,
+                },
+            ))
+            .ok();
+            sx.send(Request::EnumParams(self.id, ParamType::Profile))
+                .ok();
+        }
+    }
+
+    fn show_resampler(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        if let Some(quality) = self.props.get("resample.quality") {
+            ui.label(format!("Configured quality: {quality}"));
+        }
+
+        let Some(props) = self
+            .params
+            .get_mut(&ParamType::Props.as_raw())
+            .and_then(|params| params.values_mut().next())
+        else {
+            if ui.small_button("Load").clicked() {
+                sx.send(Request::EnumParams(self.id, ParamType::Props)).ok();
+            }
+            return;
+        };
+
+        let mut changed = Vec::new();
+
+        if let Some(Value::Int(quality)) = params::props_param_mut(props, "resample.quality") {
+            if ui
+                .add(egui::Slider::new(quality, 0..=14).text("Quality"))
+                .on_hover_text(
+                    "Higher qualities use longer filters, which cost more CPU time \
+                    and add latency. 4 is the default, 14 is the highest",
+                )
+                .changed()
+            {
+                changed.push((
+                    String::from("resample.quality"),
+                    pods::props::ParamValue::Int(*quality),
+                ));
+            }
+        }
+
+        if let Some(Value::Bool(disable)) = params::props_param_mut(props, "resample.disable") {
+            if ui
+                .checkbox(disable, "Disable")
+                .on_hover_text(
+                    "Saves the resampling cost when the rates are known to match. \
+                    Streams with a different rate than the graph will fail to play",
+                )
+                .changed()
+            {
+                changed.push((
+                    String::from("resample.disable"),
+                    pods::props::ParamValue::Bool(*disable),
+                ));
+            }
+        }
+
+        if changed.is_empty() {
+            if params::props_param_mut(props, "resample.quality").is_none()
+                && params::props_param_mut(props, "resample.disable").is_none()
+            {
+                ui.label("This node has no resampler");
+            }
+            return;
+        }
+
+        if let Some(pod) = pods::props::params(changed) {
+            sx.send(Request::CallObjectMethod(
+                self.id,
+                ObjectMethod::SetParam {
+                    id: ParamType::Props,
+                    flags: 0,
+                    pod,
+                },
+            ))
+            .ok();
+        }
+    }
+
+    fn show_channel_map(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        let input = self
+            .params(ParamType::PortConfig)
+            .find_map(params::PortConfig::from_param)
+            .map(|config| config.input);
+        let channels = self
+            .props
+            .get("audio.channels")
+            .and_then(|c| c.parse().ok())
+            .or_else(|| {
+                self.params(ParamType::Format)
+                    .find_map(params::AudioFormat::from_param)
+                    .map(|format| format.channels)
+            });
+
+        if let ObjectData::Node {
+            channel_map: Some(channel_map),
+            ..
+        } = &mut self.object_data
+        {
+            ui.collapsing("Channel map", |ui| {
+                channel_map.show(ui, sx, self.id, input, channels);
+            });
+        }
+    }
+
     pub const fn id(&self) -> u32 {
         self.id
     }
@@ -456,21 +1519,243 @@ impl Global {
         self.subobjects.push(subobject);
     }
 
+    pub fn add_provided(&mut self, provided: Weak<RefCell<Self>>) {
+        self.provided.push(provided);
+    }
+
+    /// The ID of the module that provides the object, if it's not a module itself
+    pub fn module_id(&self) -> Option<u32> {
+        if *self.object_type() == ObjectType::Module {
+            return None;
+        }
+
+        self.props.get("module.id").and_then(|id| id.parse().ok())
+    }
+
     pub const fn props(&self) -> &BTreeMap<String, String> {
         &self.props
     }
 
+    /// A property parsed according to its type, if it's known
+    pub fn typed_prop(&self, key: &str) -> Option<TypedValue> {
+        self.props
+            .get(key)
+            .and_then(|value| schema::typed_value(key, value))
+    }
+
+    /// Sets the clock settings that nodes check their overrides against
+    pub fn set_clock_settings(&mut self, settings: &ClockSettings) {
+        if let ObjectData::Node { clock_override, .. } = &mut self.object_data {
+            clock_override.set_settings(settings);
+        }
+    }
+
+    pub fn set_dimmed(&mut self, dimmed: bool) {
+        self.dimmed = dimmed;
+    }
+
     pub fn set_props(&mut self, props: BTreeMap<String, String>) {
         self.props = props;
+        self.last_updated = SystemTime::now();
+        self.activity.record_update();
         self.update();
     }
 
+    pub const fn first_seen(&self) -> SystemTime {
+        self.first_seen
+    }
+
+    pub const fn last_updated(&self) -> SystemTime {
+        self.last_updated
+    }
+
+    /// Info, property and param updates of the last seconds
+    pub fn recent_updates(&self) -> u32 {
+        self.activity.recent_updates()
+    }
+
+    /// Records the load of the node from a profiler sample
+    pub fn record_load(&mut self, load: f32) {
+        self.activity.record_load(load);
+    }
+
+    /// Destroys the object without recording it in the journal, for objects of other connections
+    pub fn set_unjournaled(&mut self) {
+        self.journaled = false;
+    }
+
+    /// Marks the object as part of the initial state of the remote
+    pub fn set_existed_on_connect(&mut self) {
+        self.existed_on_connect = true;
+    }
+
+    fn first_seen_text(&self) -> String {
+        let time = format_timestamp(unix_secs(self.first_seen));
+        if self.existed_on_connect {
+            format!("{time} (when connecting)")
+        } else {
+            time
+        }
+    }
+
     pub fn info(&self) -> Option<&[(&'static str, String)]> {
         self.info.as_deref()
     }
 
-    pub fn set_info(&mut self, info: Option<Box<[(&'static str, String)]>>) {
-        self.info = info;
+    /// The value of an info entry, like a link's `State`
+    pub fn info_value(&self, key: &str) -> Option<&str> {
+        self.info()?
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn set_restricted(&mut self, message: String) {
+        self.restricted = Some(message);
+    }
+
+    /// Returns the previous info
+    pub fn set_info(
+        &mut self,
+        info: Option<Box<[(&'static str, String)]>>,
+    ) -> Option<Box<[(&'static str, String)]>> {
+        self.last_updated = SystemTime::now();
+        self.activity.record_update();
+        std::mem::replace(&mut self.info, info)
+    }
+
+    pub fn params(&self, param_type: ParamType) -> impl Iterator<Item = &Value> {
+        self.params
+            .get(&param_type.as_raw())
+            .into_iter()
+            .flat_map(BTreeMap::values)
+    }
+
+    /// Stores a param received from [`Event::GlobalParam`](crate::backend::Event::GlobalParam)
+    pub fn set_param(&mut self, param_type: ParamType, index: u32, param: Vec<u8>) {
+        self.last_updated = SystemTime::now();
+        self.activity.record_update();
+
+        if let Some(value) = params::deserialize(&param) {
+            self.params
+                .entry(param_type.as_raw())
+                .or_default()
+                .insert(index, value);
+        }
+
+        self.raw_params
+            .entry(param_type.as_raw())
+            .or_default()
+            .insert(index, param);
+    }
+
+    /// Set when [`Event::Error`](crate::backend::Event::Error) reports a malformed param
+    pub fn set_param_error(&mut self, error: ParseError) {
+        self.param_error = Some(error);
+    }
+
+    pub fn clear_params(&mut self, param_type: ParamType) {
+        self.params.remove(&param_type.as_raw());
+        self.raw_params.remove(&param_type.as_raw());
+        self.param_error = None;
+    }
+
+    /// The processing mode of the node's ports, from its PortConfig params if it's an adapter
+    /// or else from its format
+    fn processing_mode(&self) -> Option<String> {
+        let configs: Vec<_> = self
+            .params(ParamType::PortConfig)
+            .filter_map(params::PortConfig::from_param)
+            .map(|config| {
+                let direction = if config.input { "Input" } else { "Output" };
+                format!("{direction} {}", config.mode)
+            })
+            .collect();
+        if !configs.is_empty() {
+            return Some(configs.join(", "));
+        }
+
+        self.params(ParamType::Format)
+            .find_map(params::AudioFormat::from_param)
+            .map(|format| {
+                if format.format == pw::spa::sys::SPA_AUDIO_FORMAT_F32P {
+                    String::from("DSP")
+                } else {
+                    String::from("Passthrough")
+                }
+            })
+    }
+
+    /// Whether this is a port that carries a copy of the signal of its node
+    pub fn is_monitor_port(&self) -> bool {
+        *self.object_type() == ObjectType::Port
+            && matches!(
+                self.typed_prop("port.monitor"),
+                Some(TypedValue::Bool(true))
+            )
+    }
+
+    pub const fn monitor_ports_hidden(&self) -> bool {
+        matches!(
+            self.object_data,
+            ObjectData::Node {
+                show_monitor_ports: false,
+                ..
+            }
+        )
+    }
+
+    /// Reverts the permissions of a client to the ones first fetched,
+    /// if they were changed
+    /// Updates the permissions of a client so that they can be reverted later
+    pub fn set_permissions(&mut self, permissions: Vec<Permission>, sx: &backend::Sender) {
+        if let ObjectData::Client {
+            fetched_permissions,
+            permissions_changed,
+            ..
+        } = &mut self.object_data
+        {
+            // Fetched before updating so that there's something to revert to
+            if fetched_permissions.is_none() {
+                sx.send(Request::CallObjectMethod(
+                    self.id,
+                    ObjectMethod::ClientGetPermissions {
+                        index: 0,
+                        num: u32::MAX,
+                    },
+                ))
+                .ok();
+            }
+
+            *permissions_changed = true;
+            update_permissions(sx, self.id, permissions);
+        }
+    }
+
+    pub fn revert_permissions(&mut self, sx: &backend::Sender) {
+        if let ObjectData::Client {
+            permissions: Some(permissions),
+            fetched_permissions: Some(fetched),
+            permissions_changed,
+            ..
+        } = &mut self.object_data
+        {
+            if *permissions_changed {
+                *permissions_changed = false;
+                update_permissions(sx, self.id, reverted_permissions(fetched, permissions));
+            }
+        }
+    }
+
+    /// The fetched permissions of a client
+    pub fn permissions(&self) -> Option<&[Permission]> {
+        match &self.object_data {
+            ObjectData::Client {
+                permissions: Some(permissions),
+                ..
+            } => Some(permissions),
+            _ => None,
+        }
     }
 
     pub fn object_data_mut(&mut self) -> &mut ObjectData {
@@ -481,3 +1766,21 @@ impl Global {
         self.parent
     }
 }
+
+impl model::Object for Global {
+    fn id(&self) -> u32 {
+        self.id()
+    }
+
+    fn object_type(&self) -> &ObjectType {
+        self.object_type()
+    }
+
+    fn props(&self) -> &BTreeMap<String, String> {
+        self.props()
+    }
+
+    fn info(&self) -> Option<&[(&'static str, String)]> {
+        self.info()
+    }
+}
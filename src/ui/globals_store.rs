@@ -16,28 +16,174 @@
 
 use std::{
     cell::RefCell,
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, BTreeSet, HashMap},
     rc::{Rc, Weak},
 };
 
 use eframe::egui;
-use pipewire::types::ObjectType;
+use pipewire::{
+    permissions::{Permission, PermissionFlags},
+    types::ObjectType,
+};
 
-use crate::{backend, ui::util::uis::KvMatcher};
+use crate::{
+    backend::{self, pods::profiler::Profiling, ObjectMethod, Request},
+    model::{MetadataProperties, Object, Registry},
+    pw_dump::{self, MetadataProperty},
+    redact::Redactor,
+    ui::{
+        alsa_params::parse_rates,
+        clock_override::ClockSettings,
+        compat::{self, Version},
+        pins::Pins,
+        selection::Selection,
+        shortcuts,
+        util::{
+            fuzzy::{fuzzy_match, highlighted},
+            persistence::PersistentView,
+            uis::KvMatcher,
+        },
+    },
+};
 
 #[path = "global.rs"]
 mod global;
-pub use global::{Global, ObjectData};
+pub use global::{draw_permission_flags, draw_permission_list, Global, ObjectData};
+
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "persistence", derive(serde::Serialize, serde::Deserialize))]
+enum SortOrder {
+    #[default]
+    Id,
+    NewestFirst,
+    RecentlyUpdatedFirst,
+    MostActiveFirst,
+}
+
+impl SortOrder {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Id => "ID",
+            Self::NewestFirst => "Newest first",
+            Self::RecentlyUpdatedFirst => "Recently updated first",
+            Self::MostActiveFirst => "Most active first",
+        }
+    }
+}
+
+/// Tabs that list the globals of some types, each with its own properties filter
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[cfg_attr(feature = "persistence", derive(serde::Serialize, serde::Deserialize))]
+enum Tab {
+    #[default]
+    All,
+    Nodes,
+    Ports,
+    Links,
+    Clients,
+    Devices,
+    Modules,
+    Factories,
+    Others,
+}
+
+impl Tab {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::All => "All",
+            Self::Nodes => "Nodes",
+            Self::Ports => "Ports",
+            Self::Links => "Links",
+            Self::Clients => "Clients",
+            Self::Devices => "Devices",
+            Self::Modules => "Modules",
+            Self::Factories => "Factories",
+            Self::Others => "Others",
+        }
+    }
+
+    const fn of(t: &ObjectType) -> Self {
+        match t {
+            ObjectType::Node => Self::Nodes,
+            ObjectType::Port => Self::Ports,
+            ObjectType::Link => Self::Links,
+            ObjectType::Client => Self::Clients,
+            ObjectType::Device => Self::Devices,
+            ObjectType::Module => Self::Modules,
+            ObjectType::Factory => Self::Factories,
+            _ => Self::Others,
+        }
+    }
+}
+
+/// The state of the views of a connection that its objects change when they're shown
+#[derive(Default)]
+pub struct ViewState {
+    pub selection: Selection,
+    pub pins: Pins,
+    /// The object to copy as JSON, requested with its copy button
+    pub copy_request: Option<u32>,
+}
 
 pub struct GlobalsStore {
     globals: HashMap<u32, Rc<RefCell<Global>>>,
+    // Globals added before this are the initial state of the remote
+    initial_sync_done: bool,
 
     group_subobjects: bool,
+    sort_order: SortOrder,
 
+    tab: Tab,
+    // The filters of the All tab
     shown_types: u16,
     properties_filter: KvMatcher,
+    // The filters of the other tabs
+    tab_filters: BTreeMap<Tab, KvMatcher>,
 
     filter_matches: BTreeMap<u32, Weak<RefCell<Global>>>,
+
+    search: String,
+    search_results: Vec<SearchResult>,
+    // Whether the globals changed since the search results were computed
+    search_stale: bool,
+    // IDs of the shown globals that contain matches
+    search_highlights: BTreeSet<u32>,
+    scroll_to: Option<u32>,
+
+    // The client whose permissions decide which globals are dimmed
+    view_as: Option<u32>,
+    // Globals the client can't read
+    unreadable: BTreeSet<u32>,
+    hide_unreadable: bool,
+
+    clock_settings: ClockSettings,
+    metadata: MetadataProperties,
+
+    view_state: ViewState,
+    // Whether destroying the globals is recorded in the journal
+    journaled: bool,
+}
+
+struct SearchResult {
+    id: u32,
+    score: i32,
+    label: String,
+    // The ID, name or property the search matched
+    text: String,
+    indices: Vec<usize>,
+}
+
+const MAX_SEARCH_RESULTS: usize = 50;
+
+/// Whether a client with the permissions can read an object, like the daemon decides
+/// whether to announce the object to it
+fn readable(permissions: &[Permission], id: u32) -> bool {
+    // Objects without their own permissions get the default ones, stored under PW_ID_ANY
+    permissions
+        .iter()
+        .find(|p| p.id() == id)
+        .or_else(|| permissions.iter().find(|p| p.id() == u32::MAX))
+        .is_some_and(|p| p.permission_flags().contains(PermissionFlags::R))
 }
 
 const fn object_type_flag(t: &ObjectType) -> u16 {
@@ -60,16 +206,53 @@ impl GlobalsStore {
     pub fn new() -> Self {
         Self {
             globals: HashMap::new(),
+            initial_sync_done: false,
 
             group_subobjects: true,
+            sort_order: SortOrder::default(),
 
+            tab: Tab::default(),
             shown_types: u16::MAX,
             properties_filter: KvMatcher::new(),
+            tab_filters: BTreeMap::new(),
 
             filter_matches: BTreeMap::new(),
+
+            search: String::new(),
+            search_results: Vec::new(),
+            search_stale: false,
+            search_highlights: BTreeSet::new(),
+            scroll_to: None,
+
+            view_as: None,
+            unreadable: BTreeSet::new(),
+            hide_unreadable: false,
+
+            clock_settings: ClockSettings::default(),
+            metadata: MetadataProperties::default(),
+
+            view_state: ViewState::default(),
+            journaled: true,
+        }
+    }
+
+    /// A store for a connection other than the main one, whose changes aren't recorded in
+    /// the journal since it tracks objects by their IDs in the main connection
+    pub fn unjournaled() -> Self {
+        Self {
+            journaled: false,
+            ..Self::new()
         }
     }
 
+    pub const fn view_state(&self) -> &ViewState {
+        &self.view_state
+    }
+
+    pub fn view_state_mut(&mut self) -> &mut ViewState {
+        &mut self.view_state
+    }
+
     pub fn add_global(
         &mut self,
         id: u32,
@@ -82,7 +265,29 @@ impl GlobalsStore {
 
         // Add as subobject and check filters
         {
-            let global_borrow = global.borrow();
+            let mut global_borrow = global.borrow_mut();
+            if !self.initial_sync_done {
+                global_borrow.set_existed_on_connect();
+            }
+            if !self.journaled {
+                global_borrow.set_unjournaled();
+            }
+            match *global_borrow.object_type() {
+                ObjectType::Metadata => {
+                    if global_borrow
+                        .props()
+                        .get("metadata.name")
+                        .is_some_and(|name| name == "settings")
+                    {
+                        self.clock_settings.metadata = Some(id);
+                        self.apply_clock_settings();
+                    }
+                }
+                ObjectType::Node => {
+                    global_borrow.set_clock_settings(&self.clock_settings);
+                }
+                _ => {}
+            }
             match *global_borrow.object_type() {
                 ObjectType::Node | ObjectType::Port => {
                     if let Some(parent) = self.parent_of(&global_borrow) {
@@ -104,11 +309,22 @@ impl GlobalsStore {
                 _ => {}
             }
 
+            // Objects provided by modules, directly or through the factories of the modules
+            let module = global_borrow.module_id().or_else(|| {
+                let factory = global_borrow.props().get("factory.id")?.parse().ok()?;
+                self.globals.get(&factory)?.borrow().module_id()
+            });
+            if let Some(module) = module.and_then(|id| self.globals.get(&id)) {
+                module.borrow_mut().add_provided(Rc::downgrade(&global));
+            }
+
             if self.satisfies_filters(&global_borrow) {
                 self.filter_matches.insert(id, Rc::downgrade(&global));
             }
         }
 
+        self.search_stale = true;
+
         match self.globals.entry(id) {
             Entry::Occupied(mut e) => {
                 e.insert(global);
@@ -118,12 +334,35 @@ impl GlobalsStore {
         }
     }
 
+    pub fn initial_sync_done(&mut self) {
+        self.initial_sync_done = true;
+    }
+
+    /// The globals of the new connection are announced as its initial state
+    pub fn reconnected(&mut self) {
+        self.initial_sync_done = false;
+    }
+
     pub fn get_global(&self, id: u32) -> Option<&Rc<RefCell<Global>>> {
         self.globals.get(&id)
     }
 
+    pub fn globals(&self) -> impl Iterator<Item = &Rc<RefCell<Global>>> {
+        self.globals.values()
+    }
+
     pub fn remove_global(&mut self, id: u32) -> Option<Rc<RefCell<Global>>> {
+        if self.clock_settings.metadata == Some(id) {
+            self.clock_settings = ClockSettings::default();
+            self.apply_clock_settings();
+        }
+
+        self.view_state.selection.global_removed(id);
+        self.view_state.pins.global_removed(id);
+        self.metadata.remove_metadata(id);
+
         self.filter_matches.remove(&id);
+        self.search_stale = true;
         self.globals.remove(&id)
     }
 
@@ -132,6 +371,7 @@ impl GlobalsStore {
 
         if let Some(global) = self.globals.get(&id) {
             global.borrow_mut().set_props(props);
+            self.search_stale = true;
 
             let matches = self.satisfies_filters(&global.borrow());
 
@@ -147,11 +387,144 @@ impl GlobalsStore {
         }
     }
 
+    /// Tracks the allowed rates of the graph from the settings metadata.
+    /// A `key` of `None` is for when all the properties are cleared.
+    pub fn set_metadata_property(
+        &mut self,
+        id: u32,
+        subject: u32,
+        key: Option<&str>,
+        type_: Option<&str>,
+        value: Option<&str>,
+    ) {
+        self.metadata.set_property(
+            id,
+            subject,
+            key.map(str::to_owned),
+            type_.map(str::to_owned),
+            value.map(str::to_owned),
+        );
+
+        if self.clock_settings.metadata != Some(id) {
+            return;
+        }
+
+        match key {
+            Some("clock.allowed-rates") if subject == 0 => {
+                self.clock_settings.allowed_rates = value.map(parse_rates).unwrap_or_default();
+            }
+            None => self.clock_settings.allowed_rates.clear(),
+            _ => return,
+        }
+
+        self.apply_clock_settings();
+    }
+
+    /// The version of the daemon of this connection, from the info of its core
+    pub fn daemon_version(&self) -> Option<Version> {
+        let core = self.get_global(0)?.borrow();
+        let (_, version) = core.info()?.iter().find(|(k, _)| *k == "Version")?;
+        compat::parse_version(version)
+    }
+
+    /// The properties of a metadata object, for exporting
+    pub fn metadata_properties(&self, id: u32) -> Vec<MetadataProperty<'_>> {
+        self.metadata.properties(id)
+    }
+
+    /// Puts the object whose copy button was clicked on the clipboard, as pw-dump JSON
+    pub fn handle_copy_request(&mut self, ctx: &egui::Context) {
+        let Some(global) = self
+            .view_state
+            .copy_request
+            .take()
+            .and_then(|id| self.get_global(id))
+        else {
+            return;
+        };
+
+        let json = pw_dump::object(
+            &*global.borrow(),
+            |id| self.metadata_properties(id),
+            &Redactor::new(self),
+        )
+        .to_pretty_string();
+
+        ctx.output_mut(|o| o.copied_text = json);
+    }
+
+    /// Records the time the nodes of the samples spent processing as a fraction of the quantum
+    #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+    pub fn add_profilings(&self, profilings: &[Profiling]) {
+        for p in profilings {
+            let quantum = p.clock.duration as f64 * 1_000_000_000. * f64::from(p.clock.rate.num)
+                / f64::from(p.clock.rate.denom);
+            if quantum <= 0. {
+                continue;
+            }
+
+            for block in std::iter::once(&p.driver).chain(&p.followers) {
+                // Nodes that didn't run in the cycle have no valid times
+                if block.finish < block.awake || block.awake <= 0 {
+                    continue;
+                }
+                let Some(global) = u32::try_from(block.id)
+                    .ok()
+                    .and_then(|id| self.globals.get(&id))
+                else {
+                    continue;
+                };
+                let load = (block.finish - block.awake) as f64 / quantum;
+                global.borrow_mut().record_load(load as f32);
+            }
+        }
+    }
+
+    fn apply_clock_settings(&self) {
+        for global in self.globals.values() {
+            global.borrow_mut().set_clock_settings(&self.clock_settings);
+        }
+    }
+
     fn parent_of(&self, global: &Global) -> Option<&Rc<RefCell<Global>>> {
         global.parent_id().and_then(|id| self.globals.get(&id))
     }
 
+    /// The global and the objects above it, from the topmost one.
+    /// Links are under their output port and devices under the client that created them.
+    pub fn hierarchy(&self, id: u32) -> Vec<&Rc<RefCell<Global>>> {
+        // Guards against cycles of malformed properties
+        const MAX_DEPTH: usize = 8;
+
+        let mut hierarchy = Vec::new();
+        let mut current = self.globals.get(&id);
+        while let Some(global) = current.filter(|_| hierarchy.len() < MAX_DEPTH) {
+            hierarchy.push(global);
+
+            let global = global.borrow();
+            let parent = match *global.object_type() {
+                ObjectType::Link => global
+                    .props()
+                    .get("link.output.port")
+                    .and_then(|id| id.parse().ok()),
+                ObjectType::Device => global
+                    .props()
+                    .get("client.id")
+                    .and_then(|id| id.parse().ok()),
+                _ => global.parent_id(),
+            };
+            current = parent.and_then(|id| self.globals.get(&id));
+        }
+
+        hierarchy.reverse();
+        hierarchy
+    }
+
     fn satisfies_filters(&self, global: &Global) -> bool {
+        if self.hide_unreadable && self.unreadable.contains(&global.id()) {
+            return false;
+        }
+
         if self.group_subobjects {
             if let ObjectType::Node | ObjectType::Port = *global.object_type() {
                 let mut parent = self.parent_of(global);
@@ -164,6 +537,14 @@ impl GlobalsStore {
             }
         }
 
+        if self.tab != Tab::All {
+            return Tab::of(global.object_type()) == self.tab
+                && self
+                    .tab_filters
+                    .get(&self.tab)
+                    .map_or(true, |filter| filter.matches(&global.props().iter()));
+        }
+
         if self.shown_types & object_type_flag(global.object_type()) == 0 {
             return false;
         }
@@ -183,14 +564,355 @@ impl GlobalsStore {
                 self.filter_matches.insert(id, Rc::downgrade(global));
             }
         }
+
+        self.search_stale = true;
+    }
+
+    /// Dims the globals the client that they're viewed as can't read.
+    /// Returns whether the globals it can't read changed.
+    fn update_unreadable(&mut self) -> bool {
+        if self
+            .view_as
+            .is_some_and(|id| !self.globals.contains_key(&id))
+        {
+            self.view_as = None;
+        }
+
+        let permissions = self
+            .view_as
+            .and_then(|id| self.globals.get(&id))
+            .and_then(|client| client.borrow().permissions().map(<[Permission]>::to_vec));
+
+        let mut unreadable = BTreeSet::new();
+        for (&id, global) in &self.globals {
+            let dimmed = permissions
+                .as_ref()
+                .is_some_and(|permissions| !readable(permissions, id));
+            if dimmed {
+                unreadable.insert(id);
+            }
+            global.borrow_mut().set_dimmed(dimmed);
+        }
+
+        let changed = unreadable != self.unreadable;
+        self.unreadable = unreadable;
+        changed
+    }
+
+    fn show_view_as(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) -> bool {
+        let client_label = |global: &Global| {
+            global
+                .props()
+                .get("application.name")
+                .or_else(|| global.name())
+                .map_or_else(
+                    || global.id().to_string(),
+                    |name| format!("{} {name}", global.id()),
+                )
+        };
+
+        let mut rematch = false;
+
+        ui.horizontal(|ui| {
+            let selected = self.view_as.and_then(|id| self.globals.get(&id));
+            let previous = self.view_as;
+
+            egui::ComboBox::from_label("View as client")
+                .selected_text(selected.map_or_else(
+                    || String::from("None"),
+                    |client| client_label(&client.borrow()),
+                ))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.view_as, None, "None");
+
+                    let mut clients: Vec<_> = self
+                        .globals
+                        .values()
+                        .map(|global| global.borrow())
+                        .filter(|global| *global.object_type() == ObjectType::Client)
+                        .collect();
+                    clients.sort_by_key(|global| global.id());
+                    for client in clients {
+                        ui.selectable_value(
+                            &mut self.view_as,
+                            Some(client.id()),
+                            client_label(&client),
+                        );
+                    }
+                })
+                .response
+                .on_hover_text(
+                    "Dim the objects that the client can't see because of its permissions",
+                );
+
+            if let Some(id) = self.view_as.filter(|id| previous != Some(*id)) {
+                sx.send(Request::CallObjectMethod(
+                    id,
+                    ObjectMethod::ClientGetPermissions {
+                        index: 0,
+                        num: u32::MAX,
+                    },
+                ))
+                .ok();
+            }
+
+            let Some(client) = self.view_as.and_then(|id| self.globals.get(&id)) else {
+                return;
+            };
+
+            if client.borrow().permissions().is_none() {
+                ui.weak("Getting permissions");
+            } else {
+                ui.label(format!("{} hidden", self.unreadable.len()));
+            }
+
+            rematch = ui
+                .checkbox(&mut self.hide_unreadable, "Hide")
+                .on_hover_text("Hide the objects instead of dimming them")
+                .changed();
+        });
+
+        rematch
+    }
+
+    /// Scrolls the list to the global, or the parent it's shown under
+    pub fn scroll_to(&mut self, id: u32) {
+        self.scroll_to = Some(id);
+    }
+
+    /// The global itself or the parent it's shown under
+    fn shown_ancestor(&self, id: u32) -> Option<u32> {
+        let mut id = Some(id);
+        while let Some(current) = id {
+            if self.filter_matches.contains_key(&current) {
+                return Some(current);
+            }
+            id = self.globals.get(&current)?.borrow().parent_id();
+        }
+        None
+    }
+
+    fn search(&mut self) {
+        self.search_results.clear();
+        self.search_highlights.clear();
+        self.search_stale = false;
+
+        if self.search.is_empty() {
+            return;
+        }
+
+        for (&id, global) in &self.globals {
+            let global = global.borrow();
+
+            let best = std::iter::once(id.to_string())
+                .chain(global.name().cloned())
+                .chain(global.props().iter().map(|(k, v)| format!("{k}={v}")))
+                .filter_map(|text| {
+                    fuzzy_match(&self.search, &text).map(|(score, indices)| (score, text, indices))
+                })
+                .max_by_key(|(score, ..)| *score);
+
+            if let Some((score, text, indices)) = best {
+                let label = match global.name() {
+                    Some(name) => format!("{id} {} {name}", global.object_type().to_str()),
+                    None => format!("{id} {}", global.object_type().to_str()),
+                };
+
+                self.search_results.push(SearchResult {
+                    id,
+                    score,
+                    label,
+                    text,
+                    indices,
+                });
+            }
+        }
+
+        self.search_results
+            .sort_unstable_by(|a, b| b.score.cmp(&a.score).then(a.id.cmp(&b.id)));
+        self.search_results.truncate(MAX_SEARCH_RESULTS);
+
+        self.search_highlights = self
+            .search_results
+            .iter()
+            .filter_map(|result| self.shown_ancestor(result.id))
+            .collect();
+    }
+
+    fn show_search(&mut self, ui: &mut egui::Ui) {
+        let changed = ui
+            .horizontal(|ui| {
+                ui.label("🔍");
+                let response = egui::TextEdit::singleline(&mut self.search)
+                    .hint_text("Search IDs, names and properties (Ctrl+F)")
+                    .show(ui)
+                    .response;
+                if shortcuts::take_search_focus() {
+                    response.request_focus();
+                }
+                response.changed()
+            })
+            .inner;
+
+        if changed || self.search_stale {
+            self.search();
+        }
+
+        if self.search.is_empty() {
+            return;
+        }
+
+        if self.search_results.is_empty() {
+            ui.label("No matches");
+            return;
+        }
+
+        egui::ScrollArea::vertical()
+            .id_source("search_results")
+            .max_height(150.)
+            .show(ui, |ui| {
+                for result in &self.search_results {
+                    ui.horizontal(|ui| {
+                        if ui
+                            .button(&result.label)
+                            .on_hover_text("Scroll to this object")
+                            .clicked()
+                        {
+                            self.scroll_to = Some(result.id);
+                        }
+                        ui.label(highlighted(ui, &result.text, &result.indices));
+                    });
+                }
+            });
+    }
+
+    /// Shows the pinned objects regardless of the filters
+    fn show_pinned(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        let pins = self.view_state.pins.all().to_vec();
+        if pins.is_empty() {
+            return;
+        }
+
+        egui::CollapsingHeader::new(format!("📌 Pinned ({})", pins.len()))
+            .id_source("pinned")
+            .default_open(true)
+            .show(ui, |ui| {
+                egui::ScrollArea::vertical()
+                    .id_source("pinned")
+                    .max_height(ui.available_height() / 2.)
+                    .show(ui, |ui| {
+                        ui.with_layout(egui::Layout::top_down_justified(egui::Align::Min), |ui| {
+                            for (i, pin) in pins.iter().enumerate() {
+                                ui.push_id(i, |ui| {
+                                    ui.horizontal(|ui| {
+                                        if ui.small_button("Unpin").clicked() {
+                                            self.view_state.pins.unpin(pin);
+                                        }
+                                        ui.label(pin.description());
+                                    });
+
+                                    let mut matches: Vec<Rc<RefCell<Global>>> = self
+                                        .globals
+                                        .values()
+                                        .filter(|global| pin.matches(&global.borrow()))
+                                        .cloned()
+                                        .collect();
+                                    if matches.is_empty() {
+                                        ui.weak("No objects match");
+                                        return;
+                                    }
+
+                                    matches.sort_by_key(|global| global.borrow().id());
+                                    for global in matches {
+                                        global.borrow_mut().show(
+                                            ui,
+                                            false,
+                                            sx,
+                                            Some(&mut self.view_state),
+                                        );
+                                    }
+                                });
+                            }
+                        });
+                    });
+            });
+
+        ui.separator();
     }
 
     pub fn show(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
-        ui.checkbox(&mut self.group_subobjects, "Group Subobjects")
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.group_subobjects, "Group Subobjects")
                                 .on_hover_text("Whether to group objects as parents/children (Client/Device > Nodes > Ports > Links) or show them separately");
 
+            egui::ComboBox::from_label("Sort")
+                .selected_text(self.sort_order.as_str())
+                .show_ui(ui, |ui| {
+                    for order in [
+                        SortOrder::Id,
+                        SortOrder::NewestFirst,
+                        SortOrder::RecentlyUpdatedFirst,
+                        SortOrder::MostActiveFirst,
+                    ] {
+                        ui.selectable_value(&mut self.sort_order, order, order.as_str());
+                    }
+                });
+        });
+
+        let mut rematch = self.show_view_as(ui, sx);
+
+        if self.view_as.is_some() || !self.unreadable.is_empty() {
+            rematch |= self.update_unreadable() && self.hide_unreadable;
+        }
+
+        let mut counts = BTreeMap::new();
+        for global in self.globals.values() {
+            let tab = Tab::of(global.borrow().object_type());
+            *counts.entry(tab).or_insert(0) += 1;
+        }
+
+        ui.horizontal_wrapped(|ui| {
+            for tab in [
+                Tab::All,
+                Tab::Nodes,
+                Tab::Ports,
+                Tab::Links,
+                Tab::Clients,
+                Tab::Devices,
+                Tab::Modules,
+                Tab::Factories,
+                Tab::Others,
+            ] {
+                let count = match tab {
+                    Tab::All => self.globals.len(),
+                    tab => counts.get(&tab).copied().unwrap_or(0),
+                };
+                if ui
+                    .selectable_label(self.tab == tab, format!("{} ({count})", tab.as_str()))
+                    .clicked()
+                    && self.tab != tab
+                {
+                    self.tab = tab;
+                    rematch = true;
+                }
+            }
+        });
+
         ui.collapsing("Filters", |ui| {
-            let mut rematch = false;
+            if self.tab != Tab::All {
+                ui.label("Properties").on_hover_text(format!(
+                    "Only {} with properties that match the below filters will be shown",
+                    self.tab.as_str().to_lowercase()
+                ));
+
+                rematch |= self
+                    .tab_filters
+                    .entry(self.tab)
+                    .or_insert_with(KvMatcher::new)
+                    .show(ui);
+
+                return;
+            }
 
             ui.horizontal(|ui| {
                 ui.label("Types");
@@ -231,26 +953,170 @@ impl GlobalsStore {
             );
 
             rematch |= self.properties_filter.show(ui);
+        });
+
+        self.show_search(ui);
 
-            if rematch {
-                self.repopulate_matches();
+        // Switch to the tab of the global if it's not in the current one
+        if let Some(global) = self.scroll_to.and_then(|id| self.globals.get(&id)) {
+            let tab = Tab::of(global.borrow().object_type());
+            if self.tab != Tab::All && self.tab != tab {
+                self.tab = tab;
+                rematch = true;
             }
-        });
+        }
+
+        if rematch {
+            self.repopulate_matches();
+        }
 
         ui.separator();
 
+        self.show_pinned(ui, sx);
+
+        let mut scroll_to = self.scroll_to.take().and_then(|id| self.shown_ancestor(id));
+        let mut selected = self
+            .view_state
+            .selection
+            .selected()
+            .and_then(|id| self.shown_ancestor(id));
+
+        let mut shown: Vec<(u32, Rc<RefCell<Global>>)> =
+            Vec::with_capacity(self.filter_matches.len());
+        self.filter_matches.retain(|id, v| {
+            let Some(global) = v.upgrade() else {
+                return false;
+            };
+            shown.push((*id, global));
+            true
+        });
+
+        // Already sorted by ID. Ties are broken by the newest ID since the sort is stable.
+        match self.sort_order {
+            SortOrder::Id => {}
+            SortOrder::NewestFirst => {
+                shown.reverse();
+                shown.sort_by_key(|(_, global)| std::cmp::Reverse(global.borrow().first_seen()));
+            }
+            SortOrder::RecentlyUpdatedFirst => {
+                shown.reverse();
+                shown.sort_by_key(|(_, global)| std::cmp::Reverse(global.borrow().last_updated()));
+            }
+            SortOrder::MostActiveFirst => {
+                shown.reverse();
+                shown
+                    .sort_by_key(|(_, global)| std::cmp::Reverse(global.borrow().recent_updates()));
+            }
+        }
+
+        if let Some(step) = shortcuts::list_step(ui.ctx()).filter(|_| !shown.is_empty()) {
+            let position =
+                selected.and_then(|selected| shown.iter().position(|(id, _)| *id == selected));
+            let next = match position {
+                Some(position) => position.saturating_add_signed(step).min(shown.len() - 1),
+                None if step > 0 => 0,
+                None => shown.len() - 1,
+            };
+
+            let id = shown[next].0;
+            self.view_state.selection.select(Some(id));
+            selected = Some(id);
+            scroll_to = Some(id);
+        }
+
         egui::ScrollArea::vertical().show(ui, |ui| {
             ui.with_layout(egui::Layout::top_down_justified(egui::Align::Min), |ui| {
-                self.filter_matches.retain(|_, v| {
-                    let Some(global) = v.upgrade() else {
-                        return false;
-                    };
+                for (id, global) in shown {
+                    if !self.search_highlights.contains(&id) && selected != Some(id) {
+                        global.borrow_mut().show(
+                            ui,
+                            self.group_subobjects,
+                            sx,
+                            Some(&mut self.view_state),
+                        );
+                        continue;
+                    }
 
-                    global.borrow_mut().show(ui, self.group_subobjects, sx);
+                    let response = egui::Frame::group(ui.style())
+                        .stroke(ui.visuals().selection.stroke)
+                        .show(ui, |ui| {
+                            global.borrow_mut().show(
+                                ui,
+                                self.group_subobjects,
+                                sx,
+                                Some(&mut self.view_state),
+                            );
+                        })
+                        .response;
 
-                    true
-                });
+                    if scroll_to == Some(id) {
+                        response.scroll_to_me(Some(egui::Align::TOP));
+                    }
+                }
             });
         });
     }
 }
+
+#[cfg_attr(feature = "persistence", derive(serde::Serialize, serde::Deserialize))]
+pub struct PersistentData {
+    group_subobjects: bool,
+    #[cfg_attr(feature = "persistence", serde(default))]
+    sort_order: SortOrder,
+    shown_types: u16,
+    properties_filter: KvMatcher,
+    #[cfg_attr(feature = "persistence", serde(default))]
+    tab: Tab,
+    #[cfg_attr(feature = "persistence", serde(default))]
+    tab_filters: BTreeMap<Tab, KvMatcher>,
+}
+
+impl GlobalsStore {
+    /// Applies saved filters and sorting, keeping the globals
+    pub fn set_data(&mut self, data: &PersistentData) {
+        self.group_subobjects = data.group_subobjects;
+        self.sort_order = data.sort_order;
+        self.shown_types = data.shown_types;
+        self.properties_filter = data.properties_filter.clone();
+        self.tab = data.tab;
+        self.tab_filters = data.tab_filters.clone();
+
+        self.repopulate_matches();
+    }
+}
+
+impl Registry for GlobalsStore {
+    fn for_each_object(&self, f: &mut dyn FnMut(&dyn Object)) {
+        for global in self.globals.values() {
+            f(&*global.borrow());
+        }
+    }
+}
+
+impl PersistentView for GlobalsStore {
+    type Data = PersistentData;
+
+    fn with_data(data: &Self::Data) -> Self {
+        Self {
+            group_subobjects: data.group_subobjects,
+            sort_order: data.sort_order,
+            shown_types: data.shown_types,
+            properties_filter: data.properties_filter.clone(),
+            tab: data.tab,
+            tab_filters: data.tab_filters.clone(),
+
+            ..Self::new()
+        }
+    }
+
+    fn save_data(&self) -> Option<Self::Data> {
+        Some(PersistentData {
+            group_subobjects: self.group_subobjects,
+            sort_order: self.sort_order,
+            shown_types: self.shown_types,
+            properties_filter: self.properties_filter.clone(),
+            tab: self.tab,
+            tab_filters: self.tab_filters.clone(),
+        })
+    }
+}
@@ -30,7 +30,10 @@ use pipewire::types::ObjectType;
 
 use crate::{
     backend::{self, Request},
-    ui::{globals_store::Global, util::persistence::PersistentView},
+    ui::{
+        globals_store::Global, journal, selection::Selection, theme,
+        util::persistence::PersistentView,
+    },
 };
 
 // Used to satisfy trait bounds that provide unneded features
@@ -101,11 +104,12 @@ pub enum MediaType {
 
 impl DataTypeTrait<backend::Sender> for MediaType {
     fn data_type_color(&self, _: &mut backend::Sender) -> egui::Color32 {
+        let colors = theme::graph_colors();
         match self {
-            Self::Audio => egui::Color32::BLUE,
-            Self::Video => egui::Color32::YELLOW,
-            Self::Midi => egui::Color32::RED,
-            Self::Unknown => egui::Color32::GRAY,
+            Self::Audio => colors.audio,
+            Self::Video => colors.video,
+            Self::Midi => colors.midi,
+            Self::Unknown => colors.unknown,
         }
     }
 
@@ -168,7 +172,7 @@ impl NodeDataTrait for Node {
                                 .min_scrolled_height(350f32)
                                 .max_height(350f32)
                                 .show(ui, |ui| {
-                                    global.borrow_mut().show(ui, true, sx);
+                                    global.borrow_mut().show(ui, true, sx, None);
                                 });
                         });
                 });
@@ -226,6 +230,12 @@ pub struct Graph {
 
     // Maps PipeWire global IDs to graph items
     items: BTreeMap<u32, GraphItem>,
+
+    // Kept to restore monitor ports and their links when they're shown again
+    // Port ID -> (node ID, name)
+    monitor_ports: BTreeMap<u32, (u32, String)>,
+    // Link ID -> (output port ID, input port ID)
+    links: BTreeMap<u32, (u32, u32)>,
 }
 
 impl Graph {
@@ -236,6 +246,9 @@ impl Graph {
             editor: GraphEditorState::default(),
             responses: Vec::new(),
             items: BTreeMap::new(),
+
+            monitor_ports: BTreeMap::new(),
+            links: BTreeMap::new(),
         }
     }
 
@@ -321,7 +334,29 @@ impl Graph {
         self.items.insert(id, graph_id.into());
     }
 
-    pub fn add_output_port(&mut self, id: u32, node_id: u32, name: String) {
+    /// Whether the node has been set to not show its monitor ports
+    fn monitor_ports_hidden(&self, node_id: u32) -> bool {
+        let Some(GraphItem::Node(node_id)) = self.items.get(&node_id) else {
+            return false;
+        };
+
+        self.editor
+            .graph
+            .nodes
+            .get(*node_id)
+            .and_then(|node| node.user_data.global.upgrade())
+            .is_some_and(|global| global.borrow().monitor_ports_hidden())
+    }
+
+    pub fn add_output_port(&mut self, id: u32, node_id: u32, name: String, monitor: bool) {
+        if monitor {
+            self.monitor_ports.insert(id, (node_id, name.clone()));
+
+            if self.monitor_ports_hidden(node_id) {
+                return;
+            }
+        }
+
         let Some((node_id, media_type)) = self.port_graph_node_and_media_type(id, node_id) else {
             return;
         };
@@ -335,6 +370,11 @@ impl Graph {
     }
 
     pub fn add_link(&mut self, id: u32, output_port_id: u32, input_port_id: u32) {
+        self.links.insert(id, (output_port_id, input_port_id));
+        self.connect(id, output_port_id, input_port_id);
+    }
+
+    fn connect(&mut self, id: u32, output_port_id: u32, input_port_id: u32) {
         if self.items.contains_key(&id) {
             return;
         }
@@ -353,6 +393,9 @@ impl Graph {
     }
 
     pub fn remove_item(&mut self, id: u32) {
+        self.monitor_ports.remove(&id);
+        self.links.remove(&id);
+
         let Some(item) = self.items.remove(&id) else {
             return;
         };
@@ -369,10 +412,62 @@ impl Graph {
         }
     }
 
-    pub fn show(&mut self, ui: &mut egui::Ui, sx: &mut backend::Sender) {
+    /// Adds or removes monitor ports according to their nodes' settings
+    fn sync_monitor_ports(&mut self) {
+        let changed: Vec<_> = self
+            .monitor_ports
+            .iter()
+            .filter(|(id, (node_id, _))| {
+                self.items.contains_key(*id) == self.monitor_ports_hidden(*node_id)
+            })
+            .map(|(id, (node_id, name))| (*id, *node_id, name.clone()))
+            .collect();
+
+        for (id, node_id, name) in changed {
+            if let Some(GraphItem::OutputPort(output)) = self.items.remove(&id) {
+                // The port's links are removed from the graph along with it
+                self.editor.graph.remove_output_param(output);
+                self.items
+                    .retain(|_, item| !matches!(item, GraphItem::Link(o, _) if *o == output));
+                continue;
+            }
+
+            self.add_output_port(id, node_id, name, false);
+
+            let links: Vec<_> = self
+                .links
+                .iter()
+                .filter(|(_, (output, _))| *output == id)
+                .map(|(link, (output, input))| (*link, *output, *input))
+                .collect();
+            for (link, output, input) in links {
+                self.connect(link, output, input);
+            }
+        }
+    }
+
+    /// Highlights the node of the object, or the node it belongs to if it's a port or link
+    pub fn select(&mut self, id: Option<u32>) {
+        let node = id
+            .and_then(|id| self.items.get(&id))
+            .map(|item| match *item {
+                GraphItem::Node(node) => node,
+                GraphItem::InputPort(input) => self.editor.graph.get_input(input).node,
+                GraphItem::OutputPort(output) | GraphItem::Link(output, _) => {
+                    self.editor.graph.get_output(output).node
+                }
+            });
+
+        self.editor.selected_nodes = node.into_iter().collect();
+    }
+
+    /// Shows the graph. Selecting a node selects its object in the other views of the connection.
+    pub fn show(&mut self, ui: &mut egui::Ui, sx: &mut backend::Sender, selection: &mut Selection) {
         // Never show the node finder since nodes can't be created manually
         self.editor.node_finder = None;
 
+        self.sync_monitor_ports();
+
         let reset_view = ui
             .horizontal(|ui| {
                 if ui.button("Auto arrange").clicked() {
@@ -484,7 +579,7 @@ impl Graph {
                         for (id, g) in &self.items {
                             if let GraphItem::Link(o, i) = *g {
                                 if output == o && input == i {
-                                    sx.send(Request::DestroyObject(*id)).ok();
+                                    journal::destroy_object(sx, *id);
                                     break;
                                 }
                             }
@@ -493,6 +588,14 @@ impl Graph {
                         // Discard state change made by the user
                         self.editor.graph.add_connection(output, input, 0);
                     }
+                    NodeResponse::SelectNode(node_id) => {
+                        let id = self.items.iter().find_map(|(id, item)| {
+                            matches!(item, GraphItem::Node(n) if *n == node_id).then_some(*id)
+                        });
+                        if id.is_some() {
+                            selection.select(id);
+                        }
+                    }
                     NodeResponse::ConnectEventEnded { output, input, .. } => {
                         let mut output_port = None;
                         let mut input_port = None;
@@ -563,6 +666,31 @@ pub struct PersistentData {
     zoom: f32,
 }
 
+impl Graph {
+    /// Moves the nodes to saved positions, the ones that don't exist yet are positioned when added
+    pub fn set_data(&mut self, data: &PersistentData) {
+        let mut positions = data.positions.clone();
+
+        for (id, node) in &self.editor.graph.nodes {
+            let Some(global) = node.user_data.global.upgrade() else {
+                continue;
+            };
+            let global = global.borrow();
+            if let Some(pos) = global
+                .props()
+                .get("node.name")
+                .and_then(|name| positions.get_mut(name))
+                .and_then(VecDeque::pop_front)
+            {
+                self.editor.node_positions.insert(id, pos);
+            }
+        }
+
+        self.restored_positions = Some(positions);
+        self.editor.pan_zoom.zoom = data.zoom;
+    }
+}
+
 impl PersistentView for Graph {
     type Data = PersistentData;
 
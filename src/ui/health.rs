@@ -0,0 +1,407 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap, VecDeque},
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use eframe::egui;
+use pipewire::types::ObjectType;
+
+use crate::{
+    backend::{self, pods::profiler::Profiling},
+    ui::{
+        globals_store::Global,
+        util::{config::metadata_name, uis::global_info_button},
+        GlobalEvent, MetadataChange, ToolPlugin,
+    },
+};
+
+const XRUN_WINDOW: Duration = Duration::from_secs(60);
+
+// States of nodes and links that aren't errors.
+// Errors are reported as the error message instead of a state.
+const OK_STATES: [&str; 10] = [
+    "Creating",
+    "Idle",
+    "Suspended",
+    "Running",
+    "Init",
+    "Allocating",
+    "Negotiating",
+    "Active",
+    "Paused",
+    "Unlinked",
+];
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Severity {
+    Error,
+    Warning,
+}
+
+impl Severity {
+    const fn penalty(self) -> u32 {
+        match self {
+            Self::Error => 20,
+            Self::Warning => 5,
+        }
+    }
+}
+
+struct Issue {
+    severity: Severity,
+    object: Option<Rc<RefCell<Global>>>,
+    description: String,
+}
+
+struct DriverXruns {
+    last_count: i32,
+    recent: VecDeque<Instant>,
+}
+
+/// Summarizes signs of problems in the graph
+#[derive(Default)]
+pub struct HealthPanel {
+    nodes: BTreeMap<u32, Rc<RefCell<Global>>>,
+    // Nodes and links that are in an error state, with the error
+    errors: BTreeMap<u32, (Rc<RefCell<Global>>, String)>,
+
+    default_metadata: Option<u32>,
+    defaults: BTreeMap<String, String>,
+
+    xruns: HashMap<i32, DriverXruns>,
+    // Follower node ID -> requested rate and graph rate
+    rate_mismatches: BTreeMap<u32, (u32, u32)>,
+}
+
+impl ToolPlugin for HealthPanel {
+    fn label(&self) -> &'static str {
+        "🩺 Graph Health"
+    }
+
+    fn description(&self) -> &'static str {
+        "Summary of problems in the graph"
+    }
+
+    fn interested_in(&self, object_type: &ObjectType) -> bool {
+        matches!(
+            object_type,
+            ObjectType::Node | ObjectType::Link | ObjectType::Metadata
+        )
+    }
+
+    fn global_event(&mut self, event: GlobalEvent, _: &backend::Sender) {
+        match event {
+            GlobalEvent::Added(global) => {
+                let object_type = global.borrow().object_type().clone();
+                match object_type {
+                    ObjectType::Node => self.add_node(global),
+                    ObjectType::Metadata => self.add_metadata(global),
+                    _ => {}
+                }
+            }
+            GlobalEvent::InfoChanged(global, _) => {
+                let object_type = global.borrow().object_type().clone();
+                if matches!(object_type, ObjectType::Node | ObjectType::Link) {
+                    self.update_state(global);
+                }
+            }
+            GlobalEvent::Removed { id, .. } => self.remove_global(id),
+            GlobalEvent::PropertiesChanged(_) => {}
+        }
+    }
+
+    fn metadata_property(&mut self, change: MetadataChange, _: &backend::Sender) {
+        let id = change.metadata.borrow().id();
+        match change.key {
+            Some(key) => self.set_metadata_property(id, key, change.value),
+            None => self.clear_metadata_properties(id),
+        }
+    }
+
+    fn profiler_samples(&mut self, samples: &[Profiling]) {
+        self.add_profilings(samples);
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        self.show(ui, sx);
+    }
+}
+
+impl HealthPanel {
+    fn add_node(&mut self, global: &Rc<RefCell<Global>>) {
+        let id = global.borrow().id();
+        self.nodes.insert(id, Rc::clone(global));
+    }
+
+    fn add_metadata(&mut self, global: &Rc<RefCell<Global>>) {
+        let global_borrow = global.borrow();
+        if global_borrow
+            .props()
+            .get("metadata.name")
+            .is_some_and(|name| name == "default")
+        {
+            self.default_metadata = Some(global_borrow.id());
+            self.defaults.clear();
+        }
+    }
+
+    fn remove_global(&mut self, id: u32) {
+        self.nodes.remove(&id);
+        self.errors.remove(&id);
+        self.rate_mismatches.remove(&id);
+
+        if self.default_metadata == Some(id) {
+            self.default_metadata = None;
+            self.defaults.clear();
+        }
+    }
+
+    /// Checks the state of a node or link after its info has changed
+    fn update_state(&mut self, global: &Rc<RefCell<Global>>) {
+        let global_borrow = global.borrow();
+        let id = global_borrow.id();
+
+        let error = global_borrow
+            .info()
+            .and_then(|info| info.iter().find(|(k, _)| *k == "State"))
+            .map(|(_, state)| state)
+            .filter(|state| !OK_STATES.contains(&state.as_str()));
+
+        match error {
+            Some(error) => {
+                self.errors.insert(id, (Rc::clone(global), error.clone()));
+            }
+            None => {
+                self.errors.remove(&id);
+            }
+        }
+    }
+
+    fn set_metadata_property(&mut self, id: u32, key: &str, value: Option<&str>) {
+        if self.default_metadata != Some(id) {
+            return;
+        }
+
+        match value {
+            Some(value) => {
+                self.defaults.insert(key.to_owned(), value.to_owned());
+            }
+            None => {
+                self.defaults.remove(key);
+            }
+        }
+    }
+
+    fn clear_metadata_properties(&mut self, id: u32) {
+        if self.default_metadata == Some(id) {
+            self.defaults.clear();
+        }
+    }
+
+    fn add_profilings(&mut self, profilings: &[Profiling]) {
+        let now = Instant::now();
+
+        for p in profilings {
+            let count = p.driver.xrun_count.unwrap_or(p.info.xrun_count);
+            let xruns = self.xruns.entry(p.driver.id).or_insert(DriverXruns {
+                last_count: count,
+                recent: VecDeque::new(),
+            });
+            for _ in 0..(count - xruns.last_count).clamp(0, 100) {
+                xruns.recent.push_back(now);
+            }
+            xruns.last_count = count;
+
+            let graph_rate = p.clock.rate.denom;
+            for follower in &p.followers {
+                let Ok(id) = u32::try_from(follower.id) else {
+                    continue;
+                };
+                let requested = self.nodes.get(&id).and_then(|node| {
+                    node.borrow()
+                        .props()
+                        .get("node.rate")
+                        .and_then(|rate| rate.split_once('/'))
+                        .and_then(|(_, rate)| rate.parse::<u32>().ok())
+                });
+                match requested {
+                    Some(requested) if requested != 0 && requested != graph_rate => {
+                        self.rate_mismatches.insert(id, (requested, graph_rate));
+                    }
+                    _ => {
+                        self.rate_mismatches.remove(&id);
+                    }
+                }
+            }
+        }
+    }
+
+    fn node_by_name(&self, name: &str) -> Option<&Rc<RefCell<Global>>> {
+        self.nodes.values().find(|node| {
+            node.borrow()
+                .props()
+                .get("node.name")
+                .is_some_and(|n| n == name)
+        })
+    }
+
+    fn issues(&mut self) -> Vec<Issue> {
+        let mut issues = Vec::new();
+
+        for (global, error) in self.errors.values() {
+            issues.push(Issue {
+                severity: Severity::Error,
+                object: Some(Rc::clone(global)),
+                description: format!(
+                    "{} is in an error state: {error}",
+                    global.borrow().object_type().to_str()
+                ),
+            });
+        }
+
+        if self.default_metadata.is_some() {
+            for (kind, key) in [("sink", "audio.sink"), ("source", "audio.source")] {
+                let configured = self
+                    .defaults
+                    .get(&format!("default.configured.{key}"))
+                    .and_then(|v| metadata_name(v));
+                if let Some(configured) = configured {
+                    if self.node_by_name(configured).is_none() {
+                        issues.push(Issue {
+                            severity: Severity::Warning,
+                            object: None,
+                            description: format!(
+                                "The configured default {kind} {configured} is not available"
+                            ),
+                        });
+                    }
+                }
+
+                let default = self
+                    .defaults
+                    .get(&format!("default.{key}"))
+                    .and_then(|v| metadata_name(v));
+                match default {
+                    None => issues.push(Issue {
+                        severity: Severity::Error,
+                        object: None,
+                        description: format!("There is no default {kind}"),
+                    }),
+                    Some(default) if self.node_by_name(default).is_none() => {
+                        issues.push(Issue {
+                            severity: Severity::Error,
+                            object: None,
+                            description: format!("The default {kind} {default} does not exist"),
+                        });
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+
+        let now = Instant::now();
+        for (driver, xruns) in &mut self.xruns {
+            while xruns.recent.front().is_some_and(|t| now.duration_since(*t) > XRUN_WINDOW) {
+                xruns.recent.pop_front();
+            }
+
+            if xruns.recent.is_empty() {
+                continue;
+            }
+
+            let object = u32::try_from(*driver)
+                .ok()
+                .and_then(|id| self.nodes.get(&id))
+                .cloned();
+            issues.push(Issue {
+                severity: Severity::Warning,
+                object,
+                description: format!(
+                    "{} xruns in the last minute in the graph of driver {driver}",
+                    xruns.recent.len()
+                ),
+            });
+        }
+
+        for (id, (requested, graph_rate)) in &self.rate_mismatches {
+            issues.push(Issue {
+                severity: Severity::Warning,
+                object: self.nodes.get(id).cloned(),
+                description: format!(
+                    "Node {id} requests {requested} Hz but its graph runs at {graph_rate} Hz \
+                    and is resampled"
+                ),
+            });
+        }
+
+        issues.sort_by_key(|issue| issue.severity);
+
+        issues
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        let issues = self.issues();
+
+        let score = 100u32.saturating_sub(issues.iter().map(|i| i.severity.penalty()).sum());
+
+        let (summary, color) = match score {
+            90.. => ("Healthy", egui::Color32::GREEN),
+            60..=89 => ("Some issues", ui.visuals().warn_fg_color),
+            _ => ("Unhealthy", ui.visuals().error_fg_color),
+        };
+
+        ui.horizontal(|ui| {
+            ui.heading(egui::RichText::new(summary).color(color));
+            ui.label(format!("Score: {score}/100"))
+                .on_hover_text("Errors subtract 20 points and warnings 5");
+        });
+
+        if self.default_metadata.is_none() {
+            ui.label("The default metadata object could not be found, default devices aren't checked");
+        }
+        if self.xruns.is_empty() {
+            ui.label("No profiler data has been received, xruns and rates aren't checked");
+        }
+
+        ui.separator();
+
+        if issues.is_empty() {
+            ui.label("No issues found");
+            return;
+        }
+
+        for issue in issues {
+            ui.horizontal(|ui| {
+                let (icon, color) = match issue.severity {
+                    Severity::Error => ("⛔", ui.visuals().error_fg_color),
+                    Severity::Warning => ("⚠", ui.visuals().warn_fg_color),
+                };
+                ui.colored_label(color, icon);
+
+                if issue.object.is_some() {
+                    global_info_button(ui, issue.object.as_ref(), sx);
+                }
+
+                ui.label(issue.description);
+            });
+        }
+    }
+}
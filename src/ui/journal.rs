@@ -0,0 +1,441 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+// Destructive operations are recorded along with the state they change so that they can
+// be undone. They're requested from many views that don't share state, so instead of being
+// passed to all of them the journal is thread local, the UI only runs on one thread.
+// It keeps its own copy of the state, from the events of the backend, so that it can be
+// captured right before the requests are sent.
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, VecDeque},
+};
+
+use eframe::egui;
+use pipewire::types::ObjectType;
+
+use crate::{
+    backend::{self, Event, ObjectMethod, Request},
+    ui::ToolPlugin,
+};
+
+const MAX_OPERATIONS: usize = 100;
+
+#[derive(Clone, PartialEq, Eq)]
+struct LinkPorts {
+    output_node: String,
+    output_port: String,
+    input_node: String,
+    input_port: String,
+}
+
+impl LinkPorts {
+    fn from_props(props: &BTreeMap<String, String>) -> Option<Self> {
+        let prop = |key| props.get(key).cloned();
+
+        Some(Self {
+            output_node: prop("link.output.node")?,
+            output_port: prop("link.output.port")?,
+            input_node: prop("link.input.node")?,
+            input_port: prop("link.input.port")?,
+        })
+    }
+}
+
+// Subject and key -> type and value
+type MetadataProperties = BTreeMap<(u32, String), (Option<String>, String)>;
+
+enum Operation {
+    /// Undone by creating a link between the same ports
+    DestroyLink(LinkPorts),
+    /// Undone by setting the property back to its value
+    RemoveMetadataProperty {
+        metadata: u32,
+        subject: u32,
+        key: String,
+        type_: Option<String>,
+        value: String,
+    },
+    /// Undone by setting all of the properties back to their values
+    ClearMetadata {
+        metadata: u32,
+        properties: MetadataProperties,
+    },
+}
+
+impl Operation {
+    fn description(&self) -> String {
+        match self {
+            Self::DestroyLink(ports) => format!(
+                "Destroy link {}:{} → {}:{}",
+                ports.output_node, ports.output_port, ports.input_node, ports.input_port
+            ),
+            Self::RemoveMetadataProperty {
+                metadata,
+                subject,
+                key,
+                value,
+                ..
+            } => format!("Remove {key} of {subject} from metadata {metadata} (was {value})"),
+            Self::ClearMetadata {
+                metadata,
+                properties,
+            } => format!(
+                "Clear {} properties of metadata {metadata}",
+                properties.len()
+            ),
+        }
+    }
+
+    fn undo(&self, sx: &backend::Sender) {
+        match self {
+            Self::DestroyLink(ports) => {
+                sx.send(Request::CreateObject(
+                    ObjectType::Link,
+                    String::from("link-factory"),
+                    vec![
+                        ("link.output.node".to_owned(), ports.output_node.clone()),
+                        ("link.output.port".to_owned(), ports.output_port.clone()),
+                        ("link.input.node".to_owned(), ports.input_node.clone()),
+                        ("link.input.port".to_owned(), ports.input_port.clone()),
+                        ("object.linger".to_owned(), "true".to_owned()),
+                    ],
+                ))
+                .ok();
+            }
+            Self::RemoveMetadataProperty {
+                metadata,
+                subject,
+                key,
+                type_,
+                value,
+            } => {
+                sx.send(Request::CallObjectMethod(
+                    *metadata,
+                    ObjectMethod::MetadataSetProperty {
+                        subject: *subject,
+                        key: key.clone(),
+                        type_: type_.clone(),
+                        value: Some(value.clone()),
+                    },
+                ))
+                .ok();
+            }
+            Self::ClearMetadata {
+                metadata,
+                properties,
+            } => {
+                for ((subject, key), (type_, value)) in properties {
+                    sx.send(Request::CallObjectMethod(
+                        *metadata,
+                        ObjectMethod::MetadataSetProperty {
+                            subject: *subject,
+                            key: key.clone(),
+                            type_: type_.clone(),
+                            value: Some(value.clone()),
+                        },
+                    ))
+                    .ok();
+                }
+            }
+        }
+    }
+
+    /// Returns whether the operation could be done again
+    fn redo(&self, journal: &Journal, sx: &backend::Sender) -> bool {
+        let request = match self {
+            Self::DestroyLink(ports) => {
+                // The link that was created by undoing has a new ID
+                let Some(id) = journal
+                    .links
+                    .iter()
+                    .find_map(|(id, link)| (link == ports).then_some(*id))
+                else {
+                    return false;
+                };
+                Request::DestroyObject(id)
+            }
+            Self::RemoveMetadataProperty {
+                metadata,
+                subject,
+                key,
+                type_,
+                ..
+            } => Request::CallObjectMethod(
+                *metadata,
+                ObjectMethod::MetadataSetProperty {
+                    subject: *subject,
+                    key: key.clone(),
+                    type_: type_.clone(),
+                    value: None,
+                },
+            ),
+            Self::ClearMetadata { metadata, .. } => {
+                Request::CallObjectMethod(*metadata, ObjectMethod::MetadataClear)
+            }
+        };
+
+        sx.send(request).ok();
+
+        true
+    }
+}
+
+#[derive(Default)]
+struct Journal {
+    links: BTreeMap<u32, LinkPorts>,
+    metadata: BTreeMap<u32, MetadataProperties>,
+
+    undo: VecDeque<Operation>,
+    redo: Vec<Operation>,
+}
+
+impl Journal {
+    fn record(&mut self, operation: Operation) {
+        if self.undo.len() == MAX_OPERATIONS {
+            self.undo.pop_front();
+        }
+        self.undo.push_back(operation);
+        self.redo.clear();
+    }
+
+    fn undo(&mut self, sx: &backend::Sender) {
+        if let Some(operation) = self.undo.pop_back() {
+            operation.undo(sx);
+            self.redo.push(operation);
+        }
+    }
+
+    fn redo(&mut self, sx: &backend::Sender) {
+        if let Some(operation) = self.redo.pop() {
+            if operation.redo(self, sx) {
+                self.undo.push_back(operation);
+            }
+        }
+    }
+}
+
+thread_local! {
+    static JOURNAL: RefCell<Journal> = RefCell::new(Journal::default());
+}
+
+/// Keeps track of the state that destructive operations can change
+pub fn process_event(e: &Event) {
+    JOURNAL.with(|journal| {
+        let mut journal = journal.borrow_mut();
+
+        match e {
+            Event::GlobalAdded(id, ObjectType::Link, Some(props)) => {
+                if let Some(ports) = LinkPorts::from_props(props) {
+                    journal.links.insert(*id, ports);
+                }
+            }
+            Event::GlobalRemoved(id) => {
+                journal.links.remove(id);
+                journal.metadata.remove(id);
+            }
+            // The operations refer to objects of the lost connection
+            Event::Reconnected => {
+                journal.undo.clear();
+                journal.redo.clear();
+            }
+            Event::MetadataProperty {
+                id,
+                subject,
+                key,
+                type_,
+                value,
+            } => {
+                let properties = journal.metadata.entry(*id).or_default();
+                match (key, value) {
+                    (Some(key), Some(value)) => {
+                        properties.insert((*subject, key.clone()), (type_.clone(), value.clone()));
+                    }
+                    (Some(key), None) => {
+                        properties.remove(&(*subject, key.clone()));
+                    }
+                    (None, _) => properties.clear(),
+                }
+            }
+            _ => {}
+        }
+    });
+}
+
+/// Destroys an object, recording it first if it's a link
+pub fn destroy_object(sx: &backend::Sender, id: u32) {
+    JOURNAL.with(|journal| {
+        let mut journal = journal.borrow_mut();
+        if let Some(ports) = journal.links.get(&id).cloned() {
+            journal.record(Operation::DestroyLink(ports));
+        }
+    });
+
+    sx.send(Request::DestroyObject(id)).ok();
+}
+
+/// Removes a property of a metadata object, recording its value first
+pub fn remove_metadata_property(
+    sx: &backend::Sender,
+    metadata: u32,
+    subject: u32,
+    key: String,
+    type_: Option<String>,
+) {
+    JOURNAL.with(|journal| {
+        let mut journal = journal.borrow_mut();
+        if let Some((type_, value)) = journal
+            .metadata
+            .get(&metadata)
+            .and_then(|properties| properties.get(&(subject, key.clone())))
+            .cloned()
+        {
+            journal.record(Operation::RemoveMetadataProperty {
+                metadata,
+                subject,
+                key: key.clone(),
+                type_,
+                value,
+            });
+        }
+    });
+
+    sx.send(Request::CallObjectMethod(
+        metadata,
+        ObjectMethod::MetadataSetProperty {
+            subject,
+            key,
+            type_,
+            value: None,
+        },
+    ))
+    .ok();
+}
+
+/// Clears the properties of a metadata object, recording them first
+pub fn clear_metadata(sx: &backend::Sender, metadata: u32) {
+    JOURNAL.with(|journal| {
+        let mut journal = journal.borrow_mut();
+        if let Some(properties) = journal
+            .metadata
+            .get(&metadata)
+            .filter(|properties| !properties.is_empty())
+            .cloned()
+        {
+            journal.record(Operation::ClearMetadata {
+                metadata,
+                properties,
+            });
+        }
+    });
+
+    sx.send(Request::CallObjectMethod(
+        metadata,
+        ObjectMethod::MetadataClear,
+    ))
+    .ok();
+}
+
+/// Undoes or redoes operations with the usual keyboard shortcuts
+pub fn handle_shortcuts(ctx: &egui::Context, sx: &backend::Sender) {
+    // Text fields have their own undo
+    if ctx.wants_keyboard_input() {
+        return;
+    }
+
+    let redo = egui::KeyboardShortcut::new(
+        egui::Modifiers::COMMAND | egui::Modifiers::SHIFT,
+        egui::Key::Z,
+    );
+    let undo = egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Z);
+
+    // The more specific shortcut is checked first since the other would match it too
+    let (redo, undo) = ctx.input_mut(|i| {
+        let redo = i.consume_shortcut(&redo);
+        (redo, !redo && i.consume_shortcut(&undo))
+    });
+
+    JOURNAL.with(|journal| {
+        let mut journal = journal.borrow_mut();
+        if undo {
+            journal.undo(sx);
+        } else if redo {
+            journal.redo(sx);
+        }
+    });
+}
+
+/// Lists the operations that can be undone and redone
+#[derive(Default)]
+pub struct UndoHistory;
+
+impl ToolPlugin for UndoHistory {
+    fn label(&self) -> &'static str {
+        "⮪ Undo History"
+    }
+
+    fn description(&self) -> &'static str {
+        "Undo destroying links and removing metadata properties"
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        JOURNAL.with(|journal| {
+            let mut journal = journal.borrow_mut();
+
+            ui.label(
+                "Destroying links and removing or clearing metadata properties can be undone. \
+                Links are undone by creating new ones between the same ports.",
+            );
+
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(!journal.undo.is_empty(), egui::Button::new("⮪ Undo"))
+                    .on_hover_text("Ctrl+Z")
+                    .clicked()
+                {
+                    journal.undo(sx);
+                }
+                if ui
+                    .add_enabled(!journal.redo.is_empty(), egui::Button::new("⮫ Redo"))
+                    .on_hover_text("Ctrl+Shift+Z")
+                    .clicked()
+                {
+                    journal.redo(sx);
+                }
+                if ui.button("Clear history").clicked() {
+                    journal.undo.clear();
+                    journal.redo.clear();
+                }
+            });
+
+            ui.separator();
+
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for operation in journal.redo.iter() {
+                    ui.weak(operation.description());
+                }
+                for operation in journal.undo.iter().rev() {
+                    ui.label(operation.description());
+                }
+
+                if journal.undo.is_empty() && journal.redo.is_empty() {
+                    ui.label("Nothing has been done yet");
+                }
+            });
+        });
+    }
+}
@@ -0,0 +1,436 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write,
+    rc::Rc,
+};
+
+use eframe::egui;
+use pipewire::types::ObjectType;
+
+use crate::{
+    backend::{self, Request},
+    redact::Redactor,
+    ui::{
+        globals_store::{Global, GlobalsStore},
+        GlobalEvent, ToolPlugin,
+    },
+};
+
+const HEADER: &str = "# coppwr link snapshot\n\
+    # output node\toutput port\tinput node\tinput port\n";
+
+/// A link, by the names of the objects it connects, which stay the same when they're recreated
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct LinkSpec {
+    output_node: String,
+    output_port: String,
+    input_node: String,
+    input_port: String,
+}
+
+impl LinkSpec {
+    fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.split('\t');
+        let spec = Self {
+            output_node: fields.next()?.to_owned(),
+            output_port: fields.next()?.to_owned(),
+            input_node: fields.next()?.to_owned(),
+            input_port: fields.next()?.to_owned(),
+        };
+        fields.next().is_none().then_some(spec)
+    }
+
+    fn line(&self) -> String {
+        format!(
+            "{}\t{}\t{}\t{}",
+            self.output_node, self.output_port, self.input_node, self.input_port
+        )
+    }
+}
+
+enum Status {
+    Present,
+    // The IDs of the output node and port and the input node and port
+    Missing([u32; 4]),
+    Unresolved(&'static str),
+}
+
+/// Saves the links between ports by name and recreates the ones that are missing
+pub struct LinkSnapshots {
+    nodes: BTreeMap<u32, Rc<RefCell<Global>>>,
+    ports: BTreeMap<u32, Rc<RefCell<Global>>>,
+    links: BTreeMap<u32, Rc<RefCell<Global>>>,
+
+    snapshot: Vec<LinkSpec>,
+    auto_restore: bool,
+    // Pairs of ports that links were requested between and haven't appeared yet
+    requested: BTreeSet<(u32, u32)>,
+
+    path: String,
+    // Saving requires the globals for redacting the snapshot
+    save: bool,
+    result: Option<Result<String, String>>,
+}
+
+impl Default for LinkSnapshots {
+    fn default() -> Self {
+        Self {
+            nodes: BTreeMap::new(),
+            ports: BTreeMap::new(),
+            links: BTreeMap::new(),
+
+            snapshot: Vec::new(),
+            auto_restore: false,
+            requested: BTreeSet::new(),
+
+            path: String::from("coppwr-links.txt"),
+            save: false,
+            result: None,
+        }
+    }
+}
+
+impl ToolPlugin for LinkSnapshots {
+    fn label(&self) -> &'static str {
+        "📸 Link Snapshots"
+    }
+
+    fn description(&self) -> &'static str {
+        "Save the links between ports by name and recreate the missing ones later"
+    }
+
+    fn interested_in(&self, object_type: &ObjectType) -> bool {
+        matches!(
+            object_type,
+            ObjectType::Node | ObjectType::Port | ObjectType::Link
+        )
+    }
+
+    fn global_event(&mut self, event: GlobalEvent, sx: &backend::Sender) {
+        match event {
+            GlobalEvent::Added(global) => {
+                let (id, object_type) = {
+                    let global = global.borrow();
+                    (global.id(), global.object_type().clone())
+                };
+                match object_type {
+                    ObjectType::Node => {
+                        self.nodes.insert(id, Rc::clone(global));
+                    }
+                    ObjectType::Port => {
+                        self.ports.insert(id, Rc::clone(global));
+                        // Ports appear after their node, when devices reconnect
+                        if self.auto_restore {
+                            self.restore(sx);
+                        }
+                    }
+                    ObjectType::Link => {
+                        if let Some(ports) = Self::link_ports(&global.borrow()) {
+                            self.requested.remove(&ports);
+                        }
+                        self.links.insert(id, Rc::clone(global));
+                    }
+                    _ => {}
+                }
+            }
+            GlobalEvent::Removed { id, .. } => {
+                self.nodes.remove(&id);
+                self.ports.remove(&id);
+                self.links.remove(&id);
+                self.requested
+                    .retain(|(output, input)| *output != id && *input != id);
+            }
+            GlobalEvent::PropertiesChanged(_) | GlobalEvent::InfoChanged(..) => {}
+        }
+    }
+
+    fn update(&mut self, globals: &GlobalsStore, _: &backend::Sender) {
+        if std::mem::take(&mut self.save) {
+            self.result = Some(self.save(&Redactor::new(globals)));
+        }
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        self.show(ui, sx);
+    }
+}
+
+impl LinkSnapshots {
+    fn prop(global: &Global, key: &str) -> Option<u32> {
+        global.props().get(key)?.parse().ok()
+    }
+
+    fn link_ports(link: &Global) -> Option<(u32, u32)> {
+        Some((
+            Self::prop(link, "link.output.port")?,
+            Self::prop(link, "link.input.port")?,
+        ))
+    }
+
+    fn node_name(&self, id: u32) -> Option<String> {
+        self.nodes
+            .get(&id)?
+            .borrow()
+            .props()
+            .get("node.name")
+            .cloned()
+    }
+
+    fn port_name(&self, id: u32) -> Option<String> {
+        self.ports
+            .get(&id)?
+            .borrow()
+            .props()
+            .get("port.name")
+            .cloned()
+    }
+
+    fn take_snapshot(&mut self) {
+        let mut snapshot: Vec<LinkSpec> = self
+            .links
+            .values()
+            .filter_map(|link| {
+                let link = link.borrow();
+                let id = |key: &str| Self::prop(&link, key);
+                Some(LinkSpec {
+                    output_node: self.node_name(id("link.output.node")?)?,
+                    output_port: self.port_name(id("link.output.port")?)?,
+                    input_node: self.node_name(id("link.input.node")?)?,
+                    input_port: self.port_name(id("link.input.port")?)?,
+                })
+            })
+            .collect();
+        snapshot.sort();
+        snapshot.dedup();
+
+        self.result = Some(Ok(format!("Recorded {} links", snapshot.len())));
+        self.snapshot = snapshot;
+    }
+
+    fn find_node(&self, name: &str) -> Option<u32> {
+        self.nodes.iter().find_map(|(id, node)| {
+            (node.borrow().props().get("node.name").map(String::as_str) == Some(name))
+                .then_some(*id)
+        })
+    }
+
+    fn find_port(&self, node: u32, name: &str) -> Option<u32> {
+        self.ports.iter().find_map(|(id, port)| {
+            let port = port.borrow();
+            (Self::prop(&port, "node.id") == Some(node)
+                && port.props().get("port.name").map(String::as_str) == Some(name))
+            .then_some(*id)
+        })
+    }
+
+    fn status(&self, spec: &LinkSpec, existing: &BTreeSet<(u32, u32)>) -> Status {
+        let Some(output_node) = self.find_node(&spec.output_node) else {
+            return Status::Unresolved("Output node not found");
+        };
+        let Some(output_port) = self.find_port(output_node, &spec.output_port) else {
+            return Status::Unresolved("Output port not found");
+        };
+        let Some(input_node) = self.find_node(&spec.input_node) else {
+            return Status::Unresolved("Input node not found");
+        };
+        let Some(input_port) = self.find_port(input_node, &spec.input_port) else {
+            return Status::Unresolved("Input port not found");
+        };
+
+        if existing.contains(&(output_port, input_port)) {
+            Status::Present
+        } else {
+            Status::Missing([output_node, output_port, input_node, input_port])
+        }
+    }
+
+    fn existing_links(&self) -> BTreeSet<(u32, u32)> {
+        self.links
+            .values()
+            .filter_map(|link| Self::link_ports(&link.borrow()))
+            .collect()
+    }
+
+    /// Creates the links of the snapshot that are missing and whose ports exist.
+    /// Returns how many were requested.
+    fn restore(&mut self, sx: &backend::Sender) -> usize {
+        let existing = self.existing_links();
+
+        let mut created = 0;
+        for spec in &self.snapshot {
+            let Status::Missing(ids) = self.status(spec, &existing) else {
+                continue;
+            };
+            if !self.requested.insert((ids[1], ids[3])) {
+                continue;
+            }
+
+            let props = ["output.node", "output.port", "input.node", "input.port"]
+                .into_iter()
+                .zip(ids)
+                .map(|(key, id)| (format!("link.{key}"), id.to_string()))
+                .chain(std::iter::once((
+                    String::from("object.linger"),
+                    String::from("true"),
+                )))
+                .collect();
+
+            sx.send(Request::CreateObject(
+                ObjectType::Link,
+                String::from("link-factory"),
+                props,
+            ))
+            .ok();
+            created += 1;
+        }
+
+        created
+    }
+
+    fn save(&self, redactor: &Redactor) -> Result<String, String> {
+        let mut text = String::from(HEADER);
+        for spec in &self.snapshot {
+            writeln!(text, "{}", redactor.text(&spec.line())).ok();
+        }
+
+        std::fs::write(&self.path, text)
+            .map(|()| format!("Saved {} links to {}", self.snapshot.len(), self.path))
+            .map_err(|e| format!("Failed to save the snapshot: {e}"))
+    }
+
+    fn load(&mut self) -> Result<String, String> {
+        let text = std::fs::read_to_string(&self.path)
+            .map_err(|e| format!("Failed to read the snapshot: {e}"))?;
+
+        let mut snapshot = Vec::new();
+        for (i, line) in text.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            snapshot.push(
+                LinkSpec::parse(line)
+                    .ok_or_else(|| format!("Line {} doesn't have 4 tab separated names", i + 1))?,
+            );
+        }
+
+        self.snapshot = snapshot;
+        self.requested.clear();
+        Ok(format!(
+            "Loaded {} links from {}",
+            self.snapshot.len(),
+            self.path
+        ))
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        ui.label(
+            "Links are recorded by the names of their nodes and ports instead of their IDs, \
+            so they can be recreated after the nodes are, like when devices reconnect.",
+        );
+
+        ui.horizontal(|ui| {
+            if ui.button("📸 Snapshot").clicked() {
+                self.take_snapshot();
+            }
+            ui.add(
+                egui::TextEdit::singleline(&mut self.path)
+                    .hint_text("File path")
+                    .desired_width(ui.available_width() - 120.),
+            );
+            if ui
+                .add_enabled(!self.snapshot.is_empty(), egui::Button::new("Save"))
+                .clicked()
+            {
+                self.save = true;
+            }
+            if ui.button("Load").clicked() {
+                self.result = Some(self.load());
+            }
+        });
+
+        match &self.result {
+            Some(Ok(message)) => {
+                ui.label(message);
+            }
+            Some(Err(e)) => {
+                ui.colored_label(ui.visuals().error_fg_color, e);
+            }
+            None => {}
+        }
+
+        if self.snapshot.is_empty() {
+            return;
+        }
+
+        ui.separator();
+
+        let existing = self.existing_links();
+        let statuses: Vec<Status> = self
+            .snapshot
+            .iter()
+            .map(|spec| self.status(spec, &existing))
+            .collect();
+        let missing = statuses
+            .iter()
+            .filter(|s| matches!(s, Status::Missing(_)))
+            .count();
+
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(
+                    missing != 0,
+                    egui::Button::new(format!("Restore {missing} missing")),
+                )
+                .clicked()
+            {
+                let created = self.restore(sx);
+                self.result = Some(Ok(format!("Requested {created} links")));
+            }
+            ui.checkbox(&mut self.auto_restore, "Restore automatically")
+                .on_hover_text("Recreate the missing links when ports appear");
+        });
+
+        egui::ScrollArea::both().show(ui, |ui| {
+            egui::Grid::new("link_snapshot")
+                .num_columns(2)
+                .striped(true)
+                .show(ui, |ui| {
+                    for (spec, status) in self.snapshot.iter().zip(&statuses) {
+                        match status {
+                            Status::Present => {
+                                ui.label("Present");
+                            }
+                            Status::Missing(_) => {
+                                ui.colored_label(ui.visuals().warn_fg_color, "Missing");
+                            }
+                            Status::Unresolved(reason) => {
+                                ui.weak("Unavailable").on_hover_text(*reason);
+                            }
+                        }
+                        ui.label(format!(
+                            "{}:{} → {}:{}",
+                            spec.output_node, spec.output_port, spec.input_node, spec.input_port
+                        ));
+                        ui.end_row();
+                    }
+                });
+        });
+    }
+}
@@ -0,0 +1,233 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet},
+    rc::Rc,
+};
+
+use eframe::egui;
+use pipewire::{spa::param::ParamType, types::ObjectType};
+
+use crate::{
+    backend::{self, pods::params::AudioFormat, Request},
+    ui::{globals_store::Global, journal, util::uis::global_info_button, GlobalEvent, ToolPlugin},
+};
+
+struct Row {
+    id: u32,
+    output_node: Option<u32>,
+    text: String,
+    state: String,
+    format: Option<String>,
+}
+
+/// Lists the links between ports and disconnects them
+#[derive(Default)]
+pub struct Links {
+    nodes: BTreeMap<u32, Rc<RefCell<Global>>>,
+    ports: BTreeMap<u32, Rc<RefCell<Global>>>,
+    links: BTreeMap<u32, Rc<RefCell<Global>>>,
+
+    selected: BTreeSet<u32>,
+    filter: String,
+}
+
+impl ToolPlugin for Links {
+    fn label(&self) -> &'static str {
+        "🔗 Links"
+    }
+
+    fn description(&self) -> &'static str {
+        "List and disconnect the links between ports"
+    }
+
+    fn interested_in(&self, object_type: &ObjectType) -> bool {
+        matches!(
+            object_type,
+            ObjectType::Node | ObjectType::Port | ObjectType::Link
+        )
+    }
+
+    fn global_event(&mut self, event: GlobalEvent, _: &backend::Sender) {
+        match event {
+            GlobalEvent::Added(global) => match *global.borrow().object_type() {
+                ObjectType::Node => self.add_node(global),
+                ObjectType::Port => self.add_port(global),
+                ObjectType::Link => self.add_link(global),
+                _ => {}
+            },
+            GlobalEvent::Removed { id, .. } => self.remove_global(id),
+            GlobalEvent::PropertiesChanged(_) | GlobalEvent::InfoChanged(..) => {}
+        }
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        self.show(ui, sx);
+    }
+}
+
+impl Links {
+    fn add_node(&mut self, global: &Rc<RefCell<Global>>) {
+        let id = global.borrow().id();
+        self.nodes.insert(id, Rc::clone(global));
+    }
+
+    fn add_port(&mut self, global: &Rc<RefCell<Global>>) {
+        let id = global.borrow().id();
+        self.ports.insert(id, Rc::clone(global));
+    }
+
+    fn add_link(&mut self, global: &Rc<RefCell<Global>>) {
+        let id = global.borrow().id();
+        self.links.insert(id, Rc::clone(global));
+    }
+
+    fn remove_global(&mut self, id: u32) {
+        self.nodes.remove(&id);
+        self.ports.remove(&id);
+        self.links.remove(&id);
+        self.selected.remove(&id);
+    }
+
+    fn name_of(objects: &BTreeMap<u32, Rc<RefCell<Global>>>, id: Option<u32>) -> String {
+        let Some(id) = id else {
+            return String::from("?");
+        };
+
+        objects
+            .get(&id)
+            .and_then(|global| global.borrow().name().cloned())
+            .unwrap_or_else(|| id.to_string())
+    }
+
+    fn row(&self, link: &Global) -> Row {
+        let id = |key: &str| link.props().get(key).and_then(|id| id.parse().ok());
+        let output_node = id("link.output.node");
+
+        let text = format!(
+            "{}:{} → {}:{}",
+            Self::name_of(&self.nodes, output_node),
+            Self::name_of(&self.ports, id("link.output.port")),
+            Self::name_of(&self.nodes, id("link.input.node")),
+            Self::name_of(&self.ports, id("link.input.port")),
+        );
+
+        // Links carry the format negotiated by the node they start from
+        let format = output_node
+            .and_then(|id| self.nodes.get(&id))
+            .and_then(|node| {
+                node.borrow()
+                    .params(ParamType::Format)
+                    .find_map(AudioFormat::from_param)
+            })
+            .map(|format| format!("{} Hz, {} ch", format.rate, format.channels));
+
+        Row {
+            id: link.id(),
+            output_node,
+            text,
+            state: link.info_value("State").unwrap_or("Unknown").to_owned(),
+            format,
+        }
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        let rows: Vec<Row> = self
+            .links
+            .values()
+            .map(|link| self.row(&link.borrow()))
+            .filter(|row| row.text.contains(self.filter.as_str()))
+            .collect();
+
+        ui.horizontal(|ui| {
+            egui::TextEdit::singleline(&mut self.filter)
+                .hint_text("Filter")
+                .show(ui);
+
+            if ui
+                .button("Get formats")
+                .on_hover_text("Request the formats of the nodes the links start from")
+                .clicked()
+            {
+                let nodes: BTreeSet<u32> = rows.iter().filter_map(|row| row.output_node).collect();
+                for id in nodes {
+                    sx.send(Request::EnumParams(id, ParamType::Format)).ok();
+                }
+            }
+        });
+
+        ui.horizontal(|ui| {
+            if ui.button("Select all").clicked() {
+                self.selected.extend(rows.iter().map(|row| row.id));
+            }
+            if ui.button("Select none").clicked() {
+                self.selected.clear();
+            }
+
+            if ui
+                .add_enabled(
+                    !self.selected.is_empty(),
+                    egui::Button::new(format!("Disconnect {} selected", self.selected.len())),
+                )
+                .clicked()
+            {
+                for id in std::mem::take(&mut self.selected) {
+                    journal::destroy_object(sx, id);
+                }
+            }
+        });
+
+        ui.separator();
+
+        egui::ScrollArea::both().show(ui, |ui| {
+            egui::Grid::new("links")
+                .num_columns(6)
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.label("");
+                    ui.label("");
+                    ui.label("Link");
+                    ui.label("State");
+                    ui.label("Format");
+                    ui.end_row();
+
+                    for row in &rows {
+                        let mut selected = self.selected.contains(&row.id);
+                        if ui.checkbox(&mut selected, "").changed() {
+                            if selected {
+                                self.selected.insert(row.id);
+                            } else {
+                                self.selected.remove(&row.id);
+                            }
+                        }
+
+                        global_info_button(ui, self.links.get(&row.id), sx);
+                        ui.label(&row.text);
+                        ui.label(&row.state);
+                        ui.label(row.format.as_deref().unwrap_or("Unknown"));
+
+                        if ui.small_button("Disconnect").clicked() {
+                            journal::destroy_object(sx, row.id);
+                        }
+
+                        ui.end_row();
+                    }
+                });
+        });
+    }
+}
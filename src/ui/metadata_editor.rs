@@ -16,19 +16,41 @@
 
 use std::{
     cell::RefCell,
-    collections::{btree_map::Entry, BTreeMap},
-    rc::Rc,
+    collections::{btree_map::Entry, BTreeMap, BTreeSet, VecDeque},
+    rc::{Rc, Weak},
+    time::{SystemTime, UNIX_EPOCH},
 };
 
 use eframe::egui;
+use pipewire::types::ObjectType;
 
 use crate::{
     backend::{self, ObjectMethod, Request},
-    ui::{globals_store::Global, util::uis::global_info_button, Tool},
+    pw_dump::{self, MetadataProperty},
+    ui::{
+        compat::{self, Area},
+        globals_store::Global,
+        journal,
+        util::{
+            persistence::PersistentView,
+            uis::{global_info_button, long_spa_json, spa_json_tree},
+        },
+        GlobalEvent, MetadataChange, ToolPlugin,
+    },
 };
 
+const MAX_HISTORY: usize = 500;
+
+// Metadata and key of properties that have been set or cleared from the editor.
+// A key of None is for clearing all the properties.
+type Requested = BTreeSet<(u32, Option<String>)>;
+
+#[derive(Clone)]
+#[cfg_attr(feature = "persistence", derive(serde::Serialize, serde::Deserialize))]
 struct Property {
     subject: u32,
+    #[cfg_attr(feature = "persistence", serde(skip))]
+    subject_global: Option<Weak<RefCell<Global>>>,
     type_: Option<String>,
     value: String,
 }
@@ -42,30 +64,623 @@ impl Property {
             value: Some(self.value.clone()),
         }
     }
+}
 
-    fn clear_request(&self, key: String) -> ObjectMethod {
-        ObjectMethod::MetadataSetProperty {
-            subject: self.subject,
-            key,
-            type_: self.type_.clone(),
-            value: None,
+/// Shows the subject of a property and the object it refers to
+fn show_subject(ui: &mut egui::Ui, prop: &Property, sx: &backend::Sender) {
+    match prop.subject_global.as_ref().and_then(Weak::upgrade) {
+        Some(global) => {
+            let text = {
+                let global = global.borrow();
+                format!(
+                    "{} {} {}",
+                    prop.subject,
+                    global.object_type().to_str(),
+                    global.name().map_or("", String::as_str)
+                )
+            };
+            ui.label(egui::RichText::new(text).weak())
+                .on_hover_text("Subject");
+            global_info_button(ui, Some(&global), sx);
+        }
+        None => {
+            ui.label(egui::RichText::new(prop.subject.to_string()).weak())
+                .on_hover_text("Subject");
+        }
+    }
+}
+
+/// Parses lines of `key subject value`.
+/// The value is the rest of the line, so it can contain spaces.
+fn parse_bulk(contents: &str) -> (Vec<(String, u32, String)>, Vec<String>) {
+    let mut entries = Vec::new();
+    let mut errors = Vec::new();
+
+    for (i, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut parts = line.splitn(3, char::is_whitespace);
+        let (Some(key), Some(subject), Some(value)) = (parts.next(), parts.next(), parts.next())
+        else {
+            errors.push(format!("Line {}: Expected a key, a subject and a value", i + 1));
+            continue;
+        };
+        let Ok(subject) = subject.parse() else {
+            errors.push(format!("Line {}: Invalid subject {subject}", i + 1));
+            continue;
+        };
+
+        entries.push((key.to_owned(), subject, value.trim().to_owned()));
+    }
+
+    (entries, errors)
+}
+
+#[derive(Default)]
+struct BulkSet {
+    path: String,
+    type_: Option<String>,
+    entries: Vec<(String, u32, String)>,
+    errors: Vec<String>,
+}
+
+impl BulkSet {
+    fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        id: u32,
+        properties: &BTreeMap<String, Property>,
+        requested: &mut Requested,
+        sx: &backend::Sender,
+    ) {
+        ui.label(
+            "Each line of the file should be a key, a subject and a value separated by spaces. \
+            Lines starting with # are ignored.",
+        );
+
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.path)
+                    .hint_text("File path")
+                    .desired_width(ui.available_width() - 50.),
+            );
+            if ui.button("Load").clicked() {
+                match std::fs::read_to_string(&self.path) {
+                    Ok(contents) => (self.entries, self.errors) = parse_bulk(&contents),
+                    Err(e) => {
+                        self.entries.clear();
+                        self.errors = vec![format!("Failed to read the file: {e}")];
+                    }
+                }
+            }
+        });
+
+        ui.horizontal(|ui| {
+            if ui.checkbox(&mut self.type_.is_some(), "Type").changed() {
+                if self.type_.is_none() {
+                    self.type_ = Some(String::new());
+                } else {
+                    self.type_ = None;
+                }
+            }
+            if let Some(ref mut type_) = self.type_ {
+                ui.add(
+                    egui::TextEdit::singleline(type_)
+                        .hint_text("Type of all the values, e.g. Spa:String:JSON")
+                        .desired_width(f32::INFINITY),
+                );
+            }
+        });
+
+        for error in &self.errors {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+
+        if self.entries.is_empty() {
+            return;
+        }
+
+        ui.label("Preview");
+        egui::Grid::new(("bulk_preview", id))
+            .num_columns(4)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Key");
+                ui.label("Subject");
+                ui.label("Current value");
+                ui.label("New value");
+                ui.end_row();
+
+                for (key, subject, value) in &self.entries {
+                    let current = properties
+                        .get(key)
+                        .filter(|p| p.subject == *subject)
+                        .map(|p| p.value.as_str());
+
+                    ui.label(key);
+                    ui.label(subject.to_string());
+                    ui.label(current.unwrap_or("-"));
+                    if current == Some(value.as_str()) {
+                        ui.label(value);
+                    } else {
+                        ui.colored_label(ui.visuals().warn_fg_color, value);
+                    }
+                    ui.end_row();
+                }
+            });
+
+        ui.horizontal(|ui| {
+            if ui
+                .button(format!("Apply {} properties", self.entries.len()))
+                .clicked()
+            {
+                for (key, subject, value) in std::mem::take(&mut self.entries) {
+                    requested.insert((id, Some(key.clone())));
+                    sx.send(Request::CallObjectMethod(
+                        id,
+                        ObjectMethod::MetadataSetProperty {
+                            subject,
+                            key,
+                            type_: self.type_.clone(),
+                            value: Some(value),
+                        },
+                    ))
+                    .ok();
+                }
+            }
+            if ui.button("Discard").clicked() {
+                self.entries.clear();
+                self.errors.clear();
+            }
+        });
+    }
+}
+
+/// Saves all the properties of a metadata object to a JSON file and sets the ones of a saved file
+#[derive(Default)]
+struct JsonFile {
+    path: String,
+    result: Option<Result<String, String>>,
+}
+
+impl JsonFile {
+    fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        id: u32,
+        name: Option<&String>,
+        properties: &BTreeMap<String, Property>,
+        requested: &mut Requested,
+        sx: &backend::Sender,
+    ) {
+        ui.label(
+            "The properties are saved in the format of the metadata of pw-dump. \
+            Subjects are object IDs, which differ between machines and restarts, \
+            so only properties with subject 0 can be moved reliably.",
+        );
+
+        if self.path.is_empty() {
+            self.path = format!("{}.json", name.map_or("metadata", String::as_str));
+        }
+
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.path)
+                    .hint_text("File path")
+                    .desired_width(ui.available_width() - 110.),
+            );
+
+            if ui.button("Export").clicked() {
+                let json =
+                    pw_dump::metadata(properties.iter().map(|(key, prop)| MetadataProperty {
+                        subject: prop.subject,
+                        key,
+                        type_: prop.type_.as_deref(),
+                        value: &prop.value,
+                    }));
+                self.result = Some(
+                    std::fs::write(&self.path, json)
+                        .map(|()| format!("Saved {} properties to {}", properties.len(), self.path))
+                        .map_err(|e| format!("Failed to export: {e}")),
+                );
+            }
+
+            if ui.button("Import").clicked() {
+                self.result = Some(
+                    std::fs::read_to_string(&self.path)
+                        .map_err(|e| format!("Failed to read the file: {e}"))
+                        .and_then(|contents| {
+                            pw_dump::parse_metadata(&contents).ok_or_else(|| {
+                                String::from("The file isn't a JSON array of metadata properties")
+                            })
+                        })
+                        .map(|properties| {
+                            let count = properties.len();
+                            for property in properties {
+                                requested.insert((id, Some(property.key.clone())));
+                                sx.send(Request::CallObjectMethod(
+                                    id,
+                                    ObjectMethod::MetadataSetProperty {
+                                        subject: property.subject,
+                                        key: property.key,
+                                        type_: property.type_,
+                                        value: Some(property.value),
+                                    },
+                                ))
+                                .ok();
+                            }
+                            format!("Set {count} properties")
+                        }),
+                );
+            }
+        });
+
+        match &self.result {
+            Some(Ok(message)) => {
+                ui.label(message);
+            }
+            Some(Err(e)) => {
+                ui.colored_label(ui.visuals().error_fg_color, e);
+            }
+            None => {}
+        }
+    }
+}
+
+#[derive(Default)]
+struct FindReplace {
+    find: String,
+    replace: String,
+    // Metadata to search in, all of them if None
+    scope: Option<u32>,
+    // Matches the user doesn't want replaced
+    rejected: BTreeSet<(u32, String)>,
+}
+
+impl FindReplace {
+    fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        metadatas: &BTreeMap<u32, Metadata>,
+        requested: &mut Requested,
+        sx: &backend::Sender,
+    ) {
+        let metadata_name =
+            |metadata: &Metadata| metadata.global.borrow().name().cloned().unwrap_or_default();
+
+        egui::Grid::new("find_replace")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label("Find");
+                if ui
+                    .add(egui::TextEdit::singleline(&mut self.find).desired_width(f32::INFINITY))
+                    .changed()
+                {
+                    self.rejected.clear();
+                }
+                ui.end_row();
+
+                ui.label("Replace with");
+                ui.add(egui::TextEdit::singleline(&mut self.replace).desired_width(f32::INFINITY));
+                ui.end_row();
+
+                ui.label("In");
+                egui::ComboBox::from_id_source("find_replace_scope")
+                    .selected_text(
+                        self.scope
+                            .and_then(|id| metadatas.get(&id))
+                            .map_or_else(|| String::from("All metadata"), metadata_name),
+                    )
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut self.scope, None, "All metadata");
+                        for (id, metadata) in metadatas {
+                            ui.selectable_value(
+                                &mut self.scope,
+                                Some(*id),
+                                format!("{id} {}", metadata_name(metadata)),
+                            );
+                        }
+                    });
+                ui.end_row();
+            });
+
+        if self.find.is_empty() {
+            return;
+        }
+
+        let matches = metadatas
+            .iter()
+            .filter(|(id, _)| self.scope.map_or(true, |scope| scope == **id))
+            .flat_map(|(id, metadata)| {
+                metadata
+                    .properties
+                    .iter()
+                    .filter(|(_, prop)| prop.value.contains(&self.find))
+                    .map(move |(key, prop)| (*id, metadata, key, prop))
+            })
+            .collect::<Vec<_>>();
+
+        if matches.is_empty() {
+            ui.label("No values match");
+            return;
+        }
+
+        ui.label("Uncheck the values that shouldn't be replaced");
+
+        egui::Grid::new("find_replace_matches")
+            .num_columns(4)
+            .striped(true)
+            .show(ui, |ui| {
+                for (id, metadata, key, prop) in &matches {
+                    let match_id = (*id, (*key).clone());
+                    let mut accepted = !self.rejected.contains(&match_id);
+                    if ui
+                        .checkbox(&mut accepted, format!("{}: {key}", metadata_name(metadata)))
+                        .changed()
+                    {
+                        if accepted {
+                            self.rejected.remove(&match_id);
+                        } else {
+                            self.rejected.insert(match_id);
+                        }
+                    }
+                    ui.label(&prop.value);
+                    ui.label("→");
+                    ui.label(prop.value.replace(&self.find, &self.replace));
+                    ui.end_row();
+                }
+            });
+
+        if ui.button("Replace").clicked() {
+            for (id, _, key, prop) in matches {
+                if self.rejected.contains(&(id, key.clone())) {
+                    continue;
+                }
+
+                let replaced = Property {
+                    value: prop.value.replace(&self.find, &self.replace),
+                    ..prop.clone()
+                };
+                requested.insert((id, Some(key.clone())));
+                sx.send(Request::CallObjectMethod(id, replaced.set_request(key.clone())))
+                    .ok();
+            }
+            self.rejected.clear();
+        }
+    }
+}
+
+/// A change of a property, as reported by PipeWire
+struct Change {
+    // Seconds since the Unix epoch
+    time: u64,
+    // None when all the properties were cleared
+    key: Option<String>,
+    subject: Option<u32>,
+    old: Option<String>,
+    new: Option<String>,
+    // Whether it was requested from the editor
+    by_coppwr: bool,
+}
+
+impl Change {
+    fn show(&self, ui: &mut egui::Ui) {
+        let secs = self.time % 86400;
+        ui.label(format!(
+            "{:02}:{:02}:{:02}",
+            secs / 3600,
+            secs % 3600 / 60,
+            secs % 60
+        ));
+
+        match &self.key {
+            Some(key) => ui.label(key),
+            None => ui.label(egui::RichText::new("All properties").italics()),
+        };
+        ui.label(
+            self.subject
+                .map_or_else(|| String::from("-"), |s| s.to_string()),
+        );
+
+        for value in [&self.old, &self.new] {
+            match value {
+                Some(value) => ui.label(value),
+                None => ui.label(egui::RichText::new("Unset").weak()),
+            };
         }
+
+        ui.label(if self.by_coppwr { "coppwr" } else { "Other" });
     }
 }
 
 struct Metadata {
     properties: BTreeMap<String, Property>,
     user_properties: Vec<(String, Property)>,
+    bulk_set: BulkSet,
+    json_file: JsonFile,
     global: Rc<RefCell<Global>>,
+
+    // Newest first
+    history: VecDeque<Change>,
+    history_filter: String,
+}
+
+impl Metadata {
+    fn new(
+        global: &Rc<RefCell<Global>>,
+        drafts: &mut BTreeMap<String, Vec<(String, Property)>>,
+    ) -> Self {
+        let user_properties = global
+            .borrow()
+            .props()
+            .get("metadata.name")
+            .and_then(|name| drafts.remove(name))
+            .unwrap_or_default();
+
+        Self {
+            properties: BTreeMap::new(),
+            user_properties,
+            bulk_set: BulkSet::default(),
+            json_file: JsonFile::default(),
+            global: Rc::clone(global),
+
+            history: VecDeque::new(),
+            history_filter: String::new(),
+        }
+    }
+
+    fn record(
+        &mut self,
+        key: Option<String>,
+        subject: Option<u32>,
+        old: Option<String>,
+        new: Option<String>,
+        by_coppwr: bool,
+    ) {
+        self.history.push_front(Change {
+            time: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+            key,
+            subject,
+            old,
+            new,
+            by_coppwr,
+        });
+        self.history.truncate(MAX_HISTORY);
+    }
+
+    fn show_history(&mut self, ui: &mut egui::Ui, id: u32) {
+        ui.label(
+            "PipeWire doesn't report which client changed a property. \
+            Only the changes made from this editor can be told apart.",
+        );
+
+        ui.horizontal(|ui| {
+            egui::TextEdit::singleline(&mut self.history_filter)
+                .hint_text("Filter by key")
+                .show(ui);
+            if ui.button("Clear").clicked() {
+                self.history.clear();
+            }
+        });
+
+        if self.history.is_empty() {
+            ui.label("No changes yet");
+            return;
+        }
+
+        egui::ScrollArea::vertical()
+            .id_source(("history_scroll", id))
+            .max_height(300.)
+            .show(ui, |ui| {
+                egui::Grid::new(("history", id))
+                    .num_columns(6)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.label("Time (UTC)");
+                        ui.label("Key");
+                        ui.label("Subject");
+                        ui.label("Old value");
+                        ui.label("New value");
+                        ui.label("Origin");
+                        ui.end_row();
+
+                        for change in self.history.iter().filter(|change| {
+                            change
+                                .key
+                                .as_ref()
+                                .map_or(true, |key| key.contains(self.history_filter.as_str()))
+                        }) {
+                            change.show(ui);
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
 }
 
 #[derive(Default)]
 pub struct MetadataEditor {
     metadatas: BTreeMap<u32, Metadata>,
+    // Unsent properties of metadata objects, by metadata name,
+    // that haven't been restored because their metadata hasn't appeared yet
+    drafts: BTreeMap<String, Vec<(String, Property)>>,
+    find_replace: FindReplace,
+    requested: Requested,
+}
+
+#[cfg_attr(feature = "persistence", derive(serde::Serialize, serde::Deserialize))]
+pub struct PersistentData {
+    drafts: BTreeMap<String, Vec<(String, Property)>>,
+}
+
+impl PersistentView for MetadataEditor {
+    type Data = PersistentData;
+
+    fn with_data(data: &Self::Data) -> Self {
+        Self {
+            drafts: data.drafts.clone(),
+            ..Self::default()
+        }
+    }
+
+    fn save_data(&self) -> Option<Self::Data> {
+        let mut drafts = self.drafts.clone();
+
+        for metadata in self.metadatas.values() {
+            if metadata.user_properties.is_empty() {
+                continue;
+            }
+
+            if let Some(name) = metadata.global.borrow().props().get("metadata.name") {
+                drafts.insert(name.clone(), metadata.user_properties.clone());
+            }
+        }
+
+        Some(PersistentData { drafts })
+    }
 }
 
-impl Tool for MetadataEditor {
-    const NAME: &'static str = "Metadata Editor";
+impl ToolPlugin for MetadataEditor {
+    fn label(&self) -> &'static str {
+        "🗐 Metadata Editor"
+    }
+
+    fn description(&self) -> &'static str {
+        "Edit remote metadata"
+    }
+
+    fn interested_in(&self, object_type: &ObjectType) -> bool {
+        *object_type == ObjectType::Metadata
+    }
+
+    fn global_event(&mut self, event: GlobalEvent, _: &backend::Sender) {
+        match event {
+            GlobalEvent::Added(global) => self.add_metadata(global),
+            GlobalEvent::Removed { id, .. } => self.remove_metadata(id),
+            GlobalEvent::PropertiesChanged(_) | GlobalEvent::InfoChanged(..) => {}
+        }
+    }
+
+    fn metadata_property(&mut self, change: MetadataChange, _: &backend::Sender) {
+        let id = change.metadata.borrow().id();
+        match (change.key, change.value) {
+            (Some(key), Some(value)) => self.add_property(
+                change.metadata,
+                change.subject,
+                change.subject_global,
+                key.to_owned(),
+                change.type_.map(str::to_owned),
+                value.to_owned(),
+            ),
+            (Some(key), None) => self.remove_property(id, key),
+            (None, _) => self.clear_properties(id),
+        }
+    }
 
     fn show(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
         self.show(ui, sx);
@@ -73,63 +688,80 @@ impl Tool for MetadataEditor {
 }
 
 impl MetadataEditor {
-    pub fn add_metadata(&mut self, global: &Rc<RefCell<Global>>) {
+    fn add_metadata(&mut self, global: &Rc<RefCell<Global>>) {
         let id = global.borrow().id();
-        self.metadatas.entry(id).or_insert(Metadata {
-            properties: BTreeMap::new(),
-            user_properties: Vec::new(),
-            global: Rc::clone(global),
-        });
+        if let Entry::Vacant(e) = self.metadatas.entry(id) {
+            e.insert(Metadata::new(global, &mut self.drafts));
+        }
     }
 
-    pub fn add_property(
+    fn add_property(
         &mut self,
         global: &Rc<RefCell<Global>>,
         subject: u32,
+        subject_global: Option<&Rc<RefCell<Global>>>,
         key: String,
         type_: Option<String>,
         value: String,
     ) {
         let prop = Property {
             subject,
+            subject_global: subject_global.map(Rc::downgrade),
             type_,
-            value,
+            value: value.clone(),
         };
 
         let id = global.borrow().id();
-        match self.metadatas.entry(id) {
-            Entry::Occupied(e) => {
-                let properties = &mut e.into_mut().properties;
-                properties.insert(key, prop);
-            }
-            Entry::Vacant(e) => {
-                let metadata = Metadata {
-                    properties: BTreeMap::new(),
-                    user_properties: Vec::new(),
-                    global: Rc::clone(global),
-                };
-                e.insert(metadata).properties.insert(key, prop);
-            }
-        }
+        let metadata = match self.metadatas.entry(id) {
+            Entry::Occupied(e) => e.into_mut(),
+            Entry::Vacant(e) => e.insert(Metadata::new(global, &mut self.drafts)),
+        };
+
+        let by_coppwr = self.requested.remove(&(id, Some(key.clone())));
+        let old = metadata.properties.insert(key.clone(), prop);
+        metadata.record(
+            Some(key),
+            Some(subject),
+            old.map(|prop| prop.value),
+            Some(value),
+            by_coppwr,
+        );
     }
 
-    pub fn remove_metadata(&mut self, id: u32) {
+    fn remove_metadata(&mut self, id: u32) {
         self.metadatas.remove(&id);
     }
 
-    pub fn remove_property(&mut self, id: u32, key: &str) {
-        self.metadatas.entry(id).and_modify(|m| {
-            m.properties.remove(key);
-        });
+    fn remove_property(&mut self, id: u32, key: &str) {
+        let by_coppwr = self.requested.remove(&(id, Some(key.to_owned())));
+        if let Some(metadata) = self.metadatas.get_mut(&id) {
+            let old = metadata.properties.remove(key);
+            metadata.record(
+                Some(key.to_owned()),
+                old.as_ref().map(|prop| prop.subject),
+                old.map(|prop| prop.value),
+                None,
+                by_coppwr,
+            );
+        }
     }
 
-    pub fn clear_properties(&mut self, id: u32) {
-        self.metadatas.entry(id).and_modify(|m| {
-            m.properties.clear();
-        });
+    fn clear_properties(&mut self, id: u32) {
+        let by_coppwr = self.requested.remove(&(id, None));
+        if let Some(metadata) = self.metadatas.get_mut(&id) {
+            metadata.properties.clear();
+            metadata.record(None, None, None, None, by_coppwr);
+        }
     }
 
     fn show(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        compat::show_warnings(ui, Area::Metadata);
+
+        egui::CollapsingHeader::new("Find and replace").show(ui, |ui| {
+            self.find_replace
+                .show(ui, &self.metadatas, &mut self.requested, sx);
+        });
+
         for (id, metadata) in &mut self.metadatas {
             ui.group(|ui| {
                 ui.heading(metadata.global.borrow().name().map_or("", String::as_str));
@@ -139,8 +771,8 @@ impl MetadataEditor {
                     ui.label(format!("ID: {id}"));
 
                     if ui.small_button("Clear").clicked() {
-                        sx.send(Request::CallObjectMethod(*id, ObjectMethod::MetadataClear))
-                            .ok();
+                        self.requested.insert((*id, None));
+                        journal::clear_metadata(sx, *id);
                     }
                 });
                 egui::Grid::new(id)
@@ -148,35 +780,50 @@ impl MetadataEditor {
                     .striped(true)
                     .show(ui, |ui| {
                         for (key, prop) in &mut metadata.properties {
-                            ui.label(key);
+                            ui.horizontal(|ui| {
+                                ui.label(key);
+                                show_subject(ui, prop, sx);
+                            });
 
                             ui.with_layout(egui::Layout::right_to_left(egui::Align::Min), |ui| {
                                 if ui.small_button("Clear").clicked() {
-                                    sx.send(Request::CallObjectMethod(
+                                    self.requested.insert((*id, Some(key.clone())));
+                                    journal::remove_metadata_property(
+                                        sx,
                                         *id,
-                                        prop.clear_request(key.clone()),
-                                    ))
-                                    .ok();
+                                        prop.subject,
+                                        key.clone(),
+                                        prop.type_.clone(),
+                                    );
                                 }
                                 if ui.small_button("Set").clicked() {
+                                    self.requested.insert((*id, Some(key.clone())));
                                     sx.send(Request::CallObjectMethod(
                                         *id,
                                         prop.set_request(key.clone()),
                                     ))
                                     .ok();
                                 }
+                                if let Some(value) = long_spa_json(&prop.value) {
+                                    ui.menu_button("{…}", |ui| {
+                                        egui::ScrollArea::vertical().max_height(400.).show(
+                                            ui,
+                                            |ui| {
+                                                ui.set_max_width(500.);
+                                                spa_json_tree(ui, &value);
+                                            },
+                                        );
+                                    })
+                                    .response
+                                    .on_hover_text("Show the value pretty printed");
+                                }
                                 let input = ui.add(
                                     egui::TextEdit::singleline(&mut prop.value)
                                         .hint_text("Value")
                                         .desired_width(f32::INFINITY),
                                 );
                                 if let Some(type_) = prop.type_.as_ref() {
-                                    input.on_hover_text(format!(
-                                        "Type: {type_}\nSubject: {}",
-                                        prop.subject
-                                    ));
-                                } else {
-                                    input.on_hover_text(format!("Subject: {}", prop.subject));
+                                    input.on_hover_text(format!("Type: {type_}"));
                                 }
                             });
 
@@ -224,6 +871,7 @@ impl MetadataEditor {
                             let keep = ui
                                 .horizontal(|ui| {
                                     if ui.small_button("Set").clicked() {
+                                        self.requested.insert((*id, Some(key.clone())));
                                         sx.send(Request::CallObjectMethod(
                                             *id,
                                             prop.set_request(key.clone()),
@@ -245,6 +893,7 @@ impl MetadataEditor {
                                     String::new(),
                                     Property {
                                         subject: 0,
+                                        subject_global: None,
                                         type_: None,
                                         value: String::new(),
                                     },
@@ -261,12 +910,44 @@ impl MetadataEditor {
                         ui.add_enabled_ui(!metadata.user_properties.is_empty(), |ui| {
                             if ui.button("Set all").clicked() {
                                 for (key, prop) in std::mem::take(&mut metadata.user_properties) {
+                                    self.requested.insert((*id, Some(key.clone())));
                                     sx.send(Request::CallObjectMethod(*id, prop.set_request(key)))
                                         .ok();
                                 }
                             }
                         });
                     });
+
+                egui::CollapsingHeader::new("Set from file")
+                    .id_source(("bulk_set", *id))
+                    .show(ui, |ui| {
+                        metadata.bulk_set.show(
+                            ui,
+                            *id,
+                            &metadata.properties,
+                            &mut self.requested,
+                            sx,
+                        );
+                    });
+
+                egui::CollapsingHeader::new("Import and export")
+                    .id_source(("json_file", *id))
+                    .show(ui, |ui| {
+                        metadata.json_file.show(
+                            ui,
+                            *id,
+                            metadata.global.borrow().name(),
+                            &metadata.properties,
+                            &mut self.requested,
+                            sx,
+                        );
+                    });
+
+                egui::CollapsingHeader::new("History")
+                    .id_source(("history", *id))
+                    .show(ui, |ui| {
+                        metadata.show_history(ui, *id);
+                    });
             });
         }
     }
@@ -14,22 +14,112 @@
 //
 // SPDX-License-Identifier: GPL-3.0-only
 
+mod alsa_params;
+mod bandwidth;
+mod channel_map;
+mod churn_stats;
+mod client_permissions;
+mod clock_override;
+mod compat;
+mod config_check;
+mod config_recipe;
 mod context_manager;
+mod control;
+mod daemon_restart;
+mod debug_bundle;
+mod defaults;
+mod duplicate_names;
+mod event_log;
+mod export;
+mod factories;
 mod globals_store;
 mod graph;
+mod health;
+mod journal;
+mod link_snapshots;
+mod links;
 mod metadata_editor;
+mod network_discovery;
+mod node_compare;
+mod noise_suppression;
+mod object_compare;
 mod object_creator;
+mod object_watchdog;
+mod osc_control;
+mod own_client;
+mod param_builder;
+mod permission_templates;
+mod pins;
 mod profiler;
+mod remotes;
+mod rt_scheduling;
+mod rule_generator;
+mod selection;
+mod session_recorder;
+mod settings_editor;
+mod shortcuts;
+mod snapshots;
+mod spatializer_wizard;
+mod status_bar;
+mod theme;
 mod tool;
 mod util;
 
+use bandwidth::Bandwidth;
+use churn_stats::ChurnStats;
+use client_permissions::ClientPermissions;
+use config_check::ConfigCheck;
+use config_recipe::ConfigRecipe;
 use context_manager::ContextManager;
-use globals_store::GlobalsStore;
+use daemon_restart::DaemonRestart;
+use debug_bundle::DebugBundle;
+use defaults::Defaults;
+use duplicate_names::DuplicateNames;
+use event_log::EventLog;
+use export::Export;
+use factories::Factories;
+pub use globals_store::{Global, GlobalsStore};
 use graph::Graph;
+use health::HealthPanel;
+use journal::UndoHistory;
+use link_snapshots::LinkSnapshots;
+use links::Links;
 use metadata_editor::MetadataEditor;
+use network_discovery::NetworkDiscovery;
+use node_compare::NodeCompare;
+use noise_suppression::NoiseSuppression;
+use object_compare::ObjectCompare;
 use object_creator::ObjectCreator;
+use object_watchdog::ObjectWatchdog;
+use osc_control::OscControl;
+use own_client::OwnClient;
+use param_builder::ParamBuilder;
+use permission_templates::PermissionTemplates;
 use profiler::Profiler;
-use tool::{Tool, Windowed};
+use remotes::Remotes;
+use rt_scheduling::RtScheduling;
+use session_recorder::SessionRecorder;
+use settings_editor::SettingsEditor;
+use snapshots::Snapshots;
+use spatializer_wizard::SpatializerWizard;
+use status_bar::StatusBar;
+pub use tool::{ConnectionEvent, GlobalEvent, MetadataChange, ToolPlugin, ToolRegistry};
+
+/// Registers the tools that are created without any data of the connection.
+/// Tools of forks or optional features are registered here as well.
+fn register_tools(registry: &mut ToolRegistry) {
+    registry.register(ObjectWatchdog::default());
+    registry.register(RtScheduling::default());
+    registry.register(Bandwidth::default());
+    registry.register(Links::default());
+    registry.register(DuplicateNames::default());
+    registry.register(Factories::default());
+    registry.register(ObjectCompare::default());
+    registry.register(ConfigCheck::default());
+    registry.register(ParamBuilder::default());
+    registry.register(LinkSnapshots::default());
+    registry.register(OscControl::default());
+}
 
 mod app;
 pub use app::App as CoppwrApp;
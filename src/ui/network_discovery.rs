@@ -0,0 +1,317 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet},
+    rc::Rc,
+};
+
+use eframe::egui;
+use pipewire::types::ObjectType;
+
+use crate::{
+    backend::{self, Request},
+    ui::{globals_store::Global, util::uis::global_info_button, GlobalEvent, ToolPlugin},
+};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Protocol {
+    Zeroconf,
+    Raop,
+}
+
+impl Protocol {
+    const fn module_name(self) -> &'static str {
+        match self {
+            Self::Zeroconf => "libpipewire-module-zeroconf-discover",
+            Self::Raop => "libpipewire-module-raop-discover",
+        }
+    }
+
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Zeroconf => "Zeroconf (PulseAudio tunnels)",
+            Self::Raop => "RAOP (AirPlay)",
+        }
+    }
+
+    /// Figures out whether a node has been created by one of the discovery modules
+    fn of_node(props: &BTreeMap<String, String>) -> Option<Self> {
+        let name = props.get("node.name").map_or("", String::as_str);
+
+        if name.starts_with("raop_sink.") || props.keys().any(|k| k.starts_with("raop.")) {
+            Some(Self::Raop)
+        } else if name.starts_with("tunnel.")
+            || props.contains_key("pulse.server.address")
+            || props.contains_key("tunnel.mode")
+        {
+            Some(Self::Zeroconf)
+        } else {
+            None
+        }
+    }
+}
+
+struct DiscoveryModule {
+    protocol: Protocol,
+
+    // Set when loading the module in coppwr's context has been requested.
+    // Those modules are not visible in the remote's registry, so they can't be unloaded.
+    loaded_locally: bool,
+}
+
+/// Loads the network discovery modules and manages the devices they create
+pub struct NetworkDiscovery {
+    modules: [DiscoveryModule; 2],
+
+    // Discovery modules that the remote has loaded itself
+    remote_modules: BTreeMap<u32, Rc<RefCell<Global>>>,
+
+    devices: BTreeMap<u32, (Protocol, Rc<RefCell<Global>>)>,
+
+    // Names of the devices the user has disabled.
+    // Disabled devices are destroyed every time they're discovered.
+    disabled: BTreeSet<String>,
+}
+
+impl Default for NetworkDiscovery {
+    fn default() -> Self {
+        Self {
+            modules: [
+                DiscoveryModule {
+                    protocol: Protocol::Zeroconf,
+                    loaded_locally: false,
+                },
+                DiscoveryModule {
+                    protocol: Protocol::Raop,
+                    loaded_locally: false,
+                },
+            ],
+            remote_modules: BTreeMap::new(),
+            devices: BTreeMap::new(),
+            disabled: BTreeSet::new(),
+        }
+    }
+}
+
+impl ToolPlugin for NetworkDiscovery {
+    fn label(&self) -> &'static str {
+        "📡 Network Discovery"
+    }
+
+    fn description(&self) -> &'static str {
+        "Discover and manage network audio devices"
+    }
+
+    fn interested_in(&self, object_type: &ObjectType) -> bool {
+        matches!(object_type, ObjectType::Module | ObjectType::Node)
+    }
+
+    fn global_event(&mut self, event: GlobalEvent, sx: &backend::Sender) {
+        match event {
+            GlobalEvent::Added(global) | GlobalEvent::PropertiesChanged(global) => {
+                let object_type = global.borrow().object_type().clone();
+                match object_type {
+                    ObjectType::Module => self.add_module(global),
+                    ObjectType::Node => self.add_node(global, sx),
+                    _ => {}
+                }
+            }
+            GlobalEvent::Removed { id, .. } => self.remove_global(id),
+            GlobalEvent::InfoChanged(..) => {}
+        }
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        self.show(ui, sx);
+    }
+}
+
+fn device_name(global: &Global) -> String {
+    global
+        .props()
+        .get("node.name")
+        .cloned()
+        .unwrap_or_else(|| global.id().to_string())
+}
+
+impl NetworkDiscovery {
+    fn add_module(&mut self, global: &Rc<RefCell<Global>>) {
+        let (id, is_discovery) = {
+            let global = global.borrow();
+            let is_discovery = global.props().get("module.name").is_some_and(|name| {
+                self.modules
+                    .iter()
+                    .any(|m| m.protocol.module_name() == name.as_str())
+            });
+            (global.id(), is_discovery)
+        };
+
+        if is_discovery {
+            self.remote_modules.insert(id, Rc::clone(global));
+        }
+    }
+
+    /// Checks if the node is a network device and starts tracking it if it is.
+    /// Can be called multiple times for the same node as its properties get updated.
+    fn add_node(&mut self, global: &Rc<RefCell<Global>>, sx: &backend::Sender) {
+        let (id, protocol, disabled) = {
+            let global = global.borrow();
+            if self.devices.contains_key(&global.id()) {
+                return;
+            }
+
+            let Some(protocol) = Protocol::of_node(global.props()) else {
+                return;
+            };
+            (
+                global.id(),
+                protocol,
+                self.disabled.contains(&device_name(&global)),
+            )
+        };
+
+        if disabled {
+            sx.send(Request::DestroyObject(id)).ok();
+        }
+
+        self.devices.insert(id, (protocol, Rc::clone(global)));
+    }
+
+    fn remove_global(&mut self, id: u32) {
+        self.remote_modules.remove(&id);
+        self.devices.remove(&id);
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        ui.heading("Discovery modules");
+
+        for module in &mut self.modules {
+            let protocol = module.protocol;
+
+            ui.horizontal(|ui| {
+                ui.label(protocol.as_str());
+
+                let loaded_remotely = self.remote_modules.values().find(|g| {
+                    g.borrow()
+                        .props()
+                        .get("module.name")
+                        .is_some_and(|n| n == protocol.module_name())
+                });
+
+                if let Some(global) = loaded_remotely {
+                    ui.label("Loaded by the remote");
+                    global_info_button(ui, Some(global), sx);
+                    if ui
+                        .small_button("Unload")
+                        .on_hover_text("Destroy the module global")
+                        .clicked()
+                    {
+                        sx.send(Request::DestroyObject(global.borrow().id())).ok();
+                    }
+                } else if module.loaded_locally {
+                    ui.label("Loaded in coppwr").on_hover_text(
+                        "The module lasts only while coppwr is connected. \
+                        If loading it failed, the error is in coppwr's output.",
+                    );
+                } else if ui
+                    .small_button("Load")
+                    .on_hover_text(
+                        "Load the module in coppwr's context. \
+                        Devices will be available for as long as coppwr stays connected.",
+                    )
+                    .clicked()
+                {
+                    sx.send(Request::LoadModule {
+                        module_dir: None,
+                        name: protocol.module_name().to_owned(),
+                        args: None,
+                        props: None,
+                    })
+                    .ok();
+                    module.loaded_locally = true;
+                }
+            });
+        }
+
+        ui.separator();
+
+        ui.heading("Devices");
+
+        if self.devices.is_empty() {
+            ui.label("No network devices have been discovered");
+        }
+
+        egui::Grid::new("network_devices")
+            .num_columns(4)
+            .striped(true)
+            .show(ui, |ui| {
+                for (id, (protocol, global)) in &self.devices {
+                    let name = device_name(&global.borrow());
+
+                    global_info_button(ui, Some(global), sx);
+                    ui.label(id.to_string());
+                    ui.label(global.borrow().name().unwrap_or(&name))
+                        .on_hover_text(protocol.as_str());
+
+                    let mut enabled = !self.disabled.contains(&name);
+                    if ui
+                        .checkbox(&mut enabled, "Enabled")
+                        .on_hover_text(
+                            "Disabled devices are destroyed whenever they get discovered. \
+                            Enabled devices come back once the module discovers them again.",
+                        )
+                        .changed()
+                    {
+                        if enabled {
+                            self.disabled.remove(&name);
+                        } else {
+                            sx.send(Request::DestroyObject(*id)).ok();
+                            self.disabled.insert(name);
+                        }
+                    }
+
+                    ui.end_row();
+                }
+            });
+
+        let disabled_missing = self
+            .disabled
+            .iter()
+            .filter(|name| {
+                !self
+                    .devices
+                    .values()
+                    .any(|(_, g)| device_name(&g.borrow()) == **name)
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+
+        if !disabled_missing.is_empty() {
+            ui.separator();
+            ui.label("Disabled devices that are currently gone");
+            for name in disabled_missing {
+                ui.horizontal(|ui| {
+                    ui.label(&name);
+                    if ui.small_button("Enable").clicked() {
+                        self.disabled.remove(&name);
+                    }
+                });
+            }
+        }
+    }
+}
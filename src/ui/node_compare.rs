@@ -0,0 +1,205 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet},
+    rc::Rc,
+};
+
+use eframe::egui;
+use pipewire::{spa::param::ParamType, types::ObjectType};
+
+use crate::{
+    backend::{self, pods::params::flatten, Request},
+    ui::{globals_store::Global, util::uis::global_info_button, GlobalEvent, ToolPlugin},
+};
+
+const PARAM_TYPES: [(ParamType, &str); 3] = [
+    (ParamType::Props, "Props"),
+    (ParamType::Format, "Format"),
+    (ParamType::Latency, "Latency"),
+];
+
+/// Shows the params of two nodes side by side
+#[derive(Default)]
+pub struct NodeCompare {
+    nodes: BTreeMap<u32, Rc<RefCell<Global>>>,
+    selected: [Option<u32>; 2],
+    only_differences: bool,
+}
+
+impl ToolPlugin for NodeCompare {
+    fn label(&self) -> &'static str {
+        "⚖ Node Compare"
+    }
+
+    fn description(&self) -> &'static str {
+        "Compare the params of two nodes"
+    }
+
+    fn interested_in(&self, object_type: &ObjectType) -> bool {
+        *object_type == ObjectType::Node
+    }
+
+    fn global_event(&mut self, event: GlobalEvent, _: &backend::Sender) {
+        match event {
+            GlobalEvent::Added(global) => self.add_node(global),
+            GlobalEvent::Removed { id, .. } => self.remove_node(id),
+            GlobalEvent::PropertiesChanged(_) | GlobalEvent::InfoChanged(..) => {}
+        }
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        self.show(ui, sx);
+    }
+}
+
+fn flattened(
+    global: Option<&Rc<RefCell<Global>>>,
+    param_type: ParamType,
+) -> BTreeMap<String, String> {
+    let mut flattened = BTreeMap::new();
+
+    let Some(global) = global else {
+        return flattened;
+    };
+
+    // Nodes with multiple params of the same type, like Latency, get them numbered
+    for (i, param) in global.borrow().params(param_type).enumerate() {
+        for (k, v) in flatten(param) {
+            let k = if i == 0 { k } else { format!("[{i}].{k}") };
+            flattened.insert(k, v);
+        }
+    }
+
+    flattened
+}
+
+fn label(node: &Global) -> String {
+    format!("{} {}", node.id(), node.name().map_or("", String::as_str))
+}
+
+impl NodeCompare {
+    fn add_node(&mut self, global: &Rc<RefCell<Global>>) {
+        let id = global.borrow().id();
+        self.nodes.insert(id, Rc::clone(global));
+    }
+
+    fn remove_node(&mut self, id: u32) {
+        self.nodes.remove(&id);
+        for selected in &mut self.selected {
+            if *selected == Some(id) {
+                *selected = None;
+            }
+        }
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        ui.columns(2, |ui| {
+            for (i, ui) in ui.iter_mut().enumerate() {
+                let selected = &mut self.selected[i];
+
+                ui.horizontal(|ui| {
+                    let text = selected.and_then(|id| self.nodes.get(&id)).map_or_else(
+                        || String::from("Select a node"),
+                        |node| label(&node.borrow()),
+                    );
+                    egui::ComboBox::from_id_source(("node_compare", i))
+                        .selected_text(text)
+                        .width(ui.available_width() - 30.)
+                        .show_ui(ui, |ui| {
+                            for (id, node) in &self.nodes {
+                                ui.selectable_value(selected, Some(*id), label(&node.borrow()));
+                            }
+                        });
+
+                    global_info_button(ui, selected.and_then(|id| self.nodes.get(&id)), sx);
+                });
+            }
+        });
+
+        let nodes = self.selected.map(|id| id.and_then(|id| self.nodes.get(&id)));
+
+        ui.horizontal(|ui| {
+            ui.add_enabled_ui(nodes.iter().any(Option::is_some), |ui| {
+                if ui
+                    .button("Refresh params")
+                    .on_hover_text("Enumerate the params of the selected nodes")
+                    .clicked()
+                {
+                    for node in nodes.iter().flatten() {
+                        let id = node.borrow().id();
+                        for (param_type, _) in PARAM_TYPES {
+                            node.borrow_mut().clear_params(param_type);
+                            sx.send(Request::EnumParams(id, param_type)).ok();
+                        }
+                    }
+                }
+            });
+
+            ui.checkbox(&mut self.only_differences, "Only show differences");
+        });
+
+        ui.separator();
+
+        for (param_type, name) in PARAM_TYPES {
+            let [left, right] = nodes.map(|node| flattened(node, param_type));
+
+            let keys = left.keys().chain(right.keys()).collect::<BTreeSet<_>>();
+            let differences = keys
+                .iter()
+                .filter(|k| left.get(**k) != right.get(**k))
+                .count();
+
+            egui::CollapsingHeader::new(format!("{name} ({differences} differences)"))
+                .id_source(name)
+                .default_open(true)
+                .show(ui, |ui| {
+                    if keys.is_empty() {
+                        ui.label("No params. Refresh to enumerate them.");
+                        return;
+                    }
+
+                    egui::Grid::new(name)
+                        .num_columns(3)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for key in keys {
+                                let (l, r) = (left.get(key), right.get(key));
+                                let differs = l != r;
+
+                                if self.only_differences && !differs {
+                                    continue;
+                                }
+
+                                let color = if differs {
+                                    ui.visuals().warn_fg_color
+                                } else {
+                                    ui.visuals().text_color()
+                                };
+
+                                ui.label(key);
+                                for value in [l, r] {
+                                    ui.colored_label(color, value.map_or("-", String::as_str));
+                                }
+                                ui.end_row();
+                            }
+                        });
+                });
+        }
+    }
+}
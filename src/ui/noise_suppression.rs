@@ -0,0 +1,395 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
+
+use eframe::egui;
+use pipewire::types::ObjectType;
+
+use crate::{
+    backend::{self, Request},
+    ui::{
+        globals_store::Global,
+        util::{config, uis::global_info_button},
+        GlobalEvent, ToolPlugin,
+    },
+};
+
+const NODE_NAME_PREFIX: &str = "coppwr_nc.";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Method {
+    RnNoise,
+    EchoCancel,
+}
+
+impl Method {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::RnNoise => "RNNoise (filter-chain)",
+            Self::EchoCancel => "WebRTC (echo-cancel)",
+        }
+    }
+
+    const fn description(self) -> &'static str {
+        match self {
+            Self::RnNoise => {
+                "Uses the RNNoise LADSPA plugin through the filter-chain module. \
+                Requires the noise-suppression-for-voice plugin to be installed."
+            }
+            Self::EchoCancel => {
+                "Uses the noise suppression of the WebRTC echo canceller. \
+                Works with any PipeWire build that includes the echo-cancel module."
+            }
+        }
+    }
+
+    const fn module_name(self) -> &'static str {
+        match self {
+            Self::RnNoise => "libpipewire-module-filter-chain",
+            Self::EchoCancel => "libpipewire-module-echo-cancel",
+        }
+    }
+}
+
+/// A noise suppressed source created by this tool
+struct Instance {
+    method: Method,
+    description: String,
+    source_node_name: String,
+    args: String,
+}
+
+impl Instance {
+    fn load_request(&self) -> Request {
+        Request::LoadModule {
+            module_dir: None,
+            name: self.method.module_name().to_owned(),
+            args: Some(self.args.clone()),
+            props: None,
+        }
+    }
+}
+
+/// Sets up noise suppressed virtual sources for microphones
+pub struct NoiseSuppression {
+    // Audio sources that can be used as inputs, including the ones created by this tool
+    sources: BTreeMap<u32, Rc<RefCell<Global>>>,
+
+    method: Method,
+    microphone: Option<String>,
+    description: String,
+    vad_threshold: f32,
+
+    instances: Vec<Instance>,
+}
+
+impl Default for NoiseSuppression {
+    fn default() -> Self {
+        Self {
+            sources: BTreeMap::new(),
+
+            method: Method::RnNoise,
+            microphone: None,
+            description: String::from("Noise Suppressed Microphone"),
+            vad_threshold: 50.,
+
+            instances: Vec::new(),
+        }
+    }
+}
+
+impl ToolPlugin for NoiseSuppression {
+    fn label(&self) -> &'static str {
+        "🔇 Noise Suppression"
+    }
+
+    fn description(&self) -> &'static str {
+        "Create noise suppressed microphone sources"
+    }
+
+    fn interested_in(&self, object_type: &ObjectType) -> bool {
+        *object_type == ObjectType::Node
+    }
+
+    fn global_event(&mut self, event: GlobalEvent, _: &backend::Sender) {
+        match event {
+            GlobalEvent::Added(global) => self.add_node(global),
+            GlobalEvent::Removed { id, .. } => self.remove_node(id),
+            GlobalEvent::PropertiesChanged(_) | GlobalEvent::InfoChanged(..) => {}
+        }
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        self.show(ui, sx);
+    }
+}
+
+impl NoiseSuppression {
+    fn add_node(&mut self, global: &Rc<RefCell<Global>>) {
+        let (id, is_source) = {
+            let global = global.borrow();
+            (
+                global.id(),
+                global
+                    .props()
+                    .get("media.class")
+                    .is_some_and(|class| class == "Audio/Source"),
+            )
+        };
+
+        if is_source {
+            self.sources.insert(id, Rc::clone(global));
+        }
+    }
+
+    fn remove_node(&mut self, id: u32) {
+        self.sources.remove(&id);
+    }
+
+    fn module_args(&self, microphone: &str, source_node_name: &str) -> String {
+        let description = config::quote(&self.description);
+        let target = config::quote(microphone);
+        let source_node_name = config::quote(source_node_name);
+
+        match self.method {
+            Method::RnNoise => format!(
+                "{{\n    \
+                node.description = {description}\n    \
+                media.name = {description}\n    \
+                filter.graph = {{\n        \
+                nodes = [\n            \
+                {{ type = ladspa name = rnnoise plugin = librnnoise_ladspa label = noise_suppressor_mono\n                \
+                control = {{ \"VAD Threshold (%)\" = {:.1} \"VAD Grace Period (ms)\" = 200 \"Retroactive VAD Grace (ms)\" = 0 }} }}\n        \
+                ]\n    \
+                }}\n    \
+                capture.props = {{\n        \
+                node.name = \"capture.{NODE_NAME_PREFIX}{slug}\"\n        \
+                node.passive = true\n        \
+                audio.rate = 48000\n        \
+                target.object = {target}\n    \
+                }}\n    \
+                playback.props = {{\n        \
+                node.name = {source_node_name}\n        \
+                media.class = Audio/Source\n        \
+                audio.rate = 48000\n    \
+                }}\n\
+                }}",
+                self.vad_threshold,
+                slug = slug(&self.description),
+            ),
+            Method::EchoCancel => format!(
+                "{{\n    \
+                library.name = aec/libspa-aec-webrtc\n    \
+                aec.args = {{ webrtc.noise_suppression = true webrtc.echo_cancellation = false }}\n    \
+                capture.props = {{\n        \
+                node.name = \"capture.{NODE_NAME_PREFIX}{slug}\"\n        \
+                node.passive = true\n        \
+                target.object = {target}\n    \
+                }}\n    \
+                source.props = {{\n        \
+                node.name = {source_node_name}\n        \
+                node.description = {description}\n    \
+                }}\n    \
+                sink.props = {{\n        \
+                node.name = \"sink.{NODE_NAME_PREFIX}{slug}\"\n        \
+                node.passive = true\n    \
+                }}\n\
+                }}",
+                slug = slug(&self.description),
+            ),
+        }
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        ui.heading("New noise suppressed source");
+
+        ui.horizontal(|ui| {
+            ui.label("Method");
+            for method in [Method::RnNoise, Method::EchoCancel] {
+                ui.radio_value(&mut self.method, method, method.as_str())
+                    .on_hover_text(method.description());
+            }
+        });
+
+        let microphone = self
+            .microphone
+            .as_deref()
+            .and_then(|name| source_by_node_name(&self.sources, name));
+
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_label("Microphone")
+                .selected_text(
+                    microphone
+                        .and_then(|g| g.borrow().name().cloned())
+                        .or_else(|| self.microphone.clone())
+                        .unwrap_or_else(|| String::from("Select a microphone")),
+                )
+                .show_ui(ui, |ui| {
+                    for global in self.sources.values() {
+                        let global = global.borrow();
+                        let Some(node_name) = global.props().get("node.name") else {
+                            continue;
+                        };
+
+                        // Don't offer sources that are already noise suppressed
+                        if node_name.starts_with(NODE_NAME_PREFIX) {
+                            continue;
+                        }
+
+                        ui.selectable_value(
+                            &mut self.microphone,
+                            Some(node_name.clone()),
+                            global.name().unwrap_or(node_name),
+                        );
+                    }
+                });
+
+            if let Some(global) = microphone {
+                global_info_button(ui, Some(global), sx);
+            }
+        });
+
+        ui.add(
+            egui::TextEdit::singleline(&mut self.description)
+                .hint_text("Name of the new source")
+                .desired_width(f32::INFINITY),
+        );
+
+        if self.method == Method::RnNoise {
+            ui.add(
+                egui::Slider::new(&mut self.vad_threshold, 0f32..=99f32)
+                    .suffix("%")
+                    .text("Voice activity threshold"),
+            )
+            .on_hover_text("Audio that is less likely to be voice than this will be muted");
+        }
+
+        let source_node_name = format!("{NODE_NAME_PREFIX}{}", slug(&self.description));
+        let name_taken = self
+            .instances
+            .iter()
+            .any(|i| i.source_node_name == source_node_name);
+
+        ui.add_enabled_ui(
+            self.microphone.is_some() && !self.description.is_empty() && !name_taken,
+            |ui| {
+                if ui
+                    .button("Create")
+                    .on_disabled_hover_text(
+                        "Select a microphone and give the source a unique name first",
+                    )
+                    .clicked()
+                {
+                    if let Some(microphone) = self.microphone.as_deref() {
+                        let instance = Instance {
+                            method: self.method,
+                            description: self.description.clone(),
+                            args: self.module_args(microphone, &source_node_name),
+                            source_node_name,
+                        };
+
+                        sx.send(instance.load_request()).ok();
+
+                        self.instances.push(instance);
+                    }
+                }
+            },
+        );
+
+        ui.separator();
+
+        ui.heading("Created sources");
+
+        if self.instances.is_empty() {
+            ui.label("No sources have been created");
+            return;
+        }
+
+        ui.label("Sources are available for as long as coppwr stays connected");
+
+        let sources = &self.sources;
+        self.instances.retain(|instance| {
+            let source = source_by_node_name(sources, &instance.source_node_name);
+
+            ui.horizontal(|ui| {
+                if let Some(source) = source {
+                    global_info_button(ui, Some(source), sx);
+                }
+
+                ui.label(&instance.description)
+                    .on_hover_text(instance.method.as_str());
+
+                if source.is_none() {
+                    ui.colored_label(ui.visuals().warn_fg_color, "Not running")
+                        .on_hover_text(
+                            "The source's node could not be found. \
+                            Make sure the module's requirements are installed.",
+                        );
+                }
+
+                // The modules destroy themselves when their nodes are destroyed
+                let unload = |source: Option<&Rc<RefCell<Global>>>| {
+                    if let Some(source) = source {
+                        sx.send(Request::DestroyObject(source.borrow().id())).ok();
+                    }
+                };
+
+                if ui.small_button("Reload").clicked() {
+                    unload(source);
+                    sx.send(instance.load_request()).ok();
+                }
+
+                let keep = !ui.small_button("Unload").clicked();
+                if !keep {
+                    unload(source);
+                }
+
+                ui.collapsing("Arguments", |ui| {
+                    ui.label(egui::RichText::new(&instance.args).monospace());
+                });
+
+                keep
+            })
+            .inner
+        });
+    }
+}
+
+fn source_by_node_name<'a>(
+    sources: &'a BTreeMap<u32, Rc<RefCell<Global>>>,
+    node_name: &str,
+) -> Option<&'a Rc<RefCell<Global>>> {
+    sources.values().find(|g| {
+        g.borrow()
+            .props()
+            .get("node.name")
+            .is_some_and(|n| n == node_name)
+    })
+}
+
+/// Turns a user provided name into something usable in node names
+fn slug(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
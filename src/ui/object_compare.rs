@@ -0,0 +1,220 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet},
+    rc::Rc,
+};
+
+use eframe::egui;
+use pipewire::types::ObjectType;
+
+use crate::{
+    backend,
+    ui::{globals_store::Global, util::uis::global_info_button, GlobalEvent, ToolPlugin},
+};
+
+fn label(global: &Global) -> String {
+    format!(
+        "{} {} {}",
+        global.id(),
+        global.object_type().to_str(),
+        global.name().map_or("", String::as_str)
+    )
+}
+
+fn properties(global: Option<&Rc<RefCell<Global>>>) -> BTreeMap<String, String> {
+    global.map_or_else(BTreeMap::new, |global| global.borrow().props().clone())
+}
+
+fn info(global: Option<&Rc<RefCell<Global>>>) -> BTreeMap<String, String> {
+    global
+        .and_then(|global| {
+            global.borrow().info().map(|info| {
+                info.iter()
+                    .map(|(k, v)| ((*k).to_owned(), v.clone()))
+                    .collect()
+            })
+        })
+        .unwrap_or_default()
+}
+
+/// Shows the differences between the properties and info of two objects
+#[derive(Default)]
+pub struct ObjectCompare {
+    globals: BTreeMap<u32, Rc<RefCell<Global>>>,
+    selected: [Option<u32>; 2],
+    // Only list objects of this type
+    object_type: Option<ObjectType>,
+    only_differences: bool,
+}
+
+impl ToolPlugin for ObjectCompare {
+    fn label(&self) -> &'static str {
+        "🔍 Object Compare"
+    }
+
+    fn description(&self) -> &'static str {
+        "Compare the properties and info of two objects"
+    }
+
+    fn interested_in(&self, _object_type: &ObjectType) -> bool {
+        true
+    }
+
+    fn global_event(&mut self, event: GlobalEvent, _: &backend::Sender) {
+        match event {
+            GlobalEvent::Added(global) => {
+                let id = global.borrow().id();
+                self.globals.insert(id, Rc::clone(global));
+            }
+            GlobalEvent::Removed { id, .. } => {
+                self.globals.remove(&id);
+                for selected in &mut self.selected {
+                    if *selected == Some(id) {
+                        *selected = None;
+                    }
+                }
+            }
+            GlobalEvent::PropertiesChanged(_) | GlobalEvent::InfoChanged(..) => {}
+        }
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        self.show(ui, sx);
+    }
+}
+
+impl ObjectCompare {
+    fn show(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_label("Type")
+                .selected_text(self.object_type.as_ref().map_or("All", ObjectType::to_str))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.object_type, None, "All");
+                    let types: BTreeMap<String, ObjectType> = self
+                        .globals
+                        .values()
+                        .map(|g| {
+                            let object_type = g.borrow().object_type().clone();
+                            (object_type.to_str().to_owned(), object_type)
+                        })
+                        .collect();
+                    for (name, object_type) in types {
+                        ui.selectable_value(&mut self.object_type, Some(object_type), name);
+                    }
+                });
+
+            ui.checkbox(&mut self.only_differences, "Only show differences");
+        });
+
+        ui.columns(2, |ui| {
+            for (i, ui) in ui.iter_mut().enumerate() {
+                let selected = &mut self.selected[i];
+
+                ui.horizontal(|ui| {
+                    let text = selected.and_then(|id| self.globals.get(&id)).map_or_else(
+                        || String::from("Select an object"),
+                        |global| label(&global.borrow()),
+                    );
+                    egui::ComboBox::from_id_source(("object_compare", i))
+                        .selected_text(text)
+                        .width(ui.available_width() - 30.)
+                        .show_ui(ui, |ui| {
+                            for (id, global) in &self.globals {
+                                let global = global.borrow();
+                                if self
+                                    .object_type
+                                    .as_ref()
+                                    .is_some_and(|t| t != global.object_type())
+                                {
+                                    continue;
+                                }
+                                ui.selectable_value(selected, Some(*id), label(&global));
+                            }
+                        });
+
+                    global_info_button(ui, selected.and_then(|id| self.globals.get(&id)), sx);
+                });
+            }
+        });
+
+        let globals = self
+            .selected
+            .map(|id| id.and_then(|id| self.globals.get(&id)));
+
+        if globals.iter().all(Option::is_none) {
+            ui.label("Select two objects to compare them");
+            return;
+        }
+
+        ui.horizontal(|ui| {
+            for (label, color) in [
+                ("Only in the first", ui.visuals().error_fg_color),
+                ("Only in the second", egui::Color32::GREEN),
+                ("Different", ui.visuals().warn_fg_color),
+            ] {
+                ui.colored_label(color, label);
+            }
+        });
+
+        ui.separator();
+
+        for (name, [left, right]) in [
+            ("Properties", globals.map(properties)),
+            ("Info", globals.map(info)),
+        ] {
+            let keys = left.keys().chain(right.keys()).collect::<BTreeSet<_>>();
+            let differences = keys
+                .iter()
+                .filter(|k| left.get(**k) != right.get(**k))
+                .count();
+
+            egui::CollapsingHeader::new(format!("{name} ({differences} differences)"))
+                .id_source(name)
+                .default_open(true)
+                .show(ui, |ui| {
+                    egui::Grid::new(name)
+                        .num_columns(3)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for key in keys {
+                                let (l, r) = (left.get(key), right.get(key));
+
+                                let color = match (l, r) {
+                                    (Some(_), None) => ui.visuals().error_fg_color,
+                                    (None, Some(_)) => egui::Color32::GREEN,
+                                    (l, r) if l != r => ui.visuals().warn_fg_color,
+                                    _ => {
+                                        if self.only_differences {
+                                            continue;
+                                        }
+                                        ui.visuals().text_color()
+                                    }
+                                };
+
+                                ui.colored_label(color, key);
+                                for value in [l, r] {
+                                    ui.colored_label(color, value.map_or("-", String::as_str));
+                                }
+                                ui.end_row();
+                            }
+                        });
+                });
+        }
+    }
+}
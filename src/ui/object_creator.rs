@@ -14,7 +14,11 @@
 //
 // SPDX-License-Identifier: GPL-3.0-only
 
-use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use std::{
+    cell::RefCell,
+    collections::{BTreeSet, HashMap},
+    rc::Rc,
+};
 
 use eframe::egui;
 use pipewire::types::ObjectType;
@@ -23,8 +27,11 @@ use crate::{
     backend::{self, Request},
     ui::{
         globals_store::Global,
-        util::uis::{global_info_button, EditableKVList},
-        Tool,
+        util::{
+            persistence::PersistentView,
+            uis::{global_info_button, EditableKVList},
+        },
+        GlobalEvent, ToolPlugin,
     },
 };
 
@@ -39,16 +46,72 @@ impl Factory {
     }
 }
 
+/// A factory and properties to create an object with
+#[derive(Clone)]
+#[cfg_attr(feature = "persistence", derive(serde::Serialize, serde::Deserialize))]
+struct Preset {
+    name: String,
+    factory: String,
+    props: Vec<(String, String)>,
+    // Create the object when the factory appears
+    autostart: bool,
+}
+
 #[derive(Default)]
 pub struct ObjectCreator {
     factories: HashMap<u32, Factory>,
     selected_factory: Option<u32>,
 
     props: EditableKVList,
+
+    presets: Vec<Preset>,
+    preset_name: String,
+    // Names of the presets that have been started, so they're only started once
+    started: BTreeSet<String>,
+}
+
+#[cfg_attr(feature = "persistence", derive(serde::Serialize, serde::Deserialize))]
+pub struct PersistentData {
+    presets: Vec<Preset>,
+}
+
+impl PersistentView for ObjectCreator {
+    type Data = PersistentData;
+
+    fn with_data(data: &Self::Data) -> Self {
+        Self {
+            presets: data.presets.clone(),
+            ..Self::default()
+        }
+    }
+
+    fn save_data(&self) -> Option<Self::Data> {
+        Some(PersistentData {
+            presets: self.presets.clone(),
+        })
+    }
 }
 
-impl Tool for ObjectCreator {
-    const NAME: &'static str = "Object Creator";
+impl ToolPlugin for ObjectCreator {
+    fn label(&self) -> &'static str {
+        "⛭ Object Creator"
+    }
+
+    fn description(&self) -> &'static str {
+        "Create an object on the remote"
+    }
+
+    fn interested_in(&self, object_type: &ObjectType) -> bool {
+        *object_type == ObjectType::Factory
+    }
+
+    fn global_event(&mut self, event: GlobalEvent, sx: &backend::Sender) {
+        match event {
+            GlobalEvent::Added(global) => self.add_factory(global, sx),
+            GlobalEvent::Removed { id, .. } => self.remove_factory(id),
+            GlobalEvent::PropertiesChanged(_) | GlobalEvent::InfoChanged(..) => {}
+        }
+    }
 
     fn show(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
         self.show(ui, sx);
@@ -56,48 +119,49 @@ impl Tool for ObjectCreator {
 }
 
 impl ObjectCreator {
-    pub fn add_factory(&mut self, global: &Rc<RefCell<Global>>) {
+    /// Adds a factory and creates the objects of the presets that are
+    /// started automatically with it
+    fn add_factory(&mut self, global: &Rc<RefCell<Global>>, sx: &backend::Sender) {
         let (id, object_type) = {
             let global = global.borrow();
 
-            let object_type = global.props().get("factory.type.name").map(|object_type| {
-                match object_type.as_str() {
-                    "PipeWire:Interface:Link" => ObjectType::Link,
-                    "PipeWire:Interface:Port" => ObjectType::Port,
-                    "PipeWire:Interface:Node" => ObjectType::Node,
-                    "PipeWire:Interface:Client" => ObjectType::Client,
-                    "PipeWire:Interface:Device" => ObjectType::Device,
-                    "PipeWire:Interface:Registry" => ObjectType::Registry,
-                    "PipeWire:Interface:Profiler" => ObjectType::Profiler,
-                    "PipeWire:Interface:Metadata" => ObjectType::Metadata,
-                    "PipeWire:Interface:Factory" => ObjectType::Factory,
-                    "PipeWire:Interface:Module" => ObjectType::Module,
-                    "PipeWire:Interface:Core" => ObjectType::Core,
-                    "PipeWire:Interface:Endpoint" => ObjectType::Endpoint,
-                    "PipeWire:Interface:EndpointLink" => ObjectType::EndpointLink,
-                    "PipeWire:Interface:EndpointStream" => ObjectType::EndpointStream,
-                    "PipeWire:Interface:ClientSession" => ObjectType::ClientSession,
-                    "PipeWire:Interface:ClientEndpoint" => ObjectType::ClientEndpoint,
-                    "PipeWire:Interface:ClientNode" => ObjectType::ClientNode,
-                    _ => ObjectType::Other(object_type.clone()),
-                }
-            });
+            let object_type = global
+                .props()
+                .get("factory.type.name")
+                .map(|name| backend::interface_object_type(name));
 
             (global.id(), object_type)
         };
 
-        if let Some(object_type) = object_type {
-            self.factories.insert(
-                id,
-                Factory {
-                    object_type,
-                    global: Rc::clone(global),
-                },
-            );
+        let Some(object_type) = object_type else {
+            return;
+        };
+
+        let factory = Factory {
+            object_type,
+            global: Rc::clone(global),
+        };
+        let name = factory.name();
+
+        for preset in self
+            .presets
+            .iter()
+            .filter(|p| p.autostart && p.factory == name)
+        {
+            if self.started.insert(preset.name.clone()) {
+                sx.send(Request::CreateObject(
+                    factory.object_type.clone(),
+                    name.clone(),
+                    preset.props.clone(),
+                ))
+                .ok();
+            }
         }
+
+        self.factories.insert(id, factory);
     }
 
-    pub fn remove_factory(&mut self, id: u32) {
+    fn remove_factory(&mut self, id: u32) {
         self.factories.remove(&id);
     }
 
@@ -156,7 +220,7 @@ impl ObjectCreator {
                     let factory = factory.unwrap();
                     sx.send(Request::CreateObject(
                         factory.object_type.clone(),
-                        factory_name,
+                        factory_name.clone(),
                         self.props.list().clone(),
                     ))
                     .ok();
@@ -167,5 +231,100 @@ impl ObjectCreator {
                 self.props.clear();
             }
         });
+
+        ui.separator();
+
+        ui.collapsing("Presets", |ui| {
+            ui.horizontal(|ui| {
+                egui::TextEdit::singleline(&mut self.preset_name)
+                    .hint_text("Preset name, e.g. null-sink stereo")
+                    .show(ui);
+
+                let exists = self.presets.iter().any(|p| p.name == self.preset_name);
+                if ui
+                    .add_enabled(
+                        factory.is_some() && !self.preset_name.is_empty(),
+                        egui::Button::new(if exists { "Overwrite" } else { "Save" }),
+                    )
+                    .on_disabled_hover_text("Select a factory and enter a name first")
+                    .clicked()
+                {
+                    let preset = Preset {
+                        name: std::mem::take(&mut self.preset_name),
+                        factory: factory_name.clone(),
+                        props: self.props.list().clone(),
+                        autostart: false,
+                    };
+
+                    match self.presets.iter_mut().find(|p| p.name == preset.name) {
+                        Some(existing) => {
+                            *existing = Preset {
+                                autostart: existing.autostart,
+                                ..preset
+                            };
+                        }
+                        None => self.presets.push(preset),
+                    }
+                }
+            });
+
+            if self.presets.is_empty() {
+                ui.label("No presets saved");
+                return;
+            }
+
+            egui::Grid::new("presets")
+                .num_columns(5)
+                .striped(true)
+                .show(ui, |ui| {
+                    self.presets.retain_mut(|preset| {
+                        ui.label(&preset.name).on_hover_text(
+                            preset
+                                .props
+                                .iter()
+                                .map(|(k, v)| format!("{k} = {v}"))
+                                .collect::<Vec<_>>()
+                                .join("\n"),
+                        );
+                        ui.label(&preset.factory);
+                        ui.checkbox(&mut preset.autostart, "Autostart")
+                            .on_hover_text("Create the object when coppwr connects to a remote");
+
+                        let preset_factory =
+                            self.factories.values().find(|f| f.name() == preset.factory);
+
+                        ui.horizontal(|ui| {
+                            if ui
+                                .add_enabled(preset_factory.is_some(), egui::Button::new("Create"))
+                                .on_disabled_hover_text("The factory of the preset isn't available")
+                                .clicked()
+                            {
+                                if let Some(factory) = preset_factory {
+                                    sx.send(Request::CreateObject(
+                                        factory.object_type.clone(),
+                                        preset.factory.clone(),
+                                        preset.props.clone(),
+                                    ))
+                                    .ok();
+                                }
+                            }
+
+                            if ui.button("Edit").clicked() {
+                                self.selected_factory =
+                                    self.factories.iter().find_map(|(id, f)| {
+                                        (f.name() == preset.factory).then_some(*id)
+                                    });
+                                *self.props.list_mut() = preset.props.clone();
+                                self.preset_name = preset.name.clone();
+                            }
+                        });
+
+                        let keep = !ui.button("Delete").clicked();
+                        ui.end_row();
+
+                        keep
+                    });
+                });
+        });
     }
 }
@@ -0,0 +1,297 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+    rc::Rc,
+    time::Instant,
+};
+
+use eframe::egui;
+use egui_plot::{Legend, Line, Plot, PlotPoints};
+use pipewire::types::ObjectType;
+
+use crate::{
+    backend,
+    ui::{globals_store::Global, util::uis::global_info_button, GlobalEvent, ToolPlugin},
+};
+
+const MAX_SAMPLES: usize = 4096;
+
+#[derive(Clone, Copy)]
+enum Kind {
+    Node,
+    Port,
+    Link,
+}
+
+impl Kind {
+    const ALL: [Self; 3] = [Self::Node, Self::Port, Self::Link];
+
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Node => "Nodes",
+            Self::Port => "Ports",
+            Self::Link => "Links",
+        }
+    }
+}
+
+#[derive(Default)]
+struct Series {
+    count: usize,
+    // Creations since the last removal
+    growth_streak: usize,
+    samples: Vec<[f64; 2]>,
+}
+
+impl Series {
+    fn record(&mut self, t: f64, created: bool) {
+        if created {
+            self.count += 1;
+            self.growth_streak += 1;
+        } else {
+            self.count = self.count.saturating_sub(1);
+            self.growth_streak = 0;
+        }
+
+        if self.samples.len() == MAX_SAMPLES {
+            self.samples.remove(0);
+        }
+        self.samples.push([t, self.count as f64]);
+    }
+}
+
+struct ClientStats {
+    global: Option<Rc<RefCell<Global>>>,
+    series: [Series; 3],
+}
+
+impl ClientStats {
+    fn new(global: Option<Rc<RefCell<Global>>>) -> Self {
+        Self {
+            global,
+            series: Default::default(),
+        }
+    }
+
+    fn leaking(&self, threshold: usize) -> impl Iterator<Item = (Kind, &Series)> {
+        Kind::ALL
+            .into_iter()
+            .zip(self.series.iter())
+            .filter(move |(_, s)| s.growth_streak >= threshold)
+    }
+}
+
+/// Tracks the number of nodes, ports and links owned by each client
+/// and flags the ones that only keep growing
+pub struct ObjectWatchdog {
+    start: Instant,
+    threshold: usize,
+
+    clients: BTreeMap<u32, ClientStats>,
+    // Object ID -> owning client ID and object kind
+    owners: HashMap<u32, (u32, Kind)>,
+}
+
+impl Default for ObjectWatchdog {
+    fn default() -> Self {
+        Self {
+            start: Instant::now(),
+            threshold: 50,
+
+            clients: BTreeMap::new(),
+            owners: HashMap::new(),
+        }
+    }
+}
+
+impl ToolPlugin for ObjectWatchdog {
+    fn label(&self) -> &'static str {
+        "🔍 Object Watchdog"
+    }
+
+    fn description(&self) -> &'static str {
+        "Track object counts of clients and detect leaks"
+    }
+
+    fn interested_in(&self, object_type: &ObjectType) -> bool {
+        matches!(
+            object_type,
+            ObjectType::Client | ObjectType::Node | ObjectType::Port | ObjectType::Link
+        )
+    }
+
+    fn global_event(&mut self, event: GlobalEvent, _: &backend::Sender) {
+        match event {
+            GlobalEvent::Added(global) => {
+                if *global.borrow().object_type() == ObjectType::Client {
+                    self.add_client(global);
+                } else {
+                    self.add_object(global);
+                }
+            }
+            GlobalEvent::Removed { id, .. } => self.remove_global(id),
+            GlobalEvent::PropertiesChanged(_) | GlobalEvent::InfoChanged(..) => {}
+        }
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        self.show(ui, sx);
+    }
+}
+
+impl ObjectWatchdog {
+    fn add_client(&mut self, global: &Rc<RefCell<Global>>) {
+        let id = global.borrow().id();
+        self.clients
+            .entry(id)
+            .or_insert_with(|| ClientStats::new(None))
+            .global = Some(Rc::clone(global));
+    }
+
+    fn add_object(&mut self, global: &Rc<RefCell<Global>>) {
+        let global = global.borrow();
+
+        let (kind, client) = match global.object_type() {
+            ObjectType::Node => (Kind::Node, global.props().get("client.id")),
+            ObjectType::Link => (Kind::Link, global.props().get("client.id")),
+            ObjectType::Port => {
+                // Ports belong to the client of their node
+                let client = global
+                    .parent_id()
+                    .and_then(|node| self.owners.get(&node))
+                    .map(|(client, _)| *client);
+                if let Some(client) = client {
+                    self.record(global.id(), client, Kind::Port);
+                }
+                return;
+            }
+            _ => return,
+        };
+
+        if let Some(client) = client.and_then(|c| c.parse().ok()) {
+            self.record(global.id(), client, kind);
+        }
+    }
+
+    fn record(&mut self, id: u32, client: u32, kind: Kind) {
+        // The object may have been re-added with updated properties
+        if self.owners.insert(id, (client, kind)).is_some() {
+            return;
+        }
+
+        let t = self.start.elapsed().as_secs_f64();
+        self.clients
+            .entry(client)
+            .or_insert_with(|| ClientStats::new(None))
+            .series[kind as usize]
+            .record(t, true);
+    }
+
+    fn remove_global(&mut self, id: u32) {
+        if self.clients.remove(&id).is_some() {
+            return;
+        }
+
+        let Some((client, kind)) = self.owners.remove(&id) else {
+            return;
+        };
+
+        let t = self.start.elapsed().as_secs_f64();
+        if let Some(stats) = self.clients.get_mut(&client) {
+            stats.series[kind as usize].record(t, false);
+        }
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        ui.horizontal(|ui| {
+            ui.label("Flag after");
+            ui.add(egui::DragValue::new(&mut self.threshold).clamp_range(2..=10000));
+            ui.label("creations without a removal");
+
+            if ui.small_button("Reset").clicked() {
+                self.start = Instant::now();
+                for stats in self.clients.values_mut() {
+                    for series in &mut stats.series {
+                        series.growth_streak = 0;
+                        series.samples.clear();
+                    }
+                }
+            }
+        });
+
+        ui.separator();
+
+        let threshold = self.threshold;
+
+        // Show the suspicious clients first
+        let mut clients = self.clients.iter().collect::<Vec<_>>();
+        clients.sort_by_key(|(_, stats)| stats.leaking(threshold).next().is_none());
+
+        for (id, stats) in clients {
+            if stats.series.iter().all(|s| s.count == 0 && s.samples.is_empty()) {
+                continue;
+            }
+
+            ui.horizontal(|ui| {
+                global_info_button(ui, stats.global.as_ref(), sx);
+
+                let name = stats
+                    .global
+                    .as_ref()
+                    .and_then(|g| g.borrow().name().cloned())
+                    .unwrap_or_default();
+                ui.label(format!("{id} {name}"));
+
+                for (kind, series) in Kind::ALL.into_iter().zip(stats.series.iter()) {
+                    ui.label(format!("{}: {}", kind.as_str(), series.count));
+                }
+            });
+
+            for (kind, series) in stats.leaking(threshold) {
+                ui.colored_label(
+                    ui.visuals().warn_fg_color,
+                    format!(
+                        "Possible leak: {} {} created without any being removed",
+                        series.growth_streak,
+                        kind.as_str().to_lowercase()
+                    ),
+                );
+            }
+
+            egui::CollapsingHeader::new("Plot")
+                .id_source(("watchdog_plot", id))
+                .show(ui, |ui| {
+                    Plot::new(("watchdog", id))
+                        .height(150.)
+                        .legend(Legend::default())
+                        .x_axis_formatter(|x, _, _| format!("{:.0}s", x.value))
+                        .show(ui, |ui| {
+                            for (kind, series) in Kind::ALL.into_iter().zip(stats.series.iter()) {
+                                ui.line(
+                                    Line::new(PlotPoints::from(series.samples.clone()))
+                                        .name(kind.as_str()),
+                                );
+                            }
+                        });
+                });
+
+            ui.separator();
+        }
+    }
+}
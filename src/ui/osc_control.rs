@@ -0,0 +1,454 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, VecDeque},
+    rc::Rc,
+};
+
+use eframe::egui;
+use pipewire::{spa::param::ParamType, types::ObjectType};
+
+use crate::{
+    backend::{
+        self,
+        pods::{self, params::Volume},
+        ObjectMethod, Request,
+    },
+    ui::{
+        globals_store::{Global, GlobalsStore},
+        util::{
+            config::quote,
+            osc::{Argument, Message, Server},
+        },
+        GlobalEvent, ToolPlugin,
+    },
+};
+
+const MAX_LOG: usize = 20;
+
+// Keys of the default metadata that can be set, without the default.configured. prefix
+const DEFAULTS: [&str; 3] = ["audio.sink", "audio.source", "video.source"];
+
+const ADDRESSES: [(&str, &str); 3] = [
+    (
+        "/coppwr/strip/<n>/volume",
+        "Sets the volume of the nth strip, from 0 to 1 on a cubic scale like volume mixers",
+    ),
+    (
+        "/coppwr/strip/<n>/mute",
+        "Mutes or unmutes the nth strip, or toggles it without an argument",
+    ),
+    (
+        "/coppwr/default/<audio.sink|audio.source|video.source>",
+        "Sets the configured default device to the node with the name or ID",
+    ),
+];
+
+fn node_label(node: &Global) -> String {
+    let props = node.props();
+    props
+        .get("node.description")
+        .or_else(|| props.get("node.nick"))
+        .or_else(|| node.name())
+        .cloned()
+        .unwrap_or_else(|| node.id().to_string())
+}
+
+/// Lets control surfaces change the volumes of chosen nodes and the default devices over OSC
+pub struct OscControl {
+    nodes: BTreeMap<u32, Rc<RefCell<Global>>>,
+    default_metadata: Option<Rc<RefCell<Global>>>,
+    // IDs of the nodes that can be controlled, in the order of the strips
+    strips: Vec<u32>,
+
+    port: u16,
+    all_interfaces: bool,
+    server: Option<Server>,
+    error: Option<String>,
+
+    log: VecDeque<(String, Result<String, String>)>,
+    // Addresses of mute toggles and the nodes whose fresh Props they're waiting for
+    pending_toggles: Vec<(String, u32)>,
+}
+
+impl Default for OscControl {
+    fn default() -> Self {
+        Self {
+            nodes: BTreeMap::new(),
+            default_metadata: None,
+            strips: Vec::new(),
+
+            port: 9000,
+            all_interfaces: false,
+            server: None,
+            error: None,
+
+            log: VecDeque::with_capacity(MAX_LOG),
+            pending_toggles: Vec::new(),
+        }
+    }
+}
+
+impl ToolPlugin for OscControl {
+    fn label(&self) -> &'static str {
+        "🎛 OSC Control"
+    }
+
+    fn description(&self) -> &'static str {
+        "Control the volume of nodes and the default devices from control surfaces over OSC"
+    }
+
+    fn interested_in(&self, object_type: &ObjectType) -> bool {
+        matches!(object_type, ObjectType::Node | ObjectType::Metadata)
+    }
+
+    fn global_event(&mut self, event: GlobalEvent, _: &backend::Sender) {
+        match event {
+            GlobalEvent::Added(global) | GlobalEvent::PropertiesChanged(global) => {
+                let g = global.borrow();
+                match *g.object_type() {
+                    ObjectType::Node => {
+                        self.nodes.insert(g.id(), Rc::clone(global));
+                    }
+                    ObjectType::Metadata
+                        if g.props().get("metadata.name").map(String::as_str)
+                            == Some("default") =>
+                    {
+                        self.default_metadata = Some(Rc::clone(global));
+                    }
+                    _ => {}
+                }
+            }
+            GlobalEvent::Removed { id, .. } => {
+                self.nodes.remove(&id);
+                self.strips.retain(|strip| *strip != id);
+                self.pending_toggles.retain(|(_, node)| *node != id);
+                if self
+                    .default_metadata
+                    .as_ref()
+                    .is_some_and(|m| m.borrow().id() == id)
+                {
+                    self.default_metadata = None;
+                }
+            }
+            GlobalEvent::InfoChanged(..) => {}
+        }
+    }
+
+    fn update(&mut self, _: &GlobalsStore, sx: &backend::Sender) {
+        self.toggle_mutes(sx);
+
+        let Some(server) = &self.server else {
+            return;
+        };
+
+        let messages: Vec<Message> = server.messages().collect();
+        for message in messages {
+            let result = self.handle(&message, sx);
+            self.log(message.address, result);
+        }
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        self.show(ui, sx);
+    }
+}
+
+impl OscControl {
+    fn log(&mut self, address: String, result: Result<String, String>) {
+        if self.log.len() >= MAX_LOG {
+            self.log.pop_front();
+        }
+        self.log.push_back((address, result));
+    }
+
+    fn handle(&mut self, message: &Message, sx: &backend::Sender) -> Result<String, String> {
+        let parts: Vec<&str> = message.address.trim_start_matches('/').split('/').collect();
+        let argument = message.arguments.first();
+
+        match parts.as_slice() {
+            ["coppwr", "strip", n, control] => {
+                let id = n
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|n| self.strips.get(n.checked_sub(1)?))
+                    .copied()
+                    .ok_or_else(|| format!("There is no strip {n}"))?;
+
+                match *control {
+                    "volume" => {
+                        let level = argument
+                            .and_then(Argument::as_f32)
+                            .ok_or("The volume must be a number")?
+                            .clamp(0., 1.);
+                        let linear = level.powi(3);
+                        self.set_volume(id, sx, |volume| {
+                            // Keeps the balance, with the loudest channel at the level
+                            let max = volume.channel_volumes.iter().copied().fold(0f32, f32::max);
+                            if volume.channel_volumes.is_empty() {
+                                volume.volume = Some(linear);
+                            } else if max > 0. {
+                                for channel in &mut volume.channel_volumes {
+                                    *channel *= linear / max;
+                                }
+                            } else {
+                                volume.channel_volumes.fill(linear);
+                            }
+                        })?;
+                        Ok(format!("Set the volume of {id} to {:.0}%", level * 100.))
+                    }
+                    "mute" => {
+                        let Some(muted) = argument.and_then(Argument::as_bool) else {
+                            // The cached Props may be stale, so toggle based on fresh ones
+                            let node = self
+                                .nodes
+                                .get(&id)
+                                .ok_or_else(|| format!("There is no node {id}"))?;
+                            node.borrow_mut().clear_params(ParamType::Props);
+                            sx.send(Request::EnumParams(id, ParamType::Props)).ok();
+                            self.pending_toggles.push((message.address.clone(), id));
+
+                            return Ok(format!(
+                                "Toggling the mute of {id} once its volume is updated"
+                            ));
+                        };
+
+                        self.set_volume(id, sx, |volume| volume.mute = Some(muted))?;
+                        Ok(format!("{} {id}", if muted { "Muted" } else { "Unmuted" }))
+                    }
+                    _ => Err(format!("Unknown control {control}")),
+                }
+            }
+            ["coppwr", "default", key] if DEFAULTS.contains(key) => {
+                let node = match argument {
+                    Some(Argument::String(name)) => self
+                        .nodes
+                        .values()
+                        .find(|node| node.borrow().props().get("node.name") == Some(name)),
+                    Some(Argument::Int(id)) => {
+                        u32::try_from(*id).ok().and_then(|id| self.nodes.get(&id))
+                    }
+                    _ => return Err(String::from("The device must be a node name or ID")),
+                }
+                .ok_or("There is no such node")?;
+                let name = node
+                    .borrow()
+                    .props()
+                    .get("node.name")
+                    .cloned()
+                    .ok_or("The node has no name")?;
+
+                let metadata = self
+                    .default_metadata
+                    .as_ref()
+                    .ok_or("The default metadata object could not be found")?
+                    .borrow()
+                    .id();
+                sx.send(Request::CallObjectMethod(
+                    metadata,
+                    ObjectMethod::MetadataSetProperty {
+                        subject: 0,
+                        key: format!("default.configured.{key}"),
+                        type_: Some(String::from("Spa:String:JSON")),
+                        value: Some(format!("{{ \"name\": {} }}", quote(&name))),
+                    },
+                ))
+                .ok();
+
+                Ok(format!("Set the default {key} to {name}"))
+            }
+            _ => Err(String::from("Unknown address")),
+        }
+    }
+
+    /// Toggles the mutes whose nodes have reported their Props again
+    fn toggle_mutes(&mut self, sx: &backend::Sender) {
+        for (address, id) in std::mem::take(&mut self.pending_toggles) {
+            let Some(node) = self.nodes.get(&id).filter(|node| {
+                node.borrow()
+                    .params(ParamType::Props)
+                    .any(|props| Volume::from_props(props).is_some())
+            }) else {
+                self.pending_toggles.push((address, id));
+                continue;
+            };
+
+            let mut muted = false;
+            let result = self
+                .set_volume(id, sx, |volume| {
+                    muted = !volume.mute.unwrap_or(false);
+                    volume.mute = Some(muted);
+                })
+                .map(|()| format!("{} {id}", if muted { "Muted" } else { "Unmuted" }));
+            // So that the next toggle waits for the Props with this one applied
+            node.borrow_mut().clear_params(ParamType::Props);
+            self.log(address, result);
+        }
+    }
+
+    /// Changes the volume controls the node has, as last reported by it
+    fn set_volume(
+        &self,
+        id: u32,
+        sx: &backend::Sender,
+        change: impl FnOnce(&mut Volume),
+    ) -> Result<(), String> {
+        let mut volume = self
+            .nodes
+            .get(&id)
+            .and_then(|node| {
+                node.borrow()
+                    .params(ParamType::Props)
+                    .find_map(Volume::from_props)
+            })
+            .ok_or_else(|| format!("The volume controls of {id} aren't loaded"))?;
+
+        change(&mut volume);
+
+        let pod = pods::props::volume(&volume).ok_or("Failed to serialize the volume")?;
+        sx.send(Request::CallObjectMethod(
+            id,
+            ObjectMethod::SetParam {
+                id: ParamType::Props,
+                flags: 0,
+                pod,
+            },
+        ))
+        .ok();
+        // So that the next change is based on this one
+        sx.send(Request::EnumParams(id, ParamType::Props)).ok();
+
+        Ok(())
+    }
+
+    fn show_server(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            if let Some(server) = &self.server {
+                ui.label(format!("Listening on {}", server.address()));
+                if ui.button("Stop").clicked() {
+                    self.server = None;
+                }
+                return;
+            }
+
+            ui.label("Port");
+            ui.add(egui::DragValue::new(&mut self.port));
+            ui.checkbox(&mut self.all_interfaces, "All interfaces")
+                .on_hover_text(
+                    "Accept messages from other devices on the network, \
+                    like phones and tablets. Anyone on the network will be able to send them.",
+                );
+            if ui.button("Start").clicked() {
+                match Server::start(self.port, self.all_interfaces, ui.ctx().clone()) {
+                    Ok(server) => {
+                        self.server = Some(server);
+                        self.error = None;
+                    }
+                    Err(e) => self.error = Some(format!("Failed to start the server: {e}")),
+                }
+            }
+        });
+
+        if let Some(e) = &self.error {
+            ui.colored_label(ui.visuals().error_fg_color, e);
+        }
+    }
+
+    fn show_strips(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        let mut remove = None;
+        egui::Grid::new("osc_strips")
+            .num_columns(3)
+            .striped(true)
+            .show(ui, |ui| {
+                for (i, id) in self.strips.iter().enumerate() {
+                    ui.label((i + 1).to_string());
+                    ui.label(
+                        self.nodes
+                            .get(id)
+                            .map_or_else(|| id.to_string(), |node| node_label(&node.borrow())),
+                    );
+                    if ui.small_button("Remove").clicked() {
+                        remove = Some(i);
+                    }
+                    ui.end_row();
+                }
+            });
+        if let Some(i) = remove {
+            self.strips.remove(i);
+        }
+
+        egui::ComboBox::from_id_source("osc_add_strip")
+            .selected_text("Add a node")
+            .width(250.)
+            .show_ui(ui, |ui| {
+                for (id, node) in &self.nodes {
+                    let node = node.borrow();
+                    let audio = node
+                        .props()
+                        .get("media.class")
+                        .is_some_and(|class| class.contains("Audio"));
+                    if !audio || self.strips.contains(id) {
+                        continue;
+                    }
+
+                    if ui.selectable_label(false, node_label(&node)).clicked() {
+                        self.strips.push(*id);
+                        sx.send(Request::EnumParams(*id, ParamType::Props)).ok();
+                    }
+                }
+            });
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        self.show_server(ui);
+
+        ui.separator();
+
+        ui.label("Strips").on_hover_text(
+            "The nodes whose volume can be controlled, numbered from 1 in the addresses",
+        );
+        self.show_strips(ui, sx);
+
+        ui.separator();
+
+        ui.collapsing("Addresses", |ui| {
+            egui::Grid::new("osc_addresses")
+                .num_columns(2)
+                .striped(true)
+                .show(ui, |ui| {
+                    for (address, description) in ADDRESSES {
+                        ui.monospace(address);
+                        ui.label(description);
+                        ui.end_row();
+                    }
+                });
+        });
+
+        ui.collapsing("Received", |ui| {
+            for (address, result) in self.log.iter().rev() {
+                ui.horizontal(|ui| {
+                    ui.monospace(address);
+                    match result {
+                        Ok(message) => ui.label(message),
+                        Err(e) => ui.colored_label(ui.visuals().error_fg_color, e),
+                    };
+                });
+            }
+        });
+    }
+}
@@ -0,0 +1,169 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{cell::RefCell, rc::Rc};
+
+use eframe::egui;
+use pipewire::types::ObjectType;
+
+use crate::{
+    backend::{self, ObjectMethod, Request},
+    ui::{
+        globals_store::Global,
+        util::uis::{global_info_button, MapEditor},
+        GlobalEvent, ToolPlugin,
+    },
+};
+
+const COMMON_KEYS: [(&str, &str); 4] = [
+    ("application.name", "Name shown by other applications"),
+    ("application.id", "Identifier, usually the desktop file name"),
+    ("application.icon-name", "Name of the icon shown by other applications"),
+    ("media.category", "Manager gives access to all objects on sandboxed setups"),
+];
+
+/// Editor for the properties of the client object of coppwr itself
+#[derive(Default)]
+pub struct OwnClient {
+    client: Option<Rc<RefCell<Global>>>,
+    properties: MapEditor,
+    // Properties to set when the client is found, from the connection dialog
+    pending: Vec<(String, String)>,
+}
+
+impl ToolPlugin for OwnClient {
+    fn label(&self) -> &'static str {
+        "👤 Own Client"
+    }
+
+    fn description(&self) -> &'static str {
+        "Edit the properties of coppwr's client"
+    }
+
+    fn interested_in(&self, object_type: &ObjectType) -> bool {
+        *object_type == ObjectType::Client
+    }
+
+    fn global_event(&mut self, event: GlobalEvent, sx: &backend::Sender) {
+        match event {
+            // The properties of the client might not be known when it's added
+            GlobalEvent::Added(global) | GlobalEvent::PropertiesChanged(global) => {
+                self.add_client(global, sx);
+            }
+            GlobalEvent::Removed { id, .. } => self.remove_client(id),
+            GlobalEvent::InfoChanged(..) => {}
+        }
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        self.show(ui, sx);
+    }
+}
+
+/// Whether the client is one of coppwr's own connections
+pub fn is_own(global: &Global) -> bool {
+    let pid = std::process::id().to_string();
+
+    // The security PID is the one seen from the daemon, which differs inside PID namespaces
+    ["application.process.id", "pipewire.sec.pid"]
+        .into_iter()
+        .find_map(|key| global.props().get(key))
+        .is_some_and(|p| *p == pid)
+}
+
+impl OwnClient {
+    pub fn with_pending_properties(pending: Vec<(String, String)>) -> Self {
+        Self {
+            pending,
+            ..Self::default()
+        }
+    }
+
+    fn add_client(&mut self, global: &Rc<RefCell<Global>>, sx: &backend::Sender) {
+        if self.client.is_some() || !is_own(&global.borrow()) {
+            return;
+        }
+
+        let id = global.borrow().id();
+
+        if !self.pending.is_empty() {
+            let properties = std::mem::take(&mut self.pending).into_iter().collect();
+            sx.send(Request::CallObjectMethod(
+                id,
+                ObjectMethod::ClientUpdateProperties(properties),
+            ))
+            .ok();
+        }
+
+        self.properties.set_map(global.borrow().props().clone());
+        self.client = Some(Rc::clone(global));
+    }
+
+    fn remove_client(&mut self, id: u32) {
+        if self
+            .client
+            .as_ref()
+            .is_some_and(|client| client.borrow().id() == id)
+        {
+            self.client = None;
+        }
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        let Some(client) = self.client.as_ref() else {
+            ui.label("coppwr's client object could not be found");
+            return;
+        };
+
+        let id = client.borrow().id();
+
+        ui.horizontal(|ui| {
+            global_info_button(ui, Some(client), sx);
+            ui.label(format!("ID: {id}"));
+        });
+
+        ui.separator();
+
+        self.properties.show(ui, 0f32, 300f32);
+
+        ui.horizontal_wrapped(|ui| {
+            ui.label("Common properties:");
+            for (key, description) in COMMON_KEYS {
+                if ui.small_button(key).on_hover_text(description).clicked() {
+                    self.properties.add_entry(key);
+                }
+            }
+        });
+
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            if ui.small_button("Reset").clicked() {
+                self.properties.set_map(client.borrow().props().clone());
+            }
+
+            if ui.small_button("Update properties").clicked() {
+                sx.send(Request::CallObjectMethod(
+                    id,
+                    ObjectMethod::ClientUpdateProperties(self.properties.take()),
+                ))
+                .ok();
+                // The updated properties aren't known until the daemon sends them back
+                self.properties.set_map(client.borrow().props().clone());
+            }
+        });
+    }
+}
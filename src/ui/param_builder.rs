@@ -0,0 +1,572 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
+
+use eframe::egui;
+use pipewire::{
+    spa::{
+        param::ParamType,
+        pod::{Object, Property, PropertyFlags, Value, ValueArray},
+        sys,
+        utils::{Fraction, Id, Rectangle},
+    },
+    types::ObjectType,
+};
+
+use crate::{
+    backend::{
+        self,
+        pods::{params, props},
+        ObjectMethod, Request,
+    },
+    ui::{
+        globals_store::Global,
+        util::{pod_view::pod_view, uis::global_info_button},
+        GlobalEvent, ToolPlugin,
+    },
+};
+
+// The params that can be set, the types of their objects and their names
+const PARAMS: [(ParamType, u32, &str); 6] = [
+    (ParamType::Props, sys::SPA_TYPE_OBJECT_Props, "Props"),
+    (
+        ParamType::Profile,
+        sys::SPA_TYPE_OBJECT_ParamProfile,
+        "Profile",
+    ),
+    (ParamType::Route, sys::SPA_TYPE_OBJECT_ParamRoute, "Route"),
+    (
+        ParamType::PortConfig,
+        sys::SPA_TYPE_OBJECT_ParamPortConfig,
+        "PortConfig",
+    ),
+    (ParamType::Format, sys::SPA_TYPE_OBJECT_Format, "Format"),
+    (
+        ParamType::Latency,
+        sys::SPA_TYPE_OBJECT_ParamLatency,
+        "Latency",
+    ),
+];
+
+fn object_type_name(type_: u32) -> String {
+    PARAMS
+        .iter()
+        .find_map(|(_, t, name)| (*t == type_).then(|| (*name).to_owned()))
+        .unwrap_or_else(|| type_.to_string())
+}
+
+/// An editable pod
+#[derive(Clone)]
+enum Pod {
+    None,
+    Bool(bool),
+    Id(u32),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    String(String),
+    Fraction(u32, u32),
+    Rectangle(u32, u32),
+    IdArray(Vec<u32>),
+    IntArray(Vec<i32>),
+    FloatArray(Vec<f32>),
+    Struct(Vec<Pod>),
+    Object {
+        type_: u32,
+        id: u32,
+        properties: Vec<(u32, Pod)>,
+    },
+}
+
+impl Pod {
+    /// The default values of each kind
+    fn kinds() -> [Self; 15] {
+        [
+            Self::None,
+            Self::Bool(false),
+            Self::Id(0),
+            Self::Int(0),
+            Self::Long(0),
+            Self::Float(0.),
+            Self::Double(0.),
+            Self::String(String::new()),
+            Self::Fraction(0, 1),
+            Self::Rectangle(0, 0),
+            Self::IdArray(Vec::new()),
+            Self::IntArray(Vec::new()),
+            Self::FloatArray(Vec::new()),
+            Self::Struct(Vec::new()),
+            Self::Object {
+                type_: sys::SPA_TYPE_OBJECT_Props,
+                id: sys::SPA_PARAM_Props,
+                properties: Vec::new(),
+            },
+        ]
+    }
+
+    const fn kind(&self) -> &'static str {
+        match self {
+            Self::None => "None",
+            Self::Bool(_) => "Bool",
+            Self::Id(_) => "Id",
+            Self::Int(_) => "Int",
+            Self::Long(_) => "Long",
+            Self::Float(_) => "Float",
+            Self::Double(_) => "Double",
+            Self::String(_) => "String",
+            Self::Fraction(..) => "Fraction",
+            Self::Rectangle(..) => "Rectangle",
+            Self::IdArray(_) => "Array of Id",
+            Self::IntArray(_) => "Array of Int",
+            Self::FloatArray(_) => "Array of Float",
+            Self::Struct(_) => "Struct",
+            Self::Object { .. } => "Object",
+        }
+    }
+
+    fn to_value(&self) -> Value {
+        match self {
+            Self::None => Value::None,
+            Self::Bool(b) => Value::Bool(*b),
+            Self::Id(id) => Value::Id(Id(*id)),
+            Self::Int(i) => Value::Int(*i),
+            Self::Long(l) => Value::Long(*l),
+            Self::Float(f) => Value::Float(*f),
+            Self::Double(d) => Value::Double(*d),
+            Self::String(s) => Value::String(s.clone()),
+            Self::Fraction(num, denom) => Value::Fraction(Fraction {
+                num: *num,
+                denom: *denom,
+            }),
+            Self::Rectangle(width, height) => Value::Rectangle(Rectangle {
+                width: *width,
+                height: *height,
+            }),
+            Self::IdArray(ids) => {
+                Value::ValueArray(ValueArray::Id(ids.iter().map(|id| Id(*id)).collect()))
+            }
+            Self::IntArray(ints) => Value::ValueArray(ValueArray::Int(ints.clone())),
+            Self::FloatArray(floats) => Value::ValueArray(ValueArray::Float(floats.clone())),
+            Self::Struct(fields) => Value::Struct(fields.iter().map(Self::to_value).collect()),
+            Self::Object {
+                type_,
+                id,
+                properties,
+            } => Value::Object(Object {
+                type_: *type_,
+                id: *id,
+                properties: properties
+                    .iter()
+                    .map(|(key, value)| Property {
+                        key: *key,
+                        flags: PropertyFlags::empty(),
+                        value: value.to_value(),
+                    })
+                    .collect(),
+            }),
+        }
+    }
+
+    /// Converts a received value for editing. Values that can't be edited, like choices, become `None`.
+    fn from_value(value: &Value) -> Self {
+        match value {
+            Value::Bool(b) => Self::Bool(*b),
+            Value::Id(id) => Self::Id(id.0),
+            Value::Int(i) => Self::Int(*i),
+            Value::Long(l) => Self::Long(*l),
+            Value::Float(f) => Self::Float(*f),
+            Value::Double(d) => Self::Double(*d),
+            Value::String(s) => Self::String(s.clone()),
+            Value::Fraction(f) => Self::Fraction(f.num, f.denom),
+            Value::Rectangle(r) => Self::Rectangle(r.width, r.height),
+            Value::ValueArray(ValueArray::Id(ids)) => {
+                Self::IdArray(ids.iter().map(|id| id.0).collect())
+            }
+            Value::ValueArray(ValueArray::Int(ints)) => Self::IntArray(ints.clone()),
+            Value::ValueArray(ValueArray::Float(floats)) => Self::FloatArray(floats.clone()),
+            Value::Struct(fields) => Self::Struct(fields.iter().map(Self::from_value).collect()),
+            Value::Object(object) => Self::Object {
+                type_: object.type_,
+                id: object.id,
+                properties: object
+                    .properties
+                    .iter()
+                    .map(|prop| (prop.key, Self::from_value(&prop.value)))
+                    .collect(),
+            },
+            _ => Self::None,
+        }
+    }
+}
+
+fn kind_combo(ui: &mut egui::Ui, pod: &mut Pod) {
+    egui::ComboBox::from_id_source("kind")
+        .selected_text(pod.kind())
+        .width(110.)
+        .show_ui(ui, |ui| {
+            for kind in Pod::kinds() {
+                let name = kind.kind();
+                if ui.selectable_label(pod.kind() == name, name).clicked() && pod.kind() != name {
+                    *pod = kind;
+                }
+            }
+        });
+}
+
+fn array_edit<T: egui::emath::Numeric>(ui: &mut egui::Ui, values: &mut Vec<T>, speed: f64) {
+    ui.horizontal_wrapped(|ui| {
+        let mut i = 0usize;
+        values.retain_mut(|value| {
+            let keep = ui
+                .push_id(i, |ui| {
+                    ui.add(egui::DragValue::new(value).speed(speed));
+                    !ui.small_button("➖").on_hover_text("Remove").clicked()
+                })
+                .inner;
+            i += 1;
+            keep
+        });
+
+        if ui.small_button("➕").on_hover_text("Add").clicked() {
+            values.push(values.last().copied().unwrap_or(T::from_f64(0.)));
+        }
+    });
+}
+
+fn properties_edit(ui: &mut egui::Ui, type_: u32, properties: &mut Vec<(u32, Pod)>) {
+    let keys = params::keys(type_);
+
+    let mut i = 0usize;
+    properties.retain_mut(|(key, value)| {
+        let keep = ui
+            .push_id(i, |ui| {
+                let mut keep = true;
+                ui.horizontal(|ui| {
+                    keep = !ui.small_button("🗑").on_hover_text("Remove").clicked();
+
+                    egui::ComboBox::from_id_source("key")
+                        .selected_text(params::key_name(type_, *key))
+                        .show_ui(ui, |ui| {
+                            for (k, name) in keys {
+                                ui.selectable_value(key, *k, *name);
+                            }
+                        });
+                    ui.add(egui::DragValue::new(key))
+                        .on_hover_text("The raw key, for properties without a name");
+
+                    kind_combo(ui, value);
+                });
+                ui.indent("value", |ui| {
+                    pod_edit(ui, value);
+                });
+                keep
+            })
+            .inner;
+        i += 1;
+        keep
+    });
+
+    if ui.button("Add property").clicked() {
+        let key = keys
+            .iter()
+            .map(|(k, _)| *k)
+            .find(|k| !properties.iter().any(|(key, _)| key == k))
+            .unwrap_or_default();
+        properties.push((key, Pod::None));
+    }
+}
+
+fn pod_edit(ui: &mut egui::Ui, pod: &mut Pod) {
+    match pod {
+        Pod::None => {}
+        Pod::Bool(b) => {
+            ui.checkbox(b, "");
+        }
+        Pod::Id(id) => {
+            ui.add(egui::DragValue::new(id));
+        }
+        Pod::Int(i) => {
+            ui.add(egui::DragValue::new(i));
+        }
+        Pod::Long(l) => {
+            ui.add(egui::DragValue::new(l));
+        }
+        Pod::Float(f) => {
+            ui.add(egui::DragValue::new(f).speed(0.01));
+        }
+        Pod::Double(d) => {
+            ui.add(egui::DragValue::new(d).speed(0.01));
+        }
+        Pod::String(s) => {
+            ui.add(egui::TextEdit::singleline(s).desired_width(f32::INFINITY));
+        }
+        Pod::Fraction(num, denom) => {
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(num));
+                ui.label("/");
+                ui.add(egui::DragValue::new(denom));
+            });
+        }
+        Pod::Rectangle(width, height) => {
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(width));
+                ui.label("x");
+                ui.add(egui::DragValue::new(height));
+            });
+        }
+        Pod::IdArray(ids) => array_edit(ui, ids, 1.),
+        Pod::IntArray(ints) => array_edit(ui, ints, 1.),
+        Pod::FloatArray(floats) => array_edit(ui, floats, 0.01),
+        Pod::Struct(fields) => {
+            let mut i = 0usize;
+            fields.retain_mut(|field| {
+                let keep = ui
+                    .push_id(i, |ui| {
+                        let keep = ui
+                            .horizontal(|ui| {
+                                let keep = !ui.small_button("🗑").on_hover_text("Remove").clicked();
+                                ui.label(i.to_string());
+                                kind_combo(ui, field);
+                                keep
+                            })
+                            .inner;
+                        ui.indent("field", |ui| {
+                            pod_edit(ui, field);
+                        });
+                        keep
+                    })
+                    .inner;
+                i += 1;
+                keep
+            });
+
+            if ui.button("Add field").clicked() {
+                fields.push(Pod::None);
+            }
+        }
+        Pod::Object {
+            type_,
+            id,
+            properties,
+        } => {
+            ui.horizontal(|ui| {
+                egui::ComboBox::from_id_source("object_type")
+                    .selected_text(object_type_name(*type_))
+                    .show_ui(ui, |ui| {
+                        for (param_type, t, name) in PARAMS {
+                            if ui.selectable_label(*type_ == t, name).clicked() {
+                                *type_ = t;
+                                *id = param_type.as_raw();
+                            }
+                        }
+                    });
+                ui.label("Id");
+                ui.add(egui::DragValue::new(id));
+            });
+            properties_edit(ui, *type_, properties);
+        }
+    }
+}
+
+/// Builds and sets params of any type on nodes and devices
+#[derive(Default)]
+pub struct ParamBuilder {
+    globals: BTreeMap<u32, Rc<RefCell<Global>>>,
+    selected: Option<u32>,
+
+    // Index into PARAMS
+    param: usize,
+    properties: Vec<(u32, Pod)>,
+
+    result: Option<Result<String, String>>,
+}
+
+impl ToolPlugin for ParamBuilder {
+    fn label(&self) -> &'static str {
+        "🔧 Set Param"
+    }
+
+    fn description(&self) -> &'static str {
+        "Build a param from scratch and set it on a node or device"
+    }
+
+    fn interested_in(&self, object_type: &ObjectType) -> bool {
+        matches!(object_type, ObjectType::Node | ObjectType::Device)
+    }
+
+    fn global_event(&mut self, event: GlobalEvent, _: &backend::Sender) {
+        match event {
+            GlobalEvent::Added(global) => {
+                let id = global.borrow().id();
+                self.globals.insert(id, Rc::clone(global));
+            }
+            GlobalEvent::Removed { id, .. } => {
+                self.globals.remove(&id);
+                if self.selected == Some(id) {
+                    self.selected = None;
+                }
+            }
+            GlobalEvent::PropertiesChanged(_) | GlobalEvent::InfoChanged(..) => {}
+        }
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        self.show(ui, sx);
+    }
+}
+
+impl ParamBuilder {
+    fn pod(&self) -> Option<Vec<u8>> {
+        let (param_type, type_, _) = PARAMS[self.param];
+
+        props::serialize(
+            &Pod::Object {
+                type_,
+                id: param_type.as_raw(),
+                properties: self.properties.clone(),
+            }
+            .to_value(),
+        )
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        ui.label(
+            "Sets a param built from scratch, for params that don't have a dedicated editor. \
+            Invalid params are usually ignored by the object, or may make it misbehave.",
+        );
+
+        ui.separator();
+
+        let global = self.selected.and_then(|id| self.globals.get(&id));
+
+        ui.horizontal(|ui| {
+            let label = |global: &Global| {
+                format!(
+                    "{} {} {}",
+                    global.id(),
+                    global.object_type().to_str(),
+                    global.name().map_or("", String::as_str)
+                )
+            };
+
+            egui::ComboBox::from_label("Object")
+                .selected_text(global.map_or_else(
+                    || String::from("Select a node or device"),
+                    |global| label(&global.borrow()),
+                ))
+                .show_ui(ui, |ui| {
+                    for (id, global) in &self.globals {
+                        ui.selectable_value(&mut self.selected, Some(*id), label(&global.borrow()));
+                    }
+                });
+
+            global_info_button(ui, global, sx);
+        });
+
+        ui.horizontal(|ui| {
+            let previous = self.param;
+            egui::ComboBox::from_label("Param")
+                .selected_text(PARAMS[self.param].2)
+                .show_ui(ui, |ui| {
+                    for (i, (_, _, name)) in PARAMS.iter().enumerate() {
+                        ui.selectable_value(&mut self.param, i, *name);
+                    }
+                });
+            // The keys differ for each object type
+            if self.param != previous {
+                self.properties.clear();
+            }
+
+            let current = global.and_then(|global| {
+                let global = global.borrow();
+                let current = global.params(PARAMS[self.param].0).next().cloned();
+                current
+            });
+            if ui
+                .add_enabled(current.is_some(), egui::Button::new("Load current"))
+                .on_hover_text("Start from the object's current param")
+                .on_disabled_hover_text("The object doesn't have a param of this type")
+                .clicked()
+            {
+                if let Some(Pod::Object { properties, .. }) = current.as_ref().map(Pod::from_value)
+                {
+                    self.properties = properties;
+                }
+            }
+
+            if ui.button("Clear").clicked() {
+                self.properties.clear();
+            }
+        });
+
+        let pod = self.pod();
+
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(
+                    self.selected.is_some() && pod.is_some(),
+                    egui::Button::new("Set"),
+                )
+                .clicked()
+            {
+                if let (Some(id), Some(pod)) = (self.selected, pod.clone()) {
+                    let (param_type, _, name) = PARAMS[self.param];
+                    self.result = Some(
+                        sx.send(Request::CallObjectMethod(
+                            id,
+                            ObjectMethod::SetParam {
+                                id: param_type,
+                                flags: 0,
+                                pod,
+                            },
+                        ))
+                        .map(|()| format!("Set the {name} param of {id}"))
+                        .map_err(|_| String::from("The connection is closed")),
+                    );
+                }
+            }
+
+            match &self.result {
+                Some(Ok(message)) => {
+                    ui.label(message);
+                }
+                Some(Err(e)) => {
+                    ui.colored_label(ui.visuals().error_fg_color, e);
+                }
+                None => {}
+            }
+        });
+
+        ui.separator();
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            ui.push_id("properties", |ui| {
+                properties_edit(ui, PARAMS[self.param].1, &mut self.properties);
+            });
+
+            ui.separator();
+
+            egui::CollapsingHeader::new("Preview").show(ui, |ui| match &pod {
+                Some(pod) => pod_view(ui, "param_builder", pod),
+                None => {
+                    ui.colored_label(ui.visuals().error_fg_color, "The pod couldn't be built");
+                }
+            });
+        });
+    }
+}
@@ -0,0 +1,218 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{cell::RefCell, collections::BTreeSet, rc::Rc};
+
+use eframe::egui;
+use pipewire::{
+    permissions::{Permission, PermissionFlags},
+    types::ObjectType,
+};
+
+use crate::{
+    backend::{self, ObjectMethod, Request},
+    ui::{
+        compat::{self, Area},
+        globals_store::{draw_permission_list, Global},
+        util::{persistence::PersistentView, uis::KvMatcher},
+        GlobalEvent, ToolPlugin,
+    },
+};
+
+const MAX_LOG: usize = 50;
+
+#[derive(Clone)]
+#[cfg_attr(feature = "persistence", derive(serde::Serialize, serde::Deserialize))]
+struct Template {
+    name: String,
+    enabled: bool,
+    filter: KvMatcher,
+    // Object ID and permission flags
+    permissions: Vec<(u32, u32)>,
+}
+
+impl Template {
+    fn permissions(&self) -> Vec<Permission> {
+        self.permissions
+            .iter()
+            .map(|&(id, flags)| Permission::new(id, PermissionFlags::from_bits_truncate(flags)))
+            .collect()
+    }
+}
+
+/// Applies permissions to clients that match filters as they appear
+#[derive(Default)]
+pub struct PermissionTemplates {
+    templates: Vec<Template>,
+    // Clients that templates have been applied to, so they're only applied once
+    applied: BTreeSet<u32>,
+    log: Vec<String>,
+}
+
+impl ToolPlugin for PermissionTemplates {
+    fn label(&self) -> &'static str {
+        "🛡 Permission Templates"
+    }
+
+    fn description(&self) -> &'static str {
+        "Apply permissions to new clients automatically"
+    }
+
+    fn interested_in(&self, object_type: &ObjectType) -> bool {
+        *object_type == ObjectType::Client
+    }
+
+    fn global_event(&mut self, event: GlobalEvent, sx: &backend::Sender) {
+        match event {
+            GlobalEvent::Added(global) | GlobalEvent::PropertiesChanged(global) => {
+                self.client_updated(global, sx);
+            }
+            GlobalEvent::Removed { id, .. } => self.remove_client(id),
+            GlobalEvent::InfoChanged(..) => {}
+        }
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, _: &backend::Sender) {
+        self.show(ui);
+    }
+}
+
+impl PermissionTemplates {
+    /// Applies the matching templates to a client. Called when it's added and
+    /// when its properties change, since they might not be complete when it's added.
+    fn client_updated(&mut self, global: &Rc<RefCell<Global>>, sx: &backend::Sender) {
+        let global = global.borrow();
+        let id = global.id();
+
+        if self.applied.contains(&id) || global.props().is_empty() {
+            return;
+        }
+
+        for template in self
+            .templates
+            .iter()
+            .filter(|t| t.enabled && t.filter.matches(&global.props().iter()))
+        {
+            sx.send(Request::CallObjectMethod(
+                id,
+                ObjectMethod::ClientUpdatePermissions(template.permissions()),
+            ))
+            .ok();
+
+            self.applied.insert(id);
+
+            let client = global
+                .props()
+                .get("application.name")
+                .map_or_else(|| id.to_string(), |name| format!("{id} {name}"));
+            self.log
+                .insert(0, format!("Applied {} to {client}", template.name));
+            self.log.truncate(MAX_LOG);
+        }
+    }
+
+    fn remove_client(&mut self, id: u32) {
+        self.applied.remove(&id);
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui) {
+        ui.label(
+            "Templates are applied once to every new client whose properties match their filters. \
+            Templates without filters match every client.",
+        );
+
+        compat::show_warnings(ui, Area::Permissions);
+
+        ui.separator();
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            let mut i = 0usize;
+            self.templates.retain_mut(|template| {
+                let keep = ui
+                    .push_id(i, |ui| {
+                        let mut keep = true;
+
+                        ui.horizontal(|ui| {
+                            ui.checkbox(&mut template.enabled, "");
+                            egui::TextEdit::singleline(&mut template.name)
+                                .hint_text("Name")
+                                .show(ui);
+                            keep = !ui.button("Delete").clicked();
+                        });
+
+                        ui.collapsing("Filters", |ui| {
+                            template.filter.show(ui);
+                        });
+
+                        ui.collapsing("Permissions", |ui| {
+                            draw_permission_list(ui, &mut template.permissions);
+                        });
+
+                        ui.separator();
+
+                        keep
+                    })
+                    .inner;
+
+                i += 1;
+
+                keep
+            });
+
+            if ui.button("Add template").clicked() {
+                self.templates.push(Template {
+                    name: format!("Template {}", self.templates.len() + 1),
+                    enabled: false,
+                    filter: KvMatcher::new(),
+                    permissions: Vec::new(),
+                });
+            }
+
+            ui.separator();
+
+            ui.collapsing("Log", |ui| {
+                if self.log.is_empty() {
+                    ui.label("No templates have been applied yet");
+                }
+                for entry in &self.log {
+                    ui.label(entry);
+                }
+            });
+        });
+    }
+}
+
+#[cfg_attr(feature = "persistence", derive(serde::Serialize, serde::Deserialize))]
+pub struct PersistentData {
+    templates: Vec<Template>,
+}
+
+impl PersistentView for PermissionTemplates {
+    type Data = PersistentData;
+
+    fn with_data(data: &Self::Data) -> Self {
+        Self {
+            templates: data.templates.clone(),
+            ..Self::default()
+        }
+    }
+
+    fn save_data(&self) -> Option<Self::Data> {
+        Some(PersistentData {
+            templates: self.templates.clone(),
+        })
+    }
+}
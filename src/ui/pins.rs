@@ -0,0 +1,151 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+use eframe::egui;
+use pipewire::types::ObjectType;
+
+use crate::ui::globals_store::Global;
+
+/// An object, or objects, shown at the top of the globals list
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "persistence", derive(serde::Serialize, serde::Deserialize))]
+pub enum Pin {
+    /// The object with the ID, until it's removed
+    Id(u32),
+    /// Objects of the type with all of the properties, which match recreated objects too
+    Properties {
+        object_type: String,
+        props: Vec<(String, String)>,
+    },
+}
+
+impl Pin {
+    pub fn matches(&self, global: &Global) -> bool {
+        match self {
+            Self::Id(id) => global.id() == *id,
+            Self::Properties { object_type, props } => {
+                global.object_type().to_str() == object_type
+                    && props
+                        .iter()
+                        .all(|(k, v)| global.props().get(k).is_some_and(|value| value == v))
+            }
+        }
+    }
+
+    pub fn description(&self) -> String {
+        match self {
+            Self::Id(id) => format!("ID {id}"),
+            Self::Properties { object_type, props } => {
+                let object_type = object_type.rsplit(':').next().unwrap_or(object_type);
+                let props = props
+                    .iter()
+                    .map(|(k, v)| format!("{k} = {v}"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!("{object_type} with {props}")
+            }
+        }
+    }
+}
+
+/// The pins of the objects of a connection
+#[derive(Default)]
+pub struct Pins {
+    pins: Vec<Pin>,
+}
+
+impl Pins {
+    /// Pins restored from a previous session
+    pub fn new(saved: Vec<Pin>) -> Self {
+        Self { pins: saved }
+    }
+
+    pub fn all(&self) -> &[Pin] {
+        &self.pins
+    }
+
+    /// The pins that are kept across sessions. IDs are reused so only properties are.
+    pub fn saved(&self) -> Vec<Pin> {
+        self.pins
+            .iter()
+            .filter(|pin| matches!(pin, Pin::Properties { .. }))
+            .cloned()
+            .collect()
+    }
+
+    pub fn pin(&mut self, pin: Pin) {
+        if !self.pins.contains(&pin) {
+            self.pins.push(pin);
+        }
+    }
+
+    pub fn unpin(&mut self, pin: &Pin) {
+        self.pins.retain(|p| p != pin);
+    }
+
+    pub fn is_pinned(&self, global: &Global) -> bool {
+        self.pins.iter().any(|pin| pin.matches(global))
+    }
+
+    /// Unpins the object if it was pinned by its ID
+    pub fn global_removed(&mut self, id: u32) {
+        self.unpin(&Pin::Id(id));
+    }
+
+    /// A toggle that pins the object by its identifying properties, or by its ID if it has none.
+    /// Unpins it from all of the pins that match it if it's pinned.
+    pub fn button(&mut self, ui: &mut egui::Ui, global: &Global) {
+        let mut pinned = self.is_pinned(global);
+        if !ui
+            .toggle_value(&mut pinned, "📌")
+            .on_hover_text(
+                "Pin to the top of the list. Objects are pinned by the properties \
+                that identify them, so they stay pinned when they're recreated.",
+            )
+            .changed()
+        {
+            return;
+        }
+
+        if pinned {
+            self.pin(stable_pin(global).unwrap_or(Pin::Id(global.id())));
+        } else {
+            self.pins.retain(|pin| !pin.matches(global));
+        }
+    }
+}
+
+/// A pin by the properties that identify the object across its recreations, if it has them
+fn stable_pin(global: &Global) -> Option<Pin> {
+    let keys: &[&str] = match global.object_type() {
+        ObjectType::Node => &["node.name"],
+        ObjectType::Port => &["object.path", "port.alias"],
+        ObjectType::Device => &["device.name"],
+        ObjectType::Client => &["application.process.binary", "application.name"],
+        ObjectType::Module => &["module.name"],
+        ObjectType::Factory => &["factory.name"],
+        ObjectType::Metadata => &["metadata.name"],
+        _ => &[],
+    };
+
+    keys.iter().find_map(|&key| {
+        let value = global.props().get(key)?;
+        Some(Pin::Properties {
+            object_type: global.object_type().to_str().to_owned(),
+            props: vec![(key.to_owned(), value.clone())],
+        })
+    })
+}
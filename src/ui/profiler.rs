@@ -16,7 +16,7 @@
 
 use std::{
     cell::RefCell,
-    collections::{hash_map::Entry, HashMap},
+    collections::{hash_map::Entry, BTreeSet, HashMap},
     rc::{Rc, Weak},
 };
 
@@ -26,9 +26,16 @@ use egui_plot::{self, Plot, PlotPoints};
 use crate::{
     backend::{
         self,
-        pods::profiler::{Clock, Info, NodeBlock, Profiling},
+        pods::{
+            profiler::{NodeBlock, Profiling},
+            ParseError,
+        },
+    },
+    ui::{
+        compat,
+        globals_store::{Global, GlobalsStore},
+        util::{persistence::PersistentView, uis::global_info_button},
     },
-    ui::{globals_store::Global, util::uis::global_info_button},
 };
 
 #[allow(
@@ -50,6 +57,8 @@ mod data {
         ui::globals_store::Global,
     };
 
+    use super::capture::Capture;
+
     fn pop_front_push_back<T>(queue: &mut VecDeque<T>, max: usize, value: T) {
         if queue.len() + 1 > max {
             queue.pop_front();
@@ -170,6 +179,19 @@ mod data {
         pub fn duration(&self) -> PlotPoints {
             generate_plot_points(self.measurements.iter().map(|m| m.duration))
         }
+
+        /// Sorted scheduling latencies and durations, for showing their distributions
+        pub fn distributions(&self) -> [Vec<f64>; 2] {
+            [
+                self.measurements.iter().map(|m| m.scheduling_latency),
+                self.measurements.iter().map(|m| m.duration),
+            ]
+            .map(|values| {
+                let mut values: Vec<f64> = values.filter(|v| v.is_finite()).collect();
+                values.sort_unstable_by(f64::total_cmp);
+                values
+            })
+        }
     }
 
     struct DriverMeasurement {
@@ -200,6 +222,10 @@ mod data {
         measurements: VecDeque<DriverMeasurement>,
         followers: BTreeMap<i32, Client>,
 
+        // Average CPU frequency in MHz at the time of each measurement
+        #[cfg(feature = "cpu_frequency")]
+        cpu_frequencies: VecDeque<f64>,
+
         // Stored weakly as these objects live for as long as there
         // are stored profilings of them, which can be longer than
         // the lifetime of the global
@@ -214,6 +240,9 @@ mod data {
                 measurements: VecDeque::with_capacity(max_profilings),
                 followers: BTreeMap::new(),
 
+                #[cfg(feature = "cpu_frequency")]
+                cpu_frequencies: VecDeque::with_capacity(max_profilings),
+
                 global,
             }
         }
@@ -293,6 +322,9 @@ mod data {
         pub fn clear(&mut self) {
             self.measurements.clear();
             self.followers.clear();
+
+            #[cfg(feature = "cpu_frequency")]
+            self.cpu_frequencies.clear();
         }
 
         pub fn adjust_queues(&mut self, max_profilings: usize) {
@@ -305,6 +337,8 @@ mod data {
             }
 
             adjust_queue(&mut self.measurements, max_profilings);
+            #[cfg(feature = "cpu_frequency")]
+            adjust_queue(&mut self.cpu_frequencies, max_profilings);
             for follower in self.followers.values_mut() {
                 adjust_queue(&mut follower.measurements, max_profilings);
             }
@@ -326,24 +360,995 @@ mod data {
             generate_plot_points(self.measurements.iter().map(|m| m.end_date))
         }
 
+        #[cfg(feature = "cpu_frequency")]
+        pub fn add_cpu_frequency(&mut self, mhz: f64, max_profilings: usize) {
+            pop_front_push_back(&mut self.cpu_frequencies, max_profilings, mhz);
+        }
+
+        #[cfg(feature = "cpu_frequency")]
+        pub fn cpu_frequency(&self) -> PlotPoints {
+            generate_plot_points(self.cpu_frequencies.iter().copied())
+        }
+
         pub fn clients(&self) -> impl Iterator<Item = &Client> + '_ {
             self.followers.values()
         }
 
+        pub fn client(&self, id: i32) -> Option<&Client> {
+            self.followers.get(&id)
+        }
+
         pub fn n_clients(&self) -> usize {
             self.followers.len()
         }
+
+        pub fn capture(&self, name: String) -> Capture {
+            Capture::new(
+                name,
+                [
+                    self.measurements.iter().map(|m| m.end_date).collect(),
+                    self.measurements.iter().map(|m| m.period).collect(),
+                    self.followers
+                        .values()
+                        .flat_map(|f| f.measurements.iter().map(|m| m.scheduling_latency))
+                        .collect(),
+                    self.followers
+                        .values()
+                        .flat_map(|f| f.measurements.iter().map(|m| m.duration))
+                        .collect(),
+                ],
+            )
+        }
+    }
+}
+
+#[allow(clippy::cast_precision_loss)]
+mod capture {
+    use std::fmt::Write;
+
+    use eframe::egui;
+    use egui_plot::{Plot, PlotPoints};
+
+    use crate::{redact::Redactor, ui::globals_store::GlobalsStore};
+
+    use super::data::Driver;
+
+    /// Names and descriptions of the captured measurements
+    pub const METRICS: [(&str, &str); 4] = [
+        (
+            "Driver End Date",
+            "Time between when the cycle started and when the driver finished processing",
+        ),
+        ("Period", "Time between the starts of consecutive cycles"),
+        (
+            "Clients Scheduling Latency",
+            "Wakeup delay of the followers",
+        ),
+        ("Clients Duration", "Processing time of the followers"),
+    ];
+
+    pub fn percentile(sorted: &[f64], p: f64) -> Option<f64> {
+        if sorted.is_empty() {
+            return None;
+        }
+
+        #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+        let index = ((sorted.len() - 1) as f64 * p).round() as usize;
+
+        Some(sorted[index])
+    }
+
+    /// Distributions of the measurements of a driver and its followers at some point in time
+    pub struct Capture {
+        pub name: String,
+        // Sorted, in microseconds
+        metrics: [Vec<f64>; METRICS.len()],
+    }
+
+    impl Capture {
+        pub fn new(name: String, mut metrics: [Vec<f64>; METRICS.len()]) -> Self {
+            for values in &mut metrics {
+                values.retain(|v| v.is_finite());
+                values.sort_unstable_by(f64::total_cmp);
+            }
+
+            Self { name, metrics }
+        }
+
+        pub fn metric(&self, i: usize) -> &[f64] {
+            &self.metrics[i]
+        }
+
+        /// Values of the metric over the percentiles 0 to 100
+        pub fn quantiles(&self, i: usize) -> PlotPoints {
+            let values = &self.metrics[i];
+            let last = values.len().saturating_sub(1).max(1) as f64;
+
+            PlotPoints::from_iter(
+                values
+                    .iter()
+                    .enumerate()
+                    .map(|(j, v)| [j as f64 / last * 100., *v]),
+            )
+        }
+
+        /// Serializes to lines of the name, and each metric's name followed by its values
+        pub fn to_text(&self) -> String {
+            let mut text = format!("name {}\n", self.name);
+
+            for ((name, _), values) in METRICS.iter().zip(&self.metrics) {
+                text.push_str(name);
+                for v in values {
+                    write!(text, " {v}").ok();
+                }
+                text.push('\n');
+            }
+
+            text
+        }
+
+        pub fn from_text(text: &str) -> Result<Self, String> {
+            let mut name = None;
+            let mut metrics: [Vec<f64>; METRICS.len()] = Default::default();
+
+            for line in text.lines().filter(|l| !l.trim().is_empty()) {
+                if let Some(n) = line.strip_prefix("name ") {
+                    name = Some(n.to_owned());
+                    continue;
+                }
+
+                let Some(i) = METRICS
+                    .iter()
+                    .position(|(metric, _)| line.starts_with(metric))
+                else {
+                    return Err(format!("Unknown measurement in line: {line}"));
+                };
+
+                metrics[i] = line[METRICS[i].0.len()..]
+                    .split_whitespace()
+                    .map(|v| v.parse().map_err(|_| format!("Invalid value {v}")))
+                    .collect::<Result<_, _>>()?;
+            }
+
+            Ok(Self::new(name.ok_or("The capture has no name")?, metrics))
+        }
+    }
+
+    /// Two captures whose distributions are compared
+    #[derive(Default)]
+    pub struct Comparison {
+        captures: [Option<Capture>; 2],
+        path: String,
+        error: Option<String>,
+    }
+
+    impl Comparison {
+        pub fn show(
+            &mut self,
+            ui: &mut egui::Ui,
+            driver: &Driver,
+            name: &str,
+            globals: &GlobalsStore,
+        ) {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.path)
+                    .hint_text("Capture file path")
+                    .desired_width(f32::INFINITY),
+            );
+
+            for (capture, label) in self.captures.iter_mut().zip(["A", "B"]) {
+                ui.horizontal(|ui| {
+                    ui.label(format!(
+                        "{label}: {}",
+                        capture.as_ref().map_or("Empty", |c| c.name.as_str())
+                    ));
+
+                    if ui
+                        .small_button("Capture")
+                        .on_hover_text("Capture the current samples of the selected driver")
+                        .clicked()
+                    {
+                        *capture = Some(driver.capture(name.to_owned()));
+                    }
+
+                    if ui.small_button("Load").clicked() {
+                        match std::fs::read_to_string(&self.path)
+                            .map_err(|e| format!("Failed to read the capture: {e}"))
+                            .and_then(|text| Capture::from_text(&text))
+                        {
+                            Ok(loaded) => {
+                                *capture = Some(loaded);
+                                self.error = None;
+                            }
+                            Err(e) => self.error = Some(e),
+                        }
+                    }
+
+                    if let Some(c) = capture {
+                        if ui.small_button("Save").clicked() {
+                            let text = Redactor::new(globals).text(&c.to_text());
+                            self.error = std::fs::write(&self.path, text)
+                                .err()
+                                .map(|e| format!("Failed to save the capture: {e}"));
+                        }
+                    }
+                });
+            }
+
+            if let Some(e) = &self.error {
+                ui.colored_label(ui.visuals().error_fg_color, e);
+            }
+
+            let [Some(a), Some(b)] = &self.captures else {
+                ui.label("Take or load two captures to compare them");
+                return;
+            };
+
+            egui::Grid::new("captures_percentiles")
+                .striped(true)
+                .num_columns(7)
+                .show(ui, |ui| {
+                    ui.label("");
+                    for p in ["p50", "p95", "p99"] {
+                        ui.label(format!("A {p}"));
+                        ui.label(format!("B {p}"));
+                    }
+                    ui.end_row();
+
+                    for (i, (metric, description)) in METRICS.iter().enumerate() {
+                        ui.label(*metric).on_hover_text(*description);
+                        for p in [0.5, 0.95, 0.99] {
+                            let a = percentile(a.metric(i), p);
+                            let b = percentile(b.metric(i), p);
+                            for v in [a, b] {
+                                ui.label(
+                                    v.map_or_else(|| String::from("-"), |v| format!("{v:.0}us")),
+                                );
+                            }
+                        }
+                        ui.end_row();
+                    }
+                });
+
+            ui.columns(2, |ui| {
+                for (i, (metric, description)) in METRICS.iter().enumerate() {
+                    let ui = &mut ui[i % 2];
+                    ui.label(*metric).on_hover_text(*description);
+                    Plot::new(("capture_comparison", i))
+                        .height(150.)
+                        .legend(egui_plot::Legend::default())
+                        .allow_zoom(false)
+                        .allow_drag(false)
+                        .allow_scroll(false)
+                        .label_formatter(|name, value| {
+                            if name.is_empty() {
+                                String::new()
+                            } else {
+                                format!("{name}\np{:.0}: {:.0}us", value.x, value.y)
+                            }
+                        })
+                        .show(ui, |ui| {
+                            for (label, capture) in [("A", a), ("B", b)] {
+                                ui.line(
+                                    egui_plot::Line::new(capture.quantiles(i))
+                                        .name(format!("{label}: {}", capture.name)),
+                                );
+                            }
+                        });
+                }
+            });
+        }
+    }
+}
+
+mod recording {
+    use std::{collections::VecDeque, fmt::Write};
+
+    use eframe::egui;
+
+    use crate::{
+        backend::pods::profiler::{NodeBlock, Profiling},
+        redact::Redactor,
+        ui::{globals_store::GlobalsStore, util::config::quote},
+    };
+
+    /// The nodes of a driver's cycle that passed the filter
+    struct Cycle {
+        counter: i64,
+        driver_id: i32,
+        quantum: i64,
+        rate: u32,
+        cpu_load: f32,
+        // The driver is included if it passed the filter
+        nodes: Vec<NodeBlock>,
+        driver_signal: i64,
+    }
+
+    const CSV_HEADER: &str = "counter,driver_id,quantum,rate,cpu_load,node_id,node_name,\
+        signal,awake,finish,end_date_us,scheduling_latency_us,duration_us,status,xruns";
+
+    #[derive(Clone, Copy, PartialEq, Eq)]
+    enum Format {
+        Csv,
+        Json,
+    }
+
+    impl Format {
+        const fn as_str(self) -> &'static str {
+            match self {
+                Self::Csv => "CSV",
+                Self::Json => "JSON",
+            }
+        }
+    }
+
+    /// Accumulates profiler samples of all drivers for analyzing them elsewhere
+    pub struct Recording {
+        active: bool,
+        cycles: VecDeque<Cycle>,
+        max_cycles: usize,
+        // Comma separated parts of node names, empty records all nodes
+        filter: String,
+
+        format: Format,
+        path: String,
+        result: Option<Result<String, String>>,
+    }
+
+    impl Default for Recording {
+        fn default() -> Self {
+            Self {
+                active: false,
+                cycles: VecDeque::new(),
+                max_cycles: 10_000,
+                filter: String::new(),
+
+                format: Format::Csv,
+                path: String::from("coppwr-profiler.csv"),
+                result: None,
+            }
+        }
+    }
+
+    /// Measurements in microseconds relative to the driver's start of the cycle, like in the plots
+    #[allow(clippy::cast_precision_loss)]
+    fn measurements(node: &NodeBlock, driver_signal: i64) -> [f64; 3] {
+        [
+            (node.finish - driver_signal) as f64 / 1000.,
+            (node.awake - node.signal) as f64 / 1000.,
+            (node.finish - node.awake) as f64 / 1000.,
+        ]
+    }
+
+    impl Recording {
+        pub fn add_profilings(&mut self, profilings: &[Profiling]) {
+            if !self.active {
+                return;
+            }
+
+            let filters: Vec<_> = self
+                .filter
+                .split(',')
+                .map(str::trim)
+                .filter(|f| !f.is_empty())
+                .collect();
+
+            for p in profilings {
+                let nodes: Vec<_> = std::iter::once(&p.driver)
+                    .chain(&p.followers)
+                    .filter(|n| filters.is_empty() || filters.iter().any(|f| n.name.contains(f)))
+                    .cloned()
+                    .collect();
+                if nodes.is_empty() {
+                    continue;
+                }
+
+                if self.cycles.len() >= self.max_cycles {
+                    self.cycles.pop_front();
+                }
+                self.cycles.push_back(Cycle {
+                    counter: p.info.counter,
+                    driver_id: p.driver.id,
+                    quantum: p.clock.duration * i64::from(p.clock.rate.num),
+                    rate: p.clock.rate.denom,
+                    cpu_load: p.info.cpu_load_fast,
+                    nodes,
+                    driver_signal: p.driver.signal,
+                });
+            }
+        }
+
+        fn to_csv(&self) -> String {
+            let mut csv = String::from(CSV_HEADER);
+            csv.push('\n');
+
+            for cycle in &self.cycles {
+                for node in &cycle.nodes {
+                    let [end_date, scheduling_latency, duration] =
+                        measurements(node, cycle.driver_signal);
+                    writeln!(
+                        csv,
+                        "{},{},{},{},{},{},\"{}\",{},{},{},{end_date},{scheduling_latency},{duration},{},{}",
+                        cycle.counter,
+                        cycle.driver_id,
+                        cycle.quantum,
+                        cycle.rate,
+                        cycle.cpu_load,
+                        node.id,
+                        node.name.replace('"', "\"\""),
+                        node.signal,
+                        node.awake,
+                        node.finish,
+                        node.status,
+                        node.xrun_count.map(|x| x.to_string()).unwrap_or_default()
+                    )
+                    .ok();
+                }
+            }
+
+            csv
+        }
+
+        fn to_json(&self) -> String {
+            let mut json = String::from("[\n");
+
+            for (i, cycle) in self.cycles.iter().enumerate() {
+                write!(
+                    json,
+                    "  {{ \"counter\": {}, \"driver_id\": {}, \"quantum\": {}, \"rate\": {}, \
+                    \"cpu_load\": {}, \"nodes\": [",
+                    cycle.counter, cycle.driver_id, cycle.quantum, cycle.rate, cycle.cpu_load
+                )
+                .ok();
+
+                for (j, node) in cycle.nodes.iter().enumerate() {
+                    let [end_date, scheduling_latency, duration] =
+                        measurements(node, cycle.driver_signal);
+                    write!(
+                        json,
+                        "{}\n    {{ \"id\": {}, \"name\": {}, \"signal\": {}, \"awake\": {}, \
+                        \"finish\": {}, \"end_date_us\": {end_date}, \
+                        \"scheduling_latency_us\": {scheduling_latency}, \
+                        \"duration_us\": {duration}, \"status\": {}, \"xruns\": {} }}",
+                        if j == 0 { "" } else { "," },
+                        node.id,
+                        quote(&node.name),
+                        node.signal,
+                        node.awake,
+                        node.finish,
+                        node.status,
+                        node.xrun_count
+                            .map_or_else(|| String::from("null"), |x| x.to_string())
+                    )
+                    .ok();
+                }
+
+                json.push_str(if i + 1 < self.cycles.len() {
+                    "\n  ] },\n"
+                } else {
+                    "\n  ] }\n"
+                });
+            }
+
+            json.push_str("]\n");
+            json
+        }
+
+        pub fn show(&mut self, ui: &mut egui::Ui, globals: &GlobalsStore) {
+            ui.horizontal(|ui| {
+                ui.toggle_value(&mut self.active, "⏺ Record")
+                    .on_hover_text("Keeps recording while paused");
+                ui.label(format!("{} cycles", self.cycles.len()));
+                if ui.small_button("Clear").clicked() {
+                    self.cycles.clear();
+                }
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("Cycles to keep");
+                ui.add(egui::DragValue::new(&mut self.max_cycles).clamp_range(1..=10_000_000))
+                    .on_hover_text("The oldest cycles are dropped when this is reached");
+            });
+            while self.cycles.len() > self.max_cycles {
+                self.cycles.pop_front();
+            }
+
+            ui.add(
+                egui::TextEdit::singleline(&mut self.filter)
+                    .hint_text("Nodes to record, comma separated parts of their names")
+                    .desired_width(f32::INFINITY),
+            );
+
+            ui.horizontal(|ui| {
+                for format in [Format::Csv, Format::Json] {
+                    if ui
+                        .selectable_value(&mut self.format, format, format.as_str())
+                        .clicked()
+                    {
+                        let extension = if format == Format::Csv { "csv" } else { "json" };
+                        self.path = std::path::Path::new(&self.path)
+                            .with_extension(extension)
+                            .to_string_lossy()
+                            .into_owned();
+                    }
+                }
+
+                egui::TextEdit::singleline(&mut self.path)
+                    .hint_text("File path")
+                    .show(ui);
+
+                if ui
+                    .add_enabled(!self.cycles.is_empty(), egui::Button::new("Export"))
+                    .clicked()
+                {
+                    let contents = Redactor::new(globals).text(&match self.format {
+                        Format::Csv => self.to_csv(),
+                        Format::Json => self.to_json(),
+                    });
+                    self.result = Some(
+                        std::fs::write(&self.path, contents)
+                            .map(|()| format!("Saved to {}", self.path))
+                            .map_err(|e| format!("Failed to export: {e}")),
+                    );
+                }
+            });
+
+            match &self.result {
+                Some(Ok(msg)) => {
+                    ui.label(msg);
+                }
+                Some(Err(e)) => {
+                    ui.colored_label(ui.visuals().error_fg_color, e);
+                }
+                None => {}
+            }
+        }
+    }
+}
+
+#[cfg(feature = "cpu_frequency")]
+mod cpu_frequency {
+    use std::fs;
+
+    pub struct Sample {
+        // Average of all CPUs
+        pub mhz: f64,
+        pub governors: Vec<String>,
+    }
+
+    /// Reads the current frequency and governor of each CPU from sysfs
+    pub fn sample() -> Option<Sample> {
+        let mut total_mhz = 0.;
+        let mut cpus = 0u32;
+        let mut governors = Vec::new();
+
+        for entry in fs::read_dir("/sys/devices/system/cpu").ok()?.flatten() {
+            let name = entry.file_name();
+            let Some(index) = name.to_str().and_then(|n| n.strip_prefix("cpu")) else {
+                continue;
+            };
+            if index.is_empty() || !index.bytes().all(|b| b.is_ascii_digit()) {
+                continue;
+            }
+
+            let cpufreq = entry.path().join("cpufreq");
+
+            if let Some(khz) = fs::read_to_string(cpufreq.join("scaling_cur_freq"))
+                .ok()
+                .and_then(|khz| khz.trim().parse::<f64>().ok())
+            {
+                total_mhz += khz / 1000.;
+                cpus += 1;
+            }
+
+            if let Ok(governor) = fs::read_to_string(cpufreq.join("scaling_governor")) {
+                let governor = governor.trim().to_owned();
+                if !governors.contains(&governor) {
+                    governors.push(governor);
+                }
+            }
+        }
+
+        (cpus > 0).then(|| Sample {
+            mhz: total_mhz / f64::from(cpus),
+            governors,
+        })
     }
 }
 
+use capture::{percentile, Comparison};
 use data::{Client, Driver};
+use recording::Recording;
+
+const HISTOGRAM_BINS: usize = 30;
+
+#[allow(
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss,
+    clippy::cast_possible_truncation
+)]
+fn histogram(sorted: &[f64]) -> Vec<egui_plot::Bar> {
+    let (Some(&min), Some(&max)) = (sorted.first(), sorted.last()) else {
+        return Vec::new();
+    };
+
+    let width = ((max - min) / HISTOGRAM_BINS as f64).max(1.);
+    let mut counts = [0u32; HISTOGRAM_BINS];
+    for v in sorted {
+        counts[(((v - min) / width) as usize).min(HISTOGRAM_BINS - 1)] += 1;
+    }
+
+    counts
+        .into_iter()
+        .enumerate()
+        .map(|(i, count)| {
+            egui_plot::Bar::new(min + width * (i as f64 + 0.5), f64::from(count)).width(width)
+        })
+        .collect()
+}
+
+fn show_client_histograms(ui: &mut egui::Ui, driver: &Driver, selected: &mut Option<String>) {
+    egui::ComboBox::from_label("Follower")
+        .selected_text(selected.as_deref().unwrap_or("Select a follower"))
+        .show_ui(ui, |ui| {
+            for client in driver.clients() {
+                ui.selectable_value(selected, Some(client.title().to_owned()), client.title());
+            }
+        });
+
+    let Some(client) = selected
+        .as_ref()
+        .and_then(|title| driver.clients().find(|c| c.title() == title))
+    else {
+        return;
+    };
+
+    ui.columns(2, |ui| {
+        for ((heading, explanation), (ui, values)) in [
+            (
+                "Scheduling Latency",
+                "Time between when the follower was signaled and when it woke up",
+            ),
+            (
+                "Duration",
+                "Time between when the follower woke up and when it finished processing",
+            ),
+        ]
+        .into_iter()
+        .zip(ui.iter_mut().zip(client.distributions()))
+        {
+            ui.label(heading).on_hover_text(explanation);
+
+            ui.label(
+                [("p50", 0.5), ("p90", 0.9), ("p99", 0.99), ("max", 1.)]
+                    .map(|(label, p)| {
+                        percentile(&values, p)
+                            .map_or_else(|| format!("{label}: -"), |v| format!("{label}: {v:.0}us"))
+                    })
+                    .join(" | "),
+            );
+
+            Plot::new(("client_histogram", heading))
+                .height(200.)
+                .allow_zoom(false)
+                .allow_drag(false)
+                .allow_scroll(false)
+                .x_axis_formatter(|x, _, _| format!("{}us", x.value))
+                .show(ui, |ui| {
+                    ui.bar_chart(
+                        egui_plot::BarChart::new(histogram(&values))
+                            .name(heading)
+                            .element_formatter(Box::new(|b, _| {
+                                format!("{:.0}us: {} samples", b.argument, b.value)
+                            })),
+                    );
+                });
+        }
+    });
+}
+
+/// When the driver and its followers were signaled, woke up and finished in the last cycle,
+/// relative to when the driver was signaled
+#[allow(
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss,
+    clippy::cast_possible_truncation
+)]
+fn show_cycle_timeline(ui: &mut egui::Ui, profiling: &Profiling, pinned: &BTreeSet<String>) {
+    use egui_plot::{Bar, BarChart};
+
+    let blocks: Vec<(String, &NodeBlock)> = std::iter::once(&profiling.driver)
+        .chain(profiling.followers.iter())
+        .map(|nb| (format!("{}/{}", nb.name, nb.id), nb))
+        .filter(|(title, nb)| {
+            // The driver is always shown, as the timeline starts with it
+            (nb.id == profiling.driver.id || pinned.is_empty() || pinned.contains(title))
+                // Nodes that didn't run in this cycle have no valid times
+                && nb.signal > 0
+                && nb.awake >= nb.signal
+                && nb.finish >= nb.awake
+        })
+        .collect();
+
+    if blocks.is_empty() {
+        ui.label("No nodes ran in the last cycle");
+        return;
+    }
+
+    let start = profiling.driver.signal;
+    let offset = |t: i64| (t - start) as f64 / 1000.;
+
+    let mut waiting = Vec::with_capacity(blocks.len());
+    let mut busy = Vec::with_capacity(blocks.len());
+    // Listed from the top
+    for (i, (_, nb)) in blocks.iter().rev().enumerate() {
+        waiting.push(
+            Bar::new(i as f64, offset(nb.awake) - offset(nb.signal))
+                .base_offset(offset(nb.signal))
+                .horizontal(),
+        );
+        busy.push(
+            Bar::new(i as f64, offset(nb.finish) - offset(nb.awake))
+                .base_offset(offset(nb.awake))
+                .horizontal(),
+        );
+    }
+
+    let labels: Vec<String> = blocks.into_iter().rev().map(|(title, _)| title).collect();
+    let height = (labels.len() * 30).max(100) as f32;
+
+    ui.label(
+        "Each node's row starts when it was signaled.         The first part is the time it waited to wake up and the second the time it was processing.",
+    );
+
+    Plot::new("cycle_timeline")
+        .height(height)
+        .allow_drag(egui::emath::Vec2b::new(true, false))
+        .allow_zoom(egui::emath::Vec2b::new(true, false))
+        .allow_scroll(false)
+        .show_grid(egui::Vec2b::new(true, false))
+        .legend(egui_plot::Legend::default().position(egui_plot::Corner::RightBottom))
+        .x_axis_formatter(|x, _, _| format!("{}us", x.value))
+        .y_axis_formatter(move |y, _, _| {
+            let y = y.value;
+            if y.is_sign_negative() || y % 1. != 0. {
+                String::new()
+            } else {
+                labels.get(y as usize).cloned().unwrap_or_default()
+            }
+        })
+        .show(ui, |ui| {
+            ui.bar_chart(
+                BarChart::new(waiting)
+                    .name("Waiting")
+                    .element_formatter(Box::new(|b, _| {
+                        format!(
+                            "Signaled at {:.1}us, waited {:.1}us",
+                            b.base_offset, b.value
+                        )
+                    })),
+            );
+            ui.bar_chart(
+                BarChart::new(busy)
+                    .name("Busy")
+                    .element_formatter(Box::new(|b, _| {
+                        format!(
+                            "Woke up at {:.1}us, processed for {:.1}us, finished at {:.1}us",
+                            b.base_offset,
+                            b.value,
+                            b.base_offset + b.value
+                        )
+                    })),
+            );
+        });
+}
+
+/// A column of the process viewer's tables
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "persistence", derive(serde::Serialize, serde::Deserialize))]
+enum Column {
+    Id,
+    Serial,
+    Name,
+    Class,
+    State,
+    Client,
+    Quantum,
+    Rate,
+    Waiting,
+    Busy,
+    WaitingPerQuantum,
+    BusyPerQuantum,
+    Xruns,
+}
+
+impl Column {
+    const ALL: [Self; 13] = [
+        Self::Id,
+        Self::Serial,
+        Self::Name,
+        Self::Class,
+        Self::State,
+        Self::Client,
+        Self::Quantum,
+        Self::Rate,
+        Self::Waiting,
+        Self::Busy,
+        Self::WaitingPerQuantum,
+        Self::BusyPerQuantum,
+        Self::Xruns,
+    ];
+
+    fn default_columns() -> Vec<Self> {
+        vec![
+            Self::Id,
+            Self::Name,
+            Self::Quantum,
+            Self::Rate,
+            Self::Waiting,
+            Self::Busy,
+            Self::WaitingPerQuantum,
+            Self::BusyPerQuantum,
+            Self::Xruns,
+        ]
+    }
+
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Id => "ID",
+            Self::Serial => "Serial",
+            Self::Name => "Name",
+            Self::Class => "Class",
+            Self::State => "State",
+            Self::Client => "Client",
+            Self::Quantum => "Quantum",
+            Self::Rate => "Rate",
+            Self::Waiting => "Waiting",
+            Self::Busy => "Busy",
+            Self::WaitingPerQuantum => "Waiting/Quantum",
+            Self::BusyPerQuantum => "Busy/Quantum",
+            Self::Xruns => "Xruns",
+        }
+    }
+
+    /// The value of the column for a node of a profiling, which is the driver's if `driver`
+    #[allow(clippy::cast_precision_loss)]
+    fn text(
+        self,
+        block: &NodeBlock,
+        profiling: &Profiling,
+        driver: bool,
+        global: Option<&Global>,
+    ) -> String {
+        fn format_to_time(nanos: i64) -> String {
+            let nanos = nanos as f64;
+            if nanos < 1_000_000. {
+                format!("{:.3}us", nanos / 1000.)
+            } else if nanos < 1_000_000_000. {
+                format!("{:.4}ms", nanos / 1_000_000.)
+            } else {
+                format!("{:.6}s", nanos / 1_000_000_000.)
+            }
+        }
+
+        let (clock, info) = (&profiling.clock, &profiling.info);
+
+        let prop = |key: &str| {
+            global
+                .and_then(|global| global.props().get(key).cloned())
+                .unwrap_or_default()
+        };
+
+        let latency = |n: u32| {
+            if n == 0 {
+                String::from("Using driver's")
+            } else {
+                n.to_string()
+            }
+        };
+
+        let quantum =
+            clock.duration as f64 * f64::from(clock.rate.num) / f64::from(clock.rate.denom);
+        let per_quantum = |n: i64| format!("{:.6}", n as f64 / 1_000_000_000. / quantum);
+
+        match self {
+            Self::Id => block.id.to_string(),
+            Self::Serial => prop("object.serial"),
+            Self::Name => block.name.clone(),
+            Self::Class => prop("media.class"),
+            Self::State => global
+                .and_then(|global| global.info_value("State").map(str::to_owned))
+                .unwrap_or_default(),
+            Self::Client => prop("client.id"),
+            Self::Quantum if driver => (clock.duration * i64::from(clock.rate.num)).to_string(),
+            Self::Quantum => latency(block.latency.num),
+            Self::Rate if driver => clock.rate.denom.to_string(),
+            Self::Rate => latency(block.latency.denom),
+            Self::Waiting => {
+                if block.awake >= block.signal {
+                    format_to_time(block.awake - block.signal)
+                } else if block.signal > block.prev_signal {
+                    String::from("Did not wake")
+                } else {
+                    String::from("Was not signaled")
+                }
+            }
+            Self::Busy => {
+                if block.finish >= block.awake {
+                    format_to_time(block.finish - block.awake)
+                } else if block.awake > block.prev_signal {
+                    String::from("Did not complete")
+                } else {
+                    String::from("Did not start")
+                }
+            }
+            Self::WaitingPerQuantum => per_quantum(block.awake - block.signal),
+            Self::BusyPerQuantum => per_quantum(block.finish - block.awake),
+            Self::Xruns => block.xrun_count.unwrap_or(info.xrun_count).to_string(),
+        }
+    }
+
+    /// The number that rows are sorted by, if the value of the column is one
+    #[allow(clippy::cast_precision_loss)]
+    fn sort_value(self, block: &NodeBlock, text: &str) -> Option<f64> {
+        match self {
+            Self::Waiting => {
+                (block.awake >= block.signal).then(|| (block.awake - block.signal) as f64)
+            }
+            Self::Busy => {
+                (block.finish >= block.awake).then(|| (block.finish - block.awake) as f64)
+            }
+            _ => text.parse().ok(),
+        }
+    }
+
+    const fn description(self) -> Option<&'static str> {
+        match self {
+            Self::Client => Some("The ID of the client that created the node"),
+            Self::Waiting => Some(
+                "Time between when the node was ready to start processing \
+                and when it actually started processing",
+            ),
+            Self::Busy => Some(
+                "Time between when the node started processing and when it finished \
+                and woke up the next nodes in the graph",
+            ),
+            Self::WaitingPerQuantum => Some("A measure of the graph load"),
+            Self::BusyPerQuantum => Some("A measure of the load of the driver/node"),
+            _ => None,
+        }
+    }
+}
 
 pub struct Profiler {
     max_profilings: usize,
     drivers: HashMap<i32, Driver>,
     selected_driver_id: Option<i32>,
+    selected_client: Option<String>,
+    // Titles of the followers that the client plots are limited to, all are shown if empty
+    pinned_clients: BTreeSet<String>,
     pause: bool,
 
+    comparison: Comparison,
+    recording: Recording,
+
+    // The last profiler that sent samples that couldn't be parsed
+    parse_error: Option<(u32, ParseError)>,
+    // Fields that the samples didn't have, which older daemons don't send
+    missing_fields: BTreeSet<&'static str>,
+
+    #[cfg(feature = "cpu_frequency")]
+    cpu_frequency: Option<cpu_frequency::Sample>,
+
+    // Shown in the process viewer
+    columns: Vec<Column>,
+    // Whether the process viewer shows the nodes of all drivers in one table
+    flat_table: bool,
+    // The column the table is sorted by and whether it's descending
+    sort: (Column, bool),
+    // Node ID -> ID of the driver it was last seen under
+    node_drivers: HashMap<i32, i32>,
+
     // Used for updating last profilings of nodes periodically instead of on every new profiling.
     // This is useful for not drawing new data on every egui update, such as mouse movement
     last_profs_update: std::time::Instant,
@@ -360,8 +1365,24 @@ impl Profiler {
             max_profilings,
             drivers: HashMap::new(),
             selected_driver_id: None,
+            selected_client: None,
+            pinned_clients: BTreeSet::new(),
             pause: false,
 
+            comparison: Comparison::default(),
+            recording: Recording::default(),
+
+            parse_error: None,
+            missing_fields: BTreeSet::new(),
+
+            #[cfg(feature = "cpu_frequency")]
+            cpu_frequency: None,
+
+            columns: Column::default_columns(),
+            flat_table: false,
+            sort: (Column::Id, false),
+            node_drivers: HashMap::new(),
+
             last_profs_update: std::time::Instant::now(),
         }
     }
@@ -371,6 +1392,8 @@ impl Profiler {
         profilings: Vec<Profiling>,
         global_getter: impl Fn(i32) -> Option<Weak<RefCell<Global>>>,
     ) {
+        self.recording.add_profilings(&profilings);
+
         if self.pause {
             return;
         }
@@ -390,7 +1413,29 @@ impl Profiler {
             false
         };
 
+        // Reading sysfs on every profiling would be too expensive
+        #[cfg(feature = "cpu_frequency")]
+        if update_last_profs || self.cpu_frequency.is_none() {
+            self.cpu_frequency = cpu_frequency::sample();
+        }
+
         for p in profilings {
+            #[cfg(feature = "cpu_frequency")]
+            let id = p.driver.id;
+
+            if update_last_profs {
+                for block in std::iter::once(&p.driver).chain(&p.followers) {
+                    self.node_drivers.insert(block.id, p.driver.id);
+                }
+            }
+
+            if p.clock.transport_state.is_none() {
+                self.missing_fields.insert("transport state of drivers");
+            }
+            if p.driver.xrun_count.is_none() {
+                self.missing_fields.insert("xrun counts of nodes");
+            }
+
             match self.drivers.entry(p.driver.id) {
                 Entry::Occupied(mut e) => {
                     e.get_mut().add_profiling(
@@ -412,22 +1457,77 @@ impl Profiler {
                     }
                 }
             }
+
+            #[cfg(feature = "cpu_frequency")]
+            if let (Some(sample), Some(driver)) = (&self.cpu_frequency, self.drivers.get_mut(&id)) {
+                driver.add_cpu_frequency(sample.mhz, self.max_profilings);
+            }
         }
     }
 
-    pub fn show_profiler(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+    fn show_missing_fields(&self, ui: &mut egui::Ui) {
+        if self.missing_fields.is_empty() {
+            return;
+        }
+
+        compat::warning(
+            ui,
+            &format!(
+                "The daemon doesn't report the {}, which newer versions of PipeWire do",
+                self.missing_fields
+                    .iter()
+                    .copied()
+                    .collect::<Vec<_>>()
+                    .join(" or the ")
+            ),
+        );
+    }
+
+    pub fn set_parse_error(&mut self, id: u32, error: ParseError) {
+        self.parse_error = Some((id, error));
+    }
+
+    /// The globals are for redacting the exports
+    pub fn show_profiler(
+        &mut self,
+        ui: &mut egui::Ui,
+        globals: &GlobalsStore,
+        sx: &backend::Sender,
+    ) {
+        if let Some((id, error)) = self.parse_error {
+            ui.horizontal(|ui| {
+                ui.colored_label(
+                    ui.visuals().error_fg_color,
+                    format!("Samples of profiler {id} were dropped: {error}"),
+                );
+                if ui.small_button("Dismiss").clicked() {
+                    self.parse_error = None;
+                }
+            });
+        }
+
+        self.show_missing_fields(ui);
+
         if ui
             .small_button("Reset")
             .on_hover_text("Clear all profiling data")
             .clicked()
         {
             self.drivers.clear();
+            self.node_drivers.clear();
             self.max_profilings = 250;
             self.selected_driver_id = None;
+            self.pinned_clients.clear();
             self.pause = false;
+            self.parse_error = None;
+            self.missing_fields.clear();
             return;
         }
 
+        ui.collapsing("Record", |ui| {
+            self.recording.show(ui, globals);
+        });
+
         let Some((id, driver)) = ({
             let driver = self
                 .selected_driver_id
@@ -493,6 +1593,21 @@ impl Profiler {
             self.pause = !self.pause;
         }
 
+        ui.collapsing("Compare captures", |ui| {
+            let name = driver.last_profling().map_or_else(
+                || driver.name().unwrap_or("Unnamed driver").to_owned(),
+                |p| {
+                    format!(
+                        "{} quantum {} rate {}",
+                        p.driver.name,
+                        p.clock.duration * i64::from(p.clock.rate.num),
+                        p.clock.rate.denom
+                    )
+                },
+            );
+            self.comparison.show(ui, driver, &name, globals);
+        });
+
         fn profiler_plot(
             ui: &mut egui::Ui,
             heading: &str,
@@ -580,8 +1695,75 @@ impl Profiler {
             });
         });
 
+        #[cfg(feature = "cpu_frequency")]
+        {
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                ui.heading("CPU Frequency")
+                    .on_hover_text("Average frequency of all CPUs when each sample was received");
+                if let Some(sample) = &self.cpu_frequency {
+                    ui.label(format!("Governor: {}", sample.governors.join(", ")));
+                }
+            });
+
+            Plot::new("cpu_frequency")
+                .height(150.)
+                .clamp_grid(true)
+                .allow_zoom(egui::emath::Vec2b::new(true, false))
+                .allow_drag(egui::emath::Vec2b::new(true, false))
+                .label_formatter(|_, value| {
+                    format!("{:.0}MHz\nProcess cycle: {:.0}", value.y, value.x)
+                })
+                .y_axis_formatter(|y, _, _| format!("{}MHz", y.value))
+                .show(ui, |ui| {
+                    ui.line(egui_plot::Line::new(driver.cpu_frequency()).name("CPU Frequency"));
+                });
+        }
+
         ui.separator();
 
+        ui.horizontal(|ui| {
+            ui.menu_button(
+                format!("Pinned followers ({})", self.pinned_clients.len()),
+                |ui| {
+                    if ui
+                        .add_enabled(
+                            !self.pinned_clients.is_empty(),
+                            egui::Button::new("Unpin all"),
+                        )
+                        .clicked()
+                    {
+                        self.pinned_clients.clear();
+                    }
+                    ui.separator();
+                    egui::ScrollArea::vertical()
+                        .max_height(300.)
+                        .show(ui, |ui| {
+                            for client in driver.clients() {
+                                let mut pinned = self.pinned_clients.contains(client.title());
+                                if ui.checkbox(&mut pinned, client.title()).changed() {
+                                    if pinned {
+                                        self.pinned_clients.insert(client.title().to_owned());
+                                    } else {
+                                        self.pinned_clients.remove(client.title());
+                                    }
+                                }
+                            }
+                        });
+                },
+            )
+            .response
+            .on_hover_text("Only plot the timings of the pinned followers");
+        });
+
+        let pinned = &self.pinned_clients;
+        let shown_clients = || {
+            driver
+                .clients()
+                .filter(|c| pinned.is_empty() || pinned.contains(c.title()))
+        };
+
         ui.columns(3, |ui| {
             for (i, (heading, explanation, id, measurement)) in [
                 (
@@ -604,29 +1786,183 @@ impl Profiler {
                 profiler_plot(&mut ui[i], heading, explanation, id, self.max_profilings).show(
                     &mut ui[i],
                     |ui| {
-                        for client in driver.clients() {
+                        for client in shown_clients() {
                             ui.line(egui_plot::Line::new(measurement(client)).name(client.title()));
                         }
                     },
                 );
             }
         });
+
+        ui.separator();
+
+        egui::CollapsingHeader::new("Cycle Timeline")
+            .default_open(true)
+            .show(ui, |ui| {
+                if let Some(profiling) = driver.last_profling() {
+                    show_cycle_timeline(ui, profiling, &self.pinned_clients);
+                } else {
+                    ui.label("No profilings received yet");
+                }
+            });
+
+        ui.separator();
+
+        egui::CollapsingHeader::new("Follower Distributions")
+            .default_open(false)
+            .show(ui, |ui| {
+                show_client_histograms(ui, driver, &mut self.selected_client);
+            });
+    }
+
+    /// The nodes of all drivers, with the profiling of the driver they were last seen under
+    fn show_node_table(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        let drivers = &self.drivers;
+
+        // Forget nodes whose drivers or entries in their drivers are gone
+        self.node_drivers.retain(|id, driver_id| {
+            drivers
+                .get(driver_id)
+                .is_some_and(|d| id == driver_id || d.client(*id).is_some())
+        });
+
+        let mut rows: Vec<_> = self
+            .node_drivers
+            .iter()
+            .filter_map(|(id, driver_id)| {
+                let driver = drivers.get(driver_id)?;
+                let profiling = driver.last_profling()?;
+                let (block, global) = if id == driver_id {
+                    (&profiling.driver, driver.global.upgrade())
+                } else {
+                    let client = driver.client(*id)?;
+                    (client.last_profiling()?, client.global.upgrade())
+                };
+
+                let texts = {
+                    let global = global.as_ref().map(|global| global.borrow());
+                    self.columns
+                        .iter()
+                        .map(|c| c.text(block, profiling, id == driver_id, global.as_deref()))
+                        .collect()
+                };
+
+                Some((texts, block, global))
+            })
+            .collect();
+
+        if rows.is_empty() {
+            ui.label("No profilings received yet");
+            return;
+        }
+
+        let (sort_column, descending) = self.sort;
+        if let Some(i) = self.columns.iter().position(|c| *c == sort_column) {
+            rows.sort_by(|(a, a_block, _), (b, b_block, _)| {
+                let ordering = match (
+                    sort_column.sort_value(a_block, &a[i]),
+                    sort_column.sort_value(b_block, &b[i]),
+                ) {
+                    (Some(a), Some(b)) => a.total_cmp(&b),
+                    // Rows without a number, like nodes that didn't run, go last
+                    (Some(_), None) => std::cmp::Ordering::Less,
+                    (None, Some(_)) => std::cmp::Ordering::Greater,
+                    (None, None) => a[i].cmp(&b[i]),
+                };
+                if descending {
+                    ordering.reverse()
+                } else {
+                    ordering
+                }
+            });
+        }
+
+        egui::ScrollArea::both().show(ui, |ui| {
+            egui::Grid::new("node_table")
+                .striped(true)
+                .num_columns(self.columns.len() + 1)
+                .min_col_width(0.0)
+                .show(ui, |ui| {
+                    ui.label("");
+                    for column in &self.columns {
+                        let sorted = self.sort.0 == *column;
+                        let label = match (sorted, self.sort.1) {
+                            (true, false) => format!("{} ⏶", column.name()),
+                            (true, true) => format!("{} ⏷", column.name()),
+                            (false, _) => column.name().to_owned(),
+                        };
+                        let header = ui.selectable_label(sorted, label);
+                        let clicked = header.clicked();
+                        if let Some(description) = column.description() {
+                            header.on_hover_text(description);
+                        }
+                        if clicked {
+                            self.sort = (*column, sorted && !self.sort.1);
+                        }
+                    }
+                    ui.end_row();
+
+                    for (texts, _, global) in rows {
+                        global_info_button(ui, global.as_ref(), sx);
+                        for text in texts {
+                            ui.label(text);
+                        }
+                        ui.end_row();
+                    }
+                });
+        });
     }
 
     pub fn show_process_viewer(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
-        if ui
-            .small_button("Reset")
-            .on_hover_text("Clear all profiling data")
-            .clicked()
-        {
+        let reset = ui
+            .horizontal(|ui| {
+                ui.menu_button("Columns", |ui| {
+                    for column in Column::ALL {
+                        let mut shown = self.columns.contains(&column);
+                        if ui.checkbox(&mut shown, column.name()).changed() {
+                            // Kept in the order of ALL
+                            let columns = std::mem::take(&mut self.columns);
+                            self.columns = Column::ALL
+                                .into_iter()
+                                .filter(|c| {
+                                    if *c == column {
+                                        shown
+                                    } else {
+                                        columns.contains(c)
+                                    }
+                                })
+                                .collect();
+                        }
+                    }
+                });
+
+                ui.toggle_value(&mut self.flat_table, "Single table")
+                    .on_hover_text("Show the nodes of all drivers in one table that can be sorted");
+
+                ui.small_button("Reset")
+                    .on_hover_text("Clear all profiling data")
+                    .clicked()
+            })
+            .inner;
+
+        if reset {
             self.drivers.clear();
+            self.node_drivers.clear();
             self.selected_driver_id = None;
             self.pause = false;
+            self.missing_fields.clear();
             return;
         }
 
+        self.show_missing_fields(ui);
+
         ui.separator();
 
+        if self.flat_table {
+            self.show_node_table(ui, sx);
+            return;
+        }
+
         fn draw_chart(driver: &Driver, ui: &mut egui::Ui) {
             use egui_plot::{Bar, BarChart};
 
@@ -690,9 +2026,9 @@ impl Profiler {
         }
 
         fn draw_node_block(
+            columns: &[Column],
             block: &NodeBlock,
-            clock: &Clock,
-            info: &Info,
+            profiling: &Profiling,
             driver: bool,
             global: Option<&Rc<RefCell<Global>>>,
             ui: &mut egui::Ui,
@@ -700,111 +2036,75 @@ impl Profiler {
         ) {
             global_info_button(ui, global, sx);
 
-            ui.label(block.id.to_string());
-            ui.label(&block.name);
-
-            // Quantum, Rate
-            if driver {
-                ui.label((clock.duration * i64::from(clock.rate.num)).to_string());
-                ui.label(clock.rate.denom.to_string());
-            } else {
-                for n in [block.latency.num, block.latency.denom] {
-                    if n == 0 {
-                        ui.label("Using driver's");
-                    } else {
-                        ui.label(n.to_string());
-                    }
-                }
-            }
-
-            fn format_to_time(nanos: i64) -> String {
-                let nanos = nanos as f64;
-                if nanos < 1_000_000. {
-                    format!("{:.3}us", nanos / 1000.)
-                } else if nanos < 1_000_000_000. {
-                    format!("{:.4}ms", nanos / 1_000_000.)
-                } else {
-                    format!("{:.6}s", nanos / 1_000_000_000.)
-                }
-            }
-
-            // Waiting
-            if block.awake >= block.signal {
-                ui.label(format_to_time(block.awake - block.signal));
-            } else if block.signal > block.prev_signal {
-                ui.label("Did not wake");
-            } else {
-                ui.label("Was not signaled");
-            };
-
-            // Busy
-            if block.finish >= block.awake {
-                ui.label(format_to_time(block.finish - block.awake));
-            } else if block.awake > block.prev_signal {
-                ui.label("Did not complete");
-            } else {
-                ui.label("Did not start");
-            }
-
-            // Waiting/Quantum, Busy/Quantum
-            let quantum =
-                clock.duration as f64 * f64::from(clock.rate.num) / f64::from(clock.rate.denom);
-            for n in [block.awake - block.signal, block.finish - block.awake] {
-                ui.label(format!("{:.6}", n as f64 / 1_000_000_000. / quantum));
-            }
-
-            // Xruns
-            if let Some(xruns) = block.xrun_count {
-                ui.label(xruns.to_string());
-            } else {
-                ui.label(info.xrun_count.to_string());
+            let global = global.map(|global| global.borrow());
+            for column in columns {
+                ui.label(column.text(block, profiling, driver, global.as_deref()));
             }
         }
 
         self.drivers.retain(|id, driver| {
             if let Some(p) = driver.last_profling() {
-                let keep = ui.horizontal(|ui| {
-                    let keep = !ui.small_button("Delete").clicked();
-                    if let Some(p) = driver.last_profling() {
-                        ui.label(format!("Driver: {} (ID: {id})", &p.driver.name));
-                    } else {
-                        ui.label(format!("Driver ID: {id}"));
-                    }
-                    keep
-                }).inner;
+                let keep = ui
+                    .horizontal(|ui| {
+                        let keep = !ui.small_button("Delete").clicked();
+                        if let Some(p) = driver.last_profling() {
+                            ui.label(format!("Driver: {} (ID: {id})", &p.driver.name));
+                        } else {
+                            ui.label(format!("Driver ID: {id}"));
+                        }
+                        keep
+                    })
+                    .inner;
                 ui.push_id(id, |ui| {
                     egui::ScrollArea::horizontal().show(ui, |ui| {
                         egui::Grid::new("timings")
-                        .striped(true)
-                        .num_columns(10)
-                        .min_col_width(0.0)
-                        .show(ui, |ui| {
-                            ui.label("");
-                            ui.label("ID");
-                            ui.label("Name");
-                            ui.label("Quantum");
-                            ui.label("Rate");
-                            ui.label("Waiting").on_hover_text("Time between when the node was ready to start processing and when it actually started processing");
-                            ui.label("Busy").on_hover_text("Time between when the node started processing and when it finished and woke up the next nodes in the graph");
-                            ui.label("Waiting/Quantum").on_hover_text("A measure of the graph load");
-                            ui.label("Busy/Quantum").on_hover_text("A measure of the load of the driver/node");
-                            ui.label("Xruns");
-                            ui.end_row();
-
-                            draw_node_block(&p.driver, &p.clock, &p.info, true, driver.global.upgrade().as_ref(), ui, sx);
-                            ui.end_row();
-
-                            for (client, nb) in driver.clients().filter_map(|c| c.last_profiling().map(|p| (c.global.upgrade(), p))) {
-                                draw_node_block(nb, &p.clock, &p.info, false, client.as_ref(), ui, sx);
+                            .striped(true)
+                            .num_columns(self.columns.len() + 1)
+                            .min_col_width(0.0)
+                            .show(ui, |ui| {
+                                ui.label("");
+                                for column in &self.columns {
+                                    let header = ui.label(column.name());
+                                    if let Some(description) = column.description() {
+                                        header.on_hover_text(description);
+                                    }
+                                }
                                 ui.end_row();
-                            }
-                        });
+
+                                draw_node_block(
+                                    &self.columns,
+                                    &p.driver,
+                                    p,
+                                    true,
+                                    driver.global.upgrade().as_ref(),
+                                    ui,
+                                    sx,
+                                );
+                                ui.end_row();
+
+                                for (client, nb) in driver.clients().filter_map(|c| {
+                                    c.last_profiling().map(|p| (c.global.upgrade(), p))
+                                }) {
+                                    draw_node_block(
+                                        &self.columns,
+                                        nb,
+                                        p,
+                                        false,
+                                        client.as_ref(),
+                                        ui,
+                                        sx,
+                                    );
+                                    ui.end_row();
+                                }
+                            });
                     });
                 });
 
-                egui::CollapsingHeader::new("Chart").id_source(id).show(ui, |ui| {
-                    draw_chart(driver, ui);
-                });
+                egui::CollapsingHeader::new("Chart")
+                    .id_source(id)
+                    .show(ui, |ui| {
+                        draw_chart(driver, ui);
+                    });
 
                 ui.separator();
 
@@ -815,3 +2115,37 @@ impl Profiler {
         });
     }
 }
+
+#[cfg_attr(feature = "persistence", derive(serde::Serialize, serde::Deserialize))]
+pub struct PersistentData {
+    columns: Vec<Column>,
+    #[cfg_attr(feature = "persistence", serde(default))]
+    flat_table: bool,
+}
+
+impl Profiler {
+    /// Applies saved settings, keeping the profiling data
+    pub fn set_data(&mut self, data: &PersistentData) {
+        self.columns = data.columns.clone();
+        self.flat_table = data.flat_table;
+    }
+}
+
+impl PersistentView for Profiler {
+    type Data = PersistentData;
+
+    fn with_data(data: &Self::Data) -> Self {
+        Self {
+            columns: data.columns.clone(),
+            flat_table: data.flat_table,
+            ..Self::with_max_profilings(250)
+        }
+    }
+
+    fn save_data(&self) -> Option<Self::Data> {
+        Some(PersistentData {
+            columns: self.columns.clone(),
+            flat_table: self.flat_table,
+        })
+    }
+}
@@ -0,0 +1,203 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+use eframe::egui;
+
+use crate::{
+    backend::{self, pods::ParseError, Event, RemoteInfo},
+    ui::{
+        compat,
+        globals_store::{GlobalsStore, ObjectData},
+    },
+};
+
+/// A connection besides the main one, which only has a view of its objects.
+/// The tools, graph and profiler are only for the main connection.
+struct Remote {
+    name: String,
+    handle: backend::Handle,
+    globals: GlobalsStore,
+    connected: bool,
+    // The last pod of the remote that couldn't be parsed
+    parse_error: Option<(u32, ParseError)>,
+}
+
+impl Remote {
+    fn new(name: String) -> Self {
+        Self {
+            handle: backend::Handle::run(
+                RemoteInfo::Regular(name.clone()),
+                Vec::new(),
+                vec![("media.category".to_owned(), "Manager".to_owned())],
+            ),
+            name,
+            globals: GlobalsStore::unjournaled(),
+            connected: true,
+            parse_error: None,
+        }
+    }
+
+    fn process_events(&mut self) {
+        while let Ok(e) = self.handle.rx.try_recv() {
+            match e {
+                Event::GlobalAdded(id, object_type, props) => {
+                    self.globals.add_global(id, object_type, props);
+                }
+                Event::GlobalRemoved(id) => {
+                    self.globals.remove_global(id);
+                }
+                Event::GlobalInfo(id, info) => {
+                    if let Some(global) = self.globals.get_global(id) {
+                        global.borrow_mut().set_info(Some(info));
+                    }
+                }
+                Event::GlobalProperties(id, props) => {
+                    self.globals.set_global_props(id, props);
+                }
+                Event::GlobalParam(id, param_type, index, param) => {
+                    if let Some(global) = self.globals.get_global(id) {
+                        global.borrow_mut().set_param(param_type, index, param);
+                    }
+                }
+                Event::MetadataProperty {
+                    id,
+                    subject,
+                    key,
+                    type_,
+                    value,
+                } => {
+                    self.globals.set_metadata_property(
+                        id,
+                        subject,
+                        key.as_deref(),
+                        type_.as_deref(),
+                        value.as_deref(),
+                    );
+                }
+                Event::ProfilerProfile(samples) => {
+                    self.globals.add_profilings(&samples);
+                }
+                Event::Error { id, error } => self.parse_error = Some((id, error)),
+                Event::ClientPermissions(id, _, perms) => {
+                    if let Some(global) = self.globals.get_global(id) {
+                        if let ObjectData::Client { permissions, .. } =
+                            global.borrow_mut().object_data_mut()
+                        {
+                            *permissions = Some(perms);
+                        }
+                    }
+                }
+                Event::Stop | Event::Disconnected => self.connected = false,
+                Event::Reconnected => {
+                    self.globals = GlobalsStore::unjournaled();
+                    self.parse_error = None;
+                    self.connected = true;
+                }
+                Event::GlobalRestricted { id, message } => {
+                    if let Some(global) = self.globals.get_global(id) {
+                        global.borrow_mut().set_restricted(message);
+                    }
+                }
+                Event::InitialSyncDone => self.globals.initial_sync_done(),
+                Event::ContextProperties(_) | Event::SyncDone(_) => {}
+            }
+        }
+    }
+}
+
+/// Connections to other remotes, each with its own view of its objects
+#[derive(Default)]
+pub struct Remotes {
+    remotes: Vec<Remote>,
+    selected: usize,
+    new_remote: String,
+}
+
+impl Remotes {
+    pub fn process_events(&mut self) {
+        for remote in &mut self.remotes {
+            remote.process_events();
+        }
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            egui::TextEdit::singleline(&mut self.new_remote)
+                .hint_text("Remote name or socket path")
+                .show(ui);
+
+            if ui
+                .add_enabled(!self.new_remote.is_empty(), egui::Button::new("Connect"))
+                .clicked()
+            {
+                self.remotes
+                    .push(Remote::new(std::mem::take(&mut self.new_remote)));
+                self.selected = self.remotes.len() - 1;
+            }
+        });
+
+        let mut close = None;
+        ui.horizontal_wrapped(|ui| {
+            for (i, remote) in self.remotes.iter().enumerate() {
+                ui.selectable_value(&mut self.selected, i, &remote.name);
+                if ui.small_button("❌").on_hover_text("Disconnect").clicked() {
+                    close = Some(i);
+                }
+            }
+        });
+
+        if let Some(i) = close {
+            self.remotes.remove(i);
+            if self.selected >= i {
+                self.selected = self.selected.saturating_sub(1);
+            }
+        }
+
+        ui.separator();
+
+        let Some(remote) = self.remotes.get_mut(self.selected) else {
+            ui.label("Connect to a remote to view its objects alongside the main connection");
+            return;
+        };
+
+        if !remote.connected {
+            ui.colored_label(
+                ui.visuals().error_fg_color,
+                "The connection has ended or is being reestablished. Check the terminal output for errors.",
+            );
+        }
+
+        if let Some((id, error)) = remote.parse_error {
+            ui.horizontal(|ui| {
+                ui.colored_label(
+                    ui.visuals().error_fg_color,
+                    format!("A pod of object {id} couldn't be parsed: {error}"),
+                );
+                if ui.small_button("Dismiss").clicked() {
+                    remote.parse_error = None;
+                }
+            });
+        }
+
+        // The registry views of different remotes have the same object IDs
+        ui.push_id(("remote", self.selected), |ui| {
+            compat::scoped(remote.globals.daemon_version(), || {
+                remote.globals.show(ui, &remote.handle.sx);
+            });
+        });
+        remote.globals.handle_copy_request(ui.ctx());
+    }
+}
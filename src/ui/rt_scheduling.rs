@@ -0,0 +1,266 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet},
+    rc::Rc,
+};
+
+use eframe::egui;
+use pipewire::types::ObjectType;
+
+use crate::{
+    backend,
+    ui::{
+        globals_store::Global,
+        util::{
+            procfs::{self, Thread},
+            uis::global_info_button,
+        },
+        GlobalEvent, ToolPlugin,
+    },
+};
+
+const RT_MODULE: &str = "libpipewire-module-rt";
+
+struct Process {
+    pid: u32,
+    name: String,
+    clients: Vec<Rc<RefCell<Global>>>,
+    threads: std::io::Result<Vec<Thread>>,
+}
+
+impl Process {
+    fn is_realtime(&self) -> bool {
+        self.threads
+            .as_ref()
+            .is_ok_and(|threads| threads.iter().any(|t| t.policy.is_realtime()))
+    }
+}
+
+/// Shows whether the threads of the daemon and the clients have realtime scheduling
+#[derive(Default)]
+pub struct RtScheduling {
+    core: Option<Rc<RefCell<Global>>>,
+    clients: BTreeMap<u32, Rc<RefCell<Global>>>,
+    rt_modules: BTreeSet<u32>,
+
+    daemon: Option<Process>,
+    processes: Vec<Process>,
+}
+
+impl ToolPlugin for RtScheduling {
+    fn label(&self) -> &'static str {
+        "⏰ RT Scheduling"
+    }
+
+    fn description(&self) -> &'static str {
+        "Check whether the daemon and clients have realtime priority"
+    }
+
+    fn interested_in(&self, object_type: &ObjectType) -> bool {
+        matches!(
+            object_type,
+            ObjectType::Core | ObjectType::Client | ObjectType::Module
+        )
+    }
+
+    fn global_event(&mut self, event: GlobalEvent, _: &backend::Sender) {
+        match event {
+            GlobalEvent::Added(global) => match *global.borrow().object_type() {
+                ObjectType::Client => self.add_client(global),
+                ObjectType::Module => self.add_module(global),
+                _ => {}
+            },
+            GlobalEvent::PropertiesChanged(global) => {
+                if *global.borrow().object_type() == ObjectType::Core {
+                    self.set_core(global);
+                }
+            }
+            GlobalEvent::Removed { id, .. } => self.remove_global(id),
+            GlobalEvent::InfoChanged(..) => {}
+        }
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        self.show(ui, sx);
+    }
+}
+
+impl RtScheduling {
+    fn set_core(&mut self, global: &Rc<RefCell<Global>>) {
+        self.core = Some(Rc::clone(global));
+    }
+
+    fn add_client(&mut self, global: &Rc<RefCell<Global>>) {
+        let id = global.borrow().id();
+        self.clients.insert(id, Rc::clone(global));
+    }
+
+    fn add_module(&mut self, global: &Rc<RefCell<Global>>) {
+        let global = global.borrow();
+        if global
+            .props()
+            .get("module.name")
+            .is_some_and(|name| name == RT_MODULE)
+        {
+            self.rt_modules.insert(global.id());
+        }
+    }
+
+    fn remove_global(&mut self, id: u32) {
+        self.clients.remove(&id);
+        self.rt_modules.remove(&id);
+    }
+
+    fn refresh(&mut self) {
+        self.daemon = self.core.as_ref().and_then(|core| {
+            let core = core.borrow();
+            let pid = core.props().get("application.process.id")?.parse().ok()?;
+
+            Some(Process {
+                pid,
+                name: core
+                    .name()
+                    .cloned()
+                    .unwrap_or_else(|| "pipewire".to_owned()),
+                clients: Vec::new(),
+                threads: procfs::threads(pid),
+            })
+        });
+
+        let mut processes: BTreeMap<u32, Process> = BTreeMap::new();
+        for client in self.clients.values() {
+            let Some(pid) = procfs::client_pid(client.borrow().props()) else {
+                continue;
+            };
+
+            // The daemon has clients of its own
+            if self.daemon.as_ref().is_some_and(|d| d.pid == pid) {
+                continue;
+            }
+
+            processes
+                .entry(pid)
+                .or_insert_with(|| Process {
+                    pid,
+                    name: client
+                        .borrow()
+                        .props()
+                        .get("application.name")
+                        .cloned()
+                        .unwrap_or_else(|| format!("Client {}", client.borrow().id())),
+                    clients: Vec::new(),
+                    threads: procfs::threads(pid),
+                })
+                .clients
+                .push(Rc::clone(client));
+        }
+
+        self.processes = processes.into_values().collect();
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        ui.label(
+            "Scheduling information is read from /proc, so it's only accurate \
+            for remotes on the same machine and PID namespace.",
+        );
+
+        if self.rt_modules.is_empty() {
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                format!("{RT_MODULE} is not loaded in the daemon"),
+            );
+        }
+
+        if ui.button("Refresh").clicked() {
+            self.refresh();
+        }
+
+        ui.separator();
+
+        if let Some(daemon) = &self.daemon {
+            if !daemon.is_realtime() {
+                ui.colored_label(
+                    ui.visuals().error_fg_color,
+                    "The daemon has no realtime threads. module-rt may have failed to acquire \
+                    realtime priority, check RLIMIT_RTPRIO, RTKit and the daemon's logs.",
+                );
+            }
+            show_process(ui, daemon, sx);
+        } else if self.core.is_some() {
+            ui.label("Press refresh to read the scheduling of the daemon and the clients");
+        }
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for process in &self.processes {
+                show_process(ui, process, sx);
+            }
+        });
+    }
+}
+
+fn show_process(ui: &mut egui::Ui, process: &Process, sx: &backend::Sender) {
+    let status = match &process.threads {
+        Ok(_) if process.is_realtime() => "Realtime",
+        Ok(_) => "Not realtime",
+        Err(_) => "Unknown",
+    };
+
+    egui::CollapsingHeader::new(format!("{} (PID {}) - {status}", process.name, process.pid))
+        .id_source(process.pid)
+        .show(ui, |ui| {
+            ui.horizontal_wrapped(|ui| {
+                for client in &process.clients {
+                    global_info_button(ui, Some(client), sx);
+                }
+            });
+
+            let threads = match &process.threads {
+                Ok(threads) => threads,
+                Err(e) => {
+                    ui.label(format!("Failed to read the threads: {e}"));
+                    return;
+                }
+            };
+
+            egui::Grid::new(("threads", process.pid))
+                .striped(true)
+                .num_columns(5)
+                .show(ui, |ui| {
+                    ui.label("TID");
+                    ui.label("Name");
+                    ui.label("Policy");
+                    ui.label("RT Priority");
+                    ui.label("Nice");
+                    ui.end_row();
+
+                    for thread in threads {
+                        ui.label(thread.tid.to_string());
+                        ui.label(&thread.name);
+                        if thread.policy.is_realtime() {
+                            ui.strong(thread.policy.as_str());
+                        } else {
+                            ui.label(thread.policy.as_str());
+                        }
+                        ui.label(thread.rt_priority.to_string());
+                        ui.label(thread.nice.to_string());
+                        ui.end_row();
+                    }
+                });
+        });
+}
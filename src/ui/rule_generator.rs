@@ -0,0 +1,320 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write,
+};
+
+use eframe::egui;
+use pipewire::types::ObjectType;
+
+use crate::ui::util::{config, uis::EditableKVList};
+
+// Properties that identify an object rather than configure it
+const IDENTITY_KEYS: [&str; 9] = [
+    "object.id",
+    "object.serial",
+    "object.path",
+    "client.id",
+    "device.id",
+    "factory.id",
+    "node.name",
+    "device.name",
+    "card.profile.device",
+];
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Format {
+    /// WirePlumber 0.5 and later
+    Conf,
+    /// WirePlumber 0.4
+    Lua,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Monitor {
+    Alsa,
+    Bluez,
+    None,
+}
+
+impl Monitor {
+    fn of(props: &BTreeMap<String, String>) -> Self {
+        let api = props
+            .get("device.api")
+            .or_else(|| props.get("factory.name"))
+            .map_or("", String::as_str);
+
+        if api == "alsa" || api.starts_with("api.alsa.") || props.contains_key("api.alsa.path") {
+            Self::Alsa
+        } else if api == "bluez5" || api.starts_with("api.bluez5.") {
+            Self::Bluez
+        } else {
+            Self::None
+        }
+    }
+}
+
+/// Generates WirePlumber rules that persistently apply properties to an object
+#[derive(Default)]
+pub struct RuleGenerator {
+    lua: bool,
+    selected: BTreeSet<String>,
+    user_additions: EditableKVList,
+    status: Option<String>,
+}
+
+impl RuleGenerator {
+    fn format(&self) -> Format {
+        if self.lua {
+            Format::Lua
+        } else {
+            Format::Conf
+        }
+    }
+
+    fn generate(
+        &self,
+        object_type: &ObjectType,
+        monitor: Monitor,
+        match_key: &str,
+        match_value: &str,
+        props: &[(String, String)],
+    ) -> Option<String> {
+        let mut rule = String::new();
+
+        match self.format() {
+            Format::Conf => {
+                let section = match (monitor, object_type) {
+                    (Monitor::Alsa, _) => "monitor.alsa.rules",
+                    (Monitor::Bluez, _) => "monitor.bluez.rules",
+                    (Monitor::None, ObjectType::Device) => "device.rules",
+                    (Monitor::None, _) => "node.rules",
+                };
+
+                writeln!(rule, "{section} = [").ok()?;
+                writeln!(rule, "    {{").ok()?;
+                writeln!(
+                    rule,
+                    "        matches = [ {{ {match_key} = {} }} ]",
+                    config::quote(match_value)
+                )
+                .ok()?;
+                writeln!(rule, "        actions = {{").ok()?;
+                writeln!(rule, "            update-props = {{").ok()?;
+                for (k, v) in props {
+                    writeln!(rule, "                {k} = {}", config::quote(v)).ok()?;
+                }
+                writeln!(rule, "            }}").ok()?;
+                writeln!(rule, "        }}").ok()?;
+                writeln!(rule, "    }}").ok()?;
+                writeln!(rule, "]").ok()?;
+            }
+            Format::Lua => {
+                let table = match monitor {
+                    Monitor::Alsa => "alsa_monitor.rules",
+                    Monitor::Bluez => "bluez_monitor.rules",
+                    // WirePlumber 0.4 only supports rules for monitored objects
+                    Monitor::None => return None,
+                };
+
+                writeln!(rule, "table.insert({table}, {{").ok()?;
+                writeln!(
+                    rule,
+                    "  matches = {{ {{ {{ \"{match_key}\", \"equals\", {} }} }} }},",
+                    config::quote(match_value)
+                )
+                .ok()?;
+                writeln!(rule, "  apply_properties = {{").ok()?;
+                for (k, v) in props {
+                    writeln!(rule, "    [\"{k}\"] = {},", config::quote(v)).ok()?;
+                }
+                writeln!(rule, "  }},").ok()?;
+                writeln!(rule, "}})").ok()?;
+            }
+        }
+
+        Some(rule)
+    }
+
+    fn write(
+        &self,
+        monitor: Monitor,
+        match_value: &str,
+        rule: &str,
+    ) -> std::io::Result<std::path::PathBuf> {
+        let slug = match_value
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+            .collect::<String>();
+
+        let dir = config::user_config_dir()
+            .ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    "Could not determine the configuration directory",
+                )
+            })?
+            .join("wireplumber");
+
+        let (dir, file_name) = match self.format() {
+            Format::Conf => (
+                dir.join("wireplumber.conf.d"),
+                format!("51-coppwr-{slug}.conf"),
+            ),
+            Format::Lua => (
+                dir.join(if monitor == Monitor::Bluez {
+                    "bluetooth.lua.d"
+                } else {
+                    "main.lua.d"
+                }),
+                format!("51-coppwr-{slug}.lua"),
+            ),
+        };
+
+        std::fs::create_dir_all(&dir)?;
+
+        let path = dir.join(file_name);
+        std::fs::write(&path, rule)?;
+
+        Ok(path)
+    }
+
+    /// `adjusted` are properties changed through coppwr that should be part of the rule
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        object_type: &ObjectType,
+        props: &BTreeMap<String, String>,
+        adjusted: Vec<(String, String)>,
+    ) {
+        let match_key = if *object_type == ObjectType::Device {
+            "device.name"
+        } else {
+            "node.name"
+        };
+
+        let Some(match_value) = props.get(match_key) else {
+            ui.label(format!("Rules can't be generated for objects without a {match_key}"));
+            return;
+        };
+
+        let monitor = Monitor::of(props);
+
+        ui.horizontal(|ui| {
+            ui.label("Format");
+            ui.radio_value(&mut self.lua, false, "WirePlumber 0.5 (.conf)");
+            ui.add_enabled_ui(monitor != Monitor::None, |ui| {
+                ui.radio_value(&mut self.lua, true, "WirePlumber 0.4 (Lua)")
+                    .on_disabled_hover_text(
+                        "WirePlumber 0.4 only supports rules for ALSA and Bluetooth objects",
+                    );
+            });
+        });
+
+        if !adjusted.is_empty() {
+            ui.label("Parameters adjusted in coppwr are included");
+        }
+
+        ui.collapsing("Properties to include", |ui| {
+            egui::ScrollArea::vertical()
+                .max_height(200.)
+                .show(ui, |ui| {
+                    for (k, v) in props
+                        .iter()
+                        .filter(|(k, _)| !IDENTITY_KEYS.contains(&k.as_str()))
+                    {
+                        let mut selected = self.selected.contains(k);
+                        if ui
+                            .checkbox(&mut selected, k)
+                            .on_hover_text(v)
+                            .changed()
+                        {
+                            if selected {
+                                self.selected.insert(k.clone());
+                            } else {
+                                self.selected.remove(k);
+                            }
+                        }
+                    }
+                });
+
+            ui.separator();
+
+            ui.label("Additional properties");
+            self.user_additions.show(ui);
+        });
+
+        let mut rule_props = adjusted;
+        for k in &self.selected {
+            if let Some(v) = props.get(k) {
+                if !rule_props.iter().any(|(rk, _)| rk == k) {
+                    rule_props.push((k.clone(), v.clone()));
+                }
+            }
+        }
+        rule_props.extend(
+            self.user_additions
+                .list()
+                .iter()
+                .filter(|(k, _)| !k.is_empty())
+                .cloned(),
+        );
+
+        if rule_props.is_empty() {
+            ui.label("Select properties to include in the rule");
+            return;
+        }
+
+        if self.lua && monitor == Monitor::None {
+            self.lua = false;
+        }
+
+        let Some(mut rule) =
+            self.generate(object_type, monitor, match_key, match_value, &rule_props)
+        else {
+            return;
+        };
+
+        ui.add(
+            egui::TextEdit::multiline(&mut rule)
+                .code_editor()
+                .desired_width(f32::INFINITY),
+        );
+
+        ui.horizontal(|ui| {
+            if ui.button("Copy").clicked() {
+                ui.output_mut(|o| o.copied_text = rule.clone());
+            }
+
+            if ui
+                .button("Save to WirePlumber configuration")
+                .on_hover_text("The rule will be applied after WirePlumber is restarted")
+                .clicked()
+            {
+                self.status = Some(match self.write(monitor, match_value, &rule) {
+                    Ok(path) => format!("Saved to {}", path.display()),
+                    Err(e) => format!("Failed to save the rule: {e}"),
+                });
+            }
+        });
+
+        if let Some(status) = &self.status {
+            ui.label(status);
+        }
+    }
+}
@@ -0,0 +1,67 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+use eframe::egui;
+
+/// The object selected in any view of a connection, which the other views follow.
+/// Each connection has its own since the IDs of their objects are unrelated.
+#[derive(Default)]
+pub struct Selection {
+    selected: Option<u32>,
+    // Whether the selection changed since the views that follow it last checked
+    changed: bool,
+}
+
+impl Selection {
+    pub const fn selected(&self) -> Option<u32> {
+        self.selected
+    }
+
+    pub fn is_selected(&self, id: u32) -> bool {
+        self.selected == Some(id)
+    }
+
+    pub fn select(&mut self, id: Option<u32>) {
+        if self.selected != id {
+            self.selected = id;
+            self.changed = true;
+        }
+    }
+
+    /// Whether the selection changed since the last call, for views that follow it
+    pub fn take_changed(&mut self) -> bool {
+        std::mem::take(&mut self.changed)
+    }
+
+    /// Deselects the object if it's removed
+    pub fn global_removed(&mut self, id: u32) {
+        if self.is_selected(id) {
+            self.select(None);
+        }
+    }
+
+    /// A toggle that selects the object, or deselects it if it's selected
+    pub fn button(&mut self, ui: &mut egui::Ui, id: u32) {
+        let mut selected = self.is_selected(id);
+        if ui
+            .toggle_value(&mut selected, "🎯")
+            .on_hover_text("Select, to highlight it in the graph and follow it in the other views")
+            .changed()
+        {
+            self.select(selected.then_some(id));
+        }
+    }
+}
@@ -0,0 +1,335 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{
+    collections::BTreeMap,
+    time::{Duration, Instant},
+};
+
+use eframe::egui;
+use pipewire::types::ObjectType;
+
+use crate::{
+    backend,
+    ui::{globals_store::GlobalsStore, util::uis::key_val_display, GlobalEvent, ToolPlugin},
+};
+
+#[derive(Clone)]
+struct Object {
+    object_type: String,
+    props: BTreeMap<String, String>,
+}
+
+impl Object {
+    fn label(&self, id: u32) -> String {
+        let object_type = self
+            .object_type
+            .strip_prefix("PipeWire:Interface:")
+            .unwrap_or(&self.object_type);
+
+        let name = ["node.name", "port.name", "client.name", "object.path"]
+            .iter()
+            .find_map(|key| self.props.get(*key));
+
+        match name {
+            Some(name) => format!("{id} {object_type} {name}"),
+            None => format!("{id} {object_type}"),
+        }
+    }
+}
+
+enum Change {
+    Added(Object),
+    Removed,
+    Properties(BTreeMap<String, String>),
+}
+
+struct Entry {
+    // Seconds since the recording started
+    time: f64,
+    id: u32,
+    change: Change,
+}
+
+impl Entry {
+    fn describe(&self) -> String {
+        match &self.change {
+            Change::Added(object) => format!("Added {}", object.label(self.id)),
+            Change::Removed => format!("Removed {}", self.id),
+            Change::Properties(_) => format!("Properties of {} changed", self.id),
+        }
+    }
+}
+
+/// Records the changes of the globals and reconstructs them at any point of the recording
+#[derive(Default)]
+pub struct SessionRecorder {
+    start_request: bool,
+    recording: Option<Instant>,
+
+    // Objects that existed when the recording started
+    baseline: BTreeMap<u32, Object>,
+    entries: Vec<Entry>,
+    duration: f64,
+
+    position: f64,
+    // The objects at the position they were last reconstructed at
+    state: Option<(f64, BTreeMap<u32, Object>)>,
+    filter: String,
+}
+
+impl ToolPlugin for SessionRecorder {
+    fn label(&self) -> &'static str {
+        "⏺ Session Recorder"
+    }
+
+    fn description(&self) -> &'static str {
+        "Record the changes of the objects and step through them"
+    }
+
+    fn interested_in(&self, _: &ObjectType) -> bool {
+        true
+    }
+
+    fn global_event(&mut self, event: GlobalEvent, _: &backend::Sender) {
+        match event {
+            GlobalEvent::Added(global) => {
+                let global = global.borrow();
+                self.global_added(global.id(), global.object_type(), global.props());
+            }
+            GlobalEvent::PropertiesChanged(global) => {
+                let global = global.borrow();
+                self.properties_changed(global.id(), global.props());
+            }
+            GlobalEvent::Removed { id, .. } => self.global_removed(id),
+            GlobalEvent::InfoChanged(..) => {}
+        }
+    }
+
+    fn update(&mut self, globals: &GlobalsStore, _: &backend::Sender) {
+        if self.take_start_request() {
+            self.start(globals);
+        }
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, _: &backend::Sender) {
+        self.show(ui);
+    }
+}
+
+impl SessionRecorder {
+    /// Whether a recording should be started, which requires access to the globals
+    fn take_start_request(&mut self) -> bool {
+        std::mem::take(&mut self.start_request)
+    }
+
+    fn start(&mut self, globals: &GlobalsStore) {
+        self.baseline = globals
+            .globals()
+            .map(|global| {
+                let global = global.borrow();
+                (
+                    global.id(),
+                    Object {
+                        object_type: global.object_type().to_str().to_owned(),
+                        props: global.props().clone(),
+                    },
+                )
+            })
+            .collect();
+        self.entries.clear();
+        self.duration = 0.;
+        self.position = 0.;
+        self.state = None;
+        self.recording = Some(Instant::now());
+    }
+
+    fn push(&mut self, id: u32, change: Change) {
+        if let Some(start) = self.recording {
+            self.entries.push(Entry {
+                time: start.elapsed().as_secs_f64(),
+                id,
+                change,
+            });
+        }
+    }
+
+    fn global_added(
+        &mut self,
+        id: u32,
+        object_type: &ObjectType,
+        props: &BTreeMap<String, String>,
+    ) {
+        self.push(
+            id,
+            Change::Added(Object {
+                object_type: object_type.to_str().to_owned(),
+                props: props.clone(),
+            }),
+        );
+    }
+
+    fn global_removed(&mut self, id: u32) {
+        self.push(id, Change::Removed);
+    }
+
+    fn properties_changed(&mut self, id: u32, props: &BTreeMap<String, String>) {
+        self.push(id, Change::Properties(props.clone()));
+    }
+
+    /// Applies the recorded changes up to `time` to the objects that existed at the start
+    fn state_at(&self, time: f64) -> BTreeMap<u32, Object> {
+        let mut objects = self.baseline.clone();
+
+        for entry in self.entries.iter().take_while(|e| e.time <= time) {
+            match &entry.change {
+                Change::Added(object) => {
+                    objects.insert(entry.id, object.clone());
+                }
+                Change::Removed => {
+                    objects.remove(&entry.id);
+                }
+                Change::Properties(props) => {
+                    if let Some(object) = objects.get_mut(&entry.id) {
+                        object.props.clone_from(props);
+                    }
+                }
+            }
+        }
+
+        objects
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            if let Some(start) = self.recording {
+                self.duration = start.elapsed().as_secs_f64();
+
+                if ui.button("⏹ Stop").clicked() {
+                    self.recording = None;
+                }
+                ui.label(format!(
+                    "Recording, {} changes in {:.0} s",
+                    self.entries.len(),
+                    self.duration
+                ));
+
+                ui.ctx().request_repaint_after(Duration::from_millis(500));
+            } else {
+                self.start_request = ui
+                    .button("⏺ Record")
+                    .on_hover_text("Replaces the previous recording")
+                    .clicked();
+            }
+        });
+
+        if self.recording.is_none() && self.baseline.is_empty() && self.entries.is_empty() {
+            ui.label("Record a session to step through the changes of the objects");
+            return;
+        }
+
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            let previous = self
+                .entries
+                .iter()
+                .rev()
+                .find(|e| e.time < self.position)
+                .map(|e| e.time);
+            if ui
+                .add_enabled(previous.is_some(), egui::Button::new("⏮"))
+                .on_hover_text("Previous change")
+                .clicked()
+            {
+                self.position = previous.unwrap_or_default();
+            }
+
+            let next = self
+                .entries
+                .iter()
+                .find(|e| e.time > self.position)
+                .map(|e| e.time);
+            if ui
+                .add_enabled(next.is_some(), egui::Button::new("⏭"))
+                .on_hover_text("Next change")
+                .clicked()
+            {
+                self.position = next.unwrap_or_default();
+            }
+
+            ui.add(
+                egui::Slider::new(&mut self.position, 0f64..=self.duration)
+                    .suffix(" s")
+                    .max_decimals(3),
+            );
+        });
+
+        if let Some(entry) = self.entries.iter().rev().find(|e| e.time <= self.position) {
+            ui.label(format!("Last change: {}", entry.describe()));
+        }
+
+        ui.collapsing("Changes", |ui| {
+            egui::ScrollArea::vertical()
+                .id_source("changes")
+                .max_height(200.)
+                .show(ui, |ui| {
+                    for entry in &self.entries {
+                        if ui
+                            .selectable_label(
+                                entry.time <= self.position,
+                                format!("{:.3} s - {}", entry.time, entry.describe()),
+                            )
+                            .clicked()
+                        {
+                            self.position = entry.time;
+                        }
+                    }
+                });
+        });
+
+        ui.separator();
+
+        // Rebuilt continuously while recording since the changes keep coming
+        let stale = !matches!(self.state, Some((t, _)) if t == self.position);
+        if stale || self.recording.is_some() {
+            self.state = Some((self.position, self.state_at(self.position)));
+        }
+        let Some((_, objects)) = &self.state else {
+            return;
+        };
+
+        ui.horizontal(|ui| {
+            ui.label(format!("{} objects", objects.len()));
+            egui::TextEdit::singleline(&mut self.filter)
+                .hint_text("Filter")
+                .show(ui);
+        });
+
+        egui::ScrollArea::vertical()
+            .id_source("objects")
+            .show(ui, |ui| {
+                for (id, object) in objects {
+                    let label = object.label(*id);
+                    if !label.contains(self.filter.as_str()) {
+                        continue;
+                    }
+
+                    key_val_display(ui, 100., 300., &label, object.props.iter());
+                }
+            });
+    }
+}
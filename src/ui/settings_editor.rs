@@ -0,0 +1,483 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
+
+use eframe::egui;
+use pipewire::types::ObjectType;
+
+use crate::{
+    backend::{self, ObjectMethod, Request},
+    ui::{
+        alsa_params::{parse_rates, COMMON_RATES},
+        compat::{self, Area},
+        globals_store::Global,
+        journal,
+        util::uis::global_info_button,
+        GlobalEvent, MetadataChange, ToolPlugin,
+    },
+};
+
+const LOG_LEVELS: [&str; 6] = ["None", "Error", "Warning", "Info", "Debug", "Trace"];
+
+// Per-topic levels in the settings metadata need PipeWire 1.0
+fn log_topics_supported() -> bool {
+    !compat::older_than((1, 0, 0))
+}
+
+fn parse_log_level(s: &str) -> Option<usize> {
+    let s = s.trim();
+    s.parse().ok().or_else(|| {
+        ["X", "E", "W", "I", "D", "T"]
+            .iter()
+            .position(|l| s.eq_ignore_ascii_case(l))
+    })
+}
+
+/// A log level in the format of `PIPEWIRE_DEBUG`,
+/// a global level optionally followed by `topic:level` pairs
+struct LogLevels {
+    level: usize,
+    topics: Vec<(String, usize)>,
+}
+
+impl LogLevels {
+    fn parse(s: &str) -> Self {
+        let mut level = 2;
+        let mut topics = Vec::new();
+
+        for part in s.split(',') {
+            match part.split_once(':') {
+                Some((topic, l)) => {
+                    topics.push((topic.trim().to_owned(), parse_log_level(l).unwrap_or(2)));
+                }
+                None => {
+                    if let Some(l) = parse_log_level(part) {
+                        level = l;
+                    }
+                }
+            }
+        }
+
+        Self { level, topics }
+    }
+
+    fn to_value(&self) -> String {
+        let mut value = self.level.to_string();
+        for (topic, level) in &self.topics {
+            value.push_str(&format!(",{topic}:{level}"));
+        }
+        value
+    }
+}
+
+fn log_level_combo(ui: &mut egui::Ui, id_source: impl std::hash::Hash, level: &mut usize) {
+    *level = (*level).min(LOG_LEVELS.len() - 1);
+    egui::ComboBox::from_id_source(id_source)
+        .selected_text(LOG_LEVELS[*level])
+        .show_ui(ui, |ui| {
+            for (i, name) in LOG_LEVELS.iter().enumerate() {
+                ui.selectable_value(level, i, *name);
+            }
+        });
+}
+
+enum Kind {
+    Int {
+        min: u32,
+        max: u32,
+        default: u32,
+        suffix: &'static str,
+    },
+    Rates,
+    LogLevel,
+}
+
+impl Kind {
+    fn default_value(&self) -> String {
+        match self {
+            Self::Int { default, .. } => default.to_string(),
+            Self::Rates => String::from("[ 48000 ]"),
+            Self::LogLevel => String::from("2"),
+        }
+    }
+
+    /// Returns whether the value was changed
+    fn edit(&self, ui: &mut egui::Ui, key: &str, value: &mut String) -> bool {
+        match self {
+            Self::Int {
+                min,
+                max,
+                default,
+                suffix,
+            } => {
+                let mut v: u32 = value.parse().unwrap_or(*default);
+                let changed = ui
+                    .add(
+                        egui::DragValue::new(&mut v)
+                            .clamp_range(*min..=*max)
+                            .suffix(*suffix),
+                    )
+                    .changed();
+                if changed {
+                    *value = v.to_string();
+                }
+                changed
+            }
+            Self::Rates => {
+                let mut rates = parse_rates(value);
+                let mut changed = false;
+                ui.horizontal_wrapped(|ui| {
+                    for rate in COMMON_RATES {
+                        let mut allowed = rates.contains(&rate);
+                        // At least one rate has to be allowed
+                        let enabled = !allowed || rates.len() > 1;
+                        if ui
+                            .add_enabled(
+                                enabled,
+                                egui::SelectableLabel::new(allowed, rate.to_string()),
+                            )
+                            .clicked()
+                        {
+                            allowed = !allowed;
+                            if allowed {
+                                rates.push(rate);
+                                rates.sort_unstable();
+                            } else {
+                                rates.retain(|r| *r != rate);
+                            }
+                            changed = true;
+                        }
+                    }
+                });
+                if changed {
+                    let rates = rates
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    *value = format!("[ {rates} ]");
+                }
+                changed
+            }
+            Self::LogLevel => {
+                let mut levels = LogLevels::parse(value);
+                let before = levels.to_value();
+
+                ui.vertical(|ui| {
+                    log_level_combo(ui, key, &mut levels.level);
+
+                    if !log_topics_supported() {
+                        return;
+                    }
+
+                    egui::CollapsingHeader::new("Topics")
+                        .id_source((key, "topics"))
+                        .show(ui, |ui| {
+                            let mut i = 0;
+                            levels.topics.retain_mut(|(topic, level)| {
+                                i += 1;
+                                ui.horizontal(|ui| {
+                                    ui.add(
+                                        egui::TextEdit::singleline(topic)
+                                            .hint_text("Topic, e.g. mod.*")
+                                            .desired_width(120.),
+                                    );
+                                    // These separate the topics in the value
+                                    topic.retain(|c| c != ',' && c != ':');
+                                    log_level_combo(ui, (key, i), level);
+                                    !ui.small_button("Delete").clicked()
+                                })
+                                .inner
+                            });
+
+                            if ui.small_button("Add").clicked() {
+                                levels.topics.push((String::new(), 4));
+                            }
+                        })
+                        .header_response
+                        .on_hover_text("Log levels for specific topics. Wildcards are supported.");
+                });
+
+                let after = levels.to_value();
+                let changed = after != before;
+                if changed {
+                    *value = after;
+                }
+                changed
+            }
+        }
+    }
+}
+
+struct Setting {
+    key: &'static str,
+    label: &'static str,
+    description: &'static str,
+    kind: Kind,
+}
+
+const SETTINGS: [Setting; 7] = [
+    Setting {
+        key: "clock.rate",
+        label: "Rate",
+        description: "The default sample rate of the graph",
+        kind: Kind::Int {
+            min: 8000,
+            max: 768_000,
+            default: 48000,
+            suffix: " Hz",
+        },
+    },
+    Setting {
+        key: "clock.allowed-rates",
+        label: "Allowed rates",
+        description: "Rates the graph can switch to, to avoid resampling",
+        kind: Kind::Rates,
+    },
+    Setting {
+        key: "clock.quantum",
+        label: "Quantum",
+        description: "The default number of samples processed in one cycle",
+        kind: Kind::Int {
+            min: 4,
+            max: 8192,
+            default: 1024,
+            suffix: " samples",
+        },
+    },
+    Setting {
+        key: "clock.min-quantum",
+        label: "Min. quantum",
+        description: "The smallest quantum clients can request",
+        kind: Kind::Int {
+            min: 4,
+            max: 8192,
+            default: 32,
+            suffix: " samples",
+        },
+    },
+    Setting {
+        key: "clock.max-quantum",
+        label: "Max. quantum",
+        description: "The largest quantum clients can request",
+        kind: Kind::Int {
+            min: 4,
+            max: 8192,
+            default: 2048,
+            suffix: " samples",
+        },
+    },
+    Setting {
+        key: "clock.force-rate",
+        label: "Forced rate",
+        description: "Forces the graph to run at this rate. 0 disables forcing.",
+        kind: Kind::Int {
+            min: 0,
+            max: 768_000,
+            default: 0,
+            suffix: " Hz",
+        },
+    },
+    Setting {
+        key: "log.level",
+        label: "Log level",
+        description: "The log level of the PipeWire daemon. \
+            Changes take effect immediately, without restarting the daemon.",
+        kind: Kind::LogLevel,
+    },
+];
+
+/// Typed editor for the `settings` metadata object
+#[derive(Default)]
+pub struct SettingsEditor {
+    metadata: Option<Rc<RefCell<Global>>>,
+    // Live values of the settings
+    values: BTreeMap<String, String>,
+    // Edited values that have not been set yet
+    drafts: BTreeMap<&'static str, String>,
+}
+
+impl ToolPlugin for SettingsEditor {
+    fn label(&self) -> &'static str {
+        "⚙ Settings"
+    }
+
+    fn description(&self) -> &'static str {
+        "Edit the daemon's clock and log settings"
+    }
+
+    fn interested_in(&self, object_type: &ObjectType) -> bool {
+        *object_type == ObjectType::Metadata
+    }
+
+    fn global_event(&mut self, event: GlobalEvent, _: &backend::Sender) {
+        match event {
+            GlobalEvent::Added(global) => self.add_metadata(global),
+            GlobalEvent::Removed { id, .. } => self.remove_metadata(id),
+            GlobalEvent::PropertiesChanged(_) | GlobalEvent::InfoChanged(..) => {}
+        }
+    }
+
+    fn metadata_property(&mut self, change: MetadataChange, _: &backend::Sender) {
+        let id = change.metadata.borrow().id();
+        match change.key {
+            Some(key) => self.set_property(id, change.subject, key, change.value),
+            None => self.clear_properties(id),
+        }
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        self.show(ui, sx);
+    }
+}
+
+impl SettingsEditor {
+    fn add_metadata(&mut self, global: &Rc<RefCell<Global>>) {
+        if global
+            .borrow()
+            .props()
+            .get("metadata.name")
+            .is_some_and(|name| name == "settings")
+        {
+            self.metadata = Some(Rc::clone(global));
+            self.values.clear();
+        }
+    }
+
+    fn is_settings(&self, id: u32) -> bool {
+        self.metadata
+            .as_ref()
+            .is_some_and(|metadata| metadata.borrow().id() == id)
+    }
+
+    fn remove_metadata(&mut self, id: u32) {
+        if self.is_settings(id) {
+            self.metadata = None;
+            self.values.clear();
+            self.drafts.clear();
+        }
+    }
+
+    fn set_property(&mut self, id: u32, subject: u32, key: &str, value: Option<&str>) {
+        if subject != 0 || !self.is_settings(id) {
+            return;
+        }
+
+        match value {
+            Some(value) => {
+                self.values.insert(key.to_owned(), value.to_owned());
+            }
+            None => {
+                self.values.remove(key);
+            }
+        }
+    }
+
+    fn clear_properties(&mut self, id: u32) {
+        if self.is_settings(id) {
+            self.values.clear();
+        }
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        let Some(metadata) = self.metadata.as_ref() else {
+            ui.label("The settings metadata object could not be found");
+            return;
+        };
+
+        let id = metadata.borrow().id();
+
+        ui.horizontal(|ui| {
+            global_info_button(ui, Some(metadata), sx);
+            ui.label(format!("ID: {id}"));
+        });
+
+        compat::show_warnings(ui, Area::Settings);
+
+        ui.separator();
+
+        egui::Grid::new("settings")
+            .num_columns(4)
+            .striped(true)
+            .show(ui, |ui| {
+                for setting in &SETTINGS {
+                    let current = self.values.get(setting.key);
+
+                    ui.label(setting.label).on_hover_text(format!(
+                        "{}\nKey: {}\nDefault: {}",
+                        setting.description,
+                        setting.key,
+                        setting.kind.default_value()
+                    ));
+
+                    let mut value = self
+                        .drafts
+                        .get(setting.key)
+                        .or(current)
+                        .cloned()
+                        .unwrap_or_else(|| setting.kind.default_value());
+                    if setting.kind.edit(ui, setting.key, &mut value) {
+                        self.drafts.insert(setting.key, value);
+                    }
+
+                    ui.label(current.map_or("Not set", String::as_str))
+                        .on_hover_text("Current value");
+
+                    ui.horizontal(|ui| {
+                        let draft = self.drafts.get(setting.key);
+
+                        ui.add_enabled_ui(draft.is_some(), |ui| {
+                            if ui.small_button("Set").clicked() {
+                                if let Some(draft) = self.drafts.remove(setting.key) {
+                                    sx.send(Request::CallObjectMethod(
+                                        id,
+                                        ObjectMethod::MetadataSetProperty {
+                                            subject: 0,
+                                            key: setting.key.to_owned(),
+                                            type_: None,
+                                            value: Some(draft),
+                                        },
+                                    ))
+                                    .ok();
+                                }
+                            }
+                            if ui.small_button("Discard").clicked() {
+                                self.drafts.remove(setting.key);
+                            }
+                        });
+
+                        if ui
+                            .small_button("Revert")
+                            .on_hover_text("Revert to the value from the configuration")
+                            .clicked()
+                        {
+                            self.drafts.remove(setting.key);
+                            journal::remove_metadata_property(
+                                sx,
+                                id,
+                                0,
+                                setting.key.to_owned(),
+                                None,
+                            );
+                        }
+                    });
+
+                    ui.end_row();
+                }
+            });
+    }
+}
@@ -0,0 +1,120 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::cell::Cell;
+
+use eframe::egui::{self, Key, KeyboardShortcut, Modifiers};
+
+// Requests from the shortcuts to the views that handle them when they're shown
+thread_local! {
+    static FOCUS_SEARCH: Cell<bool> = const { Cell::new(false) };
+}
+
+const SEARCH: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::F);
+const DESTROY: KeyboardShortcut = KeyboardShortcut::new(Modifiers::NONE, Key::Delete);
+const TAB_KEYS: [Key; 9] = [
+    Key::Num1,
+    Key::Num2,
+    Key::Num3,
+    Key::Num4,
+    Key::Num5,
+    Key::Num6,
+    Key::Num7,
+    Key::Num8,
+    Key::Num9,
+];
+
+/// The shortcuts and what they do, for the help
+pub const LIST: [(&str, &str); 6] = [
+    ("Ctrl+F", "Search the globals"),
+    ("Del", "Destroy the selected object"),
+    ("Ctrl+1…9", "Switch to the tab at the position"),
+    ("↑/↓", "Select the previous/next object in the globals list"),
+    ("Ctrl+Z", "Undo"),
+    ("Ctrl+Shift+Z", "Redo"),
+];
+
+pub enum Action {
+    Search,
+    Destroy,
+    /// The index of the tab among all tabs
+    SwitchTab(usize),
+}
+
+/// Consumes the pressed shortcut, if any.
+/// Only searching works while typing, the others are left to the text field.
+pub fn take_action(ctx: &egui::Context) -> Option<Action> {
+    let typing = ctx.wants_keyboard_input();
+
+    ctx.input_mut(|i| {
+        if i.consume_shortcut(&SEARCH) {
+            return Some(Action::Search);
+        }
+
+        if typing {
+            return None;
+        }
+
+        if i.consume_shortcut(&DESTROY) {
+            return Some(Action::Destroy);
+        }
+
+        TAB_KEYS
+            .iter()
+            .position(|&key| i.consume_key(Modifiers::COMMAND, key))
+            .map(Action::SwitchTab)
+    })
+}
+
+pub fn request_search_focus() {
+    FOCUS_SEARCH.with(|focus| focus.set(true));
+}
+
+/// Whether the search field should take the keyboard focus
+pub fn take_search_focus() -> bool {
+    FOCUS_SEARCH.with(|focus| focus.replace(false))
+}
+
+/// Consumes the up and down arrows, returning the step to move the selection of a list by
+pub fn list_step(ctx: &egui::Context) -> Option<isize> {
+    if ctx.wants_keyboard_input() {
+        return None;
+    }
+
+    ctx.input_mut(|i| {
+        if i.consume_key(Modifiers::NONE, Key::ArrowUp) {
+            Some(-1)
+        } else if i.consume_key(Modifiers::NONE, Key::ArrowDown) {
+            Some(1)
+        } else {
+            None
+        }
+    })
+}
+
+/// Lists the shortcuts
+pub fn show_list(ui: &mut egui::Ui) {
+    egui::Grid::new("shortcuts")
+        .num_columns(2)
+        .striped(true)
+        .show(ui, |ui| {
+            for (keys, description) in LIST {
+                ui.monospace(keys);
+                ui.label(description);
+                ui.end_row();
+            }
+        });
+}
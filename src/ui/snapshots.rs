@@ -0,0 +1,401 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    path::PathBuf,
+    time::SystemTime,
+};
+
+use eframe::egui;
+
+use crate::{
+    backend,
+    pw_dump::{self, DumpedObject},
+    redact::Redactor,
+    ui::{
+        globals_store::GlobalsStore,
+        util::{
+            config,
+            time::{format_timestamp, unix_secs},
+            uis::key_val_display,
+        },
+        ToolPlugin,
+    },
+};
+
+const NODE: &str = "PipeWire:Interface:Node";
+const LINK: &str = "PipeWire:Interface:Link";
+
+fn snapshots_dir() -> Option<PathBuf> {
+    config::user_data_dir().map(|dir| dir.join("coppwr").join("snapshots"))
+}
+
+fn short_type(object_type: &str) -> &str {
+    object_type
+        .strip_prefix("PipeWire:Interface:")
+        .unwrap_or(object_type)
+}
+
+struct Snapshot {
+    path: PathBuf,
+    // Seconds since the Unix epoch, from the file name
+    timestamp: Option<u64>,
+    objects: Option<Vec<DumpedObject>>,
+}
+
+impl Snapshot {
+    fn load(path: PathBuf) -> Self {
+        let timestamp = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(|stem| stem.strip_prefix("snapshot-"))
+            .and_then(|secs| secs.parse().ok());
+
+        Self {
+            objects: std::fs::read_to_string(&path)
+                .ok()
+                .and_then(|text| pw_dump::parse(&text)),
+            timestamp,
+            path,
+        }
+    }
+
+    fn title(&self) -> String {
+        self.timestamp.map_or_else(
+            || {
+                self.path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default()
+            },
+            format_timestamp,
+        )
+    }
+
+    fn count(&self, object_type: &str) -> usize {
+        self.objects
+            .iter()
+            .flatten()
+            .filter(|o| o.object_type == object_type)
+            .count()
+    }
+}
+
+/// Draws the nodes and links of a snapshot with sources on the left and sinks on the right
+#[allow(clippy::cast_precision_loss)]
+fn thumbnail(ui: &mut egui::Ui, objects: &[DumpedObject]) {
+    let (response, painter) = ui.allocate_painter(egui::vec2(160., 80.), egui::Sense::hover());
+    let rect = response.rect;
+    painter.rect_filled(rect, 2., ui.visuals().extreme_bg_color);
+
+    let mut columns: [Vec<u32>; 3] = Default::default();
+    for node in objects.iter().filter(|o| o.object_type == NODE) {
+        let class = node.props.get("media.class").map_or("", String::as_str);
+        let column = if class.contains("Source") || class.starts_with("Stream/Output") {
+            0
+        } else if class.contains("Sink") || class.starts_with("Stream/Input") {
+            2
+        } else {
+            1
+        };
+        columns[column].push(node.id);
+    }
+
+    let mut positions = HashMap::new();
+    for (c, column) in columns.iter().enumerate() {
+        for (i, id) in column.iter().enumerate() {
+            let x = rect.left() + rect.width() * (c as f32 * 2. + 1.) / 6.;
+            let y = rect.top() + rect.height() * (i as f32 + 1.) / (column.len() as f32 + 1.);
+            positions.insert(*id, egui::pos2(x, y));
+        }
+    }
+
+    let stroke = egui::Stroke::new(1., ui.visuals().weak_text_color());
+    for link in objects.iter().filter(|o| o.object_type == LINK) {
+        if let (Some(from), Some(to)) = (
+            link.link_node(true).and_then(|id| positions.get(&id)),
+            link.link_node(false).and_then(|id| positions.get(&id)),
+        ) {
+            painter.line_segment([*from, *to], stroke);
+        }
+    }
+
+    for pos in positions.values() {
+        painter.circle_filled(*pos, 2., ui.visuals().strong_text_color());
+    }
+}
+
+fn object_label(object: &DumpedObject) -> String {
+    format!(
+        "{} {} {}",
+        object.id,
+        short_type(&object.object_type),
+        object.name().unwrap_or_default()
+    )
+}
+
+/// Shows the objects that were added, removed or had their properties changed from `a` to `b`
+fn show_diff(ui: &mut egui::Ui, a: &[DumpedObject], b: &[DumpedObject]) {
+    let a: BTreeMap<u32, &DumpedObject> = a.iter().map(|o| (o.id, o)).collect();
+    let b: BTreeMap<u32, &DumpedObject> = b.iter().map(|o| (o.id, o)).collect();
+
+    let mut differences = 0;
+    for id in a.keys().chain(b.keys()).collect::<BTreeSet<_>>() {
+        let (old, new) = (a.get(id), b.get(id));
+
+        // IDs are reused, so a different object with the same ID is a removal and an addition
+        let replaced = old.zip(new).is_some_and(|(old, new)| {
+            old.object_type != new.object_type || old.name() != new.name()
+        });
+
+        if let Some(old) = old.filter(|_| new.is_none() || replaced) {
+            ui.colored_label(
+                ui.visuals().error_fg_color,
+                format!("- {}", object_label(old)),
+            );
+            differences += 1;
+        }
+        if let Some(new) = new.filter(|_| old.is_none() || replaced) {
+            ui.colored_label(
+                egui::Color32::from_rgb(0, 200, 0),
+                format!("+ {}", object_label(new)),
+            );
+            differences += 1;
+        }
+
+        let (Some(old), Some(new), false) = (old, new, replaced) else {
+            continue;
+        };
+
+        let changed: Vec<_> = old
+            .props
+            .keys()
+            .chain(new.props.keys())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .filter(|k| old.props.get(*k) != new.props.get(*k))
+            .map(|k| {
+                let value = |o: &DumpedObject| o.props.get(k).cloned().unwrap_or_default();
+                (k.clone(), format!("{} → {}", value(old), value(new)))
+            })
+            .collect();
+
+        if !changed.is_empty() {
+            key_val_display(
+                ui,
+                100.,
+                300.,
+                &format!("~ {}", object_label(new)),
+                changed.into_iter(),
+            );
+            differences += 1;
+        }
+    }
+
+    if differences == 0 {
+        ui.label("No differences");
+    }
+}
+
+/// Lists saved snapshots of the objects and allows viewing and comparing them
+#[derive(Default)]
+pub struct Snapshots {
+    snapshots: Vec<Snapshot>,
+    loaded: bool,
+
+    take: bool,
+    opened: Option<usize>,
+    diff: [Option<usize>; 2],
+    error: Option<String>,
+}
+
+impl ToolPlugin for Snapshots {
+    fn label(&self) -> &'static str {
+        "📸 Snapshots"
+    }
+
+    fn description(&self) -> &'static str {
+        "Save, view and compare snapshots of the objects"
+    }
+
+    fn update(&mut self, globals: &GlobalsStore, _: &backend::Sender) {
+        if self.take_snapshot_request() {
+            self.take_snapshot(globals);
+        }
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, _: &backend::Sender) {
+        self.show(ui);
+    }
+}
+
+impl Snapshots {
+    /// Whether a snapshot should be taken, which requires access to the globals
+    fn take_snapshot_request(&mut self) -> bool {
+        std::mem::take(&mut self.take)
+    }
+
+    fn take_snapshot(&mut self, globals: &GlobalsStore) {
+        let Some(dir) = snapshots_dir() else {
+            self.error = Some(String::from("Could not determine the data directory"));
+            return;
+        };
+
+        let secs = unix_secs(SystemTime::now());
+
+        // Snapshots stay on this machine so they aren't redacted
+        let json = pw_dump::dump(
+            globals,
+            |id| globals.metadata_properties(id),
+            &Redactor::disabled(),
+        );
+
+        self.error = std::fs::create_dir_all(&dir)
+            .and_then(|()| std::fs::write(dir.join(format!("snapshot-{secs}.json")), json))
+            .err()
+            .map(|e| format!("Failed to save the snapshot: {e}"));
+
+        self.refresh();
+    }
+
+    fn refresh(&mut self) {
+        self.loaded = true;
+        self.opened = None;
+        self.diff = [None; 2];
+
+        let Some(entries) = snapshots_dir().and_then(|dir| std::fs::read_dir(dir).ok()) else {
+            self.snapshots.clear();
+            return;
+        };
+
+        self.snapshots = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .map(Snapshot::load)
+            .collect();
+        self.snapshots
+            .sort_by_key(|s| std::cmp::Reverse(s.timestamp));
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui) {
+        if !self.loaded {
+            self.refresh();
+        }
+
+        ui.horizontal(|ui| {
+            self.take = ui
+                .button("Take snapshot")
+                .on_hover_text("Save the current objects")
+                .clicked();
+            if ui.button("Refresh").clicked() {
+                self.refresh();
+            }
+        });
+
+        if let Some(dir) = snapshots_dir() {
+            ui.label(format!("Stored in {}", dir.display()));
+        }
+
+        if let Some(e) = &self.error {
+            ui.colored_label(ui.visuals().error_fg_color, e);
+        }
+
+        ui.separator();
+
+        let mut delete = None;
+        egui::Grid::new("snapshots")
+            .num_columns(3)
+            .striped(true)
+            .show(ui, |ui| {
+                for (i, snapshot) in self.snapshots.iter().enumerate() {
+                    match &snapshot.objects {
+                        Some(objects) => thumbnail(ui, objects),
+                        None => {
+                            ui.label("Unreadable");
+                        }
+                    }
+
+                    ui.vertical(|ui| {
+                        ui.strong(snapshot.title());
+                        ui.label(format!(
+                            "{} objects, {} nodes, {} links",
+                            snapshot.objects.as_ref().map_or(0, Vec::len),
+                            snapshot.count(NODE),
+                            snapshot.count(LINK)
+                        ));
+                    });
+
+                    ui.vertical(|ui| {
+                        ui.add_enabled_ui(snapshot.objects.is_some(), |ui| {
+                            ui.horizontal(|ui| {
+                                ui.selectable_value(&mut self.opened, Some(i), "Open");
+                                ui.selectable_value(&mut self.diff[0], Some(i), "Diff A")
+                                    .on_hover_text("Compare from this snapshot");
+                                ui.selectable_value(&mut self.diff[1], Some(i), "Diff B")
+                                    .on_hover_text("Compare to this snapshot");
+                            });
+                        });
+                        if ui.button("🗑 Delete").clicked() {
+                            delete = Some(i);
+                        }
+                    });
+
+                    ui.end_row();
+                }
+            });
+
+        if let Some(i) = delete {
+            let snapshot = self.snapshots.remove(i);
+            self.error = std::fs::remove_file(&snapshot.path)
+                .err()
+                .map(|e| format!("Failed to delete the snapshot: {e}"));
+            self.opened = None;
+            self.diff = [None; 2];
+        }
+
+        if self.snapshots.is_empty() {
+            ui.label("No snapshots");
+        }
+
+        let objects = |i: Option<usize>| {
+            self.snapshots
+                .get(i?)
+                .and_then(|s| Some((s.title(), s.objects.as_deref()?)))
+        };
+
+        if let [Some((a_title, a)), Some((b_title, b))] = self.diff.map(objects) {
+            ui.separator();
+            ui.heading(format!("{a_title} → {b_title}"));
+            show_diff(ui, a, b);
+        }
+
+        if let Some((title, objects)) = objects(self.opened) {
+            ui.separator();
+            ui.heading(title);
+            for object in objects {
+                key_val_display(
+                    ui,
+                    100.,
+                    300.,
+                    &object_label(object),
+                    object.info.iter().chain(&object.props),
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,483 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::fmt::Write;
+
+use eframe::egui;
+
+use crate::{
+    backend::{self, Request},
+    ui::{util::config, ToolPlugin},
+};
+
+const FILTER_CHAIN_MODULE: &str = "libpipewire-module-filter-chain";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Method {
+    /// The `sofa` plugin's spatializer
+    Spatializer,
+    /// The builtin convolver with a HeSuVi-style 14 channel HRIR
+    Convolver,
+}
+
+impl Method {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Spatializer => "Spatializer (SOFA)",
+            Self::Convolver => "Convolver (HeSuVi HRIR)",
+        }
+    }
+
+    const fn description(self) -> &'static str {
+        match self {
+            Self::Spatializer => {
+                "Uses a SOFA file containing HRTFs to place each channel in a virtual position. \
+                Requires PipeWire to be built with libmysofa."
+            }
+            Self::Convolver => {
+                "Convolves each channel with a pair of impulse responses from a \
+                14 channel HeSuVi WAV file. Works with any PipeWire build."
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Speaker {
+    position: &'static str,
+    azimuth: f32,
+    // Channels of the HeSuVi file used for the left and right ear respectively
+    hesuvi_channels: (u32, u32),
+}
+
+const FL: Speaker = Speaker {
+    position: "FL",
+    azimuth: 30.,
+    hesuvi_channels: (0, 1),
+};
+const FR: Speaker = Speaker {
+    position: "FR",
+    azimuth: 330.,
+    hesuvi_channels: (8, 7),
+};
+const FC: Speaker = Speaker {
+    position: "FC",
+    azimuth: 0.,
+    hesuvi_channels: (6, 13),
+};
+const LFE: Speaker = Speaker {
+    position: "LFE",
+    azimuth: 0.,
+    hesuvi_channels: (6, 13),
+};
+const RL: Speaker = Speaker {
+    position: "RL",
+    azimuth: 150.,
+    hesuvi_channels: (4, 5),
+};
+const RR: Speaker = Speaker {
+    position: "RR",
+    azimuth: 210.,
+    hesuvi_channels: (12, 11),
+};
+const SL: Speaker = Speaker {
+    position: "SL",
+    azimuth: 90.,
+    hesuvi_channels: (2, 3),
+};
+const SR: Speaker = Speaker {
+    position: "SR",
+    azimuth: 270.,
+    hesuvi_channels: (10, 9),
+};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Layout {
+    Stereo,
+    Surround51,
+    Surround71,
+}
+
+impl Layout {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Stereo => "Stereo",
+            Self::Surround51 => "5.1 Surround",
+            Self::Surround71 => "7.1 Surround",
+        }
+    }
+
+    const fn speakers(self) -> &'static [Speaker] {
+        match self {
+            Self::Stereo => &[FL, FR],
+            Self::Surround51 => &[FL, FR, FC, LFE, RL, RR],
+            Self::Surround71 => &[FL, FR, FC, LFE, RL, RR, SL, SR],
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Step {
+    Method,
+    File,
+    Layout,
+    Review,
+}
+
+impl Step {
+    const fn next(self) -> Option<Self> {
+        match self {
+            Self::Method => Some(Self::File),
+            Self::File => Some(Self::Layout),
+            Self::Layout => Some(Self::Review),
+            Self::Review => None,
+        }
+    }
+
+    const fn previous(self) -> Option<Self> {
+        match self {
+            Self::Method => None,
+            Self::File => Some(Self::Method),
+            Self::Layout => Some(Self::File),
+            Self::Review => Some(Self::Layout),
+        }
+    }
+}
+
+/// Guides the user through setting up a virtual surround sink with the filter-chain module
+pub struct SpatializerWizard {
+    step: Step,
+
+    method: Method,
+    file: String,
+    layout: Layout,
+    // Per speaker azimuth overrides for the spatializer, in the order of the layout's speakers
+    azimuths: Vec<f32>,
+    radius: f32,
+    sink_name: String,
+
+    status: Option<String>,
+}
+
+impl Default for SpatializerWizard {
+    fn default() -> Self {
+        Self {
+            step: Step::Method,
+
+            method: Method::Spatializer,
+            file: String::new(),
+            layout: Layout::Surround71,
+            azimuths: Layout::Surround71
+                .speakers()
+                .iter()
+                .map(|s| s.azimuth)
+                .collect(),
+            radius: 3.,
+            sink_name: String::from("Virtual Surround Sink"),
+
+            status: None,
+        }
+    }
+}
+
+impl ToolPlugin for SpatializerWizard {
+    fn label(&self) -> &'static str {
+        "🎧 Virtual Surround Wizard"
+    }
+
+    fn description(&self) -> &'static str {
+        "Set up a virtual surround sink"
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        self.show(ui, sx);
+    }
+}
+
+impl SpatializerWizard {
+    fn module_args(&self) -> String {
+        let speakers = self.layout.speakers();
+
+        let mut nodes = String::new();
+        let mut links = String::new();
+        let mut inputs = Vec::with_capacity(speakers.len());
+
+        for (i, (speaker, azimuth)) in speakers.iter().zip(&self.azimuths).enumerate() {
+            let mixer_input = i + 1;
+            let pos = speaker.position;
+
+            match self.method {
+                Method::Spatializer => {
+                    writeln!(
+                        nodes,
+                        "            {{ type = sofa label = spatializer name = sp{pos}\n                \
+                        config = {{ filename = {} }}\n                \
+                        control = {{ \"Azimuth\" = {azimuth:.1} \"Elevation\" = 0.0 \"Radius\" = {:.1} }} }}",
+                        config::quote(&self.file),
+                        self.radius
+                    )
+                    .ok();
+
+                    writeln!(
+                        links,
+                        "            {{ output = \"sp{pos}:Out L\" input = \"mixL:In {mixer_input}\" }}\n            \
+                        {{ output = \"sp{pos}:Out R\" input = \"mixR:In {mixer_input}\" }}"
+                    )
+                    .ok();
+
+                    inputs.push(format!("\"sp{pos}:In\""));
+                }
+                Method::Convolver => {
+                    let (left, right) = speaker.hesuvi_channels;
+                    for (ear, channel) in [("L", left), ("R", right)] {
+                        writeln!(
+                            nodes,
+                            "            {{ type = builtin label = convolver name = conv{pos}_{ear}\n                \
+                            config = {{ filename = {} channel = {channel} }} }}",
+                            config::quote(&self.file)
+                        )
+                        .ok();
+
+                        writeln!(
+                            links,
+                            "            {{ output = \"conv{pos}_{ear}:Out\" input = \"mix{ear}:In {mixer_input}\" }}"
+                        )
+                        .ok();
+                    }
+
+                    // Both convolvers of a speaker are fed the same input
+                    nodes.push_str(&format!(
+                        "            {{ type = builtin label = copy name = copy{pos} }}\n"
+                    ));
+                    links.push_str(&format!(
+                        "            {{ output = \"copy{pos}:Out\" input = \"conv{pos}_L:In\" }}\n            \
+                        {{ output = \"copy{pos}:Out\" input = \"conv{pos}_R:In\" }}\n"
+                    ));
+
+                    inputs.push(format!("\"copy{pos}:In\""));
+                }
+            }
+        }
+
+        nodes.push_str(
+            "            { type = builtin label = mixer name = mixL }\n            \
+            { type = builtin label = mixer name = mixR }\n",
+        );
+
+        let positions = speakers
+            .iter()
+            .map(|s| s.position)
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        format!(
+            "{{\n    \
+            node.description = {description}\n    \
+            media.name = {description}\n    \
+            filter.graph = {{\n        \
+            nodes = [\n{nodes}        ]\n        \
+            links = [\n{links}        ]\n        \
+            inputs = [ {inputs} ]\n        \
+            outputs = [ \"mixL:Out\" \"mixR:Out\" ]\n    \
+            }}\n    \
+            capture.props = {{\n        \
+            node.name = \"effect_input.coppwr_surround\"\n        \
+            media.class = Audio/Sink\n        \
+            audio.channels = {channels}\n        \
+            audio.position = [ {positions} ]\n    \
+            }}\n    \
+            playback.props = {{\n        \
+            node.name = \"effect_output.coppwr_surround\"\n        \
+            node.passive = true\n        \
+            audio.channels = 2\n        \
+            audio.position = [ FL FR ]\n    \
+            }}\n\
+            }}",
+            description = config::quote(&self.sink_name),
+            inputs = inputs.join(" "),
+            channels = speakers.len(),
+        )
+    }
+
+    fn set_layout(&mut self, layout: Layout) {
+        self.layout = layout;
+        self.azimuths = layout.speakers().iter().map(|s| s.azimuth).collect();
+    }
+
+    fn can_advance(&self) -> bool {
+        match self.step {
+            Step::File => !self.file.is_empty(),
+            _ => true,
+        }
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, sx: &backend::Sender) {
+        match self.step {
+            Step::Method => {
+                ui.heading("1. Method");
+                for method in [Method::Spatializer, Method::Convolver] {
+                    ui.radio_value(&mut self.method, method, method.as_str())
+                        .on_hover_text(method.description());
+                }
+                ui.label(self.method.description());
+            }
+            Step::File => {
+                ui.heading("2. Impulse response file");
+                ui.label(match self.method {
+                    Method::Spatializer => "Path to the SOFA file",
+                    Method::Convolver => "Path to the 14 channel HeSuVi WAV file",
+                });
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.file)
+                        .hint_text("/path/to/file")
+                        .desired_width(f32::INFINITY),
+                );
+                if !self.file.is_empty() && !std::path::Path::new(&self.file).exists() {
+                    ui.colored_label(
+                        ui.visuals().warn_fg_color,
+                        "The file does not exist on this machine. \
+                        This is fine only if PipeWire runs elsewhere.",
+                    );
+                }
+            }
+            Step::Layout => {
+                ui.heading("3. Speaker layout");
+
+                let mut layout = self.layout;
+                ui.horizontal(|ui| {
+                    for l in [Layout::Stereo, Layout::Surround51, Layout::Surround71] {
+                        ui.radio_value(&mut layout, l, l.as_str());
+                    }
+                });
+                if layout != self.layout {
+                    self.set_layout(layout);
+                }
+
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.sink_name)
+                        .hint_text("Sink name")
+                        .desired_width(f32::INFINITY),
+                );
+
+                if self.method == Method::Spatializer {
+                    ui.separator();
+                    ui.label("Virtual speaker positions");
+                    egui::Grid::new("speakers").num_columns(2).show(ui, |ui| {
+                        for (speaker, azimuth) in self.layout.speakers().iter().zip(&mut self.azimuths)
+                        {
+                            ui.label(speaker.position);
+                            ui.add(
+                                egui::Slider::new(azimuth, 0f32..=359f32)
+                                    .suffix("°")
+                                    .text("Azimuth"),
+                            );
+                            ui.end_row();
+                        }
+                        ui.label("Radius");
+                        ui.add(egui::Slider::new(&mut self.radius, 0.1f32..=10f32).suffix("m"));
+                        ui.end_row();
+                    });
+                }
+            }
+            Step::Review => {
+                ui.heading("4. Review");
+                ui.label("The following filter-chain module arguments will be used");
+
+                let args = self.module_args();
+                egui::ScrollArea::vertical()
+                    .max_height(300.)
+                    .show(ui, |ui| {
+                        // Read-only, the previous steps change the arguments
+                        ui.add(
+                            egui::TextEdit::multiline(&mut args.as_str())
+                                .code_editor()
+                                .desired_width(f32::INFINITY),
+                        );
+                    });
+
+                ui.horizontal(|ui| {
+                    if ui
+                        .button("Load")
+                        .on_hover_text(
+                            "Load the filter-chain in coppwr's context. \
+                            The sink will be available for as long as coppwr stays connected.",
+                        )
+                        .clicked()
+                    {
+                        sx.send(Request::LoadModule {
+                            module_dir: None,
+                            name: FILTER_CHAIN_MODULE.to_owned(),
+                            args: Some(args.clone()),
+                            props: None,
+                        })
+                        .ok();
+
+                        self.status = Some(String::from("Requested the filter-chain to be loaded"));
+                    }
+
+                    if ui
+                        .button("Save to configuration")
+                        .on_hover_text(
+                            "Write a drop-in configuration file so that the PipeWire daemon \
+                            loads the sink on startup",
+                        )
+                        .clicked()
+                    {
+                        self.status = Some(
+                            match config::write_pipewire_conf_fragment(
+                                "coppwr-virtual-surround.conf",
+                                &config::context_module_fragment(FILTER_CHAIN_MODULE, &args),
+                            ) {
+                                Ok(path) => format!(
+                                    "Saved to {}. Restart PipeWire to apply it.",
+                                    path.display()
+                                ),
+                                Err(e) => format!("Failed to save the configuration: {e}"),
+                            },
+                        );
+                    }
+                });
+
+                if let Some(status) = &self.status {
+                    ui.label(status);
+                }
+            }
+        }
+
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            ui.add_enabled_ui(self.step.previous().is_some(), |ui| {
+                if ui.button("Back").clicked() {
+                    if let Some(previous) = self.step.previous() {
+                        self.step = previous;
+                    }
+                }
+            });
+            ui.add_enabled_ui(self.step.next().is_some() && self.can_advance(), |ui| {
+                if ui.button("Next").clicked() {
+                    if let Some(next) = self.step.next() {
+                        self.step = next;
+                        self.status = None;
+                    }
+                }
+            });
+            if ui.button("Start over").clicked() {
+                *self = Self::default();
+            }
+        });
+    }
+}
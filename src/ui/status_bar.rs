@@ -0,0 +1,168 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{
+    collections::BTreeMap,
+    time::{Duration, Instant},
+};
+
+use eframe::egui;
+
+use crate::backend::pods::profiler::Profiling;
+
+// How long the xrun counter stays highlighted after an xrun
+const FLASH: Duration = Duration::from_secs(1);
+
+struct DriverStatus {
+    name: String,
+    quantum: i64,
+    rate: u32,
+    cpu_load: [f32; 3],
+    followers: usize,
+    last_xrun_count: i32,
+}
+
+/// Summary of the state of the graph from the profiler, shown at the bottom of the window
+#[derive(Default)]
+pub struct StatusBar {
+    drivers: BTreeMap<i32, DriverStatus>,
+    xruns: u64,
+    last_xrun: Option<Instant>,
+
+    initial_sync_done: bool,
+    disconnected: bool,
+}
+
+impl StatusBar {
+    pub fn add_profilings(&mut self, profilings: &[Profiling]) {
+        for p in profilings {
+            let count = p.driver.xrun_count.unwrap_or(p.info.xrun_count);
+
+            let driver = self
+                .drivers
+                .entry(p.driver.id)
+                .or_insert_with(|| DriverStatus {
+                    name: String::new(),
+                    quantum: 0,
+                    rate: 0,
+                    cpu_load: [0.; 3],
+                    followers: 0,
+                    last_xrun_count: count,
+                });
+
+            // Counts go back to 0 when drivers are recreated
+            let new_xruns = (count - driver.last_xrun_count).max(0);
+            if new_xruns > 0 {
+                self.xruns += new_xruns as u64;
+                self.last_xrun = Some(Instant::now());
+            }
+
+            driver.name.clone_from(&p.driver.name);
+            driver.quantum = p.clock.duration;
+            driver.rate = p.clock.rate.denom;
+            driver.cpu_load = [
+                p.info.cpu_load_fast,
+                p.info.cpu_load_medium,
+                p.info.cpu_load_slow,
+            ];
+            driver.followers = p.followers.len();
+            driver.last_xrun_count = count;
+        }
+    }
+
+    pub fn remove_driver(&mut self, id: u32) {
+        if let Ok(id) = i32::try_from(id) {
+            self.drivers.remove(&id);
+        }
+    }
+
+    pub fn initial_sync_done(&mut self) {
+        self.initial_sync_done = true;
+    }
+
+    pub fn disconnected(&mut self) {
+        self.disconnected = true;
+    }
+
+    pub fn reconnected(&mut self) {
+        self.drivers.clear();
+        self.initial_sync_done = false;
+        self.disconnected = false;
+    }
+
+    // The driver with the most followers, which is usually the one of the main device
+    fn main_driver(&self) -> Option<&DriverStatus> {
+        self.drivers.values().max_by_key(|driver| driver.followers)
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            if self.disconnected {
+                ui.spinner();
+                ui.colored_label(ui.visuals().error_fg_color, "Disconnected, reconnecting")
+                    .on_hover_text("The data shown is from before the connection was lost");
+                return;
+            }
+
+            if !self.initial_sync_done {
+                ui.spinner();
+                ui.label("Loading objects");
+                ui.separator();
+            }
+
+            let Some(driver) = self.main_driver() else {
+                ui.label("No profiler data")
+                    .on_hover_text("The profiler module of PipeWire needs to be loaded");
+                return;
+            };
+
+            ui.label(&driver.name).on_hover_text(format!(
+                "The driver with the most followers, out of {} drivers",
+                self.drivers.len()
+            ));
+            ui.separator();
+            ui.label(format!("Quantum: {}", driver.quantum));
+            ui.separator();
+            ui.label(format!("Rate: {} Hz", driver.rate));
+            ui.separator();
+            ui.label(format!(
+                "CPU load: {:.2} {:.2} {:.2}",
+                driver.cpu_load[0], driver.cpu_load[1], driver.cpu_load[2]
+            ))
+            .on_hover_text("Averages of the DSP load over a short, medium and long period");
+            ui.separator();
+
+            let flashing = self.last_xrun.is_some_and(|last| last.elapsed() < FLASH);
+            let xruns = egui::RichText::new(format!("Xruns: {}", self.xruns));
+            let response = if flashing {
+                ui.ctx().request_repaint_after(FLASH);
+                ui.label(
+                    xruns
+                        .color(egui::Color32::WHITE)
+                        .background_color(ui.visuals().error_fg_color),
+                )
+            } else {
+                ui.label(xruns)
+            };
+            response.on_hover_text("Xruns of all drivers since connecting");
+
+            if ui.small_button("Reset").clicked() {
+                self.xruns = 0;
+                self.last_xrun = None;
+            }
+        });
+    }
+}
@@ -0,0 +1,235 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::cell::Cell;
+
+use eframe::egui::{self, Color32};
+
+// Size of the body text style of egui, which the others are relative to
+const DEFAULT_FONT_SIZE: f32 = 12.5;
+
+/// Colors of the ports and links of each media type in the graph
+#[derive(Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "persistence", derive(serde::Serialize, serde::Deserialize))]
+pub struct GraphColors {
+    pub audio: Color32,
+    pub video: Color32,
+    pub midi: Color32,
+    pub unknown: Color32,
+}
+
+impl Default for GraphColors {
+    fn default() -> Self {
+        Self {
+            audio: Color32::BLUE,
+            video: Color32::YELLOW,
+            midi: Color32::RED,
+            unknown: Color32::GRAY,
+        }
+    }
+}
+
+// The graph is drawn by a trait implementation that can't be given the theme
+thread_local! {
+    static GRAPH_COLORS: Cell<GraphColors> = Cell::new(GraphColors::default());
+}
+
+pub fn graph_colors() -> GraphColors {
+    GRAPH_COLORS.with(Cell::get)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "persistence", derive(serde::Serialize, serde::Deserialize))]
+enum Mode {
+    System,
+    Dark,
+    Light,
+}
+
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "persistence", derive(serde::Serialize, serde::Deserialize))]
+pub struct Theme {
+    mode: Mode,
+    zoom: f32,
+    font_size: f32,
+    // Used for selections and links instead of the one of the visuals
+    accent: Option<Color32>,
+    graph_colors: GraphColors,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            mode: Mode::System,
+            zoom: 1.,
+            font_size: DEFAULT_FONT_SIZE,
+            accent: None,
+            graph_colors: GraphColors::default(),
+        }
+    }
+}
+
+impl Theme {
+    fn apply(&self, ctx: &egui::Context, system_dark: bool) {
+        let dark = match self.mode {
+            Mode::System => system_dark,
+            Mode::Dark => true,
+            Mode::Light => false,
+        };
+
+        let mut style = egui::Style {
+            visuals: if dark {
+                egui::Visuals::dark()
+            } else {
+                egui::Visuals::light()
+            },
+            ..egui::Style::default()
+        };
+
+        if let Some(accent) = self.accent {
+            style.visuals.selection.bg_fill = accent;
+            style.visuals.hyperlink_color = accent;
+        }
+
+        let scale = self.font_size / DEFAULT_FONT_SIZE;
+        for font in style.text_styles.values_mut() {
+            font.size *= scale;
+        }
+
+        ctx.set_style(style);
+        ctx.set_zoom_factor(self.zoom);
+
+        GRAPH_COLORS.with(|colors| colors.set(self.graph_colors));
+    }
+}
+
+/// Dialog for the theme, scale and colors of the UI
+#[derive(Default)]
+pub struct ThemeSettings {
+    open: bool,
+    theme: Theme,
+    // The theme last applied and whether the system theme was dark then
+    applied: Option<(Theme, bool)>,
+}
+
+impl ThemeSettings {
+    pub fn new(theme: Theme) -> Self {
+        Self {
+            open: false,
+            theme,
+            applied: None,
+        }
+    }
+
+    pub const fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    pub fn open(&mut self) {
+        self.open = true;
+    }
+
+    /// Applies the theme if it or the system's one changed since it was last applied
+    pub fn apply(&mut self, ctx: &egui::Context, system_dark: bool) {
+        if self
+            .applied
+            .as_ref()
+            .is_some_and(|(theme, dark)| *theme == self.theme && *dark == system_dark)
+        {
+            return;
+        }
+
+        self.theme.apply(ctx, system_dark);
+        self.applied = Some((self.theme.clone(), system_dark));
+    }
+
+    pub fn window(&mut self, ctx: &egui::Context) {
+        let theme = &mut self.theme;
+
+        egui::Window::new("Appearance")
+            .collapsible(false)
+            .resizable(false)
+            .open(&mut self.open)
+            .show(ctx, |ui| {
+                egui::Grid::new("theme").num_columns(2).show(ui, |ui| {
+                    ui.label("Theme");
+                    ui.horizontal(|ui| {
+                        ui.selectable_value(&mut theme.mode, Mode::System, "System");
+                        ui.selectable_value(&mut theme.mode, Mode::Dark, "🌙 Dark");
+                        ui.selectable_value(&mut theme.mode, Mode::Light, "☀ Light");
+                    });
+                    ui.end_row();
+
+                    ui.label("UI scale");
+                    ui.add(
+                        egui::Slider::new(&mut theme.zoom, 0.5..=3.)
+                            .step_by(0.1)
+                            .custom_formatter(|z, _| format!("{:.0}%", z * 100.)),
+                    );
+                    ui.end_row();
+
+                    ui.label("Font size");
+                    ui.add(egui::Slider::new(&mut theme.font_size, 8.0..=24.).suffix(" pt"));
+                    ui.end_row();
+
+                    ui.label("Accent");
+                    ui.horizontal(|ui| {
+                        let mut custom = theme.accent.is_some();
+                        if ui.checkbox(&mut custom, "Custom").changed() {
+                            theme.accent = custom.then(|| ui.visuals().selection.bg_fill);
+                        }
+                        if let Some(accent) = &mut theme.accent {
+                            egui::color_picker::color_edit_button_srgba(
+                                ui,
+                                accent,
+                                egui::color_picker::Alpha::Opaque,
+                            );
+                        }
+                    });
+                    ui.end_row();
+                });
+
+                ui.separator();
+
+                ui.label("Graph colors of ports and links");
+                egui::Grid::new("graph_colors")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        let colors = &mut theme.graph_colors;
+                        for (label, color) in [
+                            ("Audio", &mut colors.audio),
+                            ("Video", &mut colors.video),
+                            ("MIDI", &mut colors.midi),
+                            ("Unknown", &mut colors.unknown),
+                        ] {
+                            ui.label(label);
+                            egui::color_picker::color_edit_button_srgba(
+                                ui,
+                                color,
+                                egui::color_picker::Alpha::Opaque,
+                            );
+                            ui.end_row();
+                        }
+                    });
+
+                ui.separator();
+
+                if ui.button("Reset to defaults").clicked() {
+                    *theme = Theme::default();
+                }
+            });
+    }
+}
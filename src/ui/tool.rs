@@ -14,29 +14,197 @@
 //
 // SPDX-License-Identifier: GPL-3.0-only
 
+use std::{any::Any, cell::RefCell, collections::BTreeMap, rc::Rc};
+
 use eframe::egui;
+use pipewire::types::ObjectType;
+
+use crate::{
+    backend::{self, pods::profiler::Profiling},
+    ui::globals_store::{Global, GlobalsStore},
+};
+
+/// Changes to the globals that are passed to registered tools
+#[derive(Clone, Copy)]
+pub enum GlobalEvent<'a> {
+    /// A global was added. Its properties are empty if the remote didn't announce them with it.
+    Added(&'a Rc<RefCell<Global>>),
+    /// The properties of a global changed
+    PropertiesChanged(&'a Rc<RefCell<Global>>),
+    /// The info of a global changed, with the info it had before if it had any
+    InfoChanged(
+        &'a Rc<RefCell<Global>>,
+        Option<&'a [(&'static str, String)]>,
+    ),
+    /// A global was removed. Sent to all tools regardless of their interests.
+    Removed {
+        id: u32,
+        global: &'a Rc<RefCell<Global>>,
+    },
+}
+
+/// A change of the properties of a metadata object, passed to the
+/// tools that are interested in metadata objects
+#[derive(Clone, Copy)]
+pub struct MetadataChange<'a> {
+    pub metadata: &'a Rc<RefCell<Global>>,
+    pub subject: u32,
+    /// The object the property is about, if it's known
+    pub subject_global: Option<&'a Rc<RefCell<Global>>>,
+    /// `None` if all of the properties were removed
+    pub key: Option<&'a str>,
+    pub type_: Option<&'a str>,
+    /// `None` if the property was removed
+    pub value: Option<&'a str>,
+}
+
+/// Changes of the connection that are passed to registered tools
+#[derive(Clone, Copy)]
+pub enum ConnectionEvent<'a> {
+    /// All of the globals that existed when connecting have been added
+    InitialSyncDone,
+    /// The connection was lost and made again.
+    /// The globals of the lost connection have been removed before this.
+    Reconnected,
+    /// The properties of the context of coppwr changed
+    ContextProperties(&'a BTreeMap<String, String>),
+}
+
+/// Lets the registry give out tools by their type
+pub trait AsAny: Any {
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T: Any> AsAny for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// A tool that's shown in a window and is notified of the events of the remote.
+/// All of the tools of coppwr are added to a [`ToolRegistry`], and so can the tools of forks.
+pub trait ToolPlugin: AsAny {
+    /// Label of the Tools menu entry, an icon followed by the name.
+    /// The name is also the window title and is used on the command line.
+    fn label(&self) -> &'static str;
+
+    fn description(&self) -> &'static str;
+
+    /// Whether the tool is notified of the events of globals of this type,
+    /// and of the changes of metadata properties if it's [`ObjectType::Metadata`]
+    fn interested_in(&self, _object_type: &ObjectType) -> bool {
+        false
+    }
+
+    fn global_event(&mut self, _event: GlobalEvent, _sx: &backend::Sender) {}
+
+    fn metadata_property(&mut self, _change: MetadataChange, _sx: &backend::Sender) {}
+
+    /// Called with the samples of the profiler, which arrive about every second
+    fn profiler_samples(&mut self, _samples: &[Profiling]) {}
+
+    fn connection_event(&mut self, _event: ConnectionEvent, _sx: &backend::Sender) {}
 
-use crate::backend;
+    /// The object selected in the views of the connection changed
+    fn selection_changed(&mut self, _selected: Option<u32>) {}
 
-pub trait Tool {
-    const NAME: &'static str;
+    /// Called on every frame, even when the window is closed
+    fn update(&mut self, _globals: &GlobalsStore, _sx: &backend::Sender) {}
 
     fn show(&mut self, ui: &mut egui::Ui, sx: &backend::Sender);
 }
 
+/// The tools, shown in windows, that receive the events of the remote
 #[derive(Default)]
-pub struct Windowed<T: Tool> {
-    pub open: bool,
-    pub tool: T,
-}
-
-impl<T: Tool> Windowed<T> {
-    pub fn window(&mut self, ctx: &egui::Context, sx: &backend::Sender) {
-        egui::Window::new(T::NAME)
-            .vscroll(true)
-            .open(&mut self.open)
-            .show(ctx, |ui| {
-                self.tool.show(ui, sx);
-            });
+pub struct ToolRegistry {
+    tools: Vec<(bool, Box<dyn ToolPlugin>)>,
+}
+
+impl ToolRegistry {
+    /// Adds a tool, after the ones already added in the Tools menu
+    pub fn register(&mut self, tool: impl ToolPlugin + 'static) {
+        self.tools.push((false, Box::new(tool)));
+    }
+
+    /// The first tool of the type
+    pub fn get<T: ToolPlugin>(&self) -> Option<&T> {
+        self.tools
+            .iter()
+            .find_map(|(_, tool)| (**tool).as_any().downcast_ref())
+    }
+
+    pub fn get_mut<T: ToolPlugin>(&mut self) -> Option<&mut T> {
+        self.tools
+            .iter_mut()
+            .find_map(|(_, tool)| (**tool).as_any_mut().downcast_mut())
+    }
+
+    /// The open state, label and description of each tool
+    pub fn entries(&mut self) -> impl Iterator<Item = (&mut bool, &'static str, &'static str)> {
+        self.tools
+            .iter_mut()
+            .map(|(open, tool)| (open, tool.label(), tool.description()))
+    }
+
+    pub fn dispatch(&mut self, event: GlobalEvent, sx: &backend::Sender) {
+        let object_type = match event {
+            GlobalEvent::Added(global)
+            | GlobalEvent::PropertiesChanged(global)
+            | GlobalEvent::InfoChanged(global, _) => Some(global.borrow().object_type().clone()),
+            GlobalEvent::Removed { .. } => None,
+        };
+
+        for (_, tool) in &mut self.tools {
+            if object_type.as_ref().map_or(true, |t| tool.interested_in(t)) {
+                tool.global_event(event, sx);
+            }
+        }
+    }
+
+    pub fn dispatch_metadata_property(&mut self, change: MetadataChange, sx: &backend::Sender) {
+        for (_, tool) in &mut self.tools {
+            if tool.interested_in(&ObjectType::Metadata) {
+                tool.metadata_property(change, sx);
+            }
+        }
+    }
+
+    pub fn dispatch_profiler_samples(&mut self, samples: &[Profiling]) {
+        for (_, tool) in &mut self.tools {
+            tool.profiler_samples(samples);
+        }
+    }
+
+    pub fn dispatch_connection_event(&mut self, event: ConnectionEvent, sx: &backend::Sender) {
+        for (_, tool) in &mut self.tools {
+            tool.connection_event(event, sx);
+        }
+    }
+
+    pub fn dispatch_selection(&mut self, selected: Option<u32>) {
+        for (_, tool) in &mut self.tools {
+            tool.selection_changed(selected);
+        }
+    }
+
+    pub fn windows(&mut self, ctx: &egui::Context, globals: &GlobalsStore, sx: &backend::Sender) {
+        for (open, tool) in &mut self.tools {
+            tool.update(globals, sx);
+
+            let label = tool.label();
+            let title = label.split_once(' ').map_or(label, |(_, name)| name);
+
+            egui::Window::new(title)
+                .vscroll(true)
+                .open(open)
+                .show(ctx, |ui| {
+                    tool.show(ui, sx);
+                });
+        }
     }
 }
@@ -0,0 +1,168 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use eframe::egui;
+
+// Number of one second buckets that are kept
+const SECONDS: usize = 30;
+const WINDOW: Duration = Duration::from_secs(SECONDS as u64);
+
+struct Bucket {
+    start: Instant,
+    updates: u32,
+    // The highest load the profiler reported during the second
+    load: Option<f32>,
+}
+
+/// Recent activity of an object, kept in one second buckets
+#[derive(Default)]
+pub struct Activity {
+    buckets: VecDeque<Bucket>,
+}
+
+impl Activity {
+    fn current(&mut self) -> &mut Bucket {
+        let now = Instant::now();
+
+        while self
+            .buckets
+            .front()
+            .is_some_and(|b| now.duration_since(b.start) >= WINDOW)
+        {
+            self.buckets.pop_front();
+        }
+
+        if self.buckets.back().map_or(true, |b| {
+            now.duration_since(b.start) >= Duration::from_secs(1)
+        }) {
+            self.buckets.push_back(Bucket {
+                start: now,
+                updates: 0,
+                load: None,
+            });
+        }
+
+        self.buckets.back_mut().unwrap()
+    }
+
+    /// Counts a change of the info, properties or params
+    pub fn record_update(&mut self) {
+        self.current().updates += 1;
+    }
+
+    /// Records the time a node spent processing in a cycle as a fraction of the quantum
+    pub fn record_load(&mut self, load: f32) {
+        let bucket = self.current();
+        bucket.load = Some(bucket.load.map_or(load, |l| l.max(load)));
+    }
+
+    /// Values per second from the oldest to the current one
+    #[allow(clippy::cast_possible_truncation)]
+    fn series(&self, value: impl Fn(&Bucket) -> Option<f32>) -> [Option<f32>; SECONDS] {
+        let now = Instant::now();
+        let mut series = [None; SECONDS];
+        for bucket in &self.buckets {
+            let age = now.duration_since(bucket.start).as_secs() as usize;
+            if let Some(slot) = SECONDS.checked_sub(age + 1) {
+                series[slot] = value(bucket);
+            }
+        }
+        series
+    }
+
+    /// Updates in the last seconds that are kept
+    pub fn recent_updates(&self) -> u32 {
+        let now = Instant::now();
+        self.buckets
+            .iter()
+            .filter(|b| now.duration_since(b.start) < WINDOW)
+            .map(|b| b.updates)
+            .sum()
+    }
+
+    /// Shows sparklines of the update rate and the load, if there was any recently
+    #[allow(clippy::cast_precision_loss)]
+    pub fn show(&self, ui: &mut egui::Ui) {
+        // Seconds without updates are at 0 instead of gaps
+        let updates = self
+            .series(|b| Some(b.updates as f32))
+            .map(|u| Some(u.unwrap_or(0.)));
+        let max = updates.iter().flatten().fold(0f32, |max, &u| max.max(u));
+        if max > 0. {
+            sparkline(ui, &updates, max, ui.visuals().hyperlink_color).on_hover_text(format!(
+                "{} info, property or param updates in the last {SECONDS}s, \
+                at most {max:.0} per second",
+                self.recent_updates()
+            ));
+        }
+
+        let loads = self.series(|b| b.load);
+        if loads.iter().any(Option::is_some) {
+            let last = loads.iter().rev().flatten().next().copied().unwrap_or(0.);
+            let color = if last >= 1. {
+                ui.visuals().error_fg_color
+            } else {
+                ui.visuals().warn_fg_color
+            };
+            sparkline(ui, &loads, 1., color).on_hover_text(format!(
+                "Highest processing time per quantum each second for the last {SECONDS}s, \
+                currently {:.0}%",
+                last * 100.
+            ));
+        }
+    }
+}
+
+/// A small line plot of values that leaves gaps where there are none
+#[allow(clippy::cast_precision_loss)]
+pub fn sparkline(
+    ui: &mut egui::Ui,
+    values: &[Option<f32>],
+    max: f32,
+    color: egui::Color32,
+) -> egui::Response {
+    let height = ui.text_style_height(&egui::TextStyle::Body);
+    let (response, painter) =
+        ui.allocate_painter(egui::vec2(height * 3., height), egui::Sense::hover());
+    let rect = response.rect;
+    painter.rect_filled(rect, 2., ui.visuals().extreme_bg_color);
+
+    let step = rect.width() / (values.len().max(2) - 1) as f32;
+    let point = |i: usize, v: f32| {
+        egui::pos2(
+            rect.left() + step * i as f32,
+            rect.bottom() - rect.height() * (v / max).clamp(0., 1.),
+        )
+    };
+
+    let stroke = egui::Stroke::new(1., color);
+    for (i, pair) in values.windows(2).enumerate() {
+        match pair {
+            [Some(a), Some(b)] => painter.line_segment([point(i, *a), point(i + 1, *b)], stroke),
+            // Isolated values are drawn as dots
+            [Some(v), None] => painter.circle_filled(point(i, *v), 1., color),
+            [None, Some(v)] => painter.circle_filled(point(i + 1, *v), 1., color),
+            _ => {}
+        }
+    }
+
+    response
+}
@@ -0,0 +1,135 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::path::PathBuf;
+
+/// Quotes a string so that it can be used as a SPA-JSON value
+pub fn quote(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Extracts the name from values like `{ "name": "alsa_output.pci-0000_00_1f.3.analog-stereo" }`
+pub fn metadata_name(value: &str) -> Option<&str> {
+    let rest = &value[value.find("\"name\"")? + 6..];
+    let rest = &rest[rest.find('"')? + 1..];
+    Some(&rest[..rest.find('"')?])
+}
+
+/// The user's configuration directory, as specified by the XDG base directory spec
+pub fn user_config_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+}
+
+/// The user's data directory, as specified by the XDG base directory spec
+pub fn user_data_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_DATA_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+}
+
+/// Wraps module arguments in a `context.modules` section
+pub fn context_module_fragment(name: &str, args: &str) -> String {
+    let args = args
+        .lines()
+        .map(|l| format!("        {l}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    format!(
+        "context.modules = [\n    {{ name = {name}\n        args =\n{args}\n    }}\n]\n"
+    )
+}
+
+/// Writes a fragment to the PipeWire daemon's drop-in configuration directory
+/// and returns its path
+pub fn write_pipewire_conf_fragment(file_name: &str, contents: &str) -> std::io::Result<PathBuf> {
+    let dir = user_config_dir()
+        .ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "Could not determine the configuration directory",
+            )
+        })?
+        .join("pipewire")
+        .join("pipewire.conf.d");
+
+    std::fs::create_dir_all(&dir)?;
+
+    let path = dir.join(file_name);
+    std::fs::write(&path, contents)?;
+
+    Ok(path)
+}
+
+const AUTOSTART_FILE: &str = "io.github.dimtpap.coppwr.desktop";
+
+fn autostart_entry_path() -> Option<PathBuf> {
+    user_config_dir().map(|dir| dir.join("autostart").join(AUTOSTART_FILE))
+}
+
+/// Whether an autostart entry for coppwr exists in the user's autostart directory
+pub fn autostart_installed() -> bool {
+    autostart_entry_path().is_some_and(|path| path.exists())
+}
+
+/// Installs or removes a desktop entry that launches coppwr minimized at login
+pub fn set_autostart(enabled: bool) -> std::io::Result<()> {
+    let path = autostart_entry_path().ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "Could not determine the configuration directory",
+        )
+    })?;
+
+    if !enabled {
+        return match std::fs::remove_file(&path) {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            res => res,
+        };
+    }
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+
+    std::fs::write(
+        &path,
+        "[Desktop Entry]\n\
+        Type=Application\n\
+        Name=coppwr\n\
+        Comment=Low level control GUI for the PipeWire multimedia server\n\
+        Icon=io.github.dimtpap.coppwr\n\
+        Exec=coppwr --minimized\n\
+        Terminal=false\n\
+        X-GNOME-Autostart-enabled=true\n",
+    )
+}
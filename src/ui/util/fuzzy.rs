@@ -0,0 +1,92 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+use eframe::egui::{self, text::LayoutJob};
+
+/// Matches the characters of `pattern` in order within `text`, ignoring case.
+/// Returns a score that's higher for consecutive characters and word starts,
+/// along with the byte indices of the matched characters of `text`.
+pub fn fuzzy_match(pattern: &str, text: &str) -> Option<(i32, Vec<usize>)> {
+    let mut pattern = pattern.chars().flat_map(char::to_lowercase).peekable();
+
+    let mut score = 0;
+    let mut indices = Vec::new();
+    let mut previous: Option<(usize, char)> = None;
+
+    for (i, c) in text.char_indices() {
+        let Some(p) = pattern.peek() else {
+            break;
+        };
+
+        if c.to_lowercase().eq(std::iter::once(*p)) {
+            score += 1;
+
+            match previous {
+                None => score += 3,
+                Some((_, prev)) if !prev.is_alphanumeric() => score += 3,
+                _ => {}
+            }
+            if indices
+                .last()
+                .zip(previous)
+                .is_some_and(|(last, (prev, _))| *last == prev)
+            {
+                score += 5;
+            }
+
+            indices.push(i);
+            pattern.next();
+        }
+
+        previous = Some((i, c));
+    }
+
+    pattern.peek().is_none().then_some((score, indices))
+}
+
+/// Lays out `text` with the characters at `indices` highlighted
+pub fn highlighted(ui: &egui::Ui, text: &str, indices: &[usize]) -> LayoutJob {
+    let normal = egui::TextFormat {
+        font_id: egui::TextStyle::Body.resolve(ui.style()),
+        color: ui.visuals().text_color(),
+        ..Default::default()
+    };
+    let highlight = egui::TextFormat {
+        color: ui.visuals().strong_text_color(),
+        background: ui.visuals().selection.bg_fill,
+        ..normal.clone()
+    };
+
+    let format = |h: bool| if h { highlight.clone() } else { normal.clone() };
+
+    // Consecutive characters with the same format are appended together
+    let mut job = LayoutJob::default();
+    let mut start = 0;
+    let mut start_highlighted = false;
+    for (i, _) in text.char_indices() {
+        let is_highlighted = indices.contains(&i);
+        if is_highlighted != start_highlighted && i > start {
+            job.append(&text[start..i], 0., format(start_highlighted));
+            start = i;
+        }
+        start_highlighted = is_highlighted;
+    }
+    if start < text.len() {
+        job.append(&text[start..], 0., format(start_highlighted));
+    }
+
+    job
+}
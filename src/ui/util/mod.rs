@@ -14,5 +14,14 @@
 //
 // SPDX-License-Identifier: GPL-3.0-only
 
+pub mod activity;
+pub mod config;
+pub mod fuzzy;
+pub mod osc;
 pub mod persistence;
+pub mod pod_view;
+pub mod procfs;
+pub mod spa_json;
+pub mod time;
 pub mod uis;
+pub mod zip;
@@ -0,0 +1,210 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+// Receiving of Open Sound Control messages over UDP.
+// Only the argument types that control surfaces send are supported.
+
+use std::{
+    net::{Ipv4Addr, SocketAddr, UdpSocket},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc,
+    },
+    time::Duration,
+};
+
+use eframe::egui;
+
+// How often the thread checks whether the server was stopped
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+pub enum Argument {
+    Int(i32),
+    Float(f32),
+    String(String),
+    Bool(bool),
+}
+
+impl Argument {
+    pub fn as_f32(&self) -> Option<f32> {
+        #[allow(clippy::cast_precision_loss)]
+        match self {
+            Self::Int(i) => Some(*i as f32),
+            Self::Float(f) => Some(*f),
+            Self::Bool(b) => Some(f32::from(u8::from(*b))),
+            Self::String(_) => None,
+        }
+    }
+
+    /// Numbers are true when they're at least half, like buttons that send 0 and 1
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Bool(b) => Some(*b),
+            Self::String(_) => None,
+            number => number.as_f32().map(|f| f >= 0.5),
+        }
+    }
+}
+
+pub struct Message {
+    pub address: String,
+    pub arguments: Vec<Argument>,
+}
+
+/// Reads a null terminated string padded to 4 bytes
+fn read_string(data: &[u8]) -> Option<(String, &[u8])> {
+    let len = data.iter().position(|&b| b == 0)?;
+    let string = std::str::from_utf8(&data[..len]).ok()?.to_owned();
+    let padded = (len + 4) & !3;
+    Some((string, data.get(padded..)?))
+}
+
+fn read_u32(data: &[u8]) -> Option<(u32, &[u8])> {
+    let bytes = data.get(..4)?.try_into().ok()?;
+    Some((u32::from_be_bytes(bytes), &data[4..]))
+}
+
+fn parse_message(data: &[u8]) -> Option<Message> {
+    let (address, data) = read_string(data)?;
+    // Messages without type tags have no arguments
+    let Some((tags, mut data)) = read_string(data) else {
+        return Some(Message {
+            address,
+            arguments: Vec::new(),
+        });
+    };
+
+    let mut arguments = Vec::new();
+    for tag in tags.strip_prefix(',')?.chars() {
+        let argument = match tag {
+            'i' => {
+                let (value, rest) = read_u32(data)?;
+                data = rest;
+                Argument::Int(i32::from_be_bytes(value.to_be_bytes()))
+            }
+            'f' => {
+                let (value, rest) = read_u32(data)?;
+                data = rest;
+                Argument::Float(f32::from_bits(value))
+            }
+            's' => {
+                let (value, rest) = read_string(data)?;
+                data = rest;
+                Argument::String(value)
+            }
+            'T' => Argument::Bool(true),
+            'F' => Argument::Bool(false),
+            _ => return None,
+        };
+        arguments.push(argument);
+    }
+
+    Some(Message { address, arguments })
+}
+
+/// Parses a packet, which is a message or a bundle of packets
+fn parse(data: &[u8], messages: &mut Vec<Message>) -> Option<()> {
+    let Some(mut elements) = data.strip_prefix(b"#bundle\0") else {
+        messages.push(parse_message(data)?);
+        return Some(());
+    };
+
+    // The time tag is ignored, bundles are handled when received
+    elements = elements.get(8..)?;
+    while !elements.is_empty() {
+        let (size, rest) = read_u32(elements)?;
+        let size = usize::try_from(size).ok()?;
+        parse(rest.get(..size)?, messages)?;
+        elements = &rest[size..];
+    }
+
+    Some(())
+}
+
+/// Receives messages in a separate thread until it's dropped
+pub struct Server {
+    address: SocketAddr,
+    stop: Arc<AtomicBool>,
+    rx: mpsc::Receiver<Message>,
+}
+
+impl Server {
+    /// Listens on the port of the loopback interface, or of all interfaces.
+    /// The context is repainted when messages are received so that they're handled promptly.
+    pub fn start(port: u16, all_interfaces: bool, ctx: egui::Context) -> std::io::Result<Self> {
+        let ip = if all_interfaces {
+            Ipv4Addr::UNSPECIFIED
+        } else {
+            Ipv4Addr::LOCALHOST
+        };
+        let socket = UdpSocket::bind((ip, port))?;
+        socket.set_read_timeout(Some(POLL_INTERVAL))?;
+
+        let address = socket.local_addr()?;
+        let stop = Arc::new(AtomicBool::new(false));
+        let (sx, rx) = mpsc::channel();
+
+        std::thread::spawn({
+            let stop = Arc::clone(&stop);
+            move || {
+                let mut buf = [0; 4096];
+                let mut messages = Vec::new();
+                while !stop.load(Ordering::Relaxed) {
+                    let len = match socket.recv(&mut buf) {
+                        Ok(len) => len,
+                        Err(e)
+                            if matches!(
+                                e.kind(),
+                                std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut
+                            ) =>
+                        {
+                            continue;
+                        }
+                        Err(_) => break,
+                    };
+
+                    // Malformed packets are dropped
+                    if parse(&buf[..len], &mut messages).is_none() {
+                        messages.clear();
+                        continue;
+                    }
+                    for message in messages.drain(..) {
+                        if sx.send(message).is_err() {
+                            return;
+                        }
+                    }
+                    ctx.request_repaint();
+                }
+            }
+        });
+
+        Ok(Self { address, stop, rx })
+    }
+
+    pub const fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    pub fn messages(&self) -> impl Iterator<Item = Message> + '_ {
+        self.rx.try_iter()
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,104 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::{fmt::Write, hash::Hash};
+
+use eframe::egui;
+
+use crate::backend::pods::{
+    decode::{self, PodNode},
+    params,
+};
+
+fn show_node(ui: &mut egui::Ui, node: &PodNode) {
+    if node.children.is_empty() {
+        ui.horizontal(|ui| {
+            ui.label(&node.label);
+            ui.label(egui::RichText::new(&node.value).monospace());
+        });
+        return;
+    }
+
+    egui::CollapsingHeader::new(format!("{}: {}", node.label, node.value))
+        .default_open(true)
+        .show(ui, |ui| {
+            for child in &node.children {
+                show_node(ui, child);
+            }
+        });
+}
+
+/// Formats bytes as rows of 16 with their offset, hex and ASCII
+fn hex_dump(bytes: &[u8]) -> String {
+    let mut dump = String::new();
+
+    for (i, row) in bytes.chunks(16).enumerate() {
+        write!(dump, "{:08x}  ", i * 16).ok();
+        for b in row {
+            write!(dump, "{b:02x} ").ok();
+        }
+        for _ in row.len()..16 {
+            dump.push_str("   ");
+        }
+        dump.push(' ');
+        dump.extend(row.iter().map(|&b| {
+            if b.is_ascii_graphic() || b == b' ' {
+                char::from(b)
+            } else {
+                '.'
+            }
+        }));
+        dump.push('\n');
+    }
+
+    dump
+}
+
+/// Shows a pod as a tree of its values and as raw bytes.
+/// Pods that fail to decode are still shown raw.
+pub fn pod_view(ui: &mut egui::Ui, id_source: impl Hash, bytes: &[u8]) {
+    ui.push_id(id_source, |ui| {
+        if let Some((size, t)) = decode::header(bytes) {
+            ui.label(format!(
+                "{}, body of {size} bytes, {} bytes in total",
+                decode::type_name(t),
+                bytes.len()
+            ));
+        }
+
+        match params::parse(bytes) {
+            Ok(value) => {
+                egui::CollapsingHeader::new("Tree")
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        show_node(ui, &decode::tree("Pod", &value));
+                    });
+            }
+            Err(e) => {
+                ui.colored_label(
+                    ui.visuals().error_fg_color,
+                    format!("The pod could not be decoded: {e}"),
+                );
+            }
+        }
+
+        egui::CollapsingHeader::new("Raw").show(ui, |ui| {
+            egui::ScrollArea::horizontal().show(ui, |ui| {
+                ui.label(egui::RichText::new(hex_dump(bytes)).monospace());
+            });
+        });
+    });
+}
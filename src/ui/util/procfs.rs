@@ -0,0 +1,278 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+// Reading information about processes from `/proc`.
+// Only meaningful for processes of the same PID namespace, so remotes
+// on other machines or sandboxes will show wrong or no information.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::PathBuf,
+    process::{Command, Stdio},
+    time::{Duration, Instant},
+};
+
+/// Fields of `/proc/<pid>/stat` after the command name, as numbered in proc(5)
+fn stat_fields(stat: &str) -> Option<Vec<&str>> {
+    // The command name is in parentheses and can contain spaces and parentheses itself
+    let rest = &stat[stat.rfind(')')? + 1..];
+    Some(rest.split_whitespace().collect())
+}
+
+/// Gets field `n` (as numbered in proc(5), starting from the state which is 3)
+fn stat_field<T: std::str::FromStr>(fields: &[&str], n: usize) -> Option<T> {
+    fields.get(n.checked_sub(3)?)?.parse().ok()
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum SchedPolicy {
+    Other,
+    Fifo,
+    RoundRobin,
+    Batch,
+    Idle,
+    Deadline,
+    Unknown,
+}
+
+impl SchedPolicy {
+    const fn from_raw(raw: u32) -> Self {
+        match raw {
+            0 => Self::Other,
+            1 => Self::Fifo,
+            2 => Self::RoundRobin,
+            3 => Self::Batch,
+            5 => Self::Idle,
+            6 => Self::Deadline,
+            _ => Self::Unknown,
+        }
+    }
+
+    pub const fn is_realtime(self) -> bool {
+        matches!(self, Self::Fifo | Self::RoundRobin | Self::Deadline)
+    }
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Other => "SCHED_OTHER",
+            Self::Fifo => "SCHED_FIFO",
+            Self::RoundRobin => "SCHED_RR",
+            Self::Batch => "SCHED_BATCH",
+            Self::Idle => "SCHED_IDLE",
+            Self::Deadline => "SCHED_DEADLINE",
+            Self::Unknown => "Unknown",
+        }
+    }
+}
+
+pub struct Thread {
+    pub tid: u32,
+    pub name: String,
+    pub policy: SchedPolicy,
+    pub rt_priority: u32,
+    pub nice: i32,
+}
+
+/// Scheduling information of all the threads of a process
+pub fn threads(pid: u32) -> std::io::Result<Vec<Thread>> {
+    let mut threads = Vec::new();
+
+    for entry in fs::read_dir(format!("/proc/{pid}/task"))?.flatten() {
+        let Some(tid) = entry.file_name().to_str().and_then(|t| t.parse().ok()) else {
+            continue;
+        };
+
+        let path = entry.path();
+        let (Ok(stat), Ok(name)) = (
+            fs::read_to_string(path.join("stat")),
+            fs::read_to_string(path.join("comm")),
+        ) else {
+            // The thread exited
+            continue;
+        };
+        let Some(fields) = stat_fields(&stat) else {
+            continue;
+        };
+
+        threads.push(Thread {
+            tid,
+            name: name.trim_end().to_owned(),
+            policy: SchedPolicy::from_raw(stat_field(&fields, 41).unwrap_or_default()),
+            rt_priority: stat_field(&fields, 40).unwrap_or_default(),
+            nice: stat_field(&fields, 19).unwrap_or_default(),
+        });
+    }
+
+    threads.sort_by_key(|t| t.tid);
+
+    Ok(threads)
+}
+
+/// PID of a client as seen from the daemon, or as reported by the client itself
+pub fn client_pid(props: &BTreeMap<String, String>) -> Option<u32> {
+    ["pipewire.sec.pid", "application.process.id"]
+        .into_iter()
+        .find_map(|key| props.get(key))
+        .and_then(|pid| pid.parse().ok())
+}
+
+// Clock ticks per second of the times in stat files. It is fixed by the kernel ABI.
+const USER_HZ: f64 = 100.;
+
+/// Total CPU time of a process in clock ticks
+fn cpu_ticks(pid: u32) -> Option<u64> {
+    let stat = fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+    let fields = stat_fields(&stat)?;
+
+    // User and system time
+    Some(stat_field::<u64>(&fields, 14)? + stat_field::<u64>(&fields, 15)?)
+}
+
+/// Seconds since boot when the process started
+#[allow(clippy::cast_precision_loss)]
+fn start_time(pid: u32) -> Option<f64> {
+    let stat = fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+    let fields = stat_fields(&stat)?;
+
+    Some(stat_field::<u64>(&fields, 22)? as f64 / USER_HZ)
+}
+
+/// Arguments of a process joined by spaces. Empty for kernel threads.
+fn cmdline(pid: u32) -> Option<String> {
+    let raw = fs::read(format!("/proc/{pid}/cmdline")).ok()?;
+
+    let args: Vec<_> = raw
+        .split(|b| *b == 0)
+        .filter(|arg| !arg.is_empty())
+        .map(String::from_utf8_lossy)
+        .collect();
+
+    (!args.is_empty()).then(|| args.join(" "))
+}
+
+/// Asks a process to exit by sending it SIGTERM
+pub fn terminate(pid: u32) -> Result<(), String> {
+    let status = Command::new("kill")
+        .args(["-TERM", &pid.to_string()])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_err(|e| format!("Failed to run kill: {e}"))?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("kill failed with {status}"))
+    }
+}
+
+/// Information about a process that is not provided by PipeWire
+pub struct ProcessInfo {
+    pub executable: Option<PathBuf>,
+    pub cmdline: Option<String>,
+    start_time: Option<f64>,
+    pub cgroup: Option<String>,
+    // Derived from the cgroup
+    pub flatpak_app_id: Option<String>,
+    pub systemd_unit: Option<String>,
+
+    last_cpu_sample: Option<(Instant, u64)>,
+    // Percentage of a single CPU since the last update
+    pub cpu_usage: Option<f64>,
+}
+
+impl ProcessInfo {
+    pub fn read(pid: u32) -> Self {
+        let cgroup = fs::read_to_string(format!("/proc/{pid}/cgroup"))
+            .ok()
+            .and_then(|cgroups| {
+                // The unified hierarchy is the only one on modern systems
+                cgroups
+                    .lines()
+                    .find_map(|line| line.strip_prefix("0::"))
+                    .or_else(|| cgroups.lines().next())
+                    .map(ToOwned::to_owned)
+            });
+
+        let unit = cgroup.as_deref().and_then(|cgroup| {
+            cgroup
+                .rsplit('/')
+                .find(|part| part.ends_with(".service") || part.ends_with(".scope"))
+        });
+
+        // Flatpak apps run in scopes named app-flatpak-<app id>-<number>.scope
+        let flatpak_app_id = unit
+            .and_then(|unit| unit.strip_prefix("app-flatpak-"))
+            .and_then(|rest| rest.strip_suffix(".scope"))
+            .and_then(|rest| rest.rsplit_once('-'))
+            .map(|(app_id, _)| app_id.to_owned());
+
+        let mut this = Self {
+            executable: fs::read_link(format!("/proc/{pid}/exe")).ok(),
+            cmdline: cmdline(pid),
+            start_time: start_time(pid),
+            systemd_unit: unit.map(ToOwned::to_owned),
+            flatpak_app_id,
+            cgroup,
+
+            last_cpu_sample: None,
+            cpu_usage: None,
+        };
+
+        this.update_cpu_usage(pid);
+
+        this
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    pub fn update_cpu_usage(&mut self, pid: u32) {
+        let Some(ticks) = cpu_ticks(pid) else {
+            self.cpu_usage = None;
+            return;
+        };
+        let now = Instant::now();
+
+        if let Some((last_time, last_ticks)) = self.last_cpu_sample {
+            let elapsed = now.duration_since(last_time).as_secs_f64();
+            if elapsed > 0. {
+                self.cpu_usage =
+                    Some(ticks.saturating_sub(last_ticks) as f64 / USER_HZ / elapsed * 100.);
+            }
+        }
+
+        self.last_cpu_sample = Some((now, ticks));
+    }
+
+    /// Time since the process started
+    pub fn uptime(&self) -> Option<Duration> {
+        let system_uptime: f64 = fs::read_to_string("/proc/uptime")
+            .ok()?
+            .split_whitespace()
+            .next()?
+            .parse()
+            .ok()?;
+
+        Some(Duration::from_secs_f64(
+            (system_uptime - self.start_time?).max(0.),
+        ))
+    }
+
+    pub fn last_update(&self) -> Option<Instant> {
+        self.last_cpu_sample.map(|(time, _)| time)
+    }
+}
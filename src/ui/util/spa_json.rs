@@ -0,0 +1,211 @@
+// Copyright 2023-2024 Dimitris Papaioannou <dimtpap@protonmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License version 3 as published by
+// the Free Software Foundation.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+//
+// SPDX-License-Identifier: GPL-3.0-only
+
+// Parsing of the relaxed JSON format that PipeWire's configuration files are written in.
+// Quotes are optional, `=` can be used instead of `:`, commas are optional and
+// `#` starts a comment.
+
+/// A value of a configuration file
+pub enum Value {
+    /// Strings, numbers, booleans and null, which aren't distinguished
+    Simple(String),
+    Array(Vec<Value>),
+    /// Keys can appear more than once, the last value is the one that applies
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::Simple(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Value]> {
+        match self {
+            Self::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&[(String, Value)]> {
+        match self {
+            Self::Object(entries) => Some(entries),
+            _ => None,
+        }
+    }
+
+    /// The value of a key of an object
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.as_object()?
+            .iter()
+            .rev()
+            .find_map(|(k, v)| (k == key).then_some(v))
+    }
+}
+
+struct Parser<'a> {
+    s: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.s[self.pos..].chars().next()
+    }
+
+    fn error(&self, message: &str) -> String {
+        let line = self.s[..self.pos].matches('\n').count() + 1;
+        format!("Line {line}: {message}")
+    }
+
+    /// Skips whitespace, separators and comments
+    fn skip(&mut self) {
+        while let Some(c) = self.peek() {
+            match c {
+                '#' => {
+                    self.pos = self.s[self.pos..]
+                        .find('\n')
+                        .map_or(self.s.len(), |end| self.pos + end);
+                }
+                c if c.is_whitespace() || matches!(c, ',' | ':' | '=') => {
+                    self.pos += c.len_utf8();
+                }
+                _ => break,
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        if self.peek() != Some('"') {
+            let rest = &self.s[self.pos..];
+            let end = rest
+                .find(|c: char| {
+                    c.is_whitespace()
+                        || matches!(c, ',' | ':' | '=' | '{' | '}' | '[' | ']' | '"' | '#')
+                })
+                .unwrap_or(rest.len());
+            self.pos += end;
+            return Ok(rest[..end].to_owned());
+        }
+
+        self.pos += 1;
+        let mut string = String::new();
+        let mut chars = self.s[self.pos..].char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.pos += i + 1;
+                    return Ok(string);
+                }
+                '\\' => match chars.next().map(|(_, c)| c) {
+                    Some('n') => string.push('\n'),
+                    Some('t') => string.push('\t'),
+                    Some('r') => string.push('\r'),
+                    Some(c) => string.push(c),
+                    None => break,
+                },
+                c => string.push(c),
+            }
+        }
+
+        self.pos = self.s.len();
+        Err(self.error("Unterminated string"))
+    }
+
+    fn value(&mut self) -> Result<Value, String> {
+        self.skip();
+        match self.peek() {
+            Some('{') => {
+                self.pos += 1;
+                self.entries(Some('}')).map(Value::Object)
+            }
+            Some('[') => {
+                self.pos += 1;
+                let mut values = Vec::new();
+                loop {
+                    self.skip();
+                    match self.peek() {
+                        Some(']') => {
+                            self.pos += 1;
+                            return Ok(Value::Array(values));
+                        }
+                        Some('}') => return Err(self.error("Unexpected }")),
+                        Some(_) => values.push(self.value()?),
+                        None => return Err(self.error("Unterminated array")),
+                    }
+                }
+            }
+            Some('}' | ']') => Err(self.error("Expected a value")),
+            Some(_) => self.string().map(Value::Simple),
+            None => Err(self.error("Expected a value")),
+        }
+    }
+
+    /// Parses the entries of an object until the closing character, or the end for the top level
+    fn entries(&mut self, close: Option<char>) -> Result<Vec<(String, Value)>, String> {
+        let mut entries = Vec::new();
+        loop {
+            self.skip();
+            match self.peek() {
+                c if c == close => {
+                    if close.is_some() {
+                        self.pos += 1;
+                    }
+                    return Ok(entries);
+                }
+                None => return Err(self.error("Unterminated object")),
+                Some('{' | '[' | '}' | ']') => return Err(self.error("Expected a key")),
+                Some(_) => {
+                    let key = self.string()?;
+                    let value = self.value()?;
+                    entries.push((key, value));
+                }
+            }
+        }
+    }
+}
+
+/// Parses the contents of a configuration file, which is an object that may not be enclosed in braces
+pub fn parse(s: &str) -> Result<Vec<(String, Value)>, String> {
+    let mut parser = Parser { s, pos: 0 };
+
+    parser.skip();
+    if parser.peek() == Some('{') {
+        parser.pos += 1;
+        let entries = parser.entries(Some('}'))?;
+        parser.skip();
+        return match parser.peek() {
+            None => Ok(entries),
+            Some(_) => Err(parser.error("Unexpected content after the object")),
+        };
+    }
+
+    parser.entries(None)
+}
+
+/// Parses a single value, like the value of a property
+pub fn parse_value(s: &str) -> Result<Value, String> {
+    let mut parser = Parser { s, pos: 0 };
+
+    let value = parser.value()?;
+    parser.skip();
+    match parser.peek() {
+        None => Ok(value),
+        Some(_) => Err(parser.error("Unexpected content after the value")),
+    }
+}
@@ -18,7 +18,14 @@ use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
 
 use eframe::egui;
 
-use crate::{backend, ui::globals_store::Global};
+use crate::{
+    backend,
+    schema::{self, PropertyType, TypedValue},
+    ui::{
+        globals_store::Global,
+        util::spa_json::{self, Value},
+    },
+};
 
 pub fn global_info_button(
     ui: &mut egui::Ui,
@@ -36,7 +43,7 @@ pub fn global_info_button(
                         // Remove cross-justify
                         ui.with_layout(egui::Layout::default(), |ui| {
                             ui.reset_style();
-                            global.borrow_mut().show(ui, true, sx);
+                            global.borrow_mut().show(ui, true, sx, None);
                         });
                     }
                 });
@@ -66,19 +73,149 @@ pub fn key_val_table(
         });
 }
 
+// Values longer than this get a pretty printed view if they're SPA-JSON arrays or objects
+const LONG_VALUE: usize = 80;
+
+/// Parses a value if it's a SPA-JSON array or object too long to be read in one line
+pub fn long_spa_json(value: &str) -> Option<Value> {
+    if value.len() < LONG_VALUE || !value.trim_start().starts_with(['{', '[']) {
+        return None;
+    }
+    spa_json::parse_value(value)
+        .ok()
+        .filter(|value| value.as_str().is_none())
+}
+
+/// Shows a SPA-JSON value pretty printed, with its arrays and objects folded
+/// after the first levels
+pub fn spa_json_tree(ui: &mut egui::Ui, value: &Value) {
+    fn show(ui: &mut egui::Ui, i: usize, key: &str, value: &Value, depth: usize) {
+        let (header, children): (_, Vec<(String, &Value)>) = match value {
+            Value::Simple(s) => {
+                ui.add(
+                    egui::Label::new(egui::RichText::new(format!("{key}{s}")).monospace())
+                        .wrap(true)
+                        .selectable(true),
+                );
+                return;
+            }
+            Value::Array(values) => (
+                format!("{key}[{}]", values.len()),
+                values
+                    .iter()
+                    .enumerate()
+                    .map(|(i, v)| (format!("{i}: "), v))
+                    .collect(),
+            ),
+            Value::Object(entries) => (
+                format!("{key}{{{}}}", entries.len()),
+                entries
+                    .iter()
+                    .map(|(k, v)| (format!("{k} = "), v))
+                    .collect(),
+            ),
+        };
+
+        egui::CollapsingHeader::new(egui::RichText::new(header).monospace())
+            .id_source(i)
+            .default_open(depth < 2)
+            .show(ui, |ui| {
+                for (i, (key, value)) in children.iter().enumerate() {
+                    show(ui, i, key, value, depth + 1);
+                }
+            });
+    }
+
+    show(ui, 0, "", value, 0);
+}
+
+/// How the values of a [`key_val_display`] are shown
+#[derive(Clone, Copy)]
+struct ValueStyle {
+    monospace: bool,
+    zoom: f32,
+}
+
+impl Default for ValueStyle {
+    fn default() -> Self {
+        Self {
+            monospace: false,
+            zoom: 1.,
+        }
+    }
+}
+
 /// Displays all the key-value pairs of the iterator using [`key_val_table`].
+/// The values can be selected and copied.
 pub fn key_val_display(
     ui: &mut egui::Ui,
     min_scrolled_height: f32,
     max_height: f32,
     header: &str,
-    kv: impl Iterator<Item = (impl Into<egui::WidgetText>, impl Into<egui::WidgetText>)>,
+    kv: impl Iterator<Item = (impl AsRef<str>, impl AsRef<str>)>,
 ) {
     ui.collapsing(header, |ui| {
+        let id = ui.make_persistent_id(("key_val_display", header));
+        let mut value_style: ValueStyle = ui.data_mut(|d| *d.get_temp_mut_or_default(id));
+
+        ui.horizontal(|ui| {
+            ui.toggle_value(&mut value_style.monospace, "Monospace")
+                .on_hover_text("Show the values in a monospace font, for long SPA-JSON values");
+            ui.add(
+                egui::DragValue::new(&mut value_style.zoom)
+                    .clamp_range(0.5..=3.)
+                    .speed(0.01)
+                    .prefix("Zoom: ")
+                    .suffix("×"),
+            );
+        });
+
+        ui.data_mut(|d| d.insert_temp(id, value_style));
+
+        let text_style = if value_style.monospace {
+            egui::TextStyle::Monospace
+        } else {
+            egui::TextStyle::Body
+        };
+        let size = text_style.resolve(ui.style()).size * value_style.zoom;
+
         key_val_table(ui, min_scrolled_height, max_height, |ui| {
             for (k, v) in kv {
-                ui.label(k);
-                ui.label(v);
+                let v = v.as_ref();
+
+                ui.label(k.as_ref());
+                ui.horizontal(|ui| {
+                    if ui.small_button("🗐").on_hover_text("Copy").clicked() {
+                        ui.output_mut(|o| o.copied_text = v.to_owned());
+                    }
+
+                    if let Some(value) = long_spa_json(v) {
+                        // Previews the start of the value until it's expanded
+                        let preview: String = v.chars().take(LONG_VALUE).collect();
+                        ui.vertical(|ui| {
+                            egui::CollapsingHeader::new(
+                                egui::RichText::new(format!("{preview}…"))
+                                    .text_style(text_style.clone())
+                                    .size(size),
+                            )
+                            .id_source(k.as_ref())
+                            .show(ui, |ui| {
+                                spa_json_tree(ui, &value);
+                            });
+                        });
+                        return;
+                    }
+
+                    ui.add(
+                        egui::Label::new(
+                            egui::RichText::new(v)
+                                .text_style(text_style.clone())
+                                .size(size),
+                        )
+                        .wrap(true)
+                        .selectable(true),
+                    );
+                });
                 ui.end_row();
             }
         });
@@ -86,6 +223,40 @@ pub fn key_val_display(
 }
 
 /// Displays the key-value pairs of a map with the ability to delete them and add new ones.
+/// Edits the value of a property with a widget for its type, if it's known
+pub fn property_value_edit(ui: &mut egui::Ui, key: &str, value: &mut String) {
+    match schema::typed_value(key, value) {
+        Some(TypedValue::Bool(mut b)) => {
+            if ui.checkbox(&mut b, "").changed() {
+                *value = b.to_string();
+            }
+        }
+        Some(TypedValue::Int(mut i)) => {
+            if ui.add(egui::DragValue::new(&mut i)).changed() {
+                *value = i.to_string();
+            }
+        }
+        // Values that don't match their type can still be edited as text
+        _ => match schema::property_type(key) {
+            PropertyType::Enum(options) => {
+                egui::ComboBox::from_id_source(ui.id().with(key))
+                    .selected_text(value.as_str())
+                    .show_ui(ui, |ui| {
+                        for option in options {
+                            ui.selectable_value(value, (*option).to_owned(), *option);
+                        }
+                    });
+            }
+            _ => {
+                egui::TextEdit::singleline(value)
+                    .hint_text("Value")
+                    .desired_width(f32::INFINITY)
+                    .show(ui);
+            }
+        },
+    }
+}
+
 pub fn map_editor(
     ui: &mut egui::Ui,
     min_scrolled_height: f32,
@@ -99,10 +270,7 @@ pub fn map_editor(
             let keep = ui
                 .with_layout(egui::Layout::right_to_left(egui::Align::Min), |ui| {
                     let keep = !ui.button("Delete").clicked();
-                    egui::TextEdit::singleline(v)
-                        .hint_text("Value")
-                        .desired_width(f32::INFINITY)
-                        .show(ui);
+                    property_value_edit(ui, k, v);
                     keep
                 })
                 .inner;
@@ -190,6 +358,13 @@ impl MapEditor {
         );
     }
 
+    /// Adds an entry with the given key for the user to fill in
+    pub fn add_entry(&mut self, key: &str) {
+        self.user_additions
+            .list_mut()
+            .push((key.to_owned(), String::new()));
+    }
+
     pub fn take(&mut self) -> BTreeMap<String, String> {
         self.properties.extend(self.user_additions.take());
 
@@ -201,6 +376,7 @@ mod kv_matcher {
     use eframe::egui;
 
     #[derive(PartialEq, Eq, Clone)]
+    #[cfg_attr(feature = "persistence", derive(serde::Serialize, serde::Deserialize))]
     enum StringMatchMode {
         Substring,
         StartsWith,
@@ -248,6 +424,8 @@ mod kv_matcher {
         }
     }
 
+    #[derive(Clone)]
+    #[cfg_attr(feature = "persistence", derive(serde::Serialize, serde::Deserialize))]
     struct StringFilter {
         needle: String,
         match_mode: StringMatchMode,
@@ -284,6 +462,8 @@ mod kv_matcher {
     }
 
     /// User-configurable filter for key-value pair collections.
+    #[derive(Clone)]
+    #[cfg_attr(feature = "persistence", derive(serde::Serialize, serde::Deserialize))]
     pub struct KvMatcher {
         filters: Vec<(StringFilter, StringFilter)>,
     }